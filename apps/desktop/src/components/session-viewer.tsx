@@ -1,5 +1,6 @@
 import { useEffect } from "react";
 import { useSessions } from "../hooks/use-sessions";
+import { useSessionAudio } from "../hooks/use-session-audio";
 
 function formatTime(ms: number): string {
   const seconds = Math.floor(ms / 1000);
@@ -41,6 +42,7 @@ interface SessionViewerProps {
 export function SessionViewer({ sessionId, onBack }: SessionViewerProps) {
   const { currentSession, isLoading, loadSession, clearCurrentSession } =
     useSessions();
+  const audio = useSessionAudio(sessionId);
 
   useEffect(() => {
     loadSession(sessionId);
@@ -55,6 +57,19 @@ export function SessionViewer({ sessionId, onBack }: SessionViewerProps) {
     );
   }
 
+  const speakerNames = new Map(
+    currentSession.speakers.map((s) => [s.id, s.name] as const)
+  );
+
+  const jumpTo = (startMs: number) => {
+    const segment = currentSession.segments.find((s) => s.startMs >= startMs);
+    if (segment) {
+      document
+        .getElementById(`segment-${segment.id}`)
+        ?.scrollIntoView({ behavior: "smooth", block: "start" });
+    }
+  };
+
   return (
     <div className="flex flex-col h-full">
       <div className="p-4 border-b border-gray-800">
@@ -76,26 +91,63 @@ export function SessionViewer({ sessionId, onBack }: SessionViewerProps) {
             </p>
           </div>
         </div>
+        {currentSession.chapters.length > 1 && (
+          <div className="flex flex-wrap gap-2 mt-3">
+            {currentSession.chapters.map((chapter) => (
+              <button
+                key={chapter.start_ms}
+                onClick={() => jumpTo(chapter.start_ms)}
+                className="text-xs px-2 py-1 rounded bg-gray-800 text-gray-300 hover:text-white"
+                title={chapter.keywords.join(", ")}
+              >
+                {formatTime(chapter.start_ms)} {chapter.title}
+              </button>
+            ))}
+          </div>
+        )}
       </div>
 
       <div className="flex-1 overflow-auto p-4">
         <div className="space-y-3">
-          {currentSession.segments.map((segment) => (
-            <div key={segment.id} className="p-3 rounded-lg bg-gray-800">
-              <div className="flex items-center gap-2 mb-1">
-                <span className="text-xs text-gray-500">
-                  {formatTime(segment.startMs)}
-                </span>
-                {segment.speaker !== null && (
-                  <span className="text-xs px-2 py-0.5 rounded bg-blue-600/30 text-blue-400">
-                    Speaker {segment.speaker + 1}
+          {currentSession.segments.map((segment) => {
+            const span = { kind: "segment", segment_id: segment.id } as const;
+            const isPlaying = audio.isPlaying(span);
+            return (
+              <div
+                key={segment.id}
+                id={`segment-${segment.id}`}
+                onClick={currentSession.hasAudio ? () => audio.toggle(span) : undefined}
+                className={`p-3 rounded-lg bg-gray-800 ${
+                  currentSession.hasAudio ? "cursor-pointer hover:bg-gray-700" : ""
+                } ${isPlaying ? "ring-1 ring-blue-500" : ""}`}
+                title={currentSession.hasAudio ? (isPlaying ? "Stop" : "Play this line") : undefined}
+              >
+                <div className="flex items-center gap-2 mb-1">
+                  <span className="text-xs text-gray-500">
+                    {formatTime(segment.startMs)}
                   </span>
-                )}
+                  {isPlaying && <span className="text-xs text-blue-400">Playing</span>}
+                  {segment.speaker !== null && (
+                    <span className="text-xs px-2 py-0.5 rounded bg-blue-600/30 text-blue-400">
+                      {speakerNames.get(segment.speaker) ?? `Speaker ${segment.speaker + 1}`}
+                    </span>
+                  )}
+                </div>
+                <p
+                  className="text-gray-200 leading-relaxed"
+                  title={segment.rawText ?? undefined}
+                >
+                  {segment.text}
+                </p>
               </div>
-              <p className="text-gray-200 leading-relaxed">{segment.text}</p>
-            </div>
-          ))}
+            );
+          })}
         </div>
+        {audio.error && (
+          <p className="mt-3 text-xs" style={{ color: "var(--color-danger)" }}>
+            {audio.error}
+          </p>
+        )}
       </div>
     </div>
   );
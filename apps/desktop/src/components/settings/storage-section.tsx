@@ -1,7 +1,10 @@
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useSessions } from "../../hooks/use-sessions";
-import { SectionHeader } from "./shared";
+import { useBackup } from "../../hooks/use-backup";
+import { useEncryption } from "../../hooks/use-encryption";
+import { useRetention, type RetentionSettings } from "../../hooks/use-retention";
+import { SectionHeader, formatBytes } from "./shared";
 
 export function StorageSection() {
   const { sessions, loadSessions } = useSessions();
@@ -69,7 +72,354 @@ export function StorageSection() {
             </button>
           )}
         </div>
+        <RetentionCard onApplied={loadSessions} />
+        <EncryptionCard onUnlocked={loadSessions} />
+        <BackupCard onImported={loadSessions} />
       </div>
     </section>
   );
 }
+
+type RetentionLimit =
+  | "session_max_age_days"
+  | "keep_last_sessions"
+  | "audio_max_age_days"
+  | "activity_max_age_days";
+
+const RETENTION_LIMITS: { key: RetentionLimit; label: string }[] = [
+  { key: "session_max_age_days", label: "Delete sessions after (days)" },
+  { key: "keep_last_sessions", label: "Keep only the newest sessions" },
+  { key: "audio_max_age_days", label: "Delete recordings after (days)" },
+  { key: "activity_max_age_days", label: "Delete activity after (days)" },
+];
+
+function RetentionCard({ onApplied }: { onApplied: () => void }) {
+  const { settings, report, isBusy, error, update, preview, apply } = useRetention();
+
+  if (!settings) return null;
+
+  const setLimit = (key: RetentionLimit, value: number) => {
+    if (!Number.isInteger(value) || value < 0) return;
+    update({ [key]: value } as Partial<RetentionSettings>);
+  };
+
+  const modes = Object.keys(settings.modes);
+
+  return (
+    <div className="card p-4 space-y-3" style={{ background: "var(--color-bg-secondary)" }}>
+      <div>
+        <div className="font-medium text-sm" style={{ color: "var(--color-text-primary)" }}>
+          Retention
+        </div>
+        <div className="text-xs mt-0.5" style={{ color: "var(--color-text-tertiary)" }}>
+          Applied hourly. 0 keeps forever.
+          {modes.length > 0 && ` Overrides for ${modes.join(", ")} are set in settings.toml.`}
+        </div>
+      </div>
+
+      {RETENTION_LIMITS.map(({ key, label }) => (
+        <div key={key} className="flex items-center justify-between gap-3">
+          <div className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+            {label}
+          </div>
+          <input
+            type="number"
+            min={0}
+            step={1}
+            value={settings[key]}
+            onChange={(e) => setLimit(key, Number(e.target.value))}
+            className="px-2 py-1 rounded text-sm"
+            style={{
+              width: 72,
+              background: "var(--color-bg-primary)",
+              border: "1px solid var(--color-border)",
+              color: "var(--color-text-primary)",
+            }}
+          />
+        </div>
+      ))}
+
+      <div className="flex items-center justify-between">
+        <div className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+          Keep recordings
+        </div>
+        <button
+          className="btn-secondary text-sm"
+          onClick={() => update({ discard_audio: !settings.discard_audio })}
+        >
+          {settings.discard_audio ? "Off" : "On"}
+        </button>
+      </div>
+
+      <div className="flex items-center justify-between gap-3">
+        <div className="text-xs" style={{ color: "var(--color-text-tertiary)" }}>
+          {report &&
+            (report.sessions.length + report.audio.length + report.activities === 0
+              ? "Nothing to delete"
+              : `${report.dry_run ? "Would delete" : "Deleted"} ${report.sessions.length} sessions, ` +
+                `${report.audio.length} recordings, ${report.activities} activities ` +
+                `(${formatBytes(report.audio_bytes)} of audio)`)}
+        </div>
+        <div className="flex items-center gap-2">
+          <button onClick={preview} disabled={isBusy} className="btn-secondary text-xs px-2 py-1">
+            Preview
+          </button>
+          <button
+            onClick={async () => {
+              await apply();
+              onApplied();
+            }}
+            disabled={isBusy || !report?.dry_run}
+            className="btn-danger text-xs px-2 py-1"
+          >
+            {isBusy ? "..." : "Apply Now"}
+          </button>
+        </div>
+      </div>
+
+      {error && (
+        <div className="text-xs" style={{ color: "var(--color-danger)" }}>
+          {error}
+        </div>
+      )}
+    </div>
+  );
+}
+
+function EncryptionCard({ onUnlocked }: { onUnlocked: () => void }) {
+  const { status, isBusy, error, enable, disable, unlock, rotateKey } = useEncryption();
+  const [passphrase, setPassphrase] = useState("");
+  const [newPassphrase, setNewPassphrase] = useState("");
+
+  if (!status) return null;
+
+  // A keyring key needs no passphrase; one set by the user does.
+  const needsPassphrase = status.encrypted && !status.uses_keyring;
+
+  const after = (ok: boolean) => {
+    if (!ok) return;
+    setPassphrase("");
+    setNewPassphrase("");
+  };
+
+  let description = "Sessions and recordings are stored unencrypted";
+  if (status.locked) {
+    description = "Locked: enter the passphrase to open your sessions";
+  } else if (status.uses_keyring) {
+    description = "Encrypted with a key in the system keychain";
+  } else if (status.encrypted) {
+    description = "Encrypted with your passphrase";
+  }
+
+  const keychainHint = "Passphrase (optional, uses the keychain if empty)";
+  const inputStyle = {
+    background: "var(--color-bg-primary)",
+    border: "1px solid var(--color-border)",
+    color: "var(--color-text-primary)",
+  };
+
+  return (
+    <div className="card p-4 space-y-3" style={{ background: "var(--color-bg-secondary)" }}>
+      <div>
+        <div className="font-medium text-sm" style={{ color: "var(--color-text-primary)" }}>
+          Encryption
+        </div>
+        <div className="text-xs mt-0.5" style={{ color: "var(--color-text-tertiary)" }}>
+          {description}
+        </div>
+      </div>
+
+      {status.locked ? (
+        <div className="flex items-center gap-2">
+          <input
+            type="password"
+            placeholder="Passphrase"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            className="flex-1 px-2 py-1 rounded text-sm"
+            style={inputStyle}
+          />
+          <button
+            onClick={async () => {
+              const ok = await unlock(passphrase);
+              after(ok);
+              if (ok) onUnlocked();
+            }}
+            disabled={isBusy || !passphrase}
+            className="btn-secondary text-sm"
+          >
+            {isBusy ? "..." : "Unlock"}
+          </button>
+        </div>
+      ) : (
+        <>
+          <div className="flex items-center gap-2">
+            <input
+              type="password"
+              placeholder={needsPassphrase ? "Current passphrase" : keychainHint}
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              className="flex-1 px-2 py-1 rounded text-sm"
+              style={inputStyle}
+            />
+            {status.encrypted ? (
+              <button
+                onClick={async () => after(await disable(passphrase))}
+                disabled={isBusy || (needsPassphrase && !passphrase)}
+                className="btn-secondary text-sm"
+              >
+                {isBusy ? "..." : "Decrypt"}
+              </button>
+            ) : (
+              <button
+                onClick={async () => after(await enable(passphrase))}
+                disabled={isBusy}
+                className="btn-secondary text-sm"
+              >
+                {isBusy ? "..." : "Encrypt"}
+              </button>
+            )}
+          </div>
+          {status.encrypted && (
+            <div className="flex items-center gap-2">
+              <input
+                type="password"
+                placeholder={`New ${keychainHint.toLowerCase()}`}
+                value={newPassphrase}
+                onChange={(e) => setNewPassphrase(e.target.value)}
+                className="flex-1 px-2 py-1 rounded text-sm"
+                style={inputStyle}
+              />
+              <button
+                onClick={async () => after(await rotateKey(passphrase, newPassphrase))}
+                disabled={isBusy || (needsPassphrase && !passphrase)}
+                className="btn-secondary text-sm"
+              >
+                {isBusy ? "..." : "Change Key"}
+              </button>
+            </div>
+          )}
+        </>
+      )}
+
+      {status.unsealed_recordings > 0 && (
+        <div className="text-xs" style={{ color: "var(--color-danger)" }}>
+          {status.unsealed_recordings} recordings are not encrypted yet
+        </div>
+      )}
+
+      {error && (
+        <div className="text-xs" style={{ color: "var(--color-danger)" }}>
+          {error}
+        </div>
+      )}
+    </div>
+  );
+}
+
+function BackupCard({ onImported }: { onImported: () => void }) {
+  const { manifest, report, isBusy, error, exportBackup, importBackup } = useBackup();
+  const { status } = useEncryption();
+  const [passphrase, setPassphrase] = useState("");
+  const [allowPlaintext, setAllowPlaintext] = useState(false);
+
+  // An encrypted archive is only exported in clear when asked to.
+  const needsPassphrase = !!status?.encrypted && !allowPlaintext;
+
+  let summary: string | null = null;
+  if (manifest) {
+    summary =
+      `Exported ${manifest.sessions} sessions, ${manifest.recordings} recordings, ` +
+      `${manifest.activities} activities and ${manifest.files} files`;
+  } else if (report) {
+    summary =
+      `Added ${report.sessions} sessions, ${report.recordings} recordings, ` +
+      `${report.activities} activities and ${report.files} files` +
+      (report.settings.length > 0 ? `; took settings: ${report.settings.join(", ")}` : "") +
+      ` (${report.unchanged} already here)`;
+  }
+
+  return (
+    <div className="card p-4 space-y-3" style={{ background: "var(--color-bg-secondary)" }}>
+      <div>
+        <div className="font-medium text-sm" style={{ color: "var(--color-text-primary)" }}>
+          Backup
+        </div>
+        <div className="text-xs mt-0.5" style={{ color: "var(--color-text-tertiary)" }}>
+          Sessions, recordings, activity, settings, skills and tool packs in one file, encrypted
+          with a passphrase if you give one.
+        </div>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <input
+          type="password"
+          placeholder={needsPassphrase ? "Backup passphrase" : "Backup passphrase (optional)"}
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          className="flex-1 px-2 py-1 rounded text-sm"
+          style={{
+            background: "var(--color-bg-primary)",
+            border: "1px solid var(--color-border)",
+            color: "var(--color-text-primary)",
+          }}
+        />
+        <button
+          onClick={() => exportBackup(passphrase, allowPlaintext)}
+          disabled={isBusy || (needsPassphrase && !passphrase)}
+          className="btn-secondary text-sm"
+        >
+          {isBusy ? "..." : "Export"}
+        </button>
+        <button
+          onClick={async () => {
+            if (await importBackup(passphrase)) onImported();
+          }}
+          disabled={isBusy}
+          className="btn-secondary text-sm"
+        >
+          Import
+        </button>
+      </div>
+
+      {status?.encrypted && (
+        <label
+          className="inline-flex items-center gap-2 text-xs"
+          style={{ color: "var(--color-text-secondary)" }}
+        >
+          <input
+            type="checkbox"
+            checked={allowPlaintext}
+            onChange={(e) => setAllowPlaintext(e.target.checked)}
+          />
+          Export without a passphrase, unencrypted
+        </label>
+      )}
+
+      {summary && (
+        <div className="text-xs" style={{ color: "var(--color-text-tertiary)" }}>
+          {summary}
+        </div>
+      )}
+
+      {report && report.conflicts.length > 0 && (
+        <div className="text-xs space-y-1" style={{ color: "var(--color-text-secondary)" }}>
+          <div>Kept the local copy of {report.conflicts.length} changed items:</div>
+          <ul className="list-disc pl-4">
+            {report.conflicts.map((c) => (
+              <li key={`${c.item}-${c.id}`}>
+                {c.item} {c.id}
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+
+      {error && (
+        <div className="text-xs" style={{ color: "var(--color-danger)" }}>
+          {error}
+        </div>
+      )}
+    </div>
+  );
+}
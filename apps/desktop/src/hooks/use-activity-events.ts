@@ -15,6 +15,18 @@ import { useContextStore } from "../stores/context-store";
 interface StreamCommitEvent {
   text: string;
   ts_ms: number;
+  commit_id: number;
+  revision_pending: boolean;
+}
+
+interface CommitRevisedEvent {
+  commit_id: number;
+  /** All commits of the revised utterance, oldest first. */
+  commit_ids?: number[];
+  original_text: string;
+  text: string;
+  model: string;
+  ts_ms: number;
 }
 
 interface RouterStatusPayload {
@@ -39,11 +51,14 @@ interface ContextChangedEvent {
 
 export function useActivityEvents() {
   const addActivity = useActivityStore((s) => s.addActivity);
+  const updateActivity = useActivityStore((s) => s.updateActivity);
+  const removeActivity = useActivityStore((s) => s.removeActivity);
   const updateActivityStatus = useActivityStore((s) => s.updateActivityStatus);
   const setPendingVoiceCommand = useActivityStore((s) => s.setPendingVoiceCommand);
   const getPendingVoiceCommandId = useActivityStore((s) => s.getPendingVoiceCommandId);
   const lastModeRef = useRef<string | null>(null);
   const recordingStartRef = useRef<number | null>(null);
+  const commitActivityRef = useRef(new Map<number, string>());
 
   useEffect(() => {
     let mounted = true;
@@ -59,11 +74,40 @@ export function useActivityEvents() {
           if (text) {
             const activity = createTranscriptActivity(text);
             addActivity(activity);
+            if (event.payload.revision_pending) {
+              commitActivityRef.current.set(event.payload.commit_id, activity.id);
+            }
           }
         }
       );
       if (mounted) unlisteners.push(streamCommit);
 
+      // Two-pass mode: replace an utterance's streamed transcripts with the refined text
+      const commitRevised = await listen<CommitRevisedEvent>(
+        "stt:commit_revised",
+        (event) => {
+          if (!mounted) return;
+          const { commit_id, commit_ids, text } = event.payload;
+          const commitIds = commit_ids?.length ? commit_ids : [commit_id];
+          // The first transcript takes the utterance's text; the rest merge into it.
+          let target: string | undefined;
+          for (const id of commitIds) {
+            const activityId = commitActivityRef.current.get(id);
+            commitActivityRef.current.delete(id);
+            if (!activityId) continue;
+            if (target) {
+              removeActivity(activityId);
+            } else {
+              target = activityId;
+            }
+          }
+          if (target && text.trim()) {
+            updateActivity(target, { content: { text: text.trim() } });
+          }
+        }
+      );
+      if (mounted) unlisteners.push(commitRevised);
+
       // Listen for router status (voice commands and tool results)
       const routerStatus = await listen<RouterStatusPayload>(
         "tools:router_status",
@@ -196,5 +240,12 @@ export function useActivityEvents() {
       mounted = false;
       unlisteners.forEach((fn) => fn());
     };
-  }, [addActivity, updateActivityStatus, setPendingVoiceCommand, getPendingVoiceCommandId]);
+  }, [
+    addActivity,
+    updateActivity,
+    removeActivity,
+    updateActivityStatus,
+    setPendingVoiceCommand,
+    getPendingVoiceCommandId,
+  ]);
 }
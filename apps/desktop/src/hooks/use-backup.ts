@@ -0,0 +1,90 @@
+import { useCallback, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open, save } from "@tauri-apps/plugin-dialog";
+
+export interface BackupManifest {
+  version: number;
+  created_at_ms: number;
+  sessions: number;
+  recordings: number;
+  activities: number;
+  files: number;
+}
+
+/** Something whose local copy differs from the backup; the local copy was kept. */
+export interface BackupConflict {
+  item: "session" | "recording" | "activity" | "setting" | "file";
+  id: string;
+}
+
+export interface ImportReport {
+  sessions: number;
+  recordings: number;
+  activities: number;
+  settings: string[];
+  files: number;
+  unchanged: number;
+  conflicts: BackupConflict[];
+}
+
+const FILTERS = [{ name: "Backup", extensions: ["gz"] }];
+
+export function useBackup() {
+  const [manifest, setManifest] = useState<BackupManifest | null>(null);
+  const [report, setReport] = useState<ImportReport | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  /**
+   * Encrypted with `passphrase` if given. An encrypted database is only
+   * exported without one when `allowPlaintext` is set.
+   */
+  const exportBackup = useCallback(async (passphrase: string, allowPlaintext: boolean) => {
+    const date = new Date().toISOString().slice(0, 10);
+    const path = await save({ defaultPath: `gibberish-backup-${date}.tar.gz`, filters: FILTERS });
+    if (!path) return;
+    setIsBusy(true);
+    setError(null);
+    setReport(null);
+    try {
+      setManifest(
+        await invoke<BackupManifest>("plugin:gibberish-stt|export_backup", {
+          path,
+          passphrase: passphrase || null,
+          allowPlaintext,
+        })
+      );
+    } catch (err) {
+      console.error("Failed to export backup:", err);
+      setError(String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  }, []);
+
+  /** An encrypted backup needs its `passphrase`. Returns whether anything was imported. */
+  const importBackup = useCallback(async (passphrase: string) => {
+    const path = await open({ multiple: false, directory: false, filters: FILTERS });
+    if (typeof path !== "string") return false;
+    setIsBusy(true);
+    setError(null);
+    setManifest(null);
+    try {
+      setReport(
+        await invoke<ImportReport>("plugin:gibberish-stt|import_backup", {
+          path,
+          passphrase: passphrase || null,
+        })
+      );
+      return true;
+    } catch (err) {
+      console.error("Failed to import backup:", err);
+      setError(String(err));
+      return false;
+    } finally {
+      setIsBusy(false);
+    }
+  }, []);
+
+  return { manifest, report, isBusy, error, exportBackup, importBackup };
+}
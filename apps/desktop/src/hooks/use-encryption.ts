@@ -0,0 +1,88 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export interface EncryptionStatus {
+  encrypted: boolean;
+  /** Encrypted under a passphrase that has not been entered yet. */
+  locked: boolean;
+  /** The key lives in the OS keyring instead of a passphrase. */
+  uses_keyring: boolean;
+  /** Recordings left in plain text, e.g. because sealing them failed. */
+  unsealed_recordings: number;
+}
+
+export function useEncryption() {
+  const [status, setStatus] = useState<EncryptionStatus | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setStatus(await invoke<EncryptionStatus>("plugin:gibberish-stt|get_encryption_status"));
+    } catch (err) {
+      console.error("Failed to get encryption status:", err);
+    }
+  }, []);
+
+  useEffect(() => {
+    let mounted = true;
+    let unlisten: (() => void) | null = null;
+    refresh();
+
+    listen("storage:locked", () => {
+      if (mounted) refresh();
+    }).then((un) => {
+      if (mounted) unlisten = un;
+      else un();
+    });
+
+    return () => {
+      mounted = false;
+      unlisten?.();
+    };
+  }, [refresh]);
+
+  const run = useCallback(
+    async (command: string, args: Record<string, unknown>) => {
+      setIsBusy(true);
+      setError(null);
+      try {
+        await invoke(`plugin:gibberish-stt|${command}`, args);
+        await refresh();
+        return true;
+      } catch (err) {
+        console.error(`Failed to ${command.replace(/_/g, " ")}:`, err);
+        setError(String(err));
+        return false;
+      } finally {
+        setIsBusy(false);
+      }
+    },
+    [refresh]
+  );
+
+  /** Without a passphrase the key is generated and kept in the OS keyring. */
+  const enable = useCallback(
+    (passphrase?: string) => run("enable_encryption", { passphrase: passphrase || null }),
+    [run]
+  );
+
+  const disable = useCallback(
+    (passphrase?: string) => run("disable_encryption", { passphrase: passphrase || null }),
+    [run]
+  );
+
+  const unlock = useCallback((passphrase: string) => run("unlock_database", { passphrase }), [run]);
+
+  const rotateKey = useCallback(
+    (passphrase?: string, newPassphrase?: string) =>
+      run("rotate_encryption_key", {
+        passphrase: passphrase || null,
+        newPassphrase: newPassphrase || null,
+      }),
+    [run]
+  );
+
+  return { status, isBusy, error, enable, disable, unlock, rotateKey, refresh };
+}
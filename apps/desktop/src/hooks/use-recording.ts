@@ -12,11 +12,20 @@ import {
   type SummaryEvent,
 } from "../stores/action-router-store";
 import { TranscriptSegment } from "./use-stt";
-import { useSessions } from "./use-sessions";
+import { useSessions, type NewMarker } from "./use-sessions";
+import { useSessionsStore, type MarkerKind, type SessionDigest } from "../stores/sessions-store";
+import { useContextStore } from "../stores/context-store";
 
 // Global flag to prevent duplicate listener setup across hook instances
 let listenersSetUp = false;
 
+// State of the current recording. Module-level because the listeners live
+// in whichever hook instance set them up first.
+let recordingStartedAt = 0;
+let pendingMarkers: NewMarker[] = [];
+let recordingMode: string | null = null;
+let recordingAppName: string | null = null;
+
 interface StreamingResult {
   text: string;
   volatile_text: string;
@@ -24,9 +33,16 @@ interface StreamingResult {
   buffer_duration_ms: number;
 }
 
+interface TranscriptMarkerEvent {
+  action: string;
+  marker: { marker_type: MarkerKind; note: string | null; timestamp_ms: number };
+}
+
 interface StreamCommitEvent {
   text: string;
   ts_ms: number;
+  commit_id: number;
+  revision_pending: boolean;
 }
 
 export function useRecording() {
@@ -94,7 +110,17 @@ export function useRecording() {
         const lastSegment = finalSegments[finalSegments.length - 1];
         const durationMs = lastSegment ? lastSegment.endMs : 0;
         if (finalSegments.length > 0 && durationMs > 0) {
-          await saveSession(finalSegments, durationMs);
+          // Pass the recording so speakers are re-clustered offline
+          await saveSession(
+            finalSegments,
+            durationMs,
+            undefined,
+            filePath,
+            pendingMarkers,
+            recordingMode ?? undefined,
+            recordingAppName ?? undefined
+          );
+          pendingMarkers = [];
           console.log("Session saved to database");
         }
       } catch (err) {
@@ -301,6 +327,33 @@ export function useRecording() {
       );
       if (mounted) unlisteners.push(searchErr);
 
+      // Markers placed by voice are saved with the session being recorded
+      const marker = await listen<TranscriptMarkerEvent>(
+        "tools:transcript_marker",
+        (event) => {
+          if (!mounted || !useRecordingStore.getState().isRecording) return;
+          const { marker_type, note, timestamp_ms } = event.payload.marker;
+          pendingMarkers.push({
+            kind: marker_type,
+            timestamp_ms: Math.max(0, timestamp_ms - recordingStartedAt),
+            note: note ?? undefined,
+          });
+        }
+      );
+      if (mounted) unlisteners.push(marker);
+
+      const digest = await listen<{ session_id: string; digest: SessionDigest }>(
+        "stt:session_digest",
+        (event) => {
+          if (!mounted) return;
+          useSessionsStore.getState().setLatestDigest({
+            sessionId: event.payload.session_id,
+            digest: event.payload.digest,
+          });
+        }
+      );
+      if (mounted) unlisteners.push(digest);
+
       // Listen for tool summaries (Phase 3 feedback loop)
       const summary = await listen<SummaryEvent>(
         "tools:summary",
@@ -330,6 +383,12 @@ export function useRecording() {
       setPartialText("");
       setVolatileText("");
       recordingStartTime.current = Date.now();
+      recordingStartedAt = recordingStartTime.current;
+      pendingMarkers = [];
+      // Meeting recordings get a digest when saved
+      recordingMode = useContextStore.getState().context.mode;
+      // Untitled sessions are named after their main topic and this app
+      recordingAppName = useContextStore.getState().context.activeAppName;
 
       if (isListening) {
         // Already capturing audio in listen mode - promote to recording
@@ -0,0 +1,110 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { SettingsChangedEvent } from "./use-stt";
+
+/** Per-mode overrides; unset fields use the global rule. */
+export interface ModeRetention {
+  session_max_age_days?: number | null;
+  keep_last_sessions?: number | null;
+  audio_max_age_days?: number | null;
+  discard_audio?: boolean | null;
+}
+
+/** Retention rules. Limits of 0 mean "no limit". */
+export interface RetentionSettings {
+  session_max_age_days: number;
+  keep_last_sessions: number;
+  audio_max_age_days: number;
+  discard_audio: boolean;
+  activity_max_age_days: number;
+  modes: Record<string, ModeRetention>;
+}
+
+export interface RetentionReport {
+  dry_run: boolean;
+  sessions: {
+    id: string;
+    title: string | null;
+    created_at_ms: number;
+    mode: string | null;
+    reason: "expired" | "over_limit";
+    audio_path: string | null;
+  }[];
+  audio: { session_id: string; path: string }[];
+  activities: number;
+  audio_bytes: number;
+}
+
+export function useRetention() {
+  const [settings, setSettings] = useState<RetentionSettings | null>(null);
+  const [report, setReport] = useState<RetentionReport | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let mounted = true;
+    const unlisteners: (() => void)[] = [];
+
+    invoke<RetentionSettings>("plugin:gibberish-stt|get_retention_settings")
+      .then((s) => mounted && setSettings(s))
+      .catch((err) => console.error("Failed to load retention settings:", err));
+
+    listen<SettingsChangedEvent>("settings:changed", (event) => {
+      if (!mounted || event.payload.key !== "retention") return;
+      setSettings(event.payload.value as RetentionSettings);
+      setReport(null);
+    }).then((un) => {
+      if (mounted) unlisteners.push(un);
+      else un();
+    });
+
+    listen<RetentionReport>("storage:retention_applied", (event) => {
+      if (mounted) setReport(event.payload);
+    }).then((un) => {
+      if (mounted) unlisteners.push(un);
+      else un();
+    });
+
+    return () => {
+      mounted = false;
+      unlisteners.forEach((u) => u());
+    };
+  }, []);
+
+  const update = useCallback(
+    async (updates: Partial<RetentionSettings>) => {
+      if (!settings) return;
+      setError(null);
+      try {
+        const next = { ...settings, ...updates };
+        await invoke("plugin:gibberish-stt|set_retention_settings", { settings: next });
+        setSettings(next);
+        setReport(null);
+      } catch (err) {
+        console.error("Failed to save retention settings:", err);
+        setError(String(err));
+      }
+    },
+    [settings]
+  );
+
+  const run = useCallback(async (dryRun: boolean) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      const command = dryRun ? "preview_retention" : "apply_retention";
+      setReport(await invoke<RetentionReport>(`plugin:gibberish-stt|${command}`));
+    } catch (err) {
+      console.error("Failed to run retention:", err);
+      setError(String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  }, []);
+
+  const preview = useCallback(() => run(true), [run]);
+  const apply = useCallback(() => run(false), [run]);
+
+  return { settings, report, isBusy, error, update, preview, apply };
+}
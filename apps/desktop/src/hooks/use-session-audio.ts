@@ -0,0 +1,77 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+/** Part of a session recording. */
+export type AudioSpan =
+  | { kind: "range"; start_ms: number; end_ms: number }
+  | { kind: "segment"; segment_id: string }
+  | { kind: "word"; segment_id: string; word: number };
+
+function spanKey(span: AudioSpan): string {
+  switch (span.kind) {
+    case "range":
+      return `range-${span.start_ms}-${span.end_ms}`;
+    case "segment":
+      return `segment-${span.segment_id}`;
+    case "word":
+      return `word-${span.segment_id}-${span.word}`;
+  }
+}
+
+/** Plays spans of a saved session's recording, one at a time. */
+export function useSessionAudio(sessionId: string) {
+  const [playing, setPlaying] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const audioRef = useRef<HTMLAudioElement | null>(null);
+  const urlRef = useRef<string | null>(null);
+
+  const stop = useCallback(() => {
+    audioRef.current?.pause();
+    audioRef.current = null;
+    if (urlRef.current) {
+      URL.revokeObjectURL(urlRef.current);
+      urlRef.current = null;
+    }
+    setPlaying(null);
+  }, []);
+
+  useEffect(() => stop, [sessionId, stop]);
+
+  const play = useCallback(
+    async (span: AudioSpan) => {
+      const key = spanKey(span);
+      stop();
+      setError(null);
+      setPlaying(key);
+      try {
+        const wav = await invoke<ArrayBuffer>("plugin:gibberish-stt|get_session_audio", {
+          id: sessionId,
+          span,
+        });
+        const url = URL.createObjectURL(new Blob([wav], { type: "audio/wav" }));
+        const audio = new Audio(url);
+        audioRef.current = audio;
+        urlRef.current = url;
+        audio.onended = () => {
+          if (audioRef.current === audio) stop();
+        };
+        await audio.play();
+      } catch (err) {
+        console.error("Failed to play session audio:", err);
+        setError(String(err));
+        stop();
+      }
+    },
+    [sessionId, stop]
+  );
+
+  /** Play `span`, or stop it when it is already playing. */
+  const toggle = useCallback(
+    (span: AudioSpan) => (playing === spanKey(span) ? stop() : play(span)),
+    [playing, play, stop]
+  );
+
+  const isPlaying = useCallback((span: AudioSpan) => playing === spanKey(span), [playing]);
+
+  return { play, stop, toggle, isPlaying, error };
+}
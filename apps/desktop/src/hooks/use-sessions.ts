@@ -4,6 +4,10 @@ import {
   useSessionsStore,
   type SessionSummary,
   type Session,
+  type SessionMarker,
+  type SessionDigest,
+  type SessionChapter,
+  type MarkerKind,
 } from "../stores/sessions-store";
 
 interface SessionSummaryDto {
@@ -23,12 +27,90 @@ interface SessionDto {
   segments: {
     id: string;
     text: string;
+    raw_text?: string | null;
     start_ms: number;
     end_ms: number;
     speaker: number | null;
   }[];
+  speakers: { id: number; name: string }[];
+  revision: number;
+  can_undo: boolean;
+  can_redo: boolean;
+  markers: SessionMarker[];
+  chapters: SessionChapter[];
+  digest: SessionDigest | null;
+  has_audio: boolean;
 }
 
+/** Marker found by `queryMarkers`, with its session. */
+export interface SessionMarkerResult {
+  session_id: string;
+  session_title: string | null;
+  /** When the marker was placed (Unix ms). */
+  occurred_at_ms: number;
+  marker: SessionMarker;
+}
+
+export interface MarkerQuery {
+  kinds?: MarkerKind[];
+  open_only?: boolean;
+  since_ms?: number;
+  until_ms?: number;
+  session_id?: string;
+  limit?: number;
+}
+
+/**
+ * Full-text query: `"phrases"`, `prefix*`, `AND`/`OR`/`NOT` and parentheses,
+ * with optional filters.
+ */
+export interface SearchQuery {
+  text: string;
+  since_ms?: number;
+  until_ms?: number;
+  mode?: string;
+  speaker?: number;
+  session_id?: string;
+  limit?: number;
+}
+
+/** Segment (or title, when `segment_id` is null) matching a search. */
+export interface SearchHit {
+  session_id: string;
+  session_title: string | null;
+  created_at: number;
+  duration_ms: number;
+  segment_id: string | null;
+  start_ms: number | null;
+  speaker: number | null;
+  /** Matched terms are wrapped in `<mark>`...`</mark>`. */
+  snippet: string;
+  score: number;
+}
+
+/** A marker to save with a session; the id and span are filled in. */
+export type NewMarker = Pick<SessionMarker, "kind" | "timestamp_ms"> &
+  Partial<Pick<SessionMarker, "note" | "author">>;
+
+/** Word boundary in a segment: before word `word` (0-based). */
+export interface WordPosition {
+  segment_id: string;
+  word: number;
+}
+
+export type SessionEdit =
+  | { op: "edit_text"; segment_id: string; text: string }
+  | { op: "split"; segment_id: string; at_word: number }
+  | { op: "merge"; segment_id: string }
+  | { op: "set_speaker"; segment_ids: string[]; speaker: number | null }
+  | { op: "delete_range"; start: WordPosition; end: WordPosition };
+
+export type SessionRevision = { number: number; created_at: number } & (
+  | { action: "edit"; edit: SessionEdit }
+  | { action: "undo" }
+  | { action: "redo" }
+);
+
 function mapSessionSummary(dto: SessionSummaryDto): SessionSummary {
   return {
     id: dto.id,
@@ -49,10 +131,19 @@ function mapSession(dto: SessionDto): Session {
     segments: dto.segments.map((s) => ({
       id: s.id,
       text: s.text,
+      rawText: s.raw_text ?? null,
       startMs: s.start_ms,
       endMs: s.end_ms,
       speaker: s.speaker,
     })),
+    speakers: dto.speakers,
+    revision: dto.revision,
+    canUndo: dto.can_undo,
+    canRedo: dto.can_redo,
+    markers: dto.markers,
+    chapters: dto.chapters,
+    digest: dto.digest,
+    hasAudio: dto.has_audio,
   };
 }
 
@@ -68,6 +159,7 @@ export function useSessions() {
     setSearchQuery,
     removeSession,
     updateSessionTitle,
+    updateSpeakerName,
   } = useSessionsStore();
 
   const loadSessions = useCallback(async () => {
@@ -149,11 +241,227 @@ export function useSessions() {
     [updateSessionTitle]
   );
 
+  const renameSpeaker = useCallback(
+    async (id: string, speaker: number, name: string) => {
+      try {
+        await invoke("plugin:gibberish-stt|rename_speaker", { id, speaker, name });
+        updateSpeakerName(id, speaker, name.trim() || `Speaker ${speaker + 1}`);
+      } catch (err) {
+        console.error("Failed to rename speaker:", err);
+      }
+    },
+    [updateSpeakerName]
+  );
+
+  const editSession = useCallback(
+    async (id: string, edit: SessionEdit) => {
+      try {
+        const result = await invoke<SessionDto>("plugin:gibberish-stt|edit_session", {
+          id,
+          edit,
+        });
+        setCurrentSession(mapSession(result));
+        return true;
+      } catch (err) {
+        console.error("Failed to edit session:", err);
+        return false;
+      }
+    },
+    [setCurrentSession]
+  );
+
+  const undoSessionEdit = useCallback(
+    async (id: string) => {
+      try {
+        const result = await invoke<SessionDto>(
+          "plugin:gibberish-stt|undo_session_edit",
+          { id }
+        );
+        setCurrentSession(mapSession(result));
+      } catch (err) {
+        console.error("Failed to undo session edit:", err);
+      }
+    },
+    [setCurrentSession]
+  );
+
+  const redoSessionEdit = useCallback(
+    async (id: string) => {
+      try {
+        const result = await invoke<SessionDto>(
+          "plugin:gibberish-stt|redo_session_edit",
+          { id }
+        );
+        setCurrentSession(mapSession(result));
+      } catch (err) {
+        console.error("Failed to redo session edit:", err);
+      }
+    },
+    [setCurrentSession]
+  );
+
+  const getSessionRevisions = useCallback(async (id: string) => {
+    try {
+      return await invoke<SessionRevision[]>(
+        "plugin:gibberish-stt|get_session_revisions",
+        { id }
+      );
+    } catch (err) {
+      console.error("Failed to load session revisions:", err);
+      return [];
+    }
+  }, []);
+
+  const getSessionAtRevision = useCallback(async (id: string, revision: number) => {
+    try {
+      const result = await invoke<SessionDto>(
+        "plugin:gibberish-stt|get_session_at_revision",
+        { id, revision }
+      );
+      return mapSession(result);
+    } catch (err) {
+      console.error("Failed to load session revision:", err);
+      return null;
+    }
+  }, []);
+
+  const addSessionMarker = useCallback(
+    async (id: string, marker: NewMarker) => {
+      try {
+        return await invoke<SessionMarker>("plugin:gibberish-stt|add_session_marker", {
+          id,
+          marker,
+        });
+      } catch (err) {
+        console.error("Failed to add marker:", err);
+        return null;
+      }
+    },
+    []
+  );
+
+  const setSessionMarkerDone = useCallback(
+    async (id: string, markerId: string, done: boolean) => {
+      try {
+        await invoke("plugin:gibberish-stt|set_session_marker_done", {
+          id,
+          markerId,
+          done,
+        });
+      } catch (err) {
+        console.error("Failed to update marker:", err);
+      }
+    },
+    []
+  );
+
+  const deleteSessionMarker = useCallback(async (id: string, markerId: string) => {
+    try {
+      await invoke("plugin:gibberish-stt|delete_session_marker", { id, markerId });
+    } catch (err) {
+      console.error("Failed to delete marker:", err);
+    }
+  }, []);
+
+  const searchTranscripts = useCallback(async (query: SearchQuery) => {
+    try {
+      return await invoke<SearchHit[]>("plugin:gibberish-stt|search_transcripts", { query });
+    } catch (err) {
+      console.error("Failed to search transcripts:", err);
+      return [];
+    }
+  }, []);
+
+  const queryMarkers = useCallback(async (query: MarkerQuery) => {
+    try {
+      return await invoke<SessionMarkerResult[]>("plugin:gibberish-stt|query_markers", {
+        query,
+      });
+    } catch (err) {
+      console.error("Failed to query markers:", err);
+      return [];
+    }
+  }, []);
+
+  const generateSessionDigest = useCallback(
+    async (id: string, summarySentences?: number) => {
+      try {
+        return await invoke<SessionDigest>("plugin:gibberish-stt|generate_session_digest", {
+          id,
+          options: summarySentences ? { summary_sentences: summarySentences } : null,
+        });
+      } catch (err) {
+        console.error("Failed to generate digest:", err);
+        return null;
+      }
+    },
+    []
+  );
+
+  const generateSessionChapters = useCallback(async (id: string) => {
+    try {
+      return await invoke<SessionChapter[]>("plugin:gibberish-stt|generate_session_chapters", {
+        id,
+        options: null,
+      });
+    } catch (err) {
+      console.error("Failed to generate chapters:", err);
+      return [];
+    }
+  }, []);
+
+  const exportSession = useCallback(
+    async (
+      id: string,
+      format: "srt" | "vtt" | "json" | "markdown" | "text",
+      options?: { maxLineChars?: number; maxCueMs?: number }
+    ) => {
+      try {
+        const result = await invoke<{ content: string; file_name: string }>(
+          "plugin:gibberish-stt|export_session",
+          {
+            id,
+            format,
+            options: options
+              ? { max_line_chars: options.maxLineChars, max_cue_ms: options.maxCueMs }
+              : null,
+          }
+        );
+        return { content: result.content, fileName: result.file_name };
+      } catch (err) {
+        console.error("Failed to export session:", err);
+        return null;
+      }
+    },
+    []
+  );
+
+  const importSession = useCallback(
+    async (path: string, title?: string) => {
+      try {
+        const id = await invoke<string>("plugin:gibberish-stt|import_session", {
+          path,
+          title: title ?? null,
+        });
+        await loadSessions();
+        return id;
+      } catch (err) {
+        console.error("Failed to import session:", err);
+        return null;
+      }
+    },
+    [loadSessions]
+  );
+
   const saveSession = useCallback(
     async (
       segments: { id: string; text: string; startMs: number; endMs: number; speaker?: number }[],
       durationMs: number,
-      title?: string
+      title?: string,
+      audioPath?: string,
+      markers?: NewMarker[],
+      mode?: string,
+      titleContext?: string
     ) => {
       try {
         const id = await invoke<string>("plugin:gibberish-stt|save_session", {
@@ -166,6 +474,10 @@ export function useSessions() {
           })),
           durationMs,
           title: title ?? null,
+          audioPath: audioPath ?? null,
+          markers: markers ?? null,
+          mode: mode ?? null,
+          titleContext: titleContext ?? null,
         });
         await loadSessions();
         return id;
@@ -192,9 +504,24 @@ export function useSessions() {
     searchQuery,
     loadSessions,
     searchSessions,
+    searchTranscripts,
     loadSession,
     deleteSession,
     renameSession,
+    renameSpeaker,
+    editSession,
+    undoSessionEdit,
+    redoSessionEdit,
+    getSessionRevisions,
+    getSessionAtRevision,
+    addSessionMarker,
+    setSessionMarkerDone,
+    deleteSessionMarker,
+    queryMarkers,
+    generateSessionDigest,
+    generateSessionChapters,
+    exportSession,
+    importSession,
     saveSession,
     clearCurrentSession,
   };
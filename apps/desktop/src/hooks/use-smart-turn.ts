@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import type { SettingsChangedEvent } from "./use-stt";
 
 export interface TurnModelInfo {
   name: string;
@@ -95,6 +96,14 @@ export function useSmartTurn() {
       else un();
     });
 
+    listen<SettingsChangedEvent>("settings:changed", (event) => {
+      if (!mounted || event.payload.key !== "turn") return;
+      setSettings(event.payload.value as TurnSettings);
+    }).then((un) => {
+      if (mounted) unlisteners.push(un);
+      else un();
+    });
+
     return () => {
       mounted = false;
       unlisteners.forEach((u) => u());
@@ -21,6 +21,12 @@ export interface TranscriptSegment {
 
 export type Language = "auto" | "en" | "es" | "ca";
 
+/** A stored setting changed, from the app or from an edit to settings.toml. */
+export interface SettingsChangedEvent {
+  key: string;
+  value: unknown;
+}
+
 export function useStt() {
   const [models, setModels] = useState<ModelInfo[]>([]);
   const [currentModel, setCurrentModel] = useState<string | null>(null);
@@ -113,6 +119,7 @@ export function useStt() {
     init();
 
     let unlisten: (() => void) | null = null;
+    let unlistenSettings: (() => void) | null = null;
 
     listen<[string, number]>("stt:download-progress", (event) => {
       if (mounted) {
@@ -127,11 +134,26 @@ export function useStt() {
       }
     });
 
+    listen<SettingsChangedEvent>("settings:changed", (event) => {
+      if (mounted && event.payload.key === "language") {
+        setLanguageState(event.payload.value as Language);
+      }
+    }).then((fn) => {
+      if (mounted) {
+        unlistenSettings = fn;
+      } else {
+        fn();
+      }
+    });
+
     return () => {
       mounted = false;
       if (unlisten) {
         unlisten();
       }
+      if (unlistenSettings) {
+        unlistenSettings();
+      }
     };
   }, [refreshModels, getCurrentModel, getLanguage, autoLoadLastModel]);
 
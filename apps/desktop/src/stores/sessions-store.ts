@@ -15,11 +15,72 @@ export interface Session {
   updatedAt: number;
   durationMs: number;
   segments: SessionSegment[];
+  speakers: SessionSpeaker[];
+  /** Latest revision of the edit log (0 when never edited). */
+  revision: number;
+  canUndo: boolean;
+  canRedo: boolean;
+  markers: SessionMarker[];
+  /** Topic chapters in timeline order. */
+  chapters: SessionChapter[];
+  digest: SessionDigest | null;
+  /** A recording is attached, so segments can be played back. */
+  hasAudio: boolean;
+}
+
+export interface SessionChapter {
+  start_ms: number;
+  end_ms: number;
+  title: string;
+  keywords: string[];
+}
+
+/** Offline meeting digest. Cue items are candidates found by phrasing. */
+export interface SessionDigest {
+  summary: { text: string; start_ms: number; speaker: number | null }[];
+  action_items: DigestItem[];
+  decisions: DigestItem[];
+  participants: { speaker: number; name: string; talk_time_ms: number }[];
+  generated_at: string;
+}
+
+export interface DigestItem {
+  text: string;
+  start_ms: number;
+  speaker: number | null;
+  source: "marker" | "cue";
+  marker_id?: string;
+}
+
+export type MarkerKind =
+  | "action_item"
+  | "decision"
+  | "question"
+  | "important"
+  | "follow_up"
+  | "bookmark";
+
+export interface SessionMarker {
+  id: string;
+  kind: MarkerKind;
+  /** Position on the session timeline (ms from the start of the recording). */
+  timestamp_ms: number;
+  span?: { start_ms: number; end_ms: number; text: string };
+  note?: string;
+  author?: string;
+  done: boolean;
+}
+
+export interface SessionSpeaker {
+  id: number;
+  name: string;
 }
 
 export interface SessionSegment {
   id: string;
   text: string;
+  /** Spoken-form text, when inverse text normalization changed it. */
+  rawText: string | null;
   startMs: number;
   endMs: number;
   speaker: number | null;
@@ -30,6 +91,8 @@ interface SessionsState {
   currentSession: Session | null;
   isLoading: boolean;
   searchQuery: string;
+  /** Digest of the meeting that was just saved. */
+  latestDigest: { sessionId: string; digest: SessionDigest } | null;
 
   setSessions: (sessions: SessionSummary[]) => void;
   setCurrentSession: (session: Session | null) => void;
@@ -37,6 +100,8 @@ interface SessionsState {
   setSearchQuery: (query: string) => void;
   removeSession: (id: string) => void;
   updateSessionTitle: (id: string, title: string) => void;
+  updateSpeakerName: (id: string, speaker: number, name: string) => void;
+  setLatestDigest: (latest: { sessionId: string; digest: SessionDigest } | null) => void;
 }
 
 export const useSessionsStore = create<SessionsState>((set) => ({
@@ -44,6 +109,7 @@ export const useSessionsStore = create<SessionsState>((set) => ({
   currentSession: null,
   isLoading: false,
   searchQuery: "",
+  latestDigest: null,
 
   setSessions: (sessions) => set({ sessions }),
   setCurrentSession: (session) => set({ currentSession: session }),
@@ -65,4 +131,17 @@ export const useSessionsStore = create<SessionsState>((set) => ({
           ? { ...state.currentSession, title }
           : state.currentSession,
     })),
+  updateSpeakerName: (id, speaker, name) =>
+    set((state) => ({
+      currentSession:
+        state.currentSession?.id === id
+          ? {
+              ...state.currentSession,
+              speakers: state.currentSession.speakers.map((s) =>
+                s.id === speaker ? { ...s, name } : s
+              ),
+            }
+          : state.currentSession,
+    })),
+  setLatestDigest: (latest) => set({ latestDigest: latest }),
 }));
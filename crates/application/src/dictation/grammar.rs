@@ -0,0 +1,151 @@
+//! Spoken command phrases per language.
+
+/// A dictation command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Command {
+    NewLine,
+    NewParagraph,
+    /// Insert a punctuation mark.
+    Mark(&'static str),
+    ScratchThat,
+    DeleteLastWord,
+    SelectLastSentence,
+    CapitalizeThat,
+    Undo,
+}
+
+impl Command {
+    /// Edits act on text already typed, so they are only recognized when
+    /// spoken as a whole utterance. Formatting commands may also close or
+    /// open one.
+    pub fn is_edit(self) -> bool {
+        !matches!(self, Self::NewLine | Self::NewParagraph | Self::Mark(_))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Grammar {
+    phrases: &'static [(&'static str, Command)],
+    /// Words after which a formatting phrase is read literally ("a
+    /// period of time", "a new line of products").
+    literal_after: &'static [&'static str],
+}
+
+const EN: Grammar = Grammar {
+    phrases: &[
+        ("new line", Command::NewLine),
+        ("newline", Command::NewLine),
+        ("new paragraph", Command::NewParagraph),
+        ("period", Command::Mark(".")),
+        ("full stop", Command::Mark(".")),
+        ("comma", Command::Mark(",")),
+        ("question mark", Command::Mark("?")),
+        ("exclamation mark", Command::Mark("!")),
+        ("exclamation point", Command::Mark("!")),
+        ("scratch that", Command::ScratchThat),
+        ("delete last word", Command::DeleteLastWord),
+        ("select last sentence", Command::SelectLastSentence),
+        ("capitalize that", Command::CapitalizeThat),
+        ("undo", Command::Undo),
+        ("undo that", Command::Undo),
+    ],
+    literal_after: &[
+        "a", "an", "the", "this", "that", "each", "every", "one", "per", "my", "your", "his",
+        "her", "its", "our", "their", "no", "another",
+    ],
+};
+
+const ES: Grammar = Grammar {
+    phrases: &[
+        ("nueva línea", Command::NewLine),
+        ("nueva linea", Command::NewLine),
+        ("nuevo párrafo", Command::NewParagraph),
+        ("nuevo parrafo", Command::NewParagraph),
+        ("punto", Command::Mark(".")),
+        ("coma", Command::Mark(",")),
+        ("signo de interrogación", Command::Mark("?")),
+        ("signo de exclamación", Command::Mark("!")),
+        ("borra eso", Command::ScratchThat),
+        ("borrar eso", Command::ScratchThat),
+        ("borra la última palabra", Command::DeleteLastWord),
+        ("selecciona la última frase", Command::SelectLastSentence),
+        ("mayúscula eso", Command::CapitalizeThat),
+        ("deshacer", Command::Undo),
+        ("deshaz", Command::Undo),
+    ],
+    literal_after: &[
+        "un", "una", "el", "la", "los", "las", "este", "esta", "ese", "esa", "cada", "mi", "tu",
+        "su", "otro", "otra", "del", "al",
+    ],
+};
+
+const CA: Grammar = Grammar {
+    phrases: &[
+        ("nova línia", Command::NewLine),
+        ("nou paràgraf", Command::NewParagraph),
+        ("punt", Command::Mark(".")),
+        ("coma", Command::Mark(",")),
+        ("interrogant", Command::Mark("?")),
+        ("signe d'exclamació", Command::Mark("!")),
+        ("esborra això", Command::ScratchThat),
+        ("esborra l'última paraula", Command::DeleteLastWord),
+        ("selecciona l'última frase", Command::SelectLastSentence),
+        ("majúscula això", Command::CapitalizeThat),
+        ("desfés", Command::Undo),
+    ],
+    literal_after: &[
+        "un", "una", "el", "la", "els", "les", "aquest", "aquesta", "aquell", "aquella", "cada",
+        "meu", "meva", "seu", "seva", "altre", "altra", "del", "al",
+    ],
+};
+
+impl Grammar {
+    /// Grammar for a language code. Unknown codes (and "auto") use the
+    /// English phrases.
+    pub fn for_language(language: &str) -> &'static Self {
+        let base = language.split(['-', '_']).next().unwrap_or_default();
+        match base.to_ascii_lowercase().as_str() {
+            "es" => &ES,
+            "ca" => &CA,
+            _ => &EN,
+        }
+    }
+
+    /// The longest phrase starting at `keys[start]`.
+    pub fn match_at(&self, keys: &[String], start: usize) -> Option<(Command, usize)> {
+        self.longest(|phrase| {
+            let n = phrase.len();
+            keys.get(start..start + n)
+                .is_some_and(|words| words.iter().zip(phrase).all(|(a, b)| a == b))
+        })
+    }
+
+    /// The longest phrase ending right before `keys[end]`.
+    pub fn match_ending_at(&self, keys: &[String], end: usize) -> Option<(Command, usize)> {
+        self.longest(|phrase| {
+            let n = phrase.len();
+            n <= end && keys[end - n..end].iter().zip(phrase).all(|(a, b)| a == b)
+        })
+    }
+
+    pub fn is_literal_after(&self, key: &str) -> bool {
+        self.literal_after.contains(&key)
+    }
+
+    fn longest(&self, matches: impl Fn(&[&str]) -> bool) -> Option<(Command, usize)> {
+        self.phrases
+            .iter()
+            .filter_map(|(phrase, command)| {
+                let words: Vec<&str> = phrase.split(' ').collect();
+                matches(&words).then_some((*command, words.len()))
+            })
+            .max_by_key(|(_, len)| *len)
+    }
+}
+
+/// Lowercase a word and strip the punctuation a punctuation stage may have
+/// attached ("Period." matches "period").
+pub(crate) fn key(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
@@ -0,0 +1,229 @@
+//! Type-as-you-speak dictation.
+//!
+//! Commits go through the [`DictationEditor`]; the volatile words that
+//! follow them are typed provisionally and corrected in place as the
+//! hypothesis changes, with the fewest backspaces the change allows.
+
+use super::{common_prefix, DictationEdit, DictationEditor};
+use crate::streaming::{CommitContext, WordEvent};
+
+/// Live dictation state for one focused text field.
+///
+/// Fed the commits and word events of a stream, in the order they were
+/// emitted, and returns the key sequences that keep the field in sync.
+#[derive(Debug)]
+pub struct LiveDictation {
+    editor: DictationEditor,
+    /// Volatile words, in transcript order.
+    volatile: Vec<(u64, String)>,
+    /// Provisional text typed after the editor's text.
+    tail: String,
+    type_tail: bool,
+}
+
+impl LiveDictation {
+    /// Create a session for a language code (see [`DictationEditor::new`]).
+    ///
+    /// With `type_tail` off only commits are typed, for fields where
+    /// corrections are slow or disruptive.
+    pub fn new(language: &str, type_tail: bool) -> Self {
+        Self {
+            editor: DictationEditor::new(language),
+            volatile: Vec::new(),
+            tail: String::new(),
+            type_tail,
+        }
+    }
+
+    /// Text typed this session, including the provisional tail.
+    pub fn text(&self) -> String {
+        format!("{}{}", self.editor.text(), self.tail)
+    }
+
+    /// Forget the session (e.g. when focus moves to another field).
+    pub fn reset(&mut self) {
+        self.editor.reset();
+        self.volatile.clear();
+        self.tail.clear();
+    }
+
+    /// Replace the provisional tail with a committed delta.
+    ///
+    /// When the commit is typed as text, the part of the tail it starts
+    /// with is kept rather than retyped.
+    pub fn commit(&mut self, delta: &str, context: CommitContext) -> Vec<DictationEdit> {
+        let tail = std::mem::take(&mut self.tail);
+        let mut edits = self.editor.process(delta, context).into_iter();
+        let mut out = Vec::new();
+
+        match edits.next() {
+            Some(DictationEdit::Type(text)) => {
+                let common = common_prefix(&tail, &text);
+                push(
+                    &mut out,
+                    DictationEdit::Backspace(tail[common..].chars().count()),
+                );
+                push(&mut out, DictationEdit::Type(text[common..].to_string()));
+            }
+            first => {
+                push(&mut out, DictationEdit::Backspace(tail.chars().count()));
+                if let Some(edit) = first {
+                    push(&mut out, edit);
+                }
+            }
+        }
+        for edit in edits {
+            push(&mut out, edit);
+        }
+        out
+    }
+
+    /// Apply word events and retype the tail where it changed.
+    pub fn apply_word_events(&mut self, events: &[WordEvent]) -> Vec<DictationEdit> {
+        for event in events {
+            match event {
+                WordEvent::Inserted {
+                    id, after, text, ..
+                } => {
+                    let position = after
+                        .and_then(|after| self.volatile.iter().position(|(id, _)| *id == after))
+                        .map_or(0, |i| i + 1);
+                    self.volatile.insert(position, (*id, text.clone()));
+                }
+                WordEvent::Revised { id, text, .. } => {
+                    if let Some(word) = self.volatile.iter_mut().find(|(w, _)| w == id) {
+                        word.1 = text.clone();
+                    }
+                }
+                WordEvent::Removed { id } | WordEvent::Committed { id, .. } => {
+                    self.volatile.retain(|(w, _)| w != id);
+                }
+                WordEvent::ParagraphBreak => {}
+            }
+        }
+        self.retype_tail()
+    }
+
+    fn retype_tail(&mut self) -> Vec<DictationEdit> {
+        // Typing over a selection would replace it.
+        let target = if self.type_tail && self.editor.selection == 0 && !self.volatile.is_empty() {
+            let words: Vec<&str> = self
+                .volatile
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect();
+            self.editor.format_next(&words.join(" "))
+        } else {
+            String::new()
+        };
+
+        let common = common_prefix(&self.tail, &target);
+        let mut out = Vec::new();
+        push(
+            &mut out,
+            DictationEdit::Backspace(self.tail[common..].chars().count()),
+        );
+        push(&mut out, DictationEdit::Type(target[common..].to_string()));
+        self.tail = target;
+        out
+    }
+}
+
+/// Append an edit, dropping empty ones and merging repeats.
+fn push(edits: &mut Vec<DictationEdit>, edit: DictationEdit) {
+    match (edits.last_mut(), edit) {
+        (_, DictationEdit::Backspace(0)) => {}
+        (_, DictationEdit::Type(text)) if text.is_empty() => {}
+        (Some(DictationEdit::Backspace(last)), DictationEdit::Backspace(count)) => *last += count,
+        (Some(DictationEdit::Type(last)), DictationEdit::Type(text)) => last.push_str(&text),
+        (_, edit) => edits.push(edit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DictationEdit::*;
+
+    const SPOKEN: CommitContext = CommitContext {
+        after_pause: true,
+        at_pause: true,
+        turn_end: false,
+    };
+
+    fn inserted(id: u64, after: Option<u64>, text: &str) -> WordEvent {
+        WordEvent::Inserted {
+            id,
+            after,
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 0,
+        }
+    }
+
+    fn committed(id: u64) -> WordEvent {
+        WordEvent::Committed {
+            id,
+            text: String::new(),
+            start_ms: 0,
+            end_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_tail_is_corrected_in_place() {
+        let mut live = LiveDictation::new("en", true);
+        let edits =
+            live.apply_word_events(&[inserted(0, None, "hello"), inserted(1, Some(0), "word")]);
+        assert_eq!(edits, vec![Type("hello word".to_string())]);
+
+        let edits = live.apply_word_events(&[WordEvent::Revised {
+            id: 1,
+            text: "world".to_string(),
+            start_ms: 0,
+            end_ms: 0,
+        }]);
+        assert_eq!(edits, vec![Backspace(1), Type("ld".to_string())]);
+        assert_eq!(live.text(), "hello world");
+    }
+
+    #[test]
+    fn test_commit_keeps_matching_tail() {
+        let mut live = LiveDictation::new("en", true);
+        live.apply_word_events(&[inserted(0, None, "hello"), inserted(1, Some(0), "world")]);
+
+        assert_eq!(
+            live.commit("hello world.", SPOKEN),
+            vec![Type(".".to_string())]
+        );
+        assert!(live
+            .apply_word_events(&[committed(0), committed(1)])
+            .is_empty());
+
+        live.apply_word_events(&[inserted(2, None, "next")]);
+        assert_eq!(live.text(), "hello world. Next");
+    }
+
+    #[test]
+    fn test_command_erases_tail() {
+        let mut live = LiveDictation::new("en", true);
+        live.commit("hello there", SPOKEN);
+        live.apply_word_events(&[inserted(0, None, "scratch"), inserted(1, Some(0), "that")]);
+        assert_eq!(live.text(), "hello there scratch that");
+
+        assert_eq!(live.commit("scratch that", SPOKEN), vec![Backspace(24)]);
+        assert_eq!(live.text(), "");
+    }
+
+    #[test]
+    fn test_commits_only_without_tail() {
+        let mut live = LiveDictation::new("en", false);
+        assert!(live
+            .apply_word_events(&[inserted(0, None, "hello")])
+            .is_empty());
+        assert_eq!(
+            live.commit("hello", SPOKEN),
+            vec![Type("hello".to_string())]
+        );
+    }
+}
@@ -0,0 +1,544 @@
+//! Voice editing commands for live dictation.
+//!
+//! Committed deltas pass through a [`DictationEditor`] before they are
+//! typed. Phrases such as "new line", "period" or "scratch that" become
+//! [`DictationEdit`] key sequences instead of text; everything else is
+//! typed as dictated.
+//!
+//! Commands are told apart from literal speech by their pause and turn
+//! context ([`CommitContext`]):
+//! - Edits ("scratch that", "delete last word", "select last sentence",
+//!   "capitalize that", "undo") must be the whole utterance, with a pause
+//!   on both sides.
+//! - Formatting ("new line", "new paragraph", "period", "comma",
+//!   "question mark") may also end an utterance that ends at a pause, or
+//!   start one that follows a pause. Inside a sentence they are text.
+//!
+//! Callers that type only some of what is committed, such as the action
+//! router's typer tool, pass the typed text to [`DictationEditor::process`]
+//! and offer other commits to [`DictationEditor::edit_command`].
+
+mod grammar;
+mod live;
+
+pub use live::LiveDictation;
+
+use crate::streaming::CommitContext;
+use grammar::{key, Command, Grammar};
+
+/// Undo steps kept per session.
+const MAX_HISTORY: usize = 50;
+
+/// Marks that a punctuation command replaces rather than follows.
+const TRAILING_MARKS: &[char] = &['.', ',', ';', ':', '?', '!'];
+
+/// A key sequence step, applied at the cursor in the focused app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DictationEdit {
+    /// Type text, replacing any selection.
+    Type(String),
+    /// Press Backspace this many times.
+    Backspace(usize),
+    /// Press Return this many times.
+    Return(usize),
+    /// Extend the selection this many characters to the left.
+    SelectLeft(usize),
+    /// Collapse the selection to its end.
+    Deselect,
+}
+
+/// Turns committed deltas into key sequences, interpreting voice commands.
+///
+/// Keeps a mirror of the text typed this session, assuming the cursor
+/// stays at its end, so edits know how many characters to delete or
+/// select.
+#[derive(Debug)]
+pub struct DictationEditor {
+    grammar: &'static Grammar,
+    typed: String,
+    /// Characters selected at the end of `typed`.
+    selection: usize,
+    /// Byte offset in `typed` where the last utterance started.
+    last_utterance: Option<usize>,
+    /// `typed` before each utterance that changed it.
+    history: Vec<String>,
+    edits: Vec<DictationEdit>,
+}
+
+impl DictationEditor {
+    /// Create an editor for a language code ("en", "es", "ca").
+    ///
+    /// Other codes, including "auto", use the English commands.
+    pub fn new(language: &str) -> Self {
+        Self {
+            grammar: Grammar::for_language(language),
+            typed: String::new(),
+            selection: 0,
+            last_utterance: None,
+            history: Vec::new(),
+            edits: Vec::new(),
+        }
+    }
+
+    /// Text typed this session, as the editor believes it stands.
+    pub fn text(&self) -> &str {
+        &self.typed
+    }
+
+    /// Forget the session (e.g. when focus moves to another field).
+    pub fn reset(&mut self) {
+        self.typed.clear();
+        self.selection = 0;
+        self.last_utterance = None;
+        self.history.clear();
+    }
+
+    /// Process a committed delta into the edits to send.
+    pub fn process(&mut self, delta: &str, context: CommitContext) -> Vec<DictationEdit> {
+        let words: Vec<&str> = delta.split_whitespace().collect();
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let keys: Vec<String> = words.iter().map(|w| key(w)).collect();
+        let before = self.typed.clone();
+        let bounded = context.at_pause || context.turn_end;
+
+        match self.grammar.match_at(&keys, 0) {
+            Some((command, len)) if command.is_edit() && len == keys.len() => {
+                if context.after_pause && bounded {
+                    self.edit(command);
+                    return self.finish(before, command == Command::Undo);
+                }
+                self.type_words(&words);
+                return self.finish(before, false);
+            }
+            _ => {}
+        }
+
+        let mut start = 0;
+        let mut leading = Vec::new();
+        if context.after_pause {
+            while let Some((command, len)) = self.grammar.match_at(&keys, start) {
+                if command.is_edit() {
+                    break;
+                }
+                leading.push(command);
+                start += len;
+            }
+        }
+
+        let mut end = keys.len();
+        let mut trailing = Vec::new();
+        if bounded {
+            while let Some((command, len)) = self.grammar.match_ending_at(&keys, end) {
+                let literal = end - len > start
+                    && trailing.is_empty()
+                    && self.grammar.is_literal_after(&keys[end - len - 1]);
+                if command.is_edit() || end - len < start || literal {
+                    break;
+                }
+                trailing.push(command);
+                end -= len;
+            }
+            trailing.reverse();
+        }
+
+        self.last_utterance = Some(self.typed.len());
+        for command in leading {
+            self.format(command);
+        }
+        if start < end {
+            let mut text = words[start..end].join(" ");
+            if matches!(trailing.first(), Some(Command::Mark(_))) {
+                text.truncate(text.trim_end_matches(TRAILING_MARKS).len());
+            }
+            self.type_text(&text);
+        }
+        for command in trailing {
+            self.format(command);
+        }
+        self.finish(before, false)
+    }
+
+    /// Apply a committed delta only if it is a standalone edit command
+    /// ("scratch that", "undo", ...) with a pause on both sides.
+    ///
+    /// Returns `None` and leaves the editor untouched otherwise, or when
+    /// nothing was typed yet for the command to act on.
+    pub fn edit_command(
+        &mut self,
+        delta: &str,
+        context: CommitContext,
+    ) -> Option<Vec<DictationEdit>> {
+        let standalone = context.after_pause && (context.at_pause || context.turn_end);
+        if !standalone || (self.typed.is_empty() && self.history.is_empty()) {
+            return None;
+        }
+        let keys: Vec<String> = delta.split_whitespace().map(key).collect();
+        match self.grammar.match_at(&keys, 0) {
+            Some((command, len)) if command.is_edit() && len == keys.len() => {
+                let before = self.typed.clone();
+                self.edit(command);
+                Some(self.finish(before, command == Command::Undo))
+            }
+            _ => None,
+        }
+    }
+
+    /// Record undo history and hand out the queued edits.
+    fn finish(&mut self, before: String, undo: bool) -> Vec<DictationEdit> {
+        if !undo && before != self.typed {
+            if self.history.len() == MAX_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(before);
+        }
+        std::mem::take(&mut self.edits)
+    }
+
+    fn type_words(&mut self, words: &[&str]) {
+        self.last_utterance = Some(self.typed.len());
+        self.type_text(&words.join(" "));
+    }
+
+    /// Type dictated text, spacing and capitalizing it against what
+    /// precedes it.
+    fn type_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.replace_selection();
+        let out = self.format_next(text);
+        self.push(DictationEdit::Type(out.clone()));
+        self.typed.push_str(&out);
+    }
+
+    /// `text` as it would be typed next: spaced and capitalized against
+    /// the text before the cursor.
+    fn format_next(&self, text: &str) -> String {
+        let mut out = String::new();
+        let starts_with_mark = text.starts_with(TRAILING_MARKS);
+        if self
+            .typed
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_whitespace())
+            && !starts_with_mark
+        {
+            out.push(' ');
+        }
+        let sentence_start = self
+            .typed
+            .trim_end_matches(' ')
+            .ends_with(['.', '?', '!', '\n']);
+        if sentence_start {
+            out.push_str(&capitalize(text));
+        } else {
+            out.push_str(text);
+        }
+        out
+    }
+
+    fn format(&mut self, command: Command) {
+        self.deselect();
+        match command {
+            Command::NewLine => self.press_return(1),
+            Command::NewParagraph => self.press_return(2),
+            Command::Mark(mark) => {
+                // A mark the punctuation stage already put there is replaced.
+                if self.typed.ends_with(TRAILING_MARKS) {
+                    self.typed.pop();
+                    self.push(DictationEdit::Backspace(1));
+                }
+                self.typed.push_str(mark);
+                self.push(DictationEdit::Type(mark.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    fn edit(&mut self, command: Command) {
+        match command {
+            Command::ScratchThat => {
+                self.deselect();
+                let start = self.last_utterance.take().unwrap_or(self.typed.len());
+                self.delete_to(start.min(self.typed.len()));
+            }
+            Command::DeleteLastWord => {
+                self.deselect();
+                let trimmed = self.typed.trim_end().len();
+                let word_start = self.typed[..trimmed]
+                    .rfind(char::is_whitespace)
+                    .map_or(0, |i| i + 1);
+                let start = self.typed[..word_start].trim_end_matches(' ').len();
+                self.delete_to(start);
+                self.last_utterance = None;
+            }
+            Command::SelectLastSentence => {
+                self.deselect();
+                let trimmed = self.typed.trim_end();
+                let body = trimmed.trim_end_matches(['.', '?', '!']);
+                let start = body.rfind(['.', '?', '!', '\n']).map_or(0, |i| i + 1);
+                let start = start + (trimmed[start..].len() - trimmed[start..].trim_start().len());
+                let count = self.typed[start..].chars().count();
+                if count > 0 {
+                    self.selection = count;
+                    self.push(DictationEdit::SelectLeft(count));
+                }
+            }
+            Command::CapitalizeThat => {
+                if self.selection > 0 {
+                    let start = self.selection_start();
+                    let selected = self.typed[start..].to_string();
+                    self.typed.truncate(start);
+                    self.selection = 0;
+                    let capitalized = title_case(&selected);
+                    self.typed.push_str(&capitalized);
+                    self.push(DictationEdit::Type(capitalized));
+                } else {
+                    let trimmed = self.typed.trim_end().len();
+                    let start = self.typed[..trimmed]
+                        .rfind(char::is_whitespace)
+                        .map_or(0, |i| i + 1);
+                    let word = self.typed[start..].to_string();
+                    let capitalized = capitalize(&word);
+                    if capitalized != word {
+                        self.delete_to(start);
+                        self.typed.push_str(&capitalized);
+                        self.push(DictationEdit::Type(capitalized));
+                    }
+                }
+                self.last_utterance = None;
+            }
+            Command::Undo => {
+                self.deselect();
+                if let Some(previous) = self.history.pop() {
+                    let common = common_prefix(&self.typed, &previous);
+                    self.delete_to(common);
+                    if common < previous.len() {
+                        self.push(DictationEdit::Type(previous[common..].to_string()));
+                    }
+                    self.typed = previous;
+                }
+                self.last_utterance = None;
+            }
+            Command::NewLine | Command::NewParagraph | Command::Mark(_) => self.format(command),
+        }
+    }
+
+    fn press_return(&mut self, count: usize) {
+        for _ in 0..count {
+            self.typed.push('\n');
+        }
+        self.push(DictationEdit::Return(count));
+    }
+
+    /// Delete from byte offset `start` to the end of the typed text.
+    fn delete_to(&mut self, start: usize) {
+        let count = self.typed[start..].chars().count();
+        if count > 0 {
+            self.typed.truncate(start);
+            self.push(DictationEdit::Backspace(count));
+        }
+    }
+
+    fn selection_start(&self) -> usize {
+        self.typed
+            .char_indices()
+            .rev()
+            .nth(self.selection - 1)
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Typing replaces the selection.
+    fn replace_selection(&mut self) {
+        if self.selection > 0 {
+            let start = self.selection_start();
+            self.typed.truncate(start);
+            self.selection = 0;
+        }
+    }
+
+    fn deselect(&mut self) {
+        if self.selection > 0 {
+            self.selection = 0;
+            self.push(DictationEdit::Deselect);
+        }
+    }
+
+    /// Queue an edit, merging consecutive typing.
+    fn push(&mut self, edit: DictationEdit) {
+        if let (Some(DictationEdit::Type(last)), DictationEdit::Type(text)) =
+            (self.edits.last_mut(), &edit)
+        {
+            last.push_str(text);
+            return;
+        }
+        self.edits.push(edit);
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn title_case(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for c in text.chars() {
+        if at_word_start {
+            out.extend(c.to_uppercase());
+        } else {
+            out.push(c);
+        }
+        at_word_start = c.is_whitespace();
+    }
+    out
+}
+
+/// Length in bytes of the common prefix, on a char boundary.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DictationEdit::*;
+
+    const SPOKEN: CommitContext = CommitContext {
+        after_pause: true,
+        at_pause: true,
+        turn_end: false,
+    };
+    const MID: CommitContext = CommitContext {
+        after_pause: false,
+        at_pause: false,
+        turn_end: false,
+    };
+
+    fn typed(text: &str) -> DictationEdit {
+        Type(text.to_string())
+    }
+
+    #[test]
+    fn test_formatting_commands_close_an_utterance() {
+        let mut editor = DictationEditor::new("en");
+        assert_eq!(
+            editor.process("hello world period", SPOKEN),
+            vec![typed("hello world.")]
+        );
+        assert_eq!(
+            editor.process("How are you? Question mark. New paragraph.", SPOKEN),
+            vec![typed(" How are you?"), Return(2)]
+        );
+        assert_eq!(editor.process("thanks", SPOKEN), vec![typed("Thanks")]);
+        assert_eq!(editor.text(), "hello world. How are you?\n\nThanks");
+    }
+
+    #[test]
+    fn test_commands_inside_a_sentence_are_text() {
+        let mut editor = DictationEditor::new("en");
+        editor.process("we need a new line of products", SPOKEN);
+        editor.process("after a period", SPOKEN);
+        editor.process("scratch that", MID);
+        assert_eq!(
+            editor.text(),
+            "we need a new line of products after a period scratch that"
+        );
+    }
+
+    #[test]
+    fn test_scratch_that_and_undo() {
+        let mut editor = DictationEditor::new("en");
+        editor.process("first part", SPOKEN);
+        editor.process("second part", SPOKEN);
+        assert_eq!(editor.process("Scratch that.", SPOKEN), vec![Backspace(12)]);
+        assert_eq!(editor.text(), "first part");
+        assert_eq!(editor.process("undo", SPOKEN), vec![typed(" second part")]);
+        assert_eq!(editor.text(), "first part second part");
+    }
+
+    #[test]
+    fn test_delete_last_word() {
+        let mut editor = DictationEditor::new("en");
+        editor.process("keep this word", SPOKEN);
+        assert_eq!(
+            editor.process("delete last word", SPOKEN),
+            vec![Backspace(5)]
+        );
+        assert_eq!(editor.text(), "keep this");
+    }
+
+    #[test]
+    fn test_select_and_capitalize() {
+        let mut editor = DictationEditor::new("en");
+        editor.process("one. the last sentence", SPOKEN);
+        assert_eq!(
+            editor.process("select last sentence", SPOKEN),
+            vec![SelectLeft(17)]
+        );
+        assert_eq!(
+            editor.process("capitalize that", SPOKEN),
+            vec![typed("The Last Sentence")]
+        );
+        assert_eq!(editor.text(), "one. The Last Sentence");
+
+        editor.process("and paris", SPOKEN);
+        assert_eq!(
+            editor.process("capitalize that", SPOKEN),
+            vec![Backspace(5), typed("Paris")]
+        );
+    }
+
+    #[test]
+    fn test_typing_replaces_selection() {
+        let mut editor = DictationEditor::new("en");
+        editor.process("wrong words", SPOKEN);
+        editor.process("select last sentence", SPOKEN);
+        assert_eq!(
+            editor.process("right words", SPOKEN),
+            vec![typed("right words")]
+        );
+        assert_eq!(editor.text(), "right words");
+    }
+
+    #[test]
+    fn test_edit_command_only_takes_standalone_edits() {
+        let mut editor = DictationEditor::new("en");
+        assert_eq!(editor.edit_command("scratch that", SPOKEN), None);
+
+        editor.process("hello world", SPOKEN);
+        assert_eq!(editor.edit_command("new line", SPOKEN), None);
+        assert_eq!(editor.edit_command("scratch that", MID), None);
+        assert_eq!(editor.edit_command("open safari", SPOKEN), None);
+        assert_eq!(editor.text(), "hello world");
+
+        assert_eq!(
+            editor.edit_command("Scratch that.", SPOKEN),
+            Some(vec![Backspace(11)])
+        );
+        assert_eq!(
+            editor.edit_command("undo", SPOKEN),
+            Some(vec![typed("hello world")])
+        );
+        assert_eq!(editor.text(), "hello world");
+    }
+
+    #[test]
+    fn test_catalan_commands() {
+        let mut editor = DictationEditor::new("ca");
+        editor.process("bon dia punt", SPOKEN);
+        editor.process("nova línia", SPOKEN);
+        editor.process("fins aviat", SPOKEN);
+        assert_eq!(editor.text(), "bon dia.\nFins aviat");
+        assert_eq!(editor.process("esborra això", SPOKEN), vec![Backspace(10)]);
+    }
+}
@@ -1,9 +1,15 @@
 mod constants;
+mod dictation;
 mod streaming;
 mod transcription;
 
 pub use constants::*;
-pub use streaming::{AlignmentResult, StreamingTranscriber, TimedWord};
+pub use dictation::{DictationEdit, DictationEditor, LiveDictation};
+pub use streaming::{
+    AlignmentResult, CommitContext, DecodeStats, ReplayCommit, ReplayReport, RtfController,
+    StreamTrace, StreamingPolicy, StreamingTranscriber, TextWordDiffer, TimedWord, TraceEvent,
+    TracePolicy, VadSettings, WordEvent,
+};
 pub use transcription::{
     StreamingResult, TranscriptSegment, TranscriptionError, TranscriptionService,
 };
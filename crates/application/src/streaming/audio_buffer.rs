@@ -3,7 +3,7 @@
 //! Uses a cursor-based approach with lazy compaction for O(1) logical trim.
 //! The actual memory compaction is deferred until the pending trim exceeds a threshold.
 
-use crate::constants::{COMMIT_THRESHOLD, MAX_BUFFER_SAMPLES, SAMPLE_RATE};
+use crate::constants::SAMPLE_RATE;
 
 /// Threshold for triggering actual memory compaction (16k samples = 1 second).
 const COMPACT_THRESHOLD: usize = 16000;
@@ -34,12 +34,12 @@ impl AudioBuffer {
     /// Add audio samples to the buffer.
     ///
     /// Enforces max buffer cap to bound hypothesis variance. When exceeded,
-    /// the oldest samples are trimmed to keep buffer within MAX_BUFFER_SAMPLES.
-    pub fn push(&mut self, samples: &[f32]) {
+    /// the oldest samples are trimmed to keep buffer within `max_samples`.
+    pub fn push(&mut self, samples: &[f32], max_samples: usize) {
         self.samples.extend_from_slice(samples);
 
         // Enforce max buffer cap to prevent unbounded growth
-        let excess = self.logical_len().saturating_sub(MAX_BUFFER_SAMPLES);
+        let excess = self.logical_len().saturating_sub(max_samples);
         if excess > 0 {
             self.start_cursor += excess;
             self.trimmed_samples_total += excess;
@@ -90,13 +90,19 @@ impl AudioBuffer {
     }
 
     /// Mark that transcription was performed at current buffer length.
-    pub fn mark_transcribed(&mut self) {
+    ///
+    /// Returns the duration (ms) of audio added since the previous mark.
+    pub fn mark_transcribed(&mut self) -> u64 {
+        let new_samples = self
+            .logical_len()
+            .saturating_sub(self.last_transcription_len);
         self.last_transcription_len = self.logical_len();
+        (new_samples as u64 * 1000) / SAMPLE_RATE as u64
     }
 
-    /// Check if buffer exceeds commit threshold.
-    pub fn exceeds_commit_threshold(&self) -> bool {
-        self.logical_len() >= COMMIT_THRESHOLD
+    /// Check if buffer exceeds the commit threshold (in samples).
+    pub fn exceeds_commit_threshold(&self, threshold: usize) -> bool {
+        self.logical_len() >= threshold
     }
 
     /// Trim the buffer from a given absolute timestamp.
@@ -157,17 +163,19 @@ impl AudioBuffer {
 mod tests {
     use super::*;
 
+    const MAX_SAMPLES: usize = SAMPLE_RATE * 5;
+
     #[test]
     fn test_push_and_samples() {
         let mut buffer = AudioBuffer::new();
-        buffer.push(&[1.0, 2.0, 3.0]);
+        buffer.push(&[1.0, 2.0, 3.0], MAX_SAMPLES);
         assert_eq!(buffer.samples(), &[1.0, 2.0, 3.0]);
     }
 
     #[test]
     fn test_duration_calculation() {
         let mut buffer = AudioBuffer::new();
-        buffer.push(&vec![0.0; 16000]); // 1 second at 16kHz
+        buffer.push(&vec![0.0; 16000], MAX_SAMPLES); // 1 second at 16kHz
         assert_eq!(buffer.current_duration_ms(), 1000);
         assert_eq!(buffer.total_duration_ms(), 1000);
     }
@@ -175,7 +183,7 @@ mod tests {
     #[test]
     fn test_trim_updates_offset() {
         let mut buffer = AudioBuffer::new();
-        buffer.push(&vec![0.0; 32000]); // 2 seconds
+        buffer.push(&vec![0.0; 32000], MAX_SAMPLES); // 2 seconds
 
         buffer.trim_from_ms(1000); // Trim first second
 
@@ -187,7 +195,7 @@ mod tests {
     #[test]
     fn test_clear_preserves_total_duration() {
         let mut buffer = AudioBuffer::new();
-        buffer.push(&vec![0.0; 16000]); // 1 second
+        buffer.push(&vec![0.0; 16000], MAX_SAMPLES); // 1 second
 
         buffer.clear();
 
@@ -199,7 +207,7 @@ mod tests {
     #[test]
     fn test_reset_clears_everything() {
         let mut buffer = AudioBuffer::new();
-        buffer.push(&vec![0.0; 16000]);
+        buffer.push(&vec![0.0; 16000], MAX_SAMPLES);
         buffer.trim_from_ms(500);
 
         buffer.reset();
@@ -7,16 +7,22 @@
 //! - Smart turn detection for semantic endpoint prediction
 
 mod audio_buffer;
+mod policy;
+mod replay;
 mod vad_state;
+mod word_events;
 mod word_tracker;
 
-use crate::constants::TRANSCRIBE_THRESHOLD;
-
 pub use audio_buffer::AudioBuffer;
+pub use policy::{DecodeStats, RtfController, StreamingPolicy};
+pub use replay::{ReplayCommit, ReplayReport, StreamTrace, TraceEvent, TracePolicy};
 pub use vad_state::{VadSettings, VadState};
-pub use word_tracker::{AlignmentResult, TimedWord, WordTracker};
+pub use word_events::{TextWordDiffer, WordEvent};
+pub use word_tracker::{AlignmentResult, CommitContext, TimedWord, WordTracker};
 
+use gibberish_punctuation::PunctuationStage;
 use gibberish_turn::TurnPrediction;
+use gibberish_vad::VadEvent;
 
 /// Orchestrates streaming transcription with VAD and word tracking.
 ///
@@ -28,15 +34,22 @@ pub struct StreamingTranscriber {
     buffer: AudioBuffer,
     vad: VadState,
     words: WordTracker,
+    policy: StreamingPolicy,
+    /// Decode interval in effect; differs from the policy's while auto-tuning.
+    decode_interval_ms: u64,
+    /// Adapts the decode interval to the real-time factor, when enabled.
+    auto_tune: Option<RtfController>,
+    /// New audio covered by the last decode.
+    last_decode_audio_ms: u64,
+    /// Timing of the last decode (cleared after read).
+    last_decode: Option<DecodeStats>,
+    /// Hypothesis trace being recorded, if any.
+    trace: Option<StreamTrace>,
 }
 
 impl Default for StreamingTranscriber {
     fn default() -> Self {
-        Self {
-            buffer: AudioBuffer::new(),
-            vad: VadState::new(),
-            words: WordTracker::new(),
-        }
+        Self::with_vad(VadState::new())
     }
 }
 
@@ -45,12 +58,39 @@ impl StreamingTranscriber {
         Self::default()
     }
 
+    fn with_vad(vad: VadState) -> Self {
+        let policy = StreamingPolicy::default();
+        Self {
+            buffer: AudioBuffer::new(),
+            vad,
+            words: WordTracker::new(),
+            policy,
+            decode_interval_ms: policy.decode_interval_ms,
+            auto_tune: None,
+            last_decode_audio_ms: 0,
+            last_decode: None,
+            trace: None,
+        }
+    }
+
     // --- Audio buffer operations ---
 
     /// Add audio samples to the buffer and process VAD.
     pub fn add_samples(&mut self, samples: &[f32]) {
-        self.buffer.push(samples);
-        self.vad.process(samples);
+        self.buffer.push(samples, self.policy.max_buffer_samples());
+        let events = self.vad.process(samples);
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEvent::Audio {
+                ms: policy::samples_to_ms(samples.len()),
+            });
+            for event in events {
+                trace.push(match event {
+                    VadEvent::SpeechStart { .. } => TraceEvent::SpeechStart,
+                    VadEvent::SpeechEnd { .. } => TraceEvent::SpeechEnd,
+                });
+            }
+        }
     }
 
     /// Get the current buffer for transcription.
@@ -82,6 +122,12 @@ impl StreamingTranscriber {
 
     /// Set the turn prediction result.
     pub fn set_turn_prediction(&mut self, prediction: TurnPrediction) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEvent::Turn {
+                probability: prediction.probability,
+                threshold: prediction.threshold,
+            });
+        }
         self.vad.set_turn_prediction(prediction);
     }
 
@@ -113,17 +159,37 @@ impl StreamingTranscriber {
             return true;
         }
 
-        self.buffer.has_new_audio(TRANSCRIBE_THRESHOLD)
+        self.buffer
+            .has_new_audio(policy::ms_to_samples(self.decode_interval_ms))
     }
 
     /// Mark that transcription was performed at current buffer length.
     pub fn mark_transcribed(&mut self) {
-        self.buffer.mark_transcribed();
+        self.last_decode_audio_ms = self.buffer.mark_transcribed();
         self.vad.mark_transcribed();
     }
 
+    /// Record how long the decode just marked took.
+    pub fn record_decode_time(&mut self, inference_ms: u64) {
+        self.last_decode = Some(DecodeStats {
+            inference_ms,
+            audio_ms: self.last_decode_audio_ms,
+        });
+    }
+
+    /// Take the timing of the last decode (consumes it).
+    pub fn take_last_decode(&mut self) -> Option<DecodeStats> {
+        self.last_decode.take()
+    }
+
     /// Update tracked words with new transcription.
     pub fn update_words(&mut self, new_words: &[TimedWord]) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEvent::Decode {
+                buffer_offset_ms: self.buffer.timestamp_offset_ms(),
+                words: new_words.to_vec(),
+            });
+        }
         self.words
             .update(new_words, self.buffer.timestamp_offset_ms());
     }
@@ -135,7 +201,8 @@ impl StreamingTranscriber {
         if self.vad.should_commit() {
             return true;
         }
-        self.buffer.exceeds_commit_threshold()
+        self.buffer
+            .exceeds_commit_threshold(self.policy.commit_threshold_samples())
     }
 
     /// Analyze words and determine which are stable enough to commit.
@@ -148,7 +215,8 @@ impl StreamingTranscriber {
     pub fn commit(&mut self, alignment: &AlignmentResult) {
         let buffer_end_abs = self.buffer.timestamp_offset_ms() + self.buffer.current_duration_ms();
         let is_semantic_turn_end = self.vad.is_semantic_turn_end();
-        self.words.commit(alignment, buffer_end_abs);
+        let at_pause = self.vad.has_speech_end();
+        self.words.commit(alignment, buffer_end_abs, at_pause);
 
         if let Some(trim_from_ms) = alignment.trim_from_ms {
             self.buffer.trim_from_ms(trim_from_ms);
@@ -203,6 +271,67 @@ impl StreamingTranscriber {
         self.words.take_last_committed_delta()
     }
 
+    /// Take the words of the last commit with their timestamps (consumes them).
+    pub fn take_last_committed_words(&mut self) -> Vec<TimedWord> {
+        self.words.take_last_committed_words()
+    }
+
+    /// Take the pause/turn context of the last commit (consumes it).
+    ///
+    /// Read alongside `take_last_committed_delta` to decide whether a
+    /// delta was spoken on its own, e.g. as a dictation command.
+    pub fn take_last_commit_context(&mut self) -> Option<CommitContext> {
+        self.words.take_last_commit_context()
+    }
+
+    /// Enable or disable word events (see [`WordEvent`]).
+    ///
+    /// Off by default so that nothing accumulates without a consumer.
+    pub fn set_word_events_enabled(&mut self, enabled: bool) {
+        self.words.set_word_events_enabled(enabled);
+    }
+
+    /// Take the word events since the last call (consumes them).
+    pub fn take_word_events(&mut self) -> Vec<WordEvent> {
+        self.words.take_word_events()
+    }
+
+    // --- Trace recording ---
+
+    /// Start recording a hypothesis trace (see [`StreamTrace`]).
+    ///
+    /// Call after `reset` so the trace starts with the recording.
+    pub fn start_trace(&mut self, engine: &str) {
+        self.trace = Some(StreamTrace {
+            engine: engine.to_string(),
+            policy: if self.policy == StreamingPolicy::relaxed() {
+                TracePolicy::Relaxed
+            } else {
+                TracePolicy::Default
+            },
+            events: Vec::new(),
+        });
+    }
+
+    /// Stop recording and return the trace, if one was started.
+    pub fn take_trace(&mut self) -> Option<StreamTrace> {
+        self.trace.take()
+    }
+
+    // --- Post-processing ---
+
+    /// Set (or clear) the punctuation stage applied to commits.
+    ///
+    /// Only needed for engines that emit caseless, unpunctuated text.
+    pub fn set_punctuation(&mut self, punctuation: Option<PunctuationStage>) {
+        self.words.set_punctuation(punctuation);
+    }
+
+    /// The active punctuation stage, if any.
+    pub fn punctuation(&self) -> Option<&PunctuationStage> {
+        self.words.punctuation()
+    }
+
     // --- Lifecycle ---
 
     /// Reset all state for a new recording.
@@ -210,6 +339,8 @@ impl StreamingTranscriber {
         self.buffer.reset();
         self.vad.reset();
         self.words.reset();
+        self.last_decode_audio_ms = 0;
+        self.last_decode = None;
     }
 
     /// Clear word tracking (API compatibility).
@@ -217,6 +348,60 @@ impl StreamingTranscriber {
         self.words.clear_cache();
     }
 
+    // --- Streaming policy ---
+
+    /// Get the streaming policy.
+    pub fn policy(&self) -> StreamingPolicy {
+        self.policy
+    }
+
+    /// Replace the streaming policy (e.g. when the engine changes).
+    ///
+    /// Restarts auto-tuning from the new policy's decode interval.
+    pub fn set_policy(&mut self, policy: StreamingPolicy) {
+        self.policy = policy;
+        self.decode_interval_ms = policy.decode_interval_ms;
+        self.words.set_policy(policy);
+        if self.auto_tune.is_some() {
+            self.auto_tune = Some(RtfController::new(&policy));
+        }
+    }
+
+    /// Decode interval currently in effect.
+    pub fn decode_interval_ms(&self) -> u64 {
+        self.decode_interval_ms
+    }
+
+    pub fn is_auto_tune_enabled(&self) -> bool {
+        self.auto_tune.is_some()
+    }
+
+    /// Enable or disable decode interval auto-tuning.
+    pub fn set_auto_tune(&mut self, enabled: bool) {
+        self.auto_tune = enabled.then(|| RtfController::new(&self.policy));
+        self.decode_interval_ms = self.policy.decode_interval_ms;
+    }
+
+    /// Feed the pipeline's real-time factor to the auto-tuner.
+    ///
+    /// Returns the decode interval now in effect. A no-op when auto-tuning
+    /// is disabled.
+    pub fn auto_tune(&mut self, real_time_factor: f32) -> u64 {
+        if let Some(controller) = self.auto_tune.as_mut() {
+            let interval = controller.adjust(self.decode_interval_ms, real_time_factor);
+            if interval != self.decode_interval_ms {
+                tracing::debug!(
+                    from_ms = self.decode_interval_ms,
+                    to_ms = interval,
+                    real_time_factor,
+                    "Adjusted decode interval"
+                );
+                self.decode_interval_ms = interval;
+            }
+        }
+        self.decode_interval_ms
+    }
+
     // --- VAD Settings ---
 
     /// Get current VAD settings.
@@ -265,6 +450,27 @@ mod tests {
         assert_eq!(text, "Hello world");
     }
 
+    #[test]
+    fn test_policy_bounds_buffer() {
+        let mut transcriber = StreamingTranscriber::new();
+        transcriber.set_policy(StreamingPolicy::relaxed());
+        transcriber.add_samples(&vec![0.0; 16000 * 12]);
+        assert_eq!(transcriber.get_buffer().len(), 16000 * 10);
+    }
+
+    #[test]
+    fn test_auto_tune_widens_decode_interval() {
+        let mut transcriber = StreamingTranscriber::new();
+        assert_eq!(transcriber.auto_tune(0.95), 250);
+
+        transcriber.set_auto_tune(true);
+        assert_eq!(transcriber.auto_tune(0.95), 375);
+
+        transcriber.set_policy(StreamingPolicy::default());
+        assert_eq!(transcriber.decode_interval_ms(), 250);
+        assert!(transcriber.is_auto_tune_enabled());
+    }
+
     #[test]
     fn test_reset_clears_all_state() {
         let mut transcriber = StreamingTranscriber::new();
@@ -0,0 +1,215 @@
+//! Streaming timing policy and real-time auto-tuning.
+
+use crate::constants::{
+    COMMIT_THRESHOLD, MAX_BUFFER_SAMPLES, SAMPLE_RATE, TRANSCRIBE_THRESHOLD, TRIM_PADDING_MS,
+};
+
+/// Timing and stability knobs for streaming transcription.
+///
+/// Defaults suit fast, timestamped decoders (Parakeet, Zipformer).
+/// Heavier decoders should use [`StreamingPolicy::relaxed`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamingPolicy {
+    /// New audio (ms) needed before the next decode.
+    pub decode_interval_ms: u64,
+    /// Maximum buffer length (ms); older audio is dropped.
+    pub max_buffer_ms: u64,
+    /// Buffer length (ms) that forces a commit without a pause.
+    pub commit_threshold_ms: u64,
+    /// Audio (ms) kept before the first uncommitted word when trimming.
+    pub trim_padding_ms: u64,
+    /// Consecutive decodes a word must survive to count as stable.
+    pub min_stability: u8,
+    /// Minimum timestamp overlap (IoU) to match a word across decodes.
+    pub min_timestamp_iou: f32,
+    /// Words ending within this window (ms) of the buffer end are not
+    /// committed yet.
+    pub commit_window_ms: u64,
+    /// Unstable words ending within this window (ms) of the buffer end
+    /// are shown as volatile text only.
+    pub display_tail_ms: u64,
+}
+
+impl Default for StreamingPolicy {
+    fn default() -> Self {
+        Self {
+            decode_interval_ms: samples_to_ms(TRANSCRIBE_THRESHOLD),
+            max_buffer_ms: samples_to_ms(MAX_BUFFER_SAMPLES),
+            commit_threshold_ms: samples_to_ms(COMMIT_THRESHOLD),
+            trim_padding_ms: TRIM_PADDING_MS,
+            min_stability: 2,
+            min_timestamp_iou: 0.3,
+            commit_window_ms: 500,
+            display_tail_ms: 600,
+        }
+    }
+}
+
+impl StreamingPolicy {
+    /// Policy for slow, autoregressive decoders (Whisper): decode less
+    /// often over a longer buffer, since each decode costs more.
+    pub fn relaxed() -> Self {
+        Self {
+            decode_interval_ms: 1000,
+            max_buffer_ms: 10_000,
+            commit_threshold_ms: 8_000,
+            commit_window_ms: 1000,
+            display_tail_ms: 1000,
+            ..Self::default()
+        }
+    }
+
+    /// Clamp values into workable ranges (e.g. after a settings update).
+    pub fn sanitized(self) -> Self {
+        let max_buffer_ms = self.max_buffer_ms.clamp(1000, 30_000);
+        Self {
+            decode_interval_ms: self.decode_interval_ms.clamp(50, 5000),
+            max_buffer_ms,
+            commit_threshold_ms: self.commit_threshold_ms.clamp(500, max_buffer_ms),
+            trim_padding_ms: self.trim_padding_ms.min(2000),
+            min_stability: self.min_stability.clamp(1, 10),
+            min_timestamp_iou: self.min_timestamp_iou.clamp(0.0, 1.0),
+            commit_window_ms: self.commit_window_ms.min(5000),
+            display_tail_ms: self.display_tail_ms.min(5000),
+        }
+    }
+
+    pub(crate) fn max_buffer_samples(&self) -> usize {
+        ms_to_samples(self.max_buffer_ms)
+    }
+
+    pub(crate) fn commit_threshold_samples(&self) -> usize {
+        ms_to_samples(self.commit_threshold_ms)
+    }
+}
+
+pub(crate) fn samples_to_ms(samples: usize) -> u64 {
+    (samples as u64 * 1000) / SAMPLE_RATE as u64
+}
+
+pub(crate) fn ms_to_samples(ms: u64) -> usize {
+    (ms as usize * SAMPLE_RATE) / 1000
+}
+
+/// Timing of one decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeStats {
+    /// Time spent in the engine.
+    pub inference_ms: u64,
+    /// New audio covered since the previous decode.
+    pub audio_ms: u64,
+}
+
+/// Smoothed real-time factor above which the decode interval widens.
+const WIDEN_ABOVE_RTF: f32 = 0.7;
+
+/// Smoothed real-time factor below which the decode interval tightens.
+const TIGHTEN_BELOW_RTF: f32 = 0.35;
+
+/// Weight of the newest sample in the moving average.
+const RTF_SMOOTHING: f32 = 0.3;
+
+/// Widest interval, as a multiple of the policy's interval.
+const MAX_INTERVAL_FACTOR: u64 = 4;
+
+/// Adapts the decode interval to the machine's speed.
+///
+/// Fed the real-time factor of each decode (inference time over the new
+/// audio it covered). Widens the interval as the factor approaches 1.0,
+/// where decoding would fall behind the microphone, and tightens it back
+/// toward the policy's interval when there is headroom.
+#[derive(Debug, Clone)]
+pub struct RtfController {
+    min_interval_ms: u64,
+    max_interval_ms: u64,
+    smoothed_rtf: Option<f32>,
+}
+
+impl RtfController {
+    /// Controller bounded by the policy's decode interval.
+    pub fn new(policy: &StreamingPolicy) -> Self {
+        Self {
+            min_interval_ms: policy.decode_interval_ms,
+            max_interval_ms: policy.decode_interval_ms * MAX_INTERVAL_FACTOR,
+            smoothed_rtf: None,
+        }
+    }
+
+    /// The smoothed real-time factor, once a decode has been observed.
+    pub fn smoothed_rtf(&self) -> Option<f32> {
+        self.smoothed_rtf
+    }
+
+    /// Decode interval to use after observing `real_time_factor`.
+    pub fn adjust(&mut self, interval_ms: u64, real_time_factor: f32) -> u64 {
+        if !real_time_factor.is_finite() || real_time_factor <= 0.0 {
+            return interval_ms;
+        }
+        let smoothed = match self.smoothed_rtf {
+            Some(previous) => previous + RTF_SMOOTHING * (real_time_factor - previous),
+            None => real_time_factor,
+        };
+        self.smoothed_rtf = Some(smoothed);
+
+        let next = if smoothed >= WIDEN_ABOVE_RTF {
+            interval_ms * 3 / 2
+        } else if smoothed <= TIGHTEN_BELOW_RTF {
+            interval_ms * 4 / 5
+        } else {
+            interval_ms
+        };
+        next.clamp(self.min_interval_ms, self.max_interval_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_constants() {
+        let policy = StreamingPolicy::default();
+        assert_eq!(policy.decode_interval_ms, 250);
+        assert_eq!(policy.max_buffer_samples(), MAX_BUFFER_SAMPLES);
+        assert_eq!(policy.commit_threshold_samples(), COMMIT_THRESHOLD);
+    }
+
+    #[test]
+    fn test_sanitized_keeps_commit_within_buffer() {
+        let policy = StreamingPolicy {
+            decode_interval_ms: 0,
+            max_buffer_ms: 3000,
+            commit_threshold_ms: 9000,
+            min_stability: 0,
+            ..StreamingPolicy::default()
+        }
+        .sanitized();
+        assert_eq!(policy.decode_interval_ms, 50);
+        assert_eq!(policy.commit_threshold_ms, 3000);
+        assert_eq!(policy.min_stability, 1);
+    }
+
+    #[test]
+    fn test_controller_widens_near_real_time() {
+        let policy = StreamingPolicy::default();
+        let mut controller = RtfController::new(&policy);
+        let mut interval = policy.decode_interval_ms;
+        for _ in 0..10 {
+            interval = controller.adjust(interval, 0.95);
+        }
+        assert_eq!(interval, 1000);
+    }
+
+    #[test]
+    fn test_controller_tightens_with_headroom() {
+        let policy = StreamingPolicy::default();
+        let mut controller = RtfController::new(&policy);
+        let mut interval = controller.adjust(1000, 0.9);
+        assert_eq!(interval, 1000);
+        for _ in 0..20 {
+            interval = controller.adjust(interval, 0.1);
+        }
+        assert_eq!(interval, 250);
+        assert_eq!(controller.adjust(interval, 0.5), 250);
+    }
+}
@@ -0,0 +1,308 @@
+//! Recorded hypothesis traces and deterministic replay.
+//!
+//! A [`StreamTrace`] captures what a real engine produced during a
+//! recording: the audio timeline, VAD and turn events, and every decode
+//! as (buffer offset, timed words). Replaying it through a fresh
+//! [`StreamingTranscriber`] reruns alignment, stability and commit logic
+//! without audio or models, so changes to the heuristics show up as diffs
+//! of the committed output.
+
+use gibberish_turn::TurnPrediction;
+use gibberish_vad::VadEvent;
+use serde::{Deserialize, Serialize};
+
+use super::policy::ms_to_samples;
+use super::vad_state::VadState;
+use super::word_tracker::{CommitContext, TimedWord};
+use super::{StreamingPolicy, StreamingTranscriber};
+
+/// Policy preset a trace is replayed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TracePolicy {
+    #[default]
+    Default,
+    Relaxed,
+}
+
+impl TracePolicy {
+    pub fn policy(self) -> StreamingPolicy {
+        match self {
+            Self::Default => StreamingPolicy::default(),
+            Self::Relaxed => StreamingPolicy::relaxed(),
+        }
+    }
+}
+
+/// One step of a recorded stream, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// Audio appended to the buffer.
+    Audio { ms: u64 },
+    /// VAD detected the start of speech.
+    SpeechStart,
+    /// VAD detected the end of speech.
+    SpeechEnd,
+    /// Turn detector result for the current pause.
+    Turn { probability: f32, threshold: f32 },
+    /// An engine decode. Word timestamps are relative to the buffer, which
+    /// started at `buffer_offset_ms` when the decode ran.
+    Decode {
+        buffer_offset_ms: u64,
+        words: Vec<TimedWord>,
+    },
+}
+
+/// A recorded stream of engine hypotheses.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamTrace {
+    /// Engine the trace was captured from (informational).
+    #[serde(default)]
+    pub engine: String,
+    #[serde(default)]
+    pub policy: TracePolicy,
+    pub events: Vec<TraceEvent>,
+}
+
+impl StreamTrace {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize with one event per line, so traces diff line by line.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        let mut out = format!(
+            "{{\n  \"engine\": {},\n  \"policy\": {},\n  \"events\": [\n",
+            serde_json::to_string(&self.engine)?,
+            serde_json::to_string(&self.policy)?
+        );
+        for (i, event) in self.events.iter().enumerate() {
+            let separator = if i + 1 < self.events.len() { "," } else { "" };
+            out.push_str(&format!(
+                "    {}{separator}\n",
+                serde_json::to_string(event)?
+            ));
+        }
+        out.push_str("  ]\n}\n");
+        Ok(out)
+    }
+
+    /// Append an event, merging consecutive audio.
+    pub(crate) fn push(&mut self, event: TraceEvent) {
+        if let (Some(TraceEvent::Audio { ms: last }), TraceEvent::Audio { ms }) =
+            (self.events.last_mut(), &event)
+        {
+            *last += ms;
+            return;
+        }
+        self.events.push(event);
+    }
+
+    /// Replay with the trace's policy preset.
+    pub fn replay(&self) -> ReplayReport {
+        self.replay_with(self.policy.policy())
+    }
+
+    /// Replay with an explicit policy, e.g. to try a heuristic change.
+    ///
+    /// Decodes are replayed as recorded. When the replayed buffer was
+    /// trimmed differently, word timestamps are shifted so their absolute
+    /// position in the stream is kept.
+    pub fn replay_with(&self, policy: StreamingPolicy) -> ReplayReport {
+        let mut streamer = StreamingTranscriber::with_vad(VadState::external());
+        streamer.set_policy(policy);
+        let mut report = ReplayReport::default();
+
+        for event in &self.events {
+            match event {
+                TraceEvent::Audio { ms } => {
+                    streamer.add_samples(&vec![0.0; ms_to_samples(*ms)]);
+                }
+                TraceEvent::SpeechStart => {
+                    streamer
+                        .vad
+                        .apply_event(&VadEvent::SpeechStart { timestamp_ms: 0 });
+                }
+                TraceEvent::SpeechEnd => {
+                    streamer.vad.apply_event(&VadEvent::SpeechEnd {
+                        start_ms: 0,
+                        end_ms: 0,
+                        samples: Vec::new(),
+                    });
+                }
+                TraceEvent::Turn {
+                    probability,
+                    threshold,
+                } => {
+                    streamer.set_turn_prediction(TurnPrediction {
+                        probability: *probability,
+                        threshold: *threshold,
+                    });
+                }
+                TraceEvent::Decode {
+                    buffer_offset_ms,
+                    words,
+                } => {
+                    let offset_ms = streamer.buffer.timestamp_offset_ms();
+                    let shift = |ms: u64| (buffer_offset_ms + ms).saturating_sub(offset_ms);
+                    let words: Vec<TimedWord> = words
+                        .iter()
+                        .map(|w| TimedWord {
+                            text: w.text.clone(),
+                            start_ms: shift(w.start_ms),
+                            end_ms: shift(w.end_ms),
+                        })
+                        .collect();
+
+                    streamer.mark_transcribed();
+                    streamer.update_words(&words);
+                    if streamer.should_commit() {
+                        let alignment = streamer.analyze_words(&words);
+                        if alignment.stable_word_count > 0 {
+                            streamer.commit(&alignment);
+                            streamer.clear_word_cache();
+                        }
+                    }
+                    report.record(&mut streamer);
+                }
+            }
+        }
+
+        report.committed_text = streamer.committed_text().to_string();
+        report
+    }
+}
+
+/// A commit produced during replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayCommit {
+    pub text: String,
+    /// Start of the first committed word (absolute, ms).
+    pub start_ms: u64,
+    /// End of the last committed word (absolute, ms).
+    pub end_ms: u64,
+    pub context: CommitContext,
+}
+
+/// Output of a replay: the commit sequence and the final transcript.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub commits: Vec<ReplayCommit>,
+    pub committed_text: String,
+}
+
+impl ReplayReport {
+    fn record(&mut self, streamer: &mut StreamingTranscriber) {
+        let Some(text) = streamer.take_last_committed_delta() else {
+            return;
+        };
+        let words = streamer.take_last_committed_words();
+        self.commits.push(ReplayCommit {
+            text,
+            start_ms: words.first().map_or(0, |w| w.start_ms),
+            end_ms: words.last().map_or(0, |w| w.end_ms),
+            context: streamer.take_last_commit_context().unwrap_or_default(),
+        });
+    }
+
+    /// Line-oriented rendering for golden files.
+    ///
+    /// One line per commit with its timing and context; a `¶` line marks
+    /// the paragraph break after a turn end.
+    pub fn to_golden(&self) -> String {
+        let mut out = String::new();
+        for commit in &self.commits {
+            let context = &commit.context;
+            let flags: Vec<&str> = [
+                (context.after_pause, "after_pause"),
+                (context.at_pause, "at_pause"),
+                (context.turn_end, "turn_end"),
+            ]
+            .into_iter()
+            .filter_map(|(set, name)| set.then_some(name))
+            .collect();
+            let flags = if flags.is_empty() {
+                "-".to_string()
+            } else {
+                flags.join(",")
+            };
+            out.push_str(&format!(
+                "{:>6} {:>6} {:<29} {}\n",
+                commit.start_ms, commit.end_ms, flags, commit.text
+            ));
+            if context.turn_end {
+                out.push_str("¶\n");
+            }
+        }
+        out.push_str("---\n");
+        out.push_str(&self.committed_text);
+        out.push('\n');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> TimedWord {
+        TimedWord {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+        }
+    }
+
+    fn decode(buffer_offset_ms: u64, words: &[TimedWord]) -> TraceEvent {
+        TraceEvent::Decode {
+            buffer_offset_ms,
+            words: words.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_replay_commits_at_pause() {
+        let hello = [word("hello", 100, 400), word("world", 450, 800)];
+        let trace = StreamTrace {
+            events: vec![
+                TraceEvent::SpeechStart,
+                TraceEvent::Audio { ms: 1000 },
+                decode(0, &hello[..1]),
+                TraceEvent::Audio { ms: 1000 },
+                decode(0, &hello),
+                TraceEvent::SpeechEnd,
+                decode(0, &hello),
+            ],
+            ..StreamTrace::default()
+        };
+
+        let report = trace.replay();
+        assert_eq!(
+            report.commits,
+            vec![ReplayCommit {
+                text: "hello world".to_string(),
+                start_ms: 100,
+                end_ms: 800,
+                context: CommitContext {
+                    after_pause: true,
+                    at_pause: true,
+                    turn_end: false,
+                },
+            }]
+        );
+        assert_eq!(report.committed_text, "hello world");
+    }
+
+    #[test]
+    fn test_trace_round_trips_and_merges_audio() {
+        let mut trace = StreamTrace::default();
+        trace.push(TraceEvent::Audio { ms: 100 });
+        trace.push(TraceEvent::Audio { ms: 100 });
+        trace.push(decode(0, &[word("hi", 0, 100)]));
+        assert_eq!(trace.events[0], TraceEvent::Audio { ms: 200 });
+
+        let json = trace.to_json().unwrap();
+        assert_eq!(StreamTrace::from_json(&json).unwrap(), trace);
+    }
+}
@@ -80,37 +80,60 @@ impl VadState {
         Self::default()
     }
 
-    /// Process audio samples through VAD.
-    pub fn process(&mut self, samples: &[f32]) {
+    /// VAD state driven by events from elsewhere (e.g. a recorded trace)
+    /// instead of a detector.
+    pub(crate) fn external() -> Self {
+        Self {
+            vad: None,
+            settings: VadSettings::default(),
+            in_speech: false,
+            speech_end_pending: false,
+            speech_end_turn_checked: false,
+            speech_end_transcription_count: 0,
+            last_turn_prediction: None,
+            last_turn_end_ms: None,
+            silence_injection_pending: false,
+        }
+    }
+
+    /// Process audio samples through VAD, returning the events it detected.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<VadEvent> {
         let Some(ref mut vad) = self.vad else {
-            return;
+            return Vec::new();
         };
 
         match vad.process(samples) {
             Ok(events) => {
-                for event in events {
-                    match event {
-                        VadEvent::SpeechStart { .. } => {
-                            self.in_speech = true;
-                            self.speech_end_pending = false;
-                            self.speech_end_transcription_count = 0;
-                            self.speech_end_turn_checked = false;
-                        }
-                        VadEvent::SpeechEnd { .. } => {
-                            self.in_speech = false;
-                            if !self.speech_end_pending {
-                                self.speech_end_pending = true;
-                                self.speech_end_transcription_count = 0;
-                                self.speech_end_turn_checked = false;
-                                self.silence_injection_pending = true;
-                                tracing::debug!("VAD detected speech end");
-                            }
-                        }
-                    }
+                for event in &events {
+                    self.apply_event(event);
                 }
+                events
             }
             Err(e) => {
                 tracing::warn!("VAD processing error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Update speech state from a VAD event.
+    pub(crate) fn apply_event(&mut self, event: &VadEvent) {
+        match event {
+            VadEvent::SpeechStart { .. } => {
+                self.in_speech = true;
+                self.speech_end_pending = false;
+                self.speech_end_transcription_count = 0;
+                self.speech_end_turn_checked = false;
+            }
+            VadEvent::SpeechEnd { .. } => {
+                self.in_speech = false;
+                if !self.speech_end_pending {
+                    self.speech_end_pending = true;
+                    self.speech_end_transcription_count = 0;
+                    self.speech_end_turn_checked = false;
+                    self.silence_injection_pending = true;
+                    tracing::debug!("VAD detected speech end");
+                }
             }
         }
     }
@@ -0,0 +1,221 @@
+//! Word-level edit events for live transcripts.
+//!
+//! Instead of re-diffing whole display strings, consumers apply these
+//! events to their own copy of the transcript: the volatile words change in
+//! place by id, committed words are final.
+
+/// A change to the live transcript.
+///
+/// Word ids are stable across decodes: a revised or committed word keeps
+/// the id it was inserted with. Engines without word timings report zero
+/// `start_ms`/`end_ms`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordEvent {
+    /// A new volatile word, placed right after the volatile word `after`
+    /// (or first, after the committed text, when `None`).
+    Inserted {
+        id: u64,
+        after: Option<u64>,
+        text: String,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    /// A volatile word whose text changed.
+    Revised {
+        id: u64,
+        text: String,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    /// A volatile word dropped by a later decode.
+    Removed { id: u64 },
+    /// A word became final. `text` is the committed form, which may differ
+    /// from the volatile one (punctuation, casing). Words committed without
+    /// a prior insert (segment engines) carry a fresh id.
+    Committed {
+        id: u64,
+        text: String,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    /// A paragraph break after the last committed word.
+    ParagraphBreak,
+}
+
+impl WordEvent {
+    /// Id of the word the event refers to (`None` for paragraph breaks).
+    pub fn word_id(&self) -> Option<u64> {
+        match self {
+            Self::Inserted { id, .. }
+            | Self::Revised { id, .. }
+            | Self::Removed { id }
+            | Self::Committed { id, .. } => Some(*id),
+            Self::ParagraphBreak => None,
+        }
+    }
+}
+
+/// Derives word events from plain text updates.
+///
+/// For engines that report only a partial string and committed deltas
+/// (the Sherpa worker). Volatile words are matched by position, so a word
+/// keeps its id while the engine revises it.
+#[derive(Debug, Default)]
+pub struct TextWordDiffer {
+    volatile: Vec<(u64, String)>,
+    next_word_id: u64,
+}
+
+impl TextWordDiffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Diff a new partial text against the previous one.
+    pub fn update(&mut self, partial_text: &str) -> Vec<WordEvent> {
+        let words: Vec<&str> = partial_text.split_whitespace().collect();
+        let mut events = Vec::new();
+
+        for (i, word) in words.iter().enumerate() {
+            match self.volatile.get_mut(i) {
+                Some((_, text)) if text == word => {}
+                Some((id, text)) => {
+                    *text = word.to_string();
+                    events.push(WordEvent::Revised {
+                        id: *id,
+                        text: word.to_string(),
+                        start_ms: 0,
+                        end_ms: 0,
+                    });
+                }
+                None => {
+                    let id = self.allocate_id();
+                    let after = self.volatile.last().map(|(id, _)| *id);
+                    self.volatile.push((id, word.to_string()));
+                    events.push(WordEvent::Inserted {
+                        id,
+                        after,
+                        text: word.to_string(),
+                        start_ms: 0,
+                        end_ms: 0,
+                    });
+                }
+            }
+        }
+
+        if self.volatile.len() > words.len() {
+            events.extend(
+                self.volatile
+                    .drain(words.len()..)
+                    .map(|(id, _)| WordEvent::Removed { id }),
+            );
+        }
+        events
+    }
+
+    /// Commit a delta. Its words take over the ids of the volatile words
+    /// at the same positions; leftover volatile words are removed.
+    pub fn commit(&mut self, delta: &str) -> Vec<WordEvent> {
+        let mut volatile = std::mem::take(&mut self.volatile).into_iter();
+        let mut events: Vec<WordEvent> = delta
+            .split_whitespace()
+            .map(|word| {
+                let id = match volatile.next() {
+                    Some((id, _)) => id,
+                    None => self.allocate_id(),
+                };
+                WordEvent::Committed {
+                    id,
+                    text: word.to_string(),
+                    start_ms: 0,
+                    end_ms: 0,
+                }
+            })
+            .collect();
+        events.extend(volatile.map(|(id, _)| WordEvent::Removed { id }));
+        events
+    }
+
+    /// Forget all words for a new recording.
+    pub fn reset(&mut self) {
+        self.volatile.clear();
+        self.next_word_id = 0;
+    }
+
+    fn allocate_id(&mut self) -> u64 {
+        let id = self.next_word_id;
+        self.next_word_id += 1;
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inserted(id: u64, after: Option<u64>, text: &str) -> WordEvent {
+        WordEvent::Inserted {
+            id,
+            after,
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 0,
+        }
+    }
+
+    fn committed(id: u64, text: &str) -> WordEvent {
+        WordEvent::Committed {
+            id,
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_differ_emits_minimal_edits() {
+        let mut differ = TextWordDiffer::new();
+        assert_eq!(
+            differ.update("hello word"),
+            vec![inserted(0, None, "hello"), inserted(1, Some(0), "word")]
+        );
+        assert!(differ.update("hello word").is_empty());
+        assert_eq!(
+            differ.update("hello world again"),
+            vec![
+                WordEvent::Revised {
+                    id: 1,
+                    text: "world".to_string(),
+                    start_ms: 0,
+                    end_ms: 0,
+                },
+                inserted(2, Some(1), "again"),
+            ]
+        );
+        assert_eq!(
+            differ.update("hello"),
+            vec![WordEvent::Removed { id: 1 }, WordEvent::Removed { id: 2 },]
+        );
+    }
+
+    #[test]
+    fn test_differ_commit_keeps_ids() {
+        let mut differ = TextWordDiffer::new();
+        differ.update("hello world");
+        assert_eq!(
+            differ.commit("Hello world."),
+            vec![committed(0, "Hello"), committed(1, "world.")]
+        );
+        assert_eq!(differ.update("next"), vec![inserted(2, None, "next")]);
+
+        differ.update("one two three");
+        assert_eq!(
+            differ.commit("one two"),
+            vec![
+                committed(2, "one"),
+                committed(3, "two"),
+                WordEvent::Removed { id: 4 },
+            ]
+        );
+    }
+}
@@ -1,9 +1,12 @@
 //! Word stability tracking for streaming transcription.
 
-use crate::constants::TRIM_PADDING_MS;
+use super::policy::StreamingPolicy;
+use super::word_events::WordEvent;
+use gibberish_punctuation::PunctuationStage;
+use serde::{Deserialize, Serialize};
 
 /// Represents a word with timing information (from transcription).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimedWord {
     pub text: String,
     pub start_ms: u64,
@@ -35,17 +38,20 @@ pub struct AlignmentResult {
     pub stable_end_ms: u64,
 }
 
-/// Minimum stability count before a word is considered stable.
-const MIN_STABILITY_COUNT: u8 = 2;
-
-/// Minimum IoU (Intersection over Union) for timestamp-based word matching.
-const MIN_TIMESTAMP_IOU: f32 = 0.3;
-
-/// Words ending this many ms before buffer end are candidates for commit.
-const COMMIT_WINDOW_MS: u64 = 500;
-
-/// Don't show words that end within this tail window unless they're stable.
-const DISPLAY_TAIL_MS: u64 = 600;
+/// Where a commit sits relative to pauses and turns.
+///
+/// Lets consumers of committed deltas tell a phrase spoken on its own
+/// ("scratch that" after a pause) from the same words inside a sentence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitContext {
+    /// The commit starts after a pause, a turn end, or at the start of
+    /// the recording.
+    pub after_pause: bool,
+    /// The commit ends at a speech pause.
+    pub at_pause: bool,
+    /// The commit closes a semantic turn.
+    pub turn_end: bool,
+}
 
 /// Tracks word stability across transcription decodes.
 #[derive(Debug, Default)]
@@ -56,8 +62,22 @@ pub struct WordTracker {
     committed_end_ms: u64,
     /// Text committed on the most recent commit (cleared after read).
     last_committed_delta: Option<String>,
+    /// Words committed on the most recent commit, as punctuated (cleared after read).
+    last_committed_words: Vec<TimedWord>,
+    /// Restores punctuation and casing on commit, for caseless engines.
+    punctuation: Option<PunctuationStage>,
     /// Insert a paragraph break before the next displayed/committed words.
     paragraph_break_pending: bool,
+    /// Pause/turn context of the most recent commit (cleared after read).
+    last_commit_context: Option<CommitContext>,
+    /// The last commit ended mid-utterance, without a pause.
+    mid_utterance: bool,
+    /// Stability, matching and commit windows.
+    policy: StreamingPolicy,
+    /// Record word events (off unless a consumer drains them).
+    word_events_enabled: bool,
+    /// Word events since the last read.
+    word_events: Vec<WordEvent>,
 }
 
 impl WordTracker {
@@ -75,8 +95,38 @@ impl WordTracker {
         self.committed_end_ms
     }
 
+    /// Mark the last commit as the end of a semantic turn.
     pub fn set_paragraph_break_pending(&mut self) {
+        if !self.paragraph_break_pending && !self.committed_text.is_empty() {
+            self.push_event(WordEvent::ParagraphBreak);
+        }
         self.paragraph_break_pending = true;
+        self.mid_utterance = false;
+        if let Some(context) = self.last_commit_context.as_mut() {
+            context.turn_end = true;
+        }
+    }
+
+    /// Set the stability, matching and commit windows.
+    pub fn set_policy(&mut self, policy: StreamingPolicy) {
+        self.policy = policy;
+    }
+
+    /// Enable or disable recording of word events.
+    pub fn set_word_events_enabled(&mut self, enabled: bool) {
+        self.word_events_enabled = enabled;
+        if !enabled {
+            self.word_events.clear();
+        }
+    }
+
+    /// Set (or clear) the punctuation stage applied to committed words.
+    pub fn set_punctuation(&mut self, punctuation: Option<PunctuationStage>) {
+        self.punctuation = punctuation;
+    }
+
+    pub fn punctuation(&self) -> Option<&PunctuationStage> {
+        self.punctuation.as_ref()
     }
 
     /// Update tracked words with new transcription.
@@ -91,7 +141,11 @@ impl WordTracker {
         }
 
         let old_count = self.tracked_words.len();
-        self.tracked_words = self.align_and_track(new_words, timestamp_offset_ms);
+        let tracked = self.align_and_track(new_words, timestamp_offset_ms);
+        let previous = std::mem::replace(&mut self.tracked_words, tracked);
+        if self.word_events_enabled {
+            self.record_tracked_changes(&previous);
+        }
 
         let matched_count = self
             .tracked_words
@@ -131,9 +185,10 @@ impl WordTracker {
         let first_uncommittable = self
             .tracked_words
             .iter()
-            .find(|w| w.stability < MIN_STABILITY_COUNT || w.end_ms > stable_end_ms);
+            .find(|w| w.stability < self.policy.min_stability || w.end_ms > stable_end_ms);
 
-        let trim_from_ms = first_uncommittable.map(|w| w.start_ms.saturating_sub(TRIM_PADDING_MS));
+        let trim_from_ms =
+            first_uncommittable.map(|w| w.start_ms.saturating_sub(self.policy.trim_padding_ms));
 
         AlignmentResult {
             stable_text,
@@ -144,26 +199,82 @@ impl WordTracker {
     }
 
     /// Commit stable text.
-    pub fn commit(&mut self, alignment: &AlignmentResult, buffer_end_abs_ms: u64) {
+    ///
+    /// `at_pause` marks a commit at a speech pause, which lets the
+    /// punctuation stage close the sentence.
+    pub fn commit(&mut self, alignment: &AlignmentResult, buffer_end_abs_ms: u64, at_pause: bool) {
+        let (word_ids, mut committed): (Vec<u64>, Vec<TimedWord>) = self
+            .get_committable_words(buffer_end_abs_ms)
+            .into_iter()
+            .filter(|w| !w.text.trim().is_empty())
+            .map(|w| {
+                let word = TimedWord {
+                    text: w.text.trim().to_string(),
+                    start_ms: w.start_ms,
+                    end_ms: w.end_ms,
+                };
+                (w.id, word)
+            })
+            .unzip();
         let committed_ids: std::collections::HashSet<u64> = self
             .get_committable_words(buffer_end_abs_ms)
             .into_iter()
             .map(|w| w.id)
             .collect();
 
-        if !alignment.stable_text.is_empty() {
-            self.last_committed_delta = Some(alignment.stable_text.trim().to_string());
+        let stable_text = match self.punctuation.as_mut() {
+            Some(stage) if !committed.is_empty() => {
+                let words: Vec<&str> = committed.iter().map(|w| w.text.as_str()).collect();
+                let punctuated = stage.process_words(&words, at_pause);
+                for (word, text) in committed.iter_mut().zip(punctuated) {
+                    word.text = text;
+                }
+                committed
+                    .iter()
+                    .map(|w| w.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            _ => alignment.stable_text.clone(),
+        };
+
+        if !stable_text.is_empty() {
+            for (id, word) in word_ids.iter().zip(&committed) {
+                self.push_event(WordEvent::Committed {
+                    id: *id,
+                    text: word.text.clone(),
+                    start_ms: word.start_ms,
+                    end_ms: word.end_ms,
+                });
+            }
+            self.last_committed_delta = Some(stable_text.trim().to_string());
+            self.last_committed_words = committed;
+            self.record_commit_context(at_pause);
             if self.paragraph_break_pending && !self.committed_text.is_empty() {
                 self.committed_text.push_str("\n\n");
                 self.paragraph_break_pending = false;
             } else if !self.committed_text.is_empty() {
                 self.committed_text.push(' ');
             }
-            self.committed_text.push_str(&alignment.stable_text);
+            self.committed_text.push_str(&stable_text);
+        } else if at_pause {
+            self.mid_utterance = false;
         }
 
         self.committed_end_ms = alignment.stable_end_ms;
 
+        // Words leaving the tracker without being committed.
+        let dropped: Vec<u64> = self
+            .tracked_words
+            .iter()
+            .filter(|w| alignment.trim_from_ms.is_none() || committed_ids.contains(&w.id))
+            .filter(|w| stable_text.is_empty() || !word_ids.contains(&w.id))
+            .map(|w| w.id)
+            .collect();
+        for id in dropped {
+            self.push_event(WordEvent::Removed { id });
+        }
+
         if alignment.trim_from_ms.is_some() {
             self.tracked_words
                 .retain(|w| !committed_ids.contains(&w.id));
@@ -182,7 +293,33 @@ impl WordTracker {
             return;
         }
 
+        let punctuated = self
+            .punctuation
+            .as_mut()
+            .map(|stage| stage.process_text(trimmed, true));
+        let trimmed = punctuated.as_deref().unwrap_or(trimmed);
+
+        if self.word_events_enabled {
+            let dropped: Vec<u64> = self.tracked_words.iter().map(|w| w.id).collect();
+            for id in dropped {
+                self.push_event(WordEvent::Removed { id });
+            }
+            for word in trimmed.split_whitespace() {
+                let id = self.next_word_id;
+                self.next_word_id += 1;
+                self.push_event(WordEvent::Committed {
+                    id,
+                    text: word.to_string(),
+                    start_ms: 0,
+                    end_ms: 0,
+                });
+            }
+        }
+
         self.last_committed_delta = Some(trimmed.to_string());
+        self.last_committed_words.clear();
+        // Segment engines commit whole utterances.
+        self.record_commit_context(true);
 
         if self.paragraph_break_pending && !self.committed_text.is_empty() {
             self.committed_text.push_str("\n\n");
@@ -197,7 +334,7 @@ impl WordTracker {
 
     /// Build display text from committed + non-tail tracked words.
     pub fn build_display_text(&self, buffer_end_abs_ms: u64) -> String {
-        let display_cutoff = buffer_end_abs_ms.saturating_sub(DISPLAY_TAIL_MS);
+        let display_cutoff = buffer_end_abs_ms.saturating_sub(self.policy.display_tail_ms);
 
         let displayable: Vec<&TrackedWord> = self
             .tracked_words
@@ -206,7 +343,7 @@ impl WordTracker {
                 if w.end_ms <= self.committed_end_ms {
                     return false;
                 }
-                w.stability >= MIN_STABILITY_COUNT || w.end_ms <= display_cutoff
+                w.stability >= self.policy.min_stability || w.end_ms <= display_cutoff
             })
             .collect();
 
@@ -230,7 +367,7 @@ impl WordTracker {
 
     /// Build display text including volatile tail words.
     pub fn build_full_display_text(&self, buffer_end_abs_ms: u64) -> (String, String) {
-        let display_cutoff = buffer_end_abs_ms.saturating_sub(DISPLAY_TAIL_MS);
+        let display_cutoff = buffer_end_abs_ms.saturating_sub(self.policy.display_tail_ms);
         let main_text = self.build_display_text(buffer_end_abs_ms);
 
         let tail: Vec<&TrackedWord> = self
@@ -238,7 +375,7 @@ impl WordTracker {
             .iter()
             .filter(|w| {
                 w.end_ms > self.committed_end_ms
-                    && w.stability < MIN_STABILITY_COUNT
+                    && w.stability < self.policy.min_stability
                     && w.end_ms > display_cutoff
             })
             .collect();
@@ -265,14 +402,87 @@ impl WordTracker {
         })
     }
 
+    /// Take the words of the last commit with their timestamps (consumes them).
+    ///
+    /// Empty for engines without word-level timestamps.
+    pub fn take_last_committed_words(&mut self) -> Vec<TimedWord> {
+        std::mem::take(&mut self.last_committed_words)
+    }
+
+    /// Take the pause/turn context of the last commit (consumes it).
+    pub fn take_last_commit_context(&mut self) -> Option<CommitContext> {
+        self.last_commit_context.take()
+    }
+
+    /// Take the word events recorded since the last call (consumes them).
+    pub fn take_word_events(&mut self) -> Vec<WordEvent> {
+        std::mem::take(&mut self.word_events)
+    }
+
+    fn push_event(&mut self, event: WordEvent) {
+        if self.word_events_enabled {
+            self.word_events.push(event);
+        }
+    }
+
+    /// Record inserts, revisions and removals against the previous decode.
+    fn record_tracked_changes(&mut self, previous: &[TrackedWord]) {
+        let mut events = Vec::new();
+        let mut after = None;
+        for word in &self.tracked_words {
+            match previous.iter().find(|p| p.id == word.id) {
+                None => events.push(WordEvent::Inserted {
+                    id: word.id,
+                    after,
+                    text: word.text.trim().to_string(),
+                    start_ms: word.start_ms,
+                    end_ms: word.end_ms,
+                }),
+                Some(p) if p.text.trim() != word.text.trim() => events.push(WordEvent::Revised {
+                    id: word.id,
+                    text: word.text.trim().to_string(),
+                    start_ms: word.start_ms,
+                    end_ms: word.end_ms,
+                }),
+                Some(_) => {}
+            }
+            after = Some(word.id);
+        }
+        events.extend(
+            previous
+                .iter()
+                .filter(|p| !self.tracked_words.iter().any(|w| w.id == p.id))
+                .map(|p| WordEvent::Removed { id: p.id }),
+        );
+        self.word_events.extend(events);
+    }
+
+    fn record_commit_context(&mut self, at_pause: bool) {
+        self.last_commit_context = Some(CommitContext {
+            after_pause: !self.mid_utterance,
+            at_pause,
+            turn_end: false,
+        });
+        self.mid_utterance = !at_pause;
+    }
+
     /// Reset all state for a new recording.
+    ///
+    /// The punctuation stage is kept; only its sentence state is cleared.
     pub fn reset(&mut self) {
         self.tracked_words.clear();
         self.next_word_id = 0;
         self.committed_text.clear();
         self.committed_end_ms = 0;
         self.last_committed_delta = None;
+        self.last_committed_words.clear();
         self.paragraph_break_pending = false;
+        self.last_commit_context = None;
+        self.mid_utterance = false;
+        self.word_events.clear();
+        if let Some(stage) = self.punctuation.as_mut() {
+            stage.reset();
+        }
     }
 
     /// Clear word cache (API compatibility).
@@ -283,11 +493,11 @@ impl WordTracker {
     // --- Private helpers ---
 
     fn get_committable_words(&self, buffer_end_abs_ms: u64) -> Vec<&TrackedWord> {
-        let commit_cutoff = buffer_end_abs_ms.saturating_sub(COMMIT_WINDOW_MS);
+        let commit_cutoff = buffer_end_abs_ms.saturating_sub(self.policy.commit_window_ms);
 
         self.tracked_words
             .iter()
-            .filter(|w| w.stability >= MIN_STABILITY_COUNT && w.end_ms <= commit_cutoff)
+            .filter(|w| w.stability >= self.policy.min_stability && w.end_ms <= commit_cutoff)
             .collect()
     }
 
@@ -295,7 +505,7 @@ impl WordTracker {
     fn get_stable_words(&self) -> Vec<&TrackedWord> {
         self.tracked_words
             .iter()
-            .filter(|w| w.stability >= MIN_STABILITY_COUNT)
+            .filter(|w| w.stability >= self.policy.min_stability)
             .collect()
     }
 
@@ -339,7 +549,7 @@ impl WordTracker {
                     new_abs_end,
                 );
 
-                if iou >= MIN_TIMESTAMP_IOU {
+                if iou >= self.policy.min_timestamp_iou {
                     if let Some((_, best_iou)) = best_match {
                         if iou > best_iou {
                             best_match = Some((i, iou));
@@ -503,6 +713,156 @@ mod tests {
         assert_eq!(tracker.tracked_words[0].stability, 1);
     }
 
+    fn stabilize(tracker: &mut WordTracker, words: &[TimedWord]) {
+        tracker.update(words, 0);
+        tracker.update(words, 0);
+    }
+
+    #[test]
+    fn test_commit_punctuates_and_keeps_timestamps() {
+        let mut tracker = WordTracker::new();
+        tracker.set_punctuation(Some(PunctuationStage::rule_based("en")));
+
+        let words = vec![
+            make_word("HELLO", 0, 400),
+            make_word("I", 400, 500),
+            make_word("AM", 500, 700),
+            make_word("HERE", 700, 1000),
+        ];
+        stabilize(&mut tracker, &words);
+
+        let alignment = tracker.analyze(2000);
+        tracker.commit(&alignment, 2000, true);
+
+        assert_eq!(tracker.committed_text(), "Hello I am here.");
+        assert_eq!(
+            tracker.take_last_committed_delta().as_deref(),
+            Some("Hello I am here.")
+        );
+
+        let committed = tracker.take_last_committed_words();
+        let texts: Vec<&str> = committed.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hello", "I", "am", "here."]);
+        assert_eq!(committed[3].start_ms, 700);
+        assert_eq!(committed[3].end_ms, 1000);
+    }
+
+    #[test]
+    fn test_commit_mid_sentence_stays_open() {
+        let mut tracker = WordTracker::new();
+        tracker.set_punctuation(Some(PunctuationStage::rule_based("en")));
+
+        stabilize(
+            &mut tracker,
+            &[make_word("we", 0, 300), make_word("went", 300, 600)],
+        );
+        let alignment = tracker.analyze(2000);
+        tracker.commit(&alignment, 2000, false);
+
+        stabilize(&mut tracker, &[make_word("home", 2000, 2400)]);
+        let alignment = tracker.analyze(4000);
+        tracker.commit(&alignment, 4000, true);
+
+        assert_eq!(tracker.committed_text(), "We went home.");
+    }
+
+    #[test]
+    fn test_commit_without_punctuation_is_unchanged() {
+        let mut tracker = WordTracker::new();
+        stabilize(&mut tracker, &[make_word("hello", 0, 400)]);
+        let alignment = tracker.analyze(2000);
+        tracker.commit(&alignment, 2000, true);
+
+        assert_eq!(tracker.committed_text(), "hello");
+    }
+
+    #[test]
+    fn test_commit_context_tracks_pauses_and_turns() {
+        let mut tracker = WordTracker::new();
+
+        stabilize(&mut tracker, &[make_word("we", 0, 300)]);
+        let alignment = tracker.analyze(2000);
+        tracker.commit(&alignment, 2000, false);
+        let context = tracker.take_last_commit_context().unwrap();
+        assert!(context.after_pause && !context.at_pause);
+
+        stabilize(&mut tracker, &[make_word("went", 2000, 2300)]);
+        let alignment = tracker.analyze(4000);
+        tracker.commit(&alignment, 4000, true);
+        tracker.set_paragraph_break_pending();
+        let context = tracker.take_last_commit_context().unwrap();
+        assert!(!context.after_pause && context.at_pause && context.turn_end);
+
+        tracker.commit_text("undo");
+        let context = tracker.take_last_commit_context().unwrap();
+        assert!(context.after_pause && context.at_pause && !context.turn_end);
+        assert!(tracker.take_last_commit_context().is_none());
+    }
+
+    #[test]
+    fn test_word_events_follow_decodes() {
+        let mut tracker = WordTracker::new();
+        tracker.set_word_events_enabled(true);
+
+        tracker.update(&[make_word("hello", 0, 300)], 0);
+        tracker.update(
+            &[make_word("hello", 0, 300), make_word("word", 400, 700)],
+            0,
+        );
+        tracker.update(
+            &[make_word("hello", 0, 300), make_word("world", 400, 700)],
+            0,
+        );
+        let events = tracker.take_word_events();
+        assert_eq!(
+            events,
+            vec![
+                WordEvent::Inserted {
+                    id: 0,
+                    after: None,
+                    text: "hello".to_string(),
+                    start_ms: 0,
+                    end_ms: 300,
+                },
+                WordEvent::Inserted {
+                    id: 1,
+                    after: Some(0),
+                    text: "word".to_string(),
+                    start_ms: 400,
+                    end_ms: 700,
+                },
+                WordEvent::Revised {
+                    id: 1,
+                    text: "world".to_string(),
+                    start_ms: 400,
+                    end_ms: 700,
+                },
+            ]
+        );
+
+        stabilize(
+            &mut tracker,
+            &[make_word("hello", 0, 300), make_word("world", 400, 700)],
+        );
+        tracker.take_word_events();
+        let alignment = tracker.analyze(2000);
+        tracker.commit(&alignment, 2000, true);
+        tracker.set_paragraph_break_pending();
+        let events = tracker.take_word_events();
+        let ids: Vec<Option<u64>> = events.iter().map(WordEvent::word_id).collect();
+        assert_eq!(ids, vec![Some(0), Some(1), None]);
+        assert!(matches!(events[0], WordEvent::Committed { .. }));
+        assert_eq!(events[2], WordEvent::ParagraphBreak);
+    }
+
+    #[test]
+    fn test_word_events_disabled_by_default() {
+        let mut tracker = WordTracker::new();
+        tracker.update(&[make_word("hello", 0, 300)], 0);
+        tracker.commit_text("hello");
+        assert!(tracker.take_word_events().is_empty());
+    }
+
     #[test]
     fn test_reset_clears_all() {
         let mut tracker = WordTracker::new();
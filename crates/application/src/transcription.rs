@@ -1,4 +1,5 @@
 use crate::{StreamingTranscriber, TimedWord};
+use gibberish_punctuation::PunctuationStage;
 use gibberish_stt::{Segment, SttEngine, Word};
 use gibberish_turn::{TurnDetector, TurnPrediction};
 use std::path::Path;
@@ -10,6 +11,8 @@ pub struct TranscriptSegment {
     pub start_ms: u64,
     pub end_ms: u64,
     pub speaker: Option<i32>,
+    /// Timings are energy estimates, not aligned to speech.
+    pub words_unaligned: bool,
 }
 
 impl From<Segment> for TranscriptSegment {
@@ -19,6 +22,7 @@ impl From<Segment> for TranscriptSegment {
             start_ms: seg.start_ms,
             end_ms: seg.end_ms,
             speaker: seg.speaker,
+            words_unaligned: seg.words_unaligned,
         }
     }
 }
@@ -61,6 +65,7 @@ impl TranscriptionService {
         words: &[Word],
         turn_boundaries_ms: &[u64],
         speaker: Option<i32>,
+        words_unaligned: bool,
     ) -> Vec<TranscriptSegment> {
         if words.is_empty() || turn_boundaries_ms.is_empty() {
             return Vec::new();
@@ -90,6 +95,7 @@ impl TranscriptionService {
                         start_ms,
                         end_ms,
                         speaker,
+                        words_unaligned,
                     });
                     current_words.clear();
                 }
@@ -113,6 +119,7 @@ impl TranscriptionService {
                 start_ms,
                 end_ms,
                 speaker,
+                words_unaligned,
             });
         }
 
@@ -143,9 +150,15 @@ impl TranscriptionService {
             .cloned()
             .collect();
         let speaker = segments.first().and_then(|s| s.speaker);
+        let words_unaligned = segments.iter().any(|s| s.words_unaligned);
 
         if !words.is_empty() {
-            let split = Self::split_words_on_boundaries(&words, turn_boundaries_ms, speaker);
+            let split = Self::split_words_on_boundaries(
+                &words,
+                turn_boundaries_ms,
+                speaker,
+                words_unaligned,
+            );
             if !split.is_empty() {
                 return Ok(split);
             }
@@ -154,6 +167,16 @@ impl TranscriptionService {
         Ok(segments.into_iter().map(TranscriptSegment::from).collect())
     }
 
+    /// Restore punctuation and casing on finished segments.
+    ///
+    /// Each segment is treated as ending at a pause.
+    pub fn punctuate_segments(stage: &mut PunctuationStage, segments: &mut [TranscriptSegment]) {
+        stage.reset();
+        for segment in segments {
+            segment.text = stage.process_text(&segment.text, true);
+        }
+    }
+
     pub fn process_streaming_chunk(
         streamer: &mut StreamingTranscriber,
         engine: Option<Arc<dyn SttEngine>>,
@@ -225,9 +248,11 @@ impl TranscriptionService {
             "streaming_transcription_starting"
         );
 
+        let started = std::time::Instant::now();
         let segments = engine
             .transcribe(&buffer)
             .map_err(|e| TranscriptionError::TranscriptionFailed(e.to_string()))?;
+        let inference_ms = started.elapsed().as_millis() as u64;
 
         let words: Vec<TimedWord> = segments
             .iter()
@@ -258,6 +283,7 @@ impl TranscriptionService {
         );
 
         streamer.mark_transcribed();
+        streamer.record_decode_time(inference_ms);
 
         // Handle engines without word-level timestamps (e.g., Whisper batch models).
         // Use segment text directly as volatile text, commit on speech end.
@@ -0,0 +1,104 @@
+//! Golden-file tests for streaming commit behavior.
+//!
+//! Each `tests/traces/<name>.json` is a recorded hypothesis trace; its
+//! replay must match `<name>.golden` (commit text, timings, context and
+//! paragraph breaks). After an intended change to the alignment or commit
+//! heuristics, regenerate the goldens and review the diff:
+//!
+//! ```sh
+//! UPDATE_GOLDEN=1 cargo test -p gibberish-application --test streaming_replay
+//! ```
+
+use gibberish_application::StreamTrace;
+use std::path::{Path, PathBuf};
+
+fn traces_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/traces")
+}
+
+fn find_traces() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(traces_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Line diff of `expected` against `actual` (`-` missing, `+` unexpected).
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let a: Vec<&str> = expected.lines().collect();
+    let b: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table, filled from the end.
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = String::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            out.push_str(&format!("  {}\n", a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("- {}\n", a[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", b[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[test]
+fn test_traces_match_golden_files() {
+    let traces = find_traces();
+    assert!(!traces.is_empty(), "no traces in {:?}", traces_dir());
+
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    for path in &traces {
+        let json = std::fs::read_to_string(path).unwrap();
+        let trace = StreamTrace::from_json(&json)
+            .unwrap_or_else(|e| panic!("{}: invalid trace: {e}", path.display()));
+        let actual = trace.replay().to_golden();
+
+        let golden_path = path.with_extension("golden");
+        if update {
+            std::fs::write(&golden_path, &actual).unwrap();
+            continue;
+        }
+
+        let expected = std::fs::read_to_string(&golden_path).unwrap_or_default();
+        if expected != actual {
+            failures.push(format!(
+                "{}:\n{}",
+                golden_path.display(),
+                diff_lines(&expected, &actual)
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "replay differs from golden files (rerun with UPDATE_GOLDEN=1 if intended):\n\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_diff_lines_marks_changes() {
+    assert_eq!(diff_lines("a\nb\nc\n", "a\nx\nc\n"), "  a\n- b\n+ x\n  c\n");
+}
@@ -17,6 +17,11 @@ pub use speaker::{SpeakerInput, SpeakerStream, TAP_DEVICE_NAME};
 
 pub const SAMPLE_RATE: u32 = 16000;
 
+/// The directory recordings are saved to and read back from.
+pub fn recordings_dir() -> Option<std::path::PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library/Application Support/gibberish/recordings"))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AudioError {
     #[error("device not found: {0}")]
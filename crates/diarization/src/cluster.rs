@@ -0,0 +1,251 @@
+//! Speaker clustering over embeddings.
+//!
+//! Two strategies share the same cosine-similarity threshold:
+//! - [`OnlineClusterer`] assigns each embedding as it arrives (live sessions).
+//! - [`cluster_offline`] sees all embeddings at once and merges them
+//!   agglomeratively (re-clustering when a session is saved).
+
+/// Cosine similarity of two vectors (0.0 if either is all zeros).
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b.iter()) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a <= f32::EPSILON || norm_b <= f32::EPSILON {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm <= f32::EPSILON {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+#[derive(Debug, Clone)]
+struct Centroid {
+    sum: Vec<f32>,
+}
+
+impl Centroid {
+    fn new(embedding: &[f32]) -> Self {
+        Self {
+            sum: normalized(embedding),
+        }
+    }
+
+    fn add(&mut self, embedding: &[f32]) {
+        for (s, x) in self.sum.iter_mut().zip(normalized(embedding)) {
+            *s += x;
+        }
+    }
+}
+
+/// Incremental speaker assignment: nearest centroid above the threshold,
+/// otherwise a new speaker.
+#[derive(Debug, Clone)]
+pub struct OnlineClusterer {
+    threshold: f32,
+    max_speakers: usize,
+    centroids: Vec<Centroid>,
+}
+
+impl OnlineClusterer {
+    pub fn new(threshold: f32, max_speakers: usize) -> Self {
+        Self {
+            threshold,
+            max_speakers: max_speakers.max(1),
+            centroids: Vec::new(),
+        }
+    }
+
+    /// Assign an embedding to a speaker and update that speaker's centroid.
+    ///
+    /// Once `max_speakers` exist, embeddings go to the nearest speaker even
+    /// below the threshold.
+    pub fn assign(&mut self, embedding: &[f32]) -> usize {
+        let best = self
+            .centroids
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, cosine_similarity(&c.sum, embedding)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((i, sim))
+                if sim >= self.threshold || self.centroids.len() >= self.max_speakers =>
+            {
+                self.centroids[i].add(embedding);
+                i
+            }
+            _ => {
+                self.centroids.push(Centroid::new(embedding));
+                self.centroids.len() - 1
+            }
+        }
+    }
+
+    pub fn num_speakers(&self) -> usize {
+        self.centroids.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.centroids.clear();
+    }
+}
+
+/// Agglomerative clustering with average linkage.
+///
+/// Starts with one cluster per embedding and repeatedly merges the most
+/// similar pair while their average similarity is at least `threshold`
+/// (or while more than `max_speakers` clusters remain). Returns one label per
+/// embedding; labels are numbered by first appearance.
+pub fn cluster_offline(embeddings: &[Vec<f32>], threshold: f32, max_speakers: usize) -> Vec<usize> {
+    let n = embeddings.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let max_speakers = max_speakers.max(1);
+
+    let unit: Vec<Vec<f32>> = embeddings.iter().map(|e| normalized(e)).collect();
+    let mut sim = vec![vec![0.0f32; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let s = cosine_similarity(&unit[i], &unit[j]);
+            sim[i][j] = s;
+            sim[j][i] = s;
+        }
+    }
+
+    // Each cluster is the list of member indices; similarity between
+    // clusters is the mean pairwise similarity of their members.
+    let mut clusters: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    loop {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for a in 0..clusters.len() {
+            for b in (a + 1)..clusters.len() {
+                let total: f32 = clusters[a]
+                    .iter()
+                    .flat_map(|&i| clusters[b].iter().map(move |&j| (i, j)))
+                    .map(|(i, j)| sim[i][j])
+                    .sum();
+                let avg = total / (clusters[a].len() * clusters[b].len()) as f32;
+                if best.is_none_or(|(_, _, s)| avg > s) {
+                    best = Some((a, b, avg));
+                }
+            }
+        }
+
+        match best {
+            Some((a, b, s)) if s >= threshold || clusters.len() > max_speakers => {
+                let merged = clusters.remove(b);
+                clusters[a].extend(merged);
+            }
+            _ => break,
+        }
+    }
+
+    let mut labels = vec![0usize; n];
+    for (label, members) in clusters.iter().enumerate() {
+        for &i in members {
+            labels[i] = label;
+        }
+    }
+    renumber_by_first_appearance(&labels)
+}
+
+/// Relabel so speaker 0 is whoever speaks first, speaker 1 second, etc.
+fn renumber_by_first_appearance(labels: &[usize]) -> Vec<usize> {
+    let mut mapping: Vec<(usize, usize)> = Vec::new();
+    labels
+        .iter()
+        .map(
+            |&label| match mapping.iter().find(|(old, _)| *old == label) {
+                Some((_, new)) => *new,
+                None => {
+                    let new = mapping.len();
+                    mapping.push((label, new));
+                    new
+                }
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: [f32; 3] = [1.0, 0.0, 0.0];
+    const A2: [f32; 3] = [0.9, 0.1, 0.0];
+    const B: [f32; 3] = [0.0, 1.0, 0.0];
+    const B2: [f32; 3] = [0.1, 0.95, 0.05];
+    const C: [f32; 3] = [0.0, 0.0, 1.0];
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&A, &A) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&A, &B).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&A, &[0.0; 3]), 0.0);
+    }
+
+    #[test]
+    fn test_online_assigns_similar_to_same_speaker() {
+        let mut clusterer = OnlineClusterer::new(0.7, 8);
+        assert_eq!(clusterer.assign(&A), 0);
+        assert_eq!(clusterer.assign(&B), 1);
+        assert_eq!(clusterer.assign(&A2), 0);
+        assert_eq!(clusterer.assign(&B2), 1);
+        assert_eq!(clusterer.num_speakers(), 2);
+    }
+
+    #[test]
+    fn test_online_respects_max_speakers() {
+        let mut clusterer = OnlineClusterer::new(0.7, 2);
+        clusterer.assign(&A);
+        clusterer.assign(&B);
+        let label = clusterer.assign(&C);
+        assert!(label < 2);
+        assert_eq!(clusterer.num_speakers(), 2);
+    }
+
+    #[test]
+    fn test_online_reset() {
+        let mut clusterer = OnlineClusterer::new(0.7, 8);
+        clusterer.assign(&A);
+        clusterer.assign(&B);
+        clusterer.reset();
+        assert_eq!(clusterer.num_speakers(), 0);
+        assert_eq!(clusterer.assign(&B), 0);
+    }
+
+    #[test]
+    fn test_offline_groups_speakers() {
+        let embeddings = vec![B.to_vec(), A.to_vec(), B2.to_vec(), A2.to_vec(), C.to_vec()];
+        let labels = cluster_offline(&embeddings, 0.7, 8);
+        assert_eq!(labels, vec![0, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_offline_forces_max_speakers() {
+        let embeddings = vec![A.to_vec(), B.to_vec(), C.to_vec()];
+        let labels = cluster_offline(&embeddings, 0.99, 2);
+        let distinct = labels
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        assert_eq!(distinct, 2);
+    }
+
+    #[test]
+    fn test_offline_empty() {
+        assert!(cluster_offline(&[], 0.7, 8).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+//! ONNX speaker-embedding model (WeSpeaker / 3D-Speaker style).
+//!
+//! The model takes `[1, frames, 80]` fbank features and returns a single
+//! fixed-size embedding per utterance.
+
+use crate::fbank::{compute_fbank, N_MELS};
+use crate::{DiarizationError, SpeakerEmbedder};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::Path;
+use std::sync::Mutex;
+
+#[derive(Debug)]
+pub struct OnnxSpeakerEmbedder {
+    session: Mutex<Session>,
+    input_name: String,
+    output_name: String,
+}
+
+impl OnnxSpeakerEmbedder {
+    pub fn load(model_path: impl AsRef<Path>) -> Result<Self, DiarizationError> {
+        let session = Session::builder()
+            .map_err(|e| DiarizationError::Model(e.to_string()))?
+            .with_inter_threads(1)
+            .map_err(|e| DiarizationError::Model(e.to_string()))?
+            .with_intra_threads(2)
+            .map_err(|e| DiarizationError::Model(e.to_string()))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| DiarizationError::Model(e.to_string()))?
+            .commit_from_file(model_path.as_ref())
+            .map_err(|e| DiarizationError::Model(e.to_string()))?;
+
+        let input_name = session
+            .inputs
+            .iter()
+            .find(|i| i.name == "feats" || i.name == "x")
+            .map(|i| i.name.clone())
+            .or_else(|| session.inputs.first().map(|i| i.name.clone()))
+            .ok_or_else(|| DiarizationError::Model("model has no inputs".to_string()))?;
+
+        let output_name = session
+            .outputs
+            .iter()
+            .find(|o| o.name == "embs" || o.name == "embedding")
+            .map(|o| o.name.clone())
+            .or_else(|| session.outputs.first().map(|o| o.name.clone()))
+            .ok_or_else(|| DiarizationError::Model("model has no outputs".to_string()))?;
+
+        Ok(Self {
+            session: Mutex::new(session),
+            input_name,
+            output_name,
+        })
+    }
+}
+
+impl SpeakerEmbedder for OnnxSpeakerEmbedder {
+    fn name(&self) -> &'static str {
+        "onnx-speaker-embedder"
+    }
+
+    fn embed(&self, audio_16k_mono: &[f32]) -> Result<Vec<f32>, DiarizationError> {
+        let features = compute_fbank(audio_16k_mono);
+        if features.is_empty() {
+            return Err(DiarizationError::InvalidInput(
+                "audio shorter than one frame".to_string(),
+            ));
+        }
+        let n_frames = features.len() / N_MELS;
+
+        let input = Tensor::from_array(([1i64, n_frames as i64, N_MELS as i64], features))
+            .map_err(|e| DiarizationError::Inference(e.to_string()))?;
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| DiarizationError::Inference("lock poisoned".to_string()))?;
+
+        let outputs = session
+            .run(ort::inputs![self.input_name.as_str() => input])
+            .map_err(|e| DiarizationError::Inference(e.to_string()))?;
+
+        let output = outputs
+            .get(self.output_name.as_str())
+            .ok_or_else(|| DiarizationError::Inference("missing model output".to_string()))?;
+
+        let (_shape, data) = output
+            .try_extract_tensor::<f32>()
+            .map_err(|e| DiarizationError::Inference(e.to_string()))?;
+        if data.is_empty() {
+            return Err(DiarizationError::Inference("empty embedding".to_string()));
+        }
+        Ok(data.to_vec())
+    }
+}
@@ -0,0 +1,210 @@
+//! Kaldi-compatible log mel filterbank features.
+//!
+//! Speaker-embedding models trained with Kaldi/WeSpeaker recipes expect
+//! 80-dim fbank frames (25ms window, 10ms shift, Hamming window, pre-emphasis)
+//! computed on 16-bit-scaled samples, with per-utterance mean normalization.
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::{Arc, OnceLock};
+
+const SAMPLE_RATE: usize = 16_000;
+const FRAME_LENGTH: usize = 400; // 25ms
+const FRAME_SHIFT: usize = 160; // 10ms
+const N_FFT: usize = 512;
+const N_FREQ: usize = N_FFT / 2 + 1;
+const PREEMPHASIS: f64 = 0.97;
+const LOW_FREQ: f64 = 20.0;
+
+/// Number of mel bins per frame.
+pub const N_MELS: usize = 80;
+
+struct CachedFbankData {
+    window: Vec<f64>,
+    mel_filters: Vec<Vec<f64>>,
+    fft: Arc<dyn Fft<f64>>,
+}
+
+static CACHED_DATA: OnceLock<CachedFbankData> = OnceLock::new();
+
+fn get_cached_data() -> &'static CachedFbankData {
+    CACHED_DATA.get_or_init(|| {
+        let mut planner = FftPlanner::<f64>::new();
+        CachedFbankData {
+            window: hamming_window(FRAME_LENGTH),
+            mel_filters: mel_filter_bank_kaldi(N_MELS, LOW_FREQ, SAMPLE_RATE as f64 / 2.0),
+            fft: planner.plan_fft_forward(N_FFT),
+        }
+    })
+}
+
+/// Number of frames produced for `n_samples` of audio (no edge padding).
+pub fn num_frames(n_samples: usize) -> usize {
+    if n_samples < FRAME_LENGTH {
+        0
+    } else {
+        1 + (n_samples - FRAME_LENGTH) / FRAME_SHIFT
+    }
+}
+
+/// Compute mean-normalized log mel filterbank features from 16kHz mono audio.
+///
+/// Returns `num_frames(audio.len()) * N_MELS` values in frame-major order.
+pub fn compute_fbank(audio_16k_mono: &[f32]) -> Vec<f32> {
+    let n_frames = num_frames(audio_16k_mono.len());
+    if n_frames == 0 {
+        return Vec::new();
+    }
+
+    let cached = get_cached_data();
+    let mut feats = vec![0.0f32; n_frames * N_MELS];
+    let mut frame = [0.0f64; FRAME_LENGTH];
+    let mut fft_buf = vec![Complex { re: 0.0, im: 0.0 }; N_FFT];
+
+    for frame_idx in 0..n_frames {
+        let start = frame_idx * FRAME_SHIFT;
+        for (dst, src) in frame
+            .iter_mut()
+            .zip(&audio_16k_mono[start..start + FRAME_LENGTH])
+        {
+            *dst = *src as f64 * 32768.0;
+        }
+
+        // Remove DC offset
+        let mean = frame.iter().sum::<f64>() / FRAME_LENGTH as f64;
+        for v in frame.iter_mut() {
+            *v -= mean;
+        }
+
+        // Pre-emphasis (Kaldi replicates the first sample)
+        for i in (1..FRAME_LENGTH).rev() {
+            frame[i] -= PREEMPHASIS * frame[i - 1];
+        }
+        frame[0] -= PREEMPHASIS * frame[0];
+
+        for (i, c) in fft_buf.iter_mut().enumerate() {
+            c.re = if i < FRAME_LENGTH {
+                frame[i] * cached.window[i]
+            } else {
+                0.0
+            };
+            c.im = 0.0;
+        }
+        cached.fft.process(&mut fft_buf);
+
+        let mut power = [0.0f64; N_FREQ];
+        for (p, c) in power.iter_mut().zip(fft_buf.iter()) {
+            *p = c.re * c.re + c.im * c.im;
+        }
+
+        let out = &mut feats[frame_idx * N_MELS..(frame_idx + 1) * N_MELS];
+        for (m, filter) in cached.mel_filters.iter().enumerate() {
+            let energy: f64 = filter.iter().zip(power.iter()).map(|(w, p)| w * p).sum();
+            out[m] = energy.max(f64::EPSILON).ln() as f32;
+        }
+    }
+
+    // Cepstral mean normalization over the utterance
+    for m in 0..N_MELS {
+        let mean = (0..n_frames).map(|t| feats[t * N_MELS + m]).sum::<f32>() / n_frames as f32;
+        for t in 0..n_frames {
+            feats[t * N_MELS + m] -= mean;
+        }
+    }
+
+    feats
+}
+
+fn hamming_window(n: usize) -> Vec<f64> {
+    let denom = (n - 1) as f64;
+    (0..n)
+        .map(|i| 0.54 - 0.46 * ((2.0 * std::f64::consts::PI * i as f64) / denom).cos())
+        .collect()
+}
+
+fn hertz_to_mel_kaldi(freq: f64) -> f64 {
+    1127.0 * (1.0 + freq / 700.0).ln()
+}
+
+/// Triangular filters on the Kaldi mel scale, one row per mel bin over the
+/// `N_FREQ` power-spectrum bins.
+fn mel_filter_bank_kaldi(num_mel_filters: usize, low_freq: f64, high_freq: f64) -> Vec<Vec<f64>> {
+    let mel_low = hertz_to_mel_kaldi(low_freq);
+    let mel_high = hertz_to_mel_kaldi(high_freq);
+    let mel_delta = (mel_high - mel_low) / (num_mel_filters + 1) as f64;
+    let bin_hz = SAMPLE_RATE as f64 / N_FFT as f64;
+
+    (0..num_mel_filters)
+        .map(|m| {
+            let left = mel_low + m as f64 * mel_delta;
+            let center = left + mel_delta;
+            let right = center + mel_delta;
+            (0..N_FREQ)
+                .map(|k| {
+                    let mel = hertz_to_mel_kaldi(k as f64 * bin_hz);
+                    if mel <= left || mel >= right {
+                        0.0
+                    } else if mel <= center {
+                        (mel - left) / (center - left)
+                    } else {
+                        (right - mel) / (right - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, seconds: f32) -> Vec<f32> {
+        let n = (SAMPLE_RATE as f32 * seconds) as usize;
+        (0..n)
+            .map(|i| {
+                0.3 * (2.0 * std::f32::consts::PI * freq * i as f32 / SAMPLE_RATE as f32).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_num_frames() {
+        assert_eq!(num_frames(0), 0);
+        assert_eq!(num_frames(399), 0);
+        assert_eq!(num_frames(400), 1);
+        assert_eq!(num_frames(16_000), 98);
+    }
+
+    #[test]
+    fn test_fbank_shape() {
+        let feats = compute_fbank(&tone(440.0, 1.0));
+        assert_eq!(feats.len(), 98 * N_MELS);
+        assert!(feats.iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_fbank_too_short() {
+        assert!(compute_fbank(&[0.1; 100]).is_empty());
+    }
+
+    #[test]
+    fn test_fbank_is_mean_normalized() {
+        let feats = compute_fbank(&tone(1000.0, 0.5));
+        let n_frames = feats.len() / N_MELS;
+        for m in 0..N_MELS {
+            let mean = (0..n_frames).map(|t| feats[t * N_MELS + m]).sum::<f32>() / n_frames as f32;
+            assert!(mean.abs() < 1e-3, "bin {m} mean {mean}");
+        }
+    }
+
+    #[test]
+    fn test_mel_filters_cover_spectrum() {
+        let filters = mel_filter_bank_kaldi(N_MELS, LOW_FREQ, 8000.0);
+        assert_eq!(filters.len(), N_MELS);
+        for filter in &filters {
+            assert_eq!(filter.len(), N_FREQ);
+            assert!(filter.iter().any(|w| *w > 0.0));
+        }
+    }
+}
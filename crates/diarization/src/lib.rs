@@ -0,0 +1,232 @@
+//! Speaker diarization.
+//!
+//! Extracts a speaker embedding per speech segment with an ONNX model and
+//! clusters the embeddings into speakers: online while a session is live,
+//! and offline (re-clustering all segments) when a session is saved.
+
+mod cluster;
+mod embedder;
+mod fbank;
+
+pub use cluster::{cluster_offline, cosine_similarity, OnlineClusterer};
+pub use embedder::OnnxSpeakerEmbedder;
+
+use std::sync::{Arc, Mutex};
+
+const SAMPLE_RATE: usize = 16_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiarizationError {
+    #[error("failed to load model: {0}")]
+    Model(String),
+    #[error("inference failed: {0}")]
+    Inference(String),
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+}
+
+pub type Result<T> = std::result::Result<T, DiarizationError>;
+
+pub trait SpeakerEmbedder: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn embed(&self, audio_16k_mono: &[f32]) -> Result<Vec<f32>>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiarizationConfig {
+    /// Minimum cosine similarity for two segments to share a speaker.
+    pub threshold: f32,
+    /// Upper bound on distinct speakers.
+    pub max_speakers: usize,
+    /// Segments shorter than this are too unreliable to embed.
+    pub min_segment_ms: u64,
+    /// Longer segments are embedded from their first `max_segment_ms`.
+    pub max_segment_ms: u64,
+}
+
+impl Default for DiarizationConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            max_speakers: 8,
+            min_segment_ms: 500,
+            max_segment_ms: 20_000,
+        }
+    }
+}
+
+/// Embeds segments and assigns them speaker ids.
+pub struct Diarizer {
+    embedder: Arc<dyn SpeakerEmbedder>,
+    config: DiarizationConfig,
+    online: Mutex<OnlineClusterer>,
+}
+
+impl Diarizer {
+    pub fn new(embedder: Arc<dyn SpeakerEmbedder>, config: DiarizationConfig) -> Self {
+        Self {
+            embedder,
+            config,
+            online: Mutex::new(OnlineClusterer::new(config.threshold, config.max_speakers)),
+        }
+    }
+
+    pub fn embedder_name(&self) -> &'static str {
+        self.embedder.name()
+    }
+
+    pub fn config(&self) -> DiarizationConfig {
+        self.config
+    }
+
+    /// Assign a live segment to a speaker.
+    ///
+    /// Returns `None` when the segment is too short to embed.
+    pub fn assign_live(&self, audio_16k_mono: &[f32]) -> Result<Option<i32>> {
+        let Some(embedding) = self.embed_segment(audio_16k_mono)? else {
+            return Ok(None);
+        };
+        let mut online = self
+            .online
+            .lock()
+            .map_err(|_| DiarizationError::Inference("lock poisoned".to_string()))?;
+        Ok(Some(online.assign(&embedding) as i32))
+    }
+
+    /// Forget live speakers (start of a new session).
+    pub fn reset_live(&self) {
+        if let Ok(mut online) = self.online.lock() {
+            online.reset();
+        }
+    }
+
+    /// Re-cluster a whole recording.
+    ///
+    /// `spans` are `(start_ms, end_ms)` segment bounds into `audio_16k_mono`.
+    /// Returns one speaker per span; spans too short to embed get the speaker
+    /// of the nearest embedded span.
+    pub fn diarize(
+        &self,
+        audio_16k_mono: &[f32],
+        spans: &[(u64, u64)],
+    ) -> Result<Vec<Option<i32>>> {
+        let mut embedded = Vec::new();
+        let mut embeddings = Vec::new();
+        for (idx, &(start_ms, end_ms)) in spans.iter().enumerate() {
+            let start = ms_to_samples(start_ms).min(audio_16k_mono.len());
+            let end = ms_to_samples(end_ms).clamp(start, audio_16k_mono.len());
+            if let Some(embedding) = self.embed_segment(&audio_16k_mono[start..end])? {
+                embedded.push(idx);
+                embeddings.push(embedding);
+            }
+        }
+
+        let labels = cluster_offline(&embeddings, self.config.threshold, self.config.max_speakers);
+        let mut speakers = vec![None; spans.len()];
+        for (&idx, &label) in embedded.iter().zip(labels.iter()) {
+            speakers[idx] = Some(label as i32);
+        }
+        Ok(fill_from_nearest(&speakers))
+    }
+
+    fn embed_segment(&self, audio: &[f32]) -> Result<Option<Vec<f32>>> {
+        if audio.len() < ms_to_samples(self.config.min_segment_ms) {
+            return Ok(None);
+        }
+        let audio = &audio[..audio.len().min(ms_to_samples(self.config.max_segment_ms))];
+        self.embedder.embed(audio).map(Some)
+    }
+}
+
+fn ms_to_samples(ms: u64) -> usize {
+    (ms as usize).saturating_mul(SAMPLE_RATE) / 1000
+}
+
+/// Give unlabeled entries the label of the closest labeled neighbour
+/// (preferring the previous one on ties).
+fn fill_from_nearest(labels: &[Option<i32>]) -> Vec<Option<i32>> {
+    (0..labels.len())
+        .map(|i| {
+            if labels[i].is_some() {
+                return labels[i];
+            }
+            let before = (0..i).rev().find_map(|j| labels[j].map(|l| (i - j, l)));
+            let after = (i + 1..labels.len()).find_map(|j| labels[j].map(|l| (j - i, l)));
+            match (before, after) {
+                (Some((db, lb)), Some((da, _))) if db <= da => Some(lb),
+                (_, Some((_, la))) => Some(la),
+                (Some((_, lb)), None) => Some(lb),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fake embedder: the sign of the mean sample picks the "speaker".
+    struct SignEmbedder;
+
+    impl SpeakerEmbedder for SignEmbedder {
+        fn name(&self) -> &'static str {
+            "sign"
+        }
+
+        fn embed(&self, audio: &[f32]) -> Result<Vec<f32>> {
+            let mean = audio.iter().sum::<f32>() / audio.len() as f32;
+            Ok(if mean >= 0.0 {
+                vec![1.0, 0.0]
+            } else {
+                vec![0.0, 1.0]
+            })
+        }
+    }
+
+    fn diarizer() -> Diarizer {
+        Diarizer::new(Arc::new(SignEmbedder), DiarizationConfig::default())
+    }
+
+    #[test]
+    fn test_assign_live() {
+        let diarizer = diarizer();
+        assert_eq!(diarizer.assign_live(&[0.5; 16_000]).unwrap(), Some(0));
+        assert_eq!(diarizer.assign_live(&[-0.5; 16_000]).unwrap(), Some(1));
+        assert_eq!(diarizer.assign_live(&[0.5; 16_000]).unwrap(), Some(0));
+        assert_eq!(diarizer.assign_live(&[0.5; 100]).unwrap(), None);
+
+        diarizer.reset_live();
+        assert_eq!(diarizer.assign_live(&[-0.5; 16_000]).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_diarize_spans() {
+        let mut audio = vec![0.5f32; 16_000];
+        audio.extend(vec![-0.5f32; 16_000]);
+        audio.extend(vec![0.5f32; 16_000]);
+
+        let spans = [(0, 1000), (1000, 1100), (1100, 2000), (2000, 3000)];
+        let speakers = diarizer().diarize(&audio, &spans).unwrap();
+        assert_eq!(speakers, vec![Some(0), Some(0), Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn test_diarize_spans_past_end() {
+        let audio = vec![0.5f32; 16_000];
+        let speakers = diarizer()
+            .diarize(&audio, &[(0, 1000), (5000, 6000)])
+            .unwrap();
+        assert_eq!(speakers, vec![Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn test_fill_from_nearest() {
+        let filled = fill_from_nearest(&[None, Some(1), None, None, Some(2), None]);
+        assert_eq!(
+            filled,
+            vec![Some(1), Some(1), Some(1), Some(2), Some(2), Some(2)]
+        );
+        assert_eq!(fill_from_nearest(&[None, None]), vec![None, None]);
+    }
+}
@@ -61,7 +61,8 @@ pub struct ActivityContent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 
-    /// Recording duration in milliseconds.
+    /// Duration in milliseconds: a recording's length, or how long a
+    /// command ran.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<u64>,
 
@@ -27,6 +27,113 @@ pub struct StreamCommitEvent {
     /// Timestamp in milliseconds since epoch.
     #[serde(default)]
     pub ts_ms: Option<i64>,
+    /// Commit id, referenced by a later [`CommitRevisedEvent`].
+    #[serde(default)]
+    pub commit_id: Option<u64>,
+    /// Whether a batch refiner is re-decoding this commit (two-pass mode).
+    #[serde(default)]
+    pub revision_pending: bool,
+    /// The commit starts after a pause (or the start of the recording).
+    #[serde(default)]
+    pub after_pause: bool,
+    /// The commit ends at a speech pause.
+    #[serde(default)]
+    pub at_pause: bool,
+    /// The commit closes a semantic turn.
+    #[serde(default)]
+    pub turn_end: bool,
+}
+
+/// A word-level change to the live transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WordEventPayload {
+    /// A new volatile word, placed after the volatile word `after`.
+    Inserted {
+        id: u64,
+        #[serde(default)]
+        after: Option<u64>,
+        text: String,
+        #[serde(default)]
+        start_ms: u64,
+        #[serde(default)]
+        end_ms: u64,
+    },
+    /// A volatile word whose text changed.
+    Revised {
+        id: u64,
+        text: String,
+        #[serde(default)]
+        start_ms: u64,
+        #[serde(default)]
+        end_ms: u64,
+    },
+    /// A volatile word dropped by a later decode.
+    Removed { id: u64 },
+    /// A word became final.
+    Committed {
+        id: u64,
+        text: String,
+        #[serde(default)]
+        start_ms: u64,
+        #[serde(default)]
+        end_ms: u64,
+    },
+    /// A paragraph break after the last committed word.
+    ParagraphBreak,
+}
+
+/// Event emitted with the word-level changes of a streaming result.
+///
+/// Producers: stt-worker plugin
+/// Consumers: tools plugin (live dictation), frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordEventsEvent {
+    /// Changes, in the order they apply.
+    pub events: Vec<WordEventPayload>,
+    /// Timestamp in milliseconds since epoch.
+    #[serde(default)]
+    pub ts_ms: Option<i64>,
+}
+
+/// Event emitted when a two-pass refiner replaces the text of an utterance.
+///
+/// Producers: stt-worker plugin
+/// Consumers: tools plugin (router), frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitRevisedEvent {
+    /// Id of the first [`StreamCommitEvent`] being revised.
+    pub commit_id: u64,
+    /// Ids of all commits of the revised utterance, oldest first; `text`
+    /// replaces their combined text. Empty means just `commit_id`.
+    #[serde(default)]
+    pub commit_ids: Vec<u64>,
+    /// Streamed text of the revised commits.
+    #[serde(default)]
+    pub original_text: String,
+    /// Refined text.
+    pub text: String,
+    /// Timestamp in milliseconds since epoch.
+    #[serde(default)]
+    pub ts_ms: Option<i64>,
+}
+
+/// Event emitted when the live diarizer assigns a speaker to an utterance.
+///
+/// Embedding runs off the commit path, so this follows the utterance's
+/// [`StreamCommitEvent`]s.
+///
+/// Producers: stt-worker plugin
+/// Consumers: frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSpeakerEvent {
+    /// Ids of the utterance's commits, oldest first.
+    pub commit_ids: Vec<u64>,
+    /// Live speaker id.
+    pub speaker: i32,
+    /// Timestamp in milliseconds since epoch.
+    #[serde(default)]
+    pub ts_ms: Option<i64>,
 }
 
 /// Event emitted when system context changes.
@@ -97,10 +204,31 @@ pub struct ToolErrorEvent {
     pub error: String,
 }
 
+/// Event emitted when a persisted setting changes (through a command or
+/// an edit to the config file).
+///
+/// Producers: storage settings store (owned by the stt-worker plugin)
+/// Consumers: stt-worker plugin, tools plugin (router), frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsChangedEvent {
+    /// Setting key ("language", "vad", "turn", "router").
+    pub key: String,
+    /// New value.
+    pub value: serde_json::Value,
+}
+
 /// Event names as constants to prevent typos.
 pub mod event_names {
     /// STT stream commit event.
     pub const STT_STREAM_COMMIT: &str = "stt:stream_commit";
+    /// STT commit revised by the two-pass refiner.
+    pub const STT_COMMIT_REVISED: &str = "stt:commit_revised";
+    /// Live speaker of an utterance.
+    pub const STT_COMMIT_SPEAKER: &str = "stt:commit_speaker";
+    /// Word-level edits to the live transcript.
+    pub const STT_WORD_EVENTS: &str = "stt:word_events";
+    /// Live dictation stopped (focus moved, aborted, or input failed).
+    pub const LIVE_DICTATION_STOPPED: &str = "tools:live_dictation_stopped";
     /// Context changed event.
     pub const CONTEXT_CHANGED: &str = "context:changed";
     /// Action proposed event.
@@ -109,6 +237,8 @@ pub mod event_names {
     pub const ROUTER_STATUS: &str = "tools:router_status";
     /// Tool error event.
     pub const TOOL_ERROR: &str = "tools:tool_error";
+    /// Persisted setting changed.
+    pub const SETTINGS_CHANGED: &str = "settings:changed";
 }
 
 #[cfg(test)]
@@ -129,5 +259,38 @@ mod tests {
         let event: StreamCommitEvent = serde_json::from_str(json).unwrap();
         assert_eq!(event.text, "hello");
         assert_eq!(event.ts_ms, None);
+        assert_eq!(event.commit_id, None);
+        assert!(!event.revision_pending);
+    }
+
+    #[test]
+    fn test_commit_speaker_deserialize() {
+        let json = r#"{"commit_ids": [3, 4], "speaker": 1, "ts_ms": 5}"#;
+        let event: CommitSpeakerEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.commit_ids, vec![3, 4]);
+        assert_eq!(event.speaker, 1);
+    }
+
+    #[test]
+    fn test_word_events_deserialize() {
+        let json = r#"{"events": [
+            {"kind": "inserted", "id": 4, "after": null, "text": "hi", "start_ms": 0, "end_ms": 200},
+            {"kind": "removed", "id": 2},
+            {"kind": "paragraph_break"}
+        ], "ts_ms": 1}"#;
+        let event: WordEventsEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.events.len(), 3);
+        assert_eq!(event.events[1], WordEventPayload::Removed { id: 2 });
+        assert_eq!(event.events[2], WordEventPayload::ParagraphBreak);
+    }
+
+    #[test]
+    fn test_commit_revised_deserialize() {
+        let json = r#"{"commit_id": 6, "commit_ids": [6, 7], "original_text": "eye scream", "text": "ice cream", "model": "whisper-onnx-small", "ts_ms": 1}"#;
+        let event: CommitRevisedEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.commit_id, 6);
+        assert_eq!(event.commit_ids, vec![6, 7]);
+        assert_eq!(event.original_text, "eye scream");
+        assert_eq!(event.text, "ice cream");
     }
 }
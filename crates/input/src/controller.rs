@@ -51,6 +51,21 @@ impl Default for TypeOptions {
     }
 }
 
+/// A step in an editing key sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Type text at the cursor, replacing any selection.
+    Type(String),
+    /// Press Backspace this many times.
+    Backspace(usize),
+    /// Press Return this many times.
+    Return(usize),
+    /// Extend the selection this many characters to the left (Shift+Left).
+    SelectLeft(usize),
+    /// Collapse the selection to its end (Right).
+    Deselect,
+}
+
 /// Result of a typing operation.
 #[derive(Debug)]
 pub struct TypeResult {
@@ -286,6 +301,71 @@ impl InputController {
         )
     }
 
+    /// Send an editing key sequence (backspaces, selections, typing).
+    ///
+    /// Used for voice editing commands, which act on text already typed.
+    /// Typing steps honour `options`; key presses are rate limited by
+    /// `options.delay_ms` and stop on abort.
+    ///
+    /// # Errors
+    ///
+    /// - `InputError::Aborted` if the abort flag was set
+    /// - `InputError::KeyFailed` if a key press fails
+    /// - `InputError::TypeFailed` or `InputError::FocusChanged` for typing steps
+    pub fn send_keys(
+        &mut self,
+        actions: &[KeyAction],
+        options: TypeOptions,
+    ) -> Result<(), InputError> {
+        self.abort_flag.store(false, Ordering::SeqCst);
+
+        if options.preview {
+            tracing::debug!(?actions, "Preview key sequence");
+            return Ok(());
+        }
+
+        for action in actions {
+            match action {
+                KeyAction::Type(text) => {
+                    if options.smart_mode && should_use_paste(text, options.active_app.as_deref()) {
+                        self.type_via_paste(text)?;
+                    } else {
+                        self.type_chars(text, &options)?;
+                    }
+                }
+                KeyAction::Backspace(count) => self.press(Key::Backspace, *count, &options)?,
+                KeyAction::Return(count) => self.press(Key::Return, *count, &options)?,
+                KeyAction::SelectLeft(count) => {
+                    self.enigo
+                        .key(Key::Shift, Direction::Press)
+                        .map_err(|e| InputError::KeyFailed(e.to_string()))?;
+                    let result = self.press(Key::LeftArrow, *count, &options);
+                    self.enigo
+                        .key(Key::Shift, Direction::Release)
+                        .map_err(|e| InputError::KeyFailed(e.to_string()))?;
+                    result?;
+                }
+                KeyAction::Deselect => self.press(Key::RightArrow, 1, &options)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Click a key `count` times with rate limiting.
+    fn press(&mut self, key: Key, count: usize, options: &TypeOptions) -> Result<(), InputError> {
+        for _ in 0..count {
+            if self.abort_flag.load(Ordering::SeqCst) {
+                return Err(InputError::Aborted);
+            }
+            self.enigo
+                .key(key, Direction::Click)
+                .map_err(|e| InputError::KeyFailed(e.to_string()))?;
+            if options.delay_ms > 0 {
+                thread::sleep(Duration::from_millis(options.delay_ms));
+            }
+        }
+        Ok(())
+    }
 
     /// Simulate a paste operation (Cmd+V on macOS, Ctrl+V elsewhere).
     ///
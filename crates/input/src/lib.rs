@@ -30,7 +30,7 @@ mod macos;
 use std::sync::Arc;
 
 pub use controller::{
-    InputController, TypeOptions, TypeResult, PREFER_PASTE_APPS, SMART_PASTE_THRESHOLD,
+    InputController, KeyAction, TypeOptions, TypeResult, PREFER_PASTE_APPS, SMART_PASTE_THRESHOLD,
 };
 pub use error::InputError;
 pub use panic_hotkey::{start_panic_hotkey_listener, PanicHotkeyHandle};
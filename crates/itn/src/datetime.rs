@@ -0,0 +1,395 @@
+//! Times of day and calendar dates.
+
+use crate::lexicon::{Language, Lexicon, NumberWord};
+use crate::number::{parse_cardinal, parse_ordinal};
+use crate::{Match, SpanKind};
+
+/// English words after which "three thirty" is read as a time.
+const EN_TIME_CONTEXT: &[&str] = &["at", "by", "until", "till", "around", "from"];
+
+/// An hour word (1-12).
+fn hour(lex: &Lexicon, word: &str) -> Option<u64> {
+    match lex.number(word)? {
+        NumberWord::Unit(h) | NumberWord::Teen(h) if (1..=12).contains(&h) => Some(h),
+        _ => None,
+    }
+}
+
+/// The hour before `h` on a 12-hour clock.
+fn previous_hour(h: u64) -> u64 {
+    if h == 1 {
+        12
+    } else {
+        h - 1
+    }
+}
+
+fn clock(h: u64, m: u64) -> String {
+    format!("{h}:{m:02}")
+}
+
+/// Minutes spoken as a number (1-59).
+fn minutes(lex: &Lexicon, words: &[&str]) -> Option<(u64, usize)> {
+    parse_cardinal(lex, words)
+        .filter(|c| (1..60).contains(&c.value))
+        .map(|c| (c.value, c.len))
+}
+
+pub(crate) fn match_time(lex: &Lexicon, words: &[&str], prev: Option<&str>) -> Option<Match> {
+    let (text, len) = match lex.language {
+        Language::English => english_time(lex, words, prev)?,
+        Language::Spanish => romance_time(lex, words, prev, &SPANISH_CLOCK)?,
+        Language::Catalan => catalan_quarters(lex, words)
+            .or_else(|| romance_time(lex, words, prev, &CATALAN_CLOCK))?,
+    };
+    Some(Match {
+        len,
+        text,
+        kind: SpanKind::Time,
+    })
+}
+
+/// "three thirty pm", "at ten oh five", "seven o'clock".
+///
+/// Hour and minutes alone are only a time after a word like "at"; with
+/// "am"/"pm" or "o'clock" they always are.
+fn english_time(lex: &Lexicon, words: &[&str], prev: Option<&str>) -> Option<(String, usize)> {
+    let h = hour(lex, words.first()?)?;
+    let mut len = 1;
+    let mut m = None;
+
+    match &words[1..] {
+        ["o'clock" | "oclock", ..] => return Some((clock(h, 0), 2)),
+        ["o", "clock", ..] => return Some((clock(h, 0), 3)),
+        ["oh" | "o", digit, ..] => {
+            if let Some(NumberWord::Unit(d @ 1..=9)) = lex.number(digit) {
+                m = Some(d);
+                len = 3;
+            }
+        }
+        rest => {
+            if let Some((minutes, n)) = minutes(lex, rest).filter(|(m, _)| *m >= 10) {
+                m = Some(minutes);
+                len += n;
+            }
+        }
+    }
+
+    let meridiem = match &words[len..] {
+        ["am", ..] => Some(("AM", 1)),
+        ["pm", ..] => Some(("PM", 1)),
+        ["a", "m", ..] => Some(("AM", 2)),
+        ["p", "m", ..] => Some(("PM", 2)),
+        _ => None,
+    };
+    let in_context = prev.is_some_and(|p| EN_TIME_CONTEXT.contains(&p));
+
+    let mut text = match (m, meridiem) {
+        (Some(m), _) if meridiem.is_some() || in_context => clock(h, m),
+        (None, Some(_)) => h.to_string(),
+        _ => return None,
+    };
+    if let Some((suffix, n)) = meridiem {
+        text.push(' ');
+        text.push_str(suffix);
+        len += n;
+    }
+    Some((text, len))
+}
+
+/// Clock words of a language that reads times as "las tres y media".
+struct ClockWords {
+    articles: &'static [&'static str],
+    past: &'static str,
+    to: &'static str,
+    half: &'static str,
+    quarter: &'static str,
+    sharp: [&'static str; 2],
+}
+
+const SPANISH_CLOCK: ClockWords = ClockWords {
+    articles: &["la", "las"],
+    past: "y",
+    to: "menos",
+    half: "media",
+    quarter: "cuarto",
+    sharp: ["en", "punto"],
+};
+
+const CATALAN_CLOCK: ClockWords = ClockWords {
+    articles: &["la", "les"],
+    past: "i",
+    to: "menys",
+    half: "mitja",
+    quarter: "quart",
+    sharp: ["en", "punt"],
+};
+
+/// "(las) tres y media", "(les) dues menys deu", "(la) una en punt".
+///
+/// The article must precede the hour, and the hour needs a minute part, so
+/// "las tres" on its own is left alone.
+fn romance_time(
+    lex: &Lexicon,
+    words: &[&str],
+    prev: Option<&str>,
+    clock_words: &ClockWords,
+) -> Option<(String, usize)> {
+    if !prev.is_some_and(|p| clock_words.articles.contains(&p)) {
+        return None;
+    }
+    let h = hour(lex, words.first()?)?;
+    let direction = *words.get(1)?;
+    let rest = &words[2..];
+
+    if words.get(1..3) == Some(&clock_words.sharp[..]) {
+        return Some((clock(h, 0), 3));
+    }
+    let (m, n) = match rest.first() {
+        Some(&w) if w == clock_words.half => (30, 1),
+        Some(&w) if w == clock_words.quarter => (15, 1),
+        _ => minutes(lex, rest)?,
+    };
+
+    if direction == clock_words.past {
+        Some((clock(h, m), 2 + n))
+    } else if direction == clock_words.to && m != 30 {
+        Some((clock(previous_hour(h), 60 - m), 2 + n))
+    } else {
+        None
+    }
+}
+
+/// Catalan quarter reckoning: "un quart de cinc" is 4:15, "dos quarts de
+/// cinc" 4:30 and "tres quarts d'una" 12:45.
+fn catalan_quarters(lex: &Lexicon, words: &[&str]) -> Option<(String, usize)> {
+    let quarters = match *words.first()? {
+        "un" | "u" => 1,
+        "dos" | "dues" => 2,
+        "tres" => 3,
+        _ => return None,
+    };
+    if !matches!(words.get(1), Some(&("quart" | "quarts"))) {
+        return None;
+    }
+    let (h, len) = match &words[2..] {
+        ["de", h, ..] => (hour(lex, h)?, 4),
+        [elided, ..] => (hour(lex, elided.strip_prefix("d'")?)?, 3),
+        [] => return None,
+    };
+    Some((clock(previous_hour(h), 15 * quarters), len))
+}
+
+pub(crate) fn match_date(lex: &Lexicon, words: &[&str]) -> Option<Match> {
+    let (text, len) = match lex.language {
+        Language::English => {
+            english_month_first(lex, words).or_else(|| english_day_first(lex, words))?
+        }
+        Language::Spanish | Language::Catalan => romance_date(lex, words)?,
+    };
+    Some(Match {
+        len,
+        text,
+        kind: SpanKind::Date,
+    })
+}
+
+/// Day of the month, spoken as an ordinal or a cardinal.
+fn day(lex: &Lexicon, words: &[&str]) -> Option<(u64, usize)> {
+    let (value, len) = match parse_ordinal(lex, words) {
+        Some(ordinal) => (ordinal.value, ordinal.len),
+        None => {
+            let cardinal = parse_cardinal(lex, words)?;
+            (cardinal.value, cardinal.len)
+        }
+    };
+    (1..=31).contains(&value).then_some((value, len))
+}
+
+/// A year: "two thousand twenty four", "nineteen ninety", "twenty oh five".
+pub(crate) fn year(lex: &Lexicon, words: &[&str]) -> Option<(u64, usize)> {
+    let cardinal = parse_cardinal(lex, words)?;
+    if (1000..3000).contains(&cardinal.value) {
+        return Some((cardinal.value, cardinal.len));
+    }
+    if lex.language != Language::English || cardinal.len != 1 {
+        return None;
+    }
+
+    // English reads most years as two pairs of digits.
+    let century = cardinal.value;
+    if !(10..30).contains(&century) {
+        return None;
+    }
+    match &words[1..] {
+        ["oh", digit, ..] => match lex.number(digit)? {
+            NumberWord::Unit(d @ 1..=9) => Some((century * 100 + d, 3)),
+            _ => None,
+        },
+        rest => {
+            let tail = parse_cardinal(lex, rest).filter(|c| (10..100).contains(&c.value))?;
+            Some((century * 100 + tail.value, 1 + tail.len))
+        }
+    }
+}
+
+/// "march fifth", "june twenty first twenty twenty five".
+fn english_month_first(lex: &Lexicon, words: &[&str]) -> Option<(String, usize)> {
+    let month = *words.first()?;
+    lex.month(month)?;
+    let (day, day_len) = day(lex, &words[1..])?;
+    let mut len = 1 + day_len;
+    let mut text = format!("{} {day}", lex.month_text(month));
+    if let Some((year, year_len)) = year(lex, &words[len..]) {
+        text.push_str(&format!(", {year}"));
+        len += year_len;
+    }
+    Some((text, len))
+}
+
+/// "the fifth of march".
+fn english_day_first(lex: &Lexicon, words: &[&str]) -> Option<(String, usize)> {
+    if words.first() != Some(&"the") {
+        return None;
+    }
+    let ordinal = parse_ordinal(lex, &words[1..]).filter(|o| (1..=31).contains(&o.value))?;
+    let mut len = 1 + ordinal.len;
+    if words.get(len) != Some(&"of") {
+        return None;
+    }
+    let month = *words.get(len + 1)?;
+    lex.month(month)?;
+    len += 2;
+    let mut text = format!("{} {}", lex.month_text(month), ordinal.value);
+    if let Some((year, year_len)) = year(lex, &words[len..]) {
+        text.push_str(&format!(", {year}"));
+        len += year_len;
+    }
+    Some((text, len))
+}
+
+/// "cinco de marzo de dos mil veinticuatro", "primer d'abril".
+fn romance_date(lex: &Lexicon, words: &[&str]) -> Option<(String, usize)> {
+    let (day, mut len) = match lex.ordinal(words.first()?) {
+        Some((1, _)) => (1, 1),
+        _ => {
+            let cardinal = parse_cardinal(lex, words)?;
+            (cardinal.value, cardinal.len)
+        }
+    };
+    if !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let month = match &words[len..] {
+        ["de", month, ..] if lex.month(month).is_some() => {
+            len += 2;
+            format!("de {month}")
+        }
+        [month, ..] if month.starts_with("d'") && lex.month(month).is_some() => {
+            len += 1;
+            month.to_string()
+        }
+        _ => return None,
+    };
+    let mut text = format!("{day} {month}");
+
+    if let [preposition @ ("de" | "del"), rest @ ..] = &words[len..] {
+        if let Some(year) = parse_cardinal(lex, rest).filter(|c| (1000..3000).contains(&c.value)) {
+            text.push_str(&format!(" {preposition} {}", year.value));
+            len += 1 + year.len;
+        }
+    }
+    Some((text, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(language: Language, prev: Option<&str>, text: &str) -> Option<(String, usize)> {
+        let lex = Lexicon::new(language);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match_time(&lex, &words, prev).map(|m| (m.text, m.len))
+    }
+
+    fn date(language: Language, text: &str) -> Option<(String, usize)> {
+        let lex = Lexicon::new(language);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match_date(&lex, &words).map(|m| (m.text, m.len))
+    }
+
+    #[test]
+    fn test_english_times() {
+        let en = Language::English;
+        assert_eq!(
+            time(en, Some("at"), "three thirty on"),
+            Some(("3:30".into(), 2))
+        );
+        assert_eq!(
+            time(en, None, "ten oh five p m"),
+            Some(("10:05 PM".into(), 5))
+        );
+        assert_eq!(time(en, None, "seven pm"), Some(("7 PM".into(), 2)));
+        assert_eq!(time(en, None, "six o'clock"), Some(("6:00".into(), 2)));
+        assert_eq!(time(en, None, "three thirty"), None);
+        assert_eq!(time(en, Some("at"), "three"), None);
+    }
+
+    #[test]
+    fn test_spanish_and_catalan_times() {
+        assert_eq!(
+            time(Language::Spanish, Some("las"), "tres y media"),
+            Some(("3:30".into(), 3))
+        );
+        assert_eq!(
+            time(Language::Spanish, Some("la"), "una menos cuarto"),
+            Some(("12:45".into(), 3))
+        );
+        assert_eq!(time(Language::Spanish, None, "tres y media"), None);
+        assert_eq!(
+            time(Language::Catalan, Some("les"), "deu i vint"),
+            Some(("10:20".into(), 3))
+        );
+        assert_eq!(
+            time(Language::Catalan, None, "tres quarts d'una"),
+            Some(("12:45".into(), 3))
+        );
+        assert_eq!(
+            time(Language::Catalan, None, "un quart de cinc"),
+            Some(("4:15".into(), 4))
+        );
+    }
+
+    #[test]
+    fn test_english_dates() {
+        let en = Language::English;
+        assert_eq!(date(en, "march fifth"), Some(("March 5".into(), 2)));
+        assert_eq!(
+            date(en, "june twenty first twenty twenty five"),
+            Some(("June 21, 2025".into(), 6))
+        );
+        assert_eq!(
+            date(en, "the third of may nineteen ninety"),
+            Some(("May 3, 1990".into(), 6))
+        );
+        assert_eq!(date(en, "may i ask"), None);
+    }
+
+    #[test]
+    fn test_spanish_and_catalan_dates() {
+        assert_eq!(
+            date(Language::Spanish, "cinco de marzo de dos mil veinticuatro"),
+            Some(("5 de marzo de 2024".into(), 7))
+        );
+        assert_eq!(
+            date(Language::Spanish, "primero de mayo"),
+            Some(("1 de mayo".into(), 3))
+        );
+        assert_eq!(
+            date(Language::Catalan, "vint-i-u d'abril"),
+            Some(("21 d'abril".into(), 2))
+        );
+        assert_eq!(date(Language::Catalan, "tres de les"), None);
+    }
+}
@@ -0,0 +1,623 @@
+//! Per-language number words, ordinals and month names.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Language {
+    English,
+    Spanish,
+    Catalan,
+}
+
+impl Language {
+    /// Parse an ISO 639-1 code, ignoring any region ("en-US").
+    pub(crate) fn from_code(code: &str) -> Option<Self> {
+        let primary = code
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::English),
+            "es" => Some(Self::Spanish),
+            "ca" => Some(Self::Catalan),
+            _ => None,
+        }
+    }
+}
+
+/// How a number word combines with its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NumberWord {
+    /// 0-9.
+    Unit(u64),
+    /// 10-99 in a single word that takes no unit after it ("eleven",
+    /// "twenty-five", "veintiuno").
+    Teen(u64),
+    /// 20, 30, ... 90.
+    Tens(u64),
+    /// A whole hundreds word ("ciento", "doscientos", "dos-cents").
+    Hundreds(u64),
+    /// English "hundred", which multiplies the words before it.
+    Hundred,
+    /// thousand, million, ...
+    Scale(u64),
+}
+
+/// Grammatical form of an ordinal, which selects its written suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Gender {
+    Neutral,
+    Masculine,
+    Feminine,
+    /// Spanish "primer"/"tercer" before a noun.
+    Apocopated,
+}
+
+const EN_UNITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const EN_TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const EN_TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const EN_UNIT_ORDINALS: [&str; 9] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+];
+const EN_TEEN_ORDINALS: [&str; 10] = [
+    "tenth",
+    "eleventh",
+    "twelfth",
+    "thirteenth",
+    "fourteenth",
+    "fifteenth",
+    "sixteenth",
+    "seventeenth",
+    "eighteenth",
+    "nineteenth",
+];
+const EN_TENS_ORDINALS: [&str; 8] = [
+    "twentieth",
+    "thirtieth",
+    "fortieth",
+    "fiftieth",
+    "sixtieth",
+    "seventieth",
+    "eightieth",
+    "ninetieth",
+];
+const EN_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const ES_MONTHS: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+
+const CA_UNITS: [&[&str]; 10] = [
+    &["zero"],
+    &["u", "un", "una"],
+    &["dos", "dues"],
+    &["tres"],
+    &["quatre"],
+    &["cinc"],
+    &["sis"],
+    &["set"],
+    &["vuit"],
+    &["nou"],
+];
+const CA_TEENS: [&str; 10] = [
+    "deu", "onze", "dotze", "tretze", "catorze", "quinze", "setze", "disset", "divuit", "dinou",
+];
+const CA_TENS: [&str; 8] = [
+    "vint",
+    "trenta",
+    "quaranta",
+    "cinquanta",
+    "seixanta",
+    "setanta",
+    "vuitanta",
+    "noranta",
+];
+/// Masculine unit ordinals as they appear in compounds ("vint-i-cinquè").
+const CA_COMPOUND_ORDINALS: [&str; 9] = [
+    "unè", "dosè", "tresè", "quatrè", "cinquè", "sisè", "setè", "vuitè", "novè",
+];
+const CA_MONTHS: [&str; 12] = [
+    "gener", "febrer", "març", "abril", "maig", "juny", "juliol", "agost", "setembre", "octubre",
+    "novembre", "desembre",
+];
+
+/// Words and conventions for one language.
+#[derive(Debug)]
+pub(crate) struct Lexicon {
+    pub language: Language,
+    numbers: HashMap<String, NumberWord>,
+    ordinals: HashMap<String, (u64, Gender)>,
+    months: HashMap<String, u32>,
+    /// Joins parts of a number: "and" after hundreds in English, "y"/"i"
+    /// between tens and units in Spanish and Catalan.
+    pub conjunction: &'static str,
+    /// Introduces the fractional part of a decimal.
+    pub point: &'static str,
+    /// Number words that stay spelled out unless part of a larger expression.
+    ambiguous: &'static [&'static str],
+}
+
+impl Lexicon {
+    pub(crate) fn new(language: Language) -> Self {
+        match language {
+            Language::English => Self::english(),
+            Language::Spanish => Self::spanish(),
+            Language::Catalan => Self::catalan(),
+        }
+    }
+
+    pub(crate) fn number(&self, word: &str) -> Option<NumberWord> {
+        self.numbers.get(word).copied()
+    }
+
+    pub(crate) fn ordinal(&self, word: &str) -> Option<(u64, Gender)> {
+        self.ordinals.get(word).copied()
+    }
+
+    /// Month number (1-12) of a month name, including Catalan elided forms
+    /// ("d'abril").
+    pub(crate) fn month(&self, word: &str) -> Option<u32> {
+        self.months.get(word).copied()
+    }
+
+    pub(crate) fn is_ambiguous(&self, word: &str) -> bool {
+        self.ambiguous.contains(&word)
+    }
+
+    /// Written form of a month name.
+    pub(crate) fn month_text(&self, word: &str) -> String {
+        match self.language {
+            Language::English => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            Language::Spanish | Language::Catalan => word.to_string(),
+        }
+    }
+
+    pub(crate) fn decimal_separator(&self) -> char {
+        match self.language {
+            Language::English => '.',
+            Language::Spanish | Language::Catalan => ',',
+        }
+    }
+
+    /// Digits of an integer, grouped by thousands from five digits up so
+    /// that years and four-digit amounts stay compact.
+    pub(crate) fn format_integer(&self, value: u64) -> String {
+        let digits = value.to_string();
+        if value < 10_000 {
+            return digits;
+        }
+        let separator = match self.language {
+            Language::English => ',',
+            Language::Spanish | Language::Catalan => '.',
+        };
+        let head = match digits.len() % 3 {
+            0 => 3,
+            n => n,
+        };
+        let mut out = digits[..head].to_string();
+        for group in digits.as_bytes()[head..].chunks(3) {
+            out.push(separator);
+            out.push_str(std::str::from_utf8(group).unwrap_or_default());
+        }
+        out
+    }
+
+    pub(crate) fn format_ordinal(&self, value: u64, gender: Gender) -> String {
+        let suffix = match self.language {
+            Language::English => match (value % 10, value % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            },
+            Language::Spanish => match gender {
+                Gender::Feminine => ".ª",
+                Gender::Apocopated => ".er",
+                Gender::Masculine | Gender::Neutral => ".º",
+            },
+            Language::Catalan => match (gender, value) {
+                (Gender::Feminine, _) => "a",
+                (_, 1 | 3) => "r",
+                (_, 2) => "n",
+                (_, 4) => "t",
+                _ => "è",
+            },
+        };
+        format!("{value}{suffix}")
+    }
+
+    fn english() -> Self {
+        let mut numbers = HashMap::new();
+        let mut ordinals = HashMap::new();
+
+        for (v, word) in EN_UNITS.iter().enumerate() {
+            numbers.insert(word.to_string(), NumberWord::Unit(v as u64));
+        }
+        for (v, word) in EN_TEENS.iter().enumerate() {
+            numbers.insert(word.to_string(), NumberWord::Teen(10 + v as u64));
+        }
+        for (t, tens) in EN_TENS.iter().enumerate() {
+            let tens_value = 20 + 10 * t as u64;
+            numbers.insert(tens.to_string(), NumberWord::Tens(tens_value));
+            for u in 1..10 {
+                numbers.insert(
+                    format!("{tens}-{}", EN_UNITS[u]),
+                    NumberWord::Teen(tens_value + u as u64),
+                );
+                ordinals.insert(
+                    format!("{tens}-{}", EN_UNIT_ORDINALS[u - 1]),
+                    (tens_value + u as u64, Gender::Neutral),
+                );
+            }
+        }
+        numbers.insert("hundred".to_string(), NumberWord::Hundred);
+        for (word, scale) in [
+            ("thousand", 1_000),
+            ("million", 1_000_000),
+            ("billion", 1_000_000_000),
+            ("trillion", 1_000_000_000_000),
+        ] {
+            numbers.insert(word.to_string(), NumberWord::Scale(scale));
+        }
+
+        for (v, word) in EN_UNIT_ORDINALS.iter().enumerate() {
+            ordinals.insert(word.to_string(), (1 + v as u64, Gender::Neutral));
+        }
+        for (v, word) in EN_TEEN_ORDINALS.iter().enumerate() {
+            ordinals.insert(word.to_string(), (10 + v as u64, Gender::Neutral));
+        }
+        for (t, word) in EN_TENS_ORDINALS.iter().enumerate() {
+            ordinals.insert(word.to_string(), (20 + 10 * t as u64, Gender::Neutral));
+        }
+        for (word, value) in [
+            ("hundredth", 100),
+            ("thousandth", 1_000),
+            ("millionth", 1_000_000),
+        ] {
+            ordinals.insert(word.to_string(), (value, Gender::Neutral));
+        }
+
+        Self {
+            language: Language::English,
+            numbers,
+            ordinals,
+            months: months(&EN_MONTHS),
+            conjunction: "and",
+            point: "point",
+            ambiguous: &[],
+        }
+    }
+
+    fn spanish() -> Self {
+        let mut numbers = HashMap::new();
+        let mut insert = |words: &[&str], word: NumberWord| {
+            for w in words {
+                numbers.insert(w.to_string(), word);
+            }
+        };
+
+        insert(&["cero"], NumberWord::Unit(0));
+        insert(&["uno", "un", "una"], NumberWord::Unit(1));
+        insert(&["dos"], NumberWord::Unit(2));
+        insert(&["tres"], NumberWord::Unit(3));
+        insert(&["cuatro"], NumberWord::Unit(4));
+        insert(&["cinco"], NumberWord::Unit(5));
+        insert(&["seis"], NumberWord::Unit(6));
+        insert(&["siete"], NumberWord::Unit(7));
+        insert(&["ocho"], NumberWord::Unit(8));
+        insert(&["nueve"], NumberWord::Unit(9));
+
+        let teens: [&[&str]; 20] = [
+            &["diez"],
+            &["once"],
+            &["doce"],
+            &["trece"],
+            &["catorce"],
+            &["quince"],
+            &["dieciséis", "dieciseis"],
+            &["diecisiete"],
+            &["dieciocho"],
+            &["diecinueve"],
+            &["veinte"],
+            &["veintiuno", "veintiún", "veintiun", "veintiuna"],
+            &["veintidós", "veintidos"],
+            &["veintitrés", "veintitres"],
+            &["veinticuatro"],
+            &["veinticinco"],
+            &["veintiséis", "veintiseis"],
+            &["veintisiete"],
+            &["veintiocho"],
+            &["veintinueve"],
+        ];
+        for (v, words) in teens.iter().enumerate() {
+            let value = 10 + v as u64;
+            let word = if value == 20 {
+                NumberWord::Tens(value)
+            } else {
+                NumberWord::Teen(value)
+            };
+            insert(words, word);
+        }
+
+        for (v, word) in [
+            "treinta",
+            "cuarenta",
+            "cincuenta",
+            "sesenta",
+            "setenta",
+            "ochenta",
+            "noventa",
+        ]
+        .iter()
+        .enumerate()
+        {
+            insert(&[word], NumberWord::Tens(30 + 10 * v as u64));
+        }
+
+        let hundreds: [&[&str]; 9] = [
+            &["cien", "ciento"],
+            &["doscientos", "doscientas"],
+            &["trescientos", "trescientas"],
+            &["cuatrocientos", "cuatrocientas"],
+            &["quinientos", "quinientas"],
+            &["seiscientos", "seiscientas"],
+            &["setecientos", "setecientas"],
+            &["ochocientos", "ochocientas"],
+            &["novecientos", "novecientas"],
+        ];
+        for (v, words) in hundreds.iter().enumerate() {
+            insert(words, NumberWord::Hundreds(100 * (1 + v as u64)));
+        }
+
+        insert(&["mil"], NumberWord::Scale(1_000));
+        insert(
+            &["millón", "millon", "millones"],
+            NumberWord::Scale(1_000_000),
+        );
+        insert(
+            &["billón", "billon", "billones"],
+            NumberWord::Scale(1_000_000_000_000),
+        );
+
+        let mut ordinals = HashMap::new();
+        let gendered: [(&str, &str, u64); 15] = [
+            ("primero", "primera", 1),
+            ("segundo", "segunda", 2),
+            ("tercero", "tercera", 3),
+            ("cuarto", "cuarta", 4),
+            ("quinto", "quinta", 5),
+            ("sexto", "sexta", 6),
+            ("séptimo", "séptima", 7),
+            ("octavo", "octava", 8),
+            ("noveno", "novena", 9),
+            ("décimo", "décima", 10),
+            ("undécimo", "undécima", 11),
+            ("duodécimo", "duodécima", 12),
+            ("vigésimo", "vigésima", 20),
+            ("trigésimo", "trigésima", 30),
+            ("centésimo", "centésima", 100),
+        ];
+        for (masculine, feminine, value) in gendered {
+            ordinals.insert(masculine.to_string(), (value, Gender::Masculine));
+            ordinals.insert(feminine.to_string(), (value, Gender::Feminine));
+        }
+        ordinals.insert("primer".to_string(), (1, Gender::Apocopated));
+        ordinals.insert("tercer".to_string(), (3, Gender::Apocopated));
+
+        let mut months = months(&ES_MONTHS);
+        months.insert("setiembre".to_string(), 9);
+
+        Self {
+            language: Language::Spanish,
+            numbers,
+            ordinals,
+            months,
+            conjunction: "y",
+            point: "coma",
+            ambiguous: &[],
+        }
+    }
+
+    fn catalan() -> Self {
+        let mut numbers = HashMap::new();
+        let mut ordinals = HashMap::new();
+
+        for (v, words) in CA_UNITS.iter().enumerate() {
+            for word in *words {
+                numbers.insert(word.to_string(), NumberWord::Unit(v as u64));
+            }
+        }
+        for (v, word) in CA_TEENS.iter().enumerate() {
+            numbers.insert(word.to_string(), NumberWord::Teen(10 + v as u64));
+        }
+        for (t, tens) in CA_TENS.iter().enumerate() {
+            let tens_value = 20 + 10 * t as u64;
+            // "vint-i-cinc" but "trenta-cinc".
+            let joiner = if tens_value == 20 { "-i-" } else { "-" };
+            numbers.insert(tens.to_string(), NumberWord::Tens(tens_value));
+            for (u, words) in CA_UNITS.iter().enumerate().skip(1) {
+                for unit in *words {
+                    numbers.insert(
+                        format!("{tens}{joiner}{unit}"),
+                        NumberWord::Teen(tens_value + u as u64),
+                    );
+                }
+            }
+            for (u, ordinal) in CA_COMPOUND_ORDINALS.iter().enumerate() {
+                ordinals.insert(
+                    format!("{tens}{joiner}{ordinal}"),
+                    (tens_value + 1 + u as u64, Gender::Masculine),
+                );
+            }
+        }
+
+        numbers.insert("cent".to_string(), NumberWord::Hundreds(100));
+        for (v, words) in CA_UNITS.iter().enumerate().skip(2) {
+            for unit in *words {
+                let hundreds = NumberWord::Hundreds(100 * v as u64);
+                numbers.insert(format!("{unit}-cents"), hundreds);
+                numbers.insert(format!("{unit}-centes"), hundreds);
+            }
+        }
+        for (word, scale) in [
+            ("mil", 1_000),
+            ("milió", 1_000_000),
+            ("milio", 1_000_000),
+            ("milions", 1_000_000),
+            ("bilió", 1_000_000_000_000),
+            ("bilions", 1_000_000_000_000),
+        ] {
+            numbers.insert(word.to_string(), NumberWord::Scale(scale));
+        }
+
+        // Feminine ordinals from ten up ("desena", "vintena") are collective
+        // nouns, so only the masculine forms are listed there.
+        let gendered: [(&str, Option<&str>, u64); 22] = [
+            ("primer", Some("primera"), 1),
+            ("segon", Some("segona"), 2),
+            ("tercer", Some("tercera"), 3),
+            ("quart", Some("quarta"), 4),
+            ("cinquè", Some("cinquena"), 5),
+            ("sisè", Some("sisena"), 6),
+            ("setè", Some("setena"), 7),
+            ("vuitè", Some("vuitena"), 8),
+            ("novè", Some("novena"), 9),
+            ("desè", None, 10),
+            ("onzè", None, 11),
+            ("dotzè", None, 12),
+            ("tretzè", None, 13),
+            ("catorzè", None, 14),
+            ("quinzè", None, 15),
+            ("setzè", None, 16),
+            ("dissetè", None, 17),
+            ("divuitè", None, 18),
+            ("dinovè", None, 19),
+            ("vintè", None, 20),
+            ("trentè", None, 30),
+            ("centè", None, 100),
+        ];
+        for (masculine, feminine, value) in gendered {
+            ordinals.insert(masculine.to_string(), (value, Gender::Masculine));
+            if let Some(feminine) = feminine {
+                ordinals.insert(feminine.to_string(), (value, Gender::Feminine));
+            }
+        }
+
+        let mut months = months(&CA_MONTHS);
+        for (word, month) in [("d'abril", 4), ("d'agost", 8), ("d'octubre", 10)] {
+            months.insert(word.to_string(), month);
+        }
+
+        Self {
+            language: Language::Catalan,
+            numbers,
+            ordinals,
+            months,
+            conjunction: "i",
+            point: "coma",
+            // "deu" is also "owes".
+            ambiguous: &["deu"],
+        }
+    }
+}
+
+fn months(names: &[&str; 12]) -> HashMap<String, u32> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), 1 + i as u32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("en-US"), Some(Language::English));
+        assert_eq!(Language::from_code("ca"), Some(Language::Catalan));
+        assert_eq!(Language::from_code("auto"), None);
+    }
+
+    #[test]
+    fn test_compound_words() {
+        let en = Lexicon::new(Language::English);
+        assert_eq!(en.number("forty-two"), Some(NumberWord::Teen(42)));
+        assert_eq!(en.ordinal("twenty-first"), Some((21, Gender::Neutral)));
+
+        let ca = Lexicon::new(Language::Catalan);
+        assert_eq!(ca.number("vint-i-dues"), Some(NumberWord::Teen(22)));
+        assert_eq!(ca.number("trenta-cinc"), Some(NumberWord::Teen(35)));
+        assert_eq!(ca.number("dues-centes"), Some(NumberWord::Hundreds(200)));
+        assert_eq!(ca.month("d'abril"), Some(4));
+    }
+
+    #[test]
+    fn test_format_integer() {
+        let en = Lexicon::new(Language::English);
+        assert_eq!(en.format_integer(2024), "2024");
+        assert_eq!(en.format_integer(2_000_000), "2,000,000");
+        let es = Lexicon::new(Language::Spanish);
+        assert_eq!(es.format_integer(25_000), "25.000");
+    }
+
+    #[test]
+    fn test_format_ordinal() {
+        let en = Lexicon::new(Language::English);
+        assert_eq!(en.format_ordinal(21, Gender::Neutral), "21st");
+        assert_eq!(en.format_ordinal(112, Gender::Neutral), "112th");
+        let es = Lexicon::new(Language::Spanish);
+        assert_eq!(es.format_ordinal(20, Gender::Feminine), "20.ª");
+        let ca = Lexicon::new(Language::Catalan);
+        assert_eq!(ca.format_ordinal(4, Gender::Masculine), "4t");
+        assert_eq!(ca.format_ordinal(25, Gender::Masculine), "25è");
+    }
+}
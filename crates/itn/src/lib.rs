@@ -0,0 +1,302 @@
+//! Inverse text normalization (ITN) for dictation output.
+//!
+//! Speech engines spell everything out: "twenty five percent of two million
+//! dollars". [`InverseNormalizer`] rewrites committed text into written form
+//! ("25% of $2,000,000") for English, Spanish and Catalan, covering
+//! numbers, ordinals, dates, times, currencies, percentages, units, email
+//! addresses and URLs. Each output [`Span`] records the input words it came
+//! from, so callers can keep the raw text alongside and map back onto it.
+
+mod datetime;
+mod lexicon;
+mod measure;
+mod number;
+mod web;
+
+use lexicon::{Language, Lexicon};
+use std::ops::Range;
+
+/// What a span of output was recognized as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// Copied from the input unchanged.
+    Verbatim,
+    Cardinal,
+    Decimal,
+    Ordinal,
+    Date,
+    Time,
+    Money,
+    Percent,
+    Measure,
+    Email,
+    Url,
+}
+
+/// One output token and the input words it was produced from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Range of whitespace-separated input words.
+    pub source: Range<usize>,
+    pub text: String,
+    pub kind: SpanKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized {
+    pub text: String,
+    pub spans: Vec<Span>,
+}
+
+impl Normalized {
+    /// Whether normalization left the text as it was.
+    pub fn is_unchanged(&self) -> bool {
+        self.spans.iter().all(|s| s.kind == SpanKind::Verbatim)
+    }
+
+    /// The span produced from input word `index`.
+    pub fn span_for_word(&self, index: usize) -> Option<&Span> {
+        self.spans.iter().find(|s| s.source.contains(&index))
+    }
+}
+
+/// A recognized expression at the start of a window of words.
+#[derive(Debug)]
+pub(crate) struct Match {
+    /// Words consumed.
+    pub len: usize,
+    pub text: String,
+    pub kind: SpanKind,
+}
+
+/// Punctuation kept outside a word while matching it.
+const LEADING_PUNCTUATION: &[char] = &['¿', '¡', '(', '[', '"', '\'', '«', '“', '‘'];
+const TRAILING_PUNCTUATION: &[char] = &[
+    ',', '.', ';', ':', '!', '?', '…', ')', ']', '"', '\'', '»', '”', '’',
+];
+
+/// An input word split into surrounding punctuation and a lowercase core.
+#[derive(Debug)]
+struct Token<'a> {
+    raw: &'a str,
+    lead: &'a str,
+    core: String,
+    trail: &'a str,
+}
+
+impl<'a> Token<'a> {
+    fn new(raw: &'a str) -> Self {
+        let rest = raw.trim_start_matches(LEADING_PUNCTUATION);
+        let lead = &raw[..raw.len() - rest.len()];
+        let mut core_str = rest.trim_end_matches(TRAILING_PUNCTUATION);
+        let mut trail = &rest[core_str.len()..];
+
+        // "p.m." keeps its abbreviation dots.
+        if matches!(core_str.to_lowercase().as_str(), "a.m" | "p.m") && trail.starts_with('.') {
+            core_str = &rest[..core_str.len() + 1];
+            trail = &trail[1..];
+        }
+
+        let core = core_str.to_lowercase().replace('’', "'");
+        let core = match core.as_str() {
+            "a.m." => "am".to_string(),
+            "p.m." => "pm".to_string(),
+            _ => core,
+        };
+
+        Self {
+            raw,
+            lead,
+            core,
+            trail,
+        }
+    }
+}
+
+/// Rewrites spoken-form text into written form for one language.
+#[derive(Debug)]
+pub struct InverseNormalizer {
+    lexicon: Option<Lexicon>,
+}
+
+impl InverseNormalizer {
+    /// Create a normalizer for an ISO 639-1 language code.
+    ///
+    /// Unsupported languages (including "auto") pass text through unchanged.
+    pub fn new(language: &str) -> Self {
+        Self {
+            lexicon: Language::from_code(language).map(Lexicon::new),
+        }
+    }
+
+    /// Whether `language` has normalization rules.
+    pub fn supports(language: &str) -> bool {
+        Language::from_code(language).is_some()
+    }
+
+    pub fn normalize(&self, text: &str) -> Normalized {
+        let tokens: Vec<Token> = text.split_whitespace().map(Token::new).collect();
+        let cores: Vec<&str> = tokens.iter().map(|t| t.core.as_str()).collect();
+
+        let mut spans = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+        while i < tokens.len() {
+            let end = window_end(&tokens, i);
+            let prev = i.checked_sub(1).map(|p| cores[p]);
+            let found = self
+                .lexicon
+                .as_ref()
+                .and_then(|lex| match_at(lex, &cores[i..end], prev));
+
+            let span = match found {
+                Some(m) => {
+                    let last = &tokens[i + m.len - 1];
+                    Span {
+                        source: i..i + m.len,
+                        text: format!("{}{}{}", tokens[i].lead, m.text, last.trail),
+                        kind: m.kind,
+                    }
+                }
+                None => Span {
+                    source: i..i + 1,
+                    text: tokens[i].raw.to_string(),
+                    kind: SpanKind::Verbatim,
+                },
+            };
+            i = span.source.end;
+            spans.push(span);
+        }
+
+        let text = spans
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Normalized { text, spans }
+    }
+}
+
+/// End of the words an expression starting at `start` may span.
+///
+/// Punctuation inside a run ends it: "three, four" is two numbers.
+fn window_end(tokens: &[Token], start: usize) -> usize {
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        if i > start && (!token.lead.is_empty() || token.core.is_empty()) {
+            return i;
+        }
+        if !token.trail.is_empty() {
+            return i + 1;
+        }
+    }
+    tokens.len()
+}
+
+fn match_at(lex: &Lexicon, words: &[&str], prev: Option<&str>) -> Option<Match> {
+    if words.first()?.is_empty() {
+        return None;
+    }
+    web::match_email(lex, words)
+        .or_else(|| web::match_url(lex, words))
+        .or_else(|| datetime::match_time(lex, words, prev))
+        .or_else(|| datetime::match_date(lex, words))
+        .or_else(|| measure::match_quantity(lex, words))
+        .or_else(|| number::match_number(lex, words))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(language: &str, text: &str) -> String {
+        InverseNormalizer::new(language).normalize(text).text
+    }
+
+    #[test]
+    fn test_english_dictation() {
+        assert_eq!(
+            normalize(
+                "en",
+                "meet me at three thirty on march fifth, budget is twenty five percent of two million dollars"
+            ),
+            "meet me at 3:30 on March 5, budget is 25% of $2,000,000"
+        );
+        assert_eq!(
+            normalize("en", "Email john at example dot com by ten am."),
+            "Email john@example.com by 10 AM."
+        );
+    }
+
+    #[test]
+    fn test_spanish_and_catalan_dictation() {
+        assert_eq!(
+            normalize(
+                "es",
+                "nos vemos a las tres y media el cinco de marzo, el presupuesto es el veinticinco por ciento de dos millones de euros"
+            ),
+            "nos vemos a las 3:30 el 5 de marzo, el presupuesto es el 25 % de 2.000.000 €"
+        );
+        assert_eq!(
+            normalize(
+                "ca",
+                "Quedem a les deu i quart, són vint-i-cinc quilòmetres."
+            ),
+            "Quedem a les 10:15, són 25 km."
+        );
+    }
+
+    #[test]
+    fn test_small_numbers_stay_spelled_out() {
+        assert_eq!(
+            normalize("en", "one of the first two options"),
+            "one of the first two options"
+        );
+        assert_eq!(normalize("en", "three, four"), "three, four");
+        assert_eq!(normalize("en", "twenty, one"), "20, one");
+    }
+
+    #[test]
+    fn test_standalone_years() {
+        assert_eq!(
+            normalize("en", "back in nineteen ninety nine"),
+            "back in 1999"
+        );
+        assert_eq!(normalize("en", "since twenty twenty"), "since 2020");
+        assert_eq!(normalize("en", "nineteen oh five"), "1905");
+        assert_eq!(normalize("en", "ten fifteen"), "10 15");
+    }
+
+    #[test]
+    fn test_unsupported_language_is_unchanged() {
+        let normalized = InverseNormalizer::new("auto").normalize("twenty five");
+        assert_eq!(normalized.text, "twenty five");
+        assert!(normalized.is_unchanged());
+        assert!(!InverseNormalizer::supports("de"));
+    }
+
+    #[test]
+    fn test_spans_map_to_source_words() {
+        let normalized = InverseNormalizer::new("en").normalize("pay twenty five dollars now");
+        let spans: Vec<(Range<usize>, &str, SpanKind)> = normalized
+            .spans
+            .iter()
+            .map(|s| (s.source.clone(), s.text.as_str(), s.kind))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0..1, "pay", SpanKind::Verbatim),
+                (1..4, "$25", SpanKind::Money),
+                (4..5, "now", SpanKind::Verbatim),
+            ]
+        );
+        assert_eq!(normalized.span_for_word(2).unwrap().text, "$25");
+    }
+
+    #[test]
+    fn test_meridiem_abbreviation() {
+        assert_eq!(
+            normalize("en", "call at four p.m. tomorrow"),
+            "call at 4 PM tomorrow"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Numbers with a currency, percent sign or unit of measure.
+
+use crate::lexicon::{Language, Lexicon};
+use crate::number::{format_amount, parse_amount, parse_cardinal, Amount, Class};
+use crate::{Match, SpanKind};
+
+struct Currency {
+    words: &'static [&'static str],
+    symbol: &'static str,
+}
+
+const EN_CURRENCIES: &[Currency] = &[
+    Currency {
+        words: &["dollars", "dollar", "bucks"],
+        symbol: "$",
+    },
+    Currency {
+        words: &["euros", "euro"],
+        symbol: "€",
+    },
+];
+
+const ES_CURRENCIES: &[Currency] = &[
+    Currency {
+        words: &["euros", "euro"],
+        symbol: "€",
+    },
+    Currency {
+        words: &["dólares", "dólar", "dolares", "dolar"],
+        symbol: "$",
+    },
+];
+
+const CA_CURRENCIES: &[Currency] = &[
+    Currency {
+        words: &["euros", "euro", "d'euros"],
+        symbol: "€",
+    },
+    Currency {
+        words: &["dòlars", "dòlar", "dolars", "dolar"],
+        symbol: "$",
+    },
+];
+
+/// Words for the hundredths of a currency, and what joins them to the
+/// whole amount ("five dollars and ten cents").
+struct Cents {
+    words: &'static [&'static str],
+    joiners: &'static [&'static str],
+}
+
+const EN_CENTS: Cents = Cents {
+    words: &["cents", "cent"],
+    joiners: &["and"],
+};
+
+const ES_CENTS: Cents = Cents {
+    words: &["céntimos", "céntimo", "centimos", "centimo", "centavos"],
+    joiners: &["con", "y"],
+};
+
+const CA_CENTS: Cents = Cents {
+    words: &["cèntims", "cèntim", "centims"],
+    joiners: &["amb", "i"],
+};
+
+const EN_PERCENT: &[&[&str]] = &[&["percent"], &["per", "cent"]];
+const ES_PERCENT: &[&[&str]] = &[&["por", "ciento"]];
+const CA_PERCENT: &[&[&str]] = &[&["per", "cent"]];
+
+/// Spoken unit names and their symbols. Longer names come first so that
+/// "kilometers per hour" wins over "kilometers".
+const EN_UNITS: &[(&[&str], &str)] = &[
+    (&["kilometers", "per", "hour"], "km/h"),
+    (&["kilometres", "per", "hour"], "km/h"),
+    (&["miles", "per", "hour"], "mph"),
+    (&["degrees", "celsius"], "°C"),
+    (&["degrees", "fahrenheit"], "°F"),
+    (&["degrees"], "°"),
+    (&["kilometers"], "km"),
+    (&["kilometres"], "km"),
+    (&["kilometer"], "km"),
+    (&["kilometre"], "km"),
+    (&["meters"], "m"),
+    (&["metres"], "m"),
+    (&["meter"], "m"),
+    (&["metre"], "m"),
+    (&["centimeters"], "cm"),
+    (&["centimetres"], "cm"),
+    (&["millimeters"], "mm"),
+    (&["millimetres"], "mm"),
+    (&["kilograms"], "kg"),
+    (&["kilogram"], "kg"),
+    (&["kilos"], "kg"),
+    (&["grams"], "g"),
+    (&["liters"], "L"),
+    (&["litres"], "L"),
+    (&["milliliters"], "mL"),
+    (&["millilitres"], "mL"),
+    (&["kilobytes"], "KB"),
+    (&["megabytes"], "MB"),
+    (&["gigabytes"], "GB"),
+    (&["terabytes"], "TB"),
+];
+
+const ES_UNITS: &[(&[&str], &str)] = &[
+    (&["kilómetros", "por", "hora"], "km/h"),
+    (&["grados", "centígrados"], "°C"),
+    (&["grados", "celsius"], "°C"),
+    (&["grados"], "°"),
+    (&["kilómetros"], "km"),
+    (&["kilómetro"], "km"),
+    (&["metros"], "m"),
+    (&["metro"], "m"),
+    (&["centímetros"], "cm"),
+    (&["milímetros"], "mm"),
+    (&["kilogramos"], "kg"),
+    (&["kilos"], "kg"),
+    (&["kilo"], "kg"),
+    (&["gramos"], "g"),
+    (&["litros"], "l"),
+    (&["litro"], "l"),
+    (&["mililitros"], "ml"),
+    (&["megabytes"], "MB"),
+    (&["megas"], "MB"),
+    (&["gigabytes"], "GB"),
+    (&["gigas"], "GB"),
+    (&["terabytes"], "TB"),
+];
+
+const CA_UNITS: &[(&[&str], &str)] = &[
+    (&["quilòmetres", "per", "hora"], "km/h"),
+    (&["graus", "centígrads"], "°C"),
+    (&["graus", "celsius"], "°C"),
+    (&["graus"], "°"),
+    (&["quilòmetres"], "km"),
+    (&["quilòmetre"], "km"),
+    (&["metres"], "m"),
+    (&["metre"], "m"),
+    (&["centímetres"], "cm"),
+    (&["mil·límetres"], "mm"),
+    (&["quilograms"], "kg"),
+    (&["quilos"], "kg"),
+    (&["quilo"], "kg"),
+    (&["grams"], "g"),
+    (&["litres"], "l"),
+    (&["litre"], "l"),
+    (&["mil·lilitres"], "ml"),
+    (&["megabytes"], "MB"),
+    (&["gigabytes"], "GB"),
+    (&["terabytes"], "TB"),
+];
+
+/// A number followed by a currency, "percent" or a unit.
+///
+/// Unlike standalone numbers, small values are converted too ("five
+/// percent" becomes "5%").
+pub(crate) fn match_quantity(lex: &Lexicon, words: &[&str]) -> Option<Match> {
+    let amount = parse_amount(lex, words)?;
+    money(lex, &amount, words)
+        .or_else(|| percent(lex, &amount, words))
+        .or_else(|| measure(lex, &amount, words))
+}
+
+fn money(lex: &Lexicon, amount: &Amount, words: &[&str]) -> Option<Match> {
+    let (currencies, cents) = match lex.language {
+        Language::English => (EN_CURRENCIES, &EN_CENTS),
+        Language::Spanish => (ES_CURRENCIES, &ES_CENTS),
+        Language::Catalan => (CA_CURRENCIES, &CA_CENTS),
+    };
+
+    let mut len = amount.len;
+    // "dos millones de euros"
+    if lex.language != Language::English
+        && amount.last == Class::Scale
+        && words.get(len) == Some(&"de")
+    {
+        len += 1;
+    }
+    let word = words.get(len)?;
+    let currency = currencies.iter().find(|c| c.words.contains(word))?;
+    len += 1;
+
+    let mut fraction = amount.fraction.clone();
+    if fraction.is_none() {
+        if let [joiner, rest @ ..] = &words[len..] {
+            if cents.joiners.contains(joiner) {
+                if let Some(c) = parse_cardinal(lex, rest).filter(|c| c.value < 100) {
+                    if rest.get(c.len).is_some_and(|w| cents.words.contains(w)) {
+                        fraction = Some(format!("{:02}", c.value));
+                        len += 1 + c.len + 1;
+                    }
+                }
+            }
+        }
+    }
+
+    let number = format_amount(lex, amount.integer, fraction.as_deref());
+    let text = match lex.language {
+        Language::English => format!("{}{number}", currency.symbol),
+        Language::Spanish | Language::Catalan => format!("{number} {}", currency.symbol),
+    };
+    Some(Match {
+        len,
+        text,
+        kind: SpanKind::Money,
+    })
+}
+
+fn percent(lex: &Lexicon, amount: &Amount, words: &[&str]) -> Option<Match> {
+    let phrases = match lex.language {
+        Language::English => EN_PERCENT,
+        Language::Spanish => ES_PERCENT,
+        Language::Catalan => CA_PERCENT,
+    };
+    let rest = &words[amount.len..];
+    let phrase = phrases.iter().find(|p| rest.starts_with(p))?;
+
+    let number = format_amount(lex, amount.integer, amount.fraction.as_deref());
+    let text = match lex.language {
+        Language::English => format!("{number}%"),
+        Language::Spanish | Language::Catalan => format!("{number} %"),
+    };
+    Some(Match {
+        len: amount.len + phrase.len(),
+        text,
+        kind: SpanKind::Percent,
+    })
+}
+
+fn measure(lex: &Lexicon, amount: &Amount, words: &[&str]) -> Option<Match> {
+    let units = match lex.language {
+        Language::English => EN_UNITS,
+        Language::Spanish => ES_UNITS,
+        Language::Catalan => CA_UNITS,
+    };
+    let rest = &words[amount.len..];
+    let (unit, symbol) = units.iter().find(|(unit, _)| rest.starts_with(unit))?;
+
+    let number = format_amount(lex, amount.integer, amount.fraction.as_deref());
+    let text = if *symbol == "°" {
+        format!("{number}°")
+    } else {
+        format!("{number} {symbol}")
+    };
+    Some(Match {
+        len: amount.len + unit.len(),
+        text,
+        kind: SpanKind::Measure,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantity(language: Language, text: &str) -> Option<(String, usize)> {
+        let lex = Lexicon::new(language);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match_quantity(&lex, &words).map(|m| (m.text, m.len))
+    }
+
+    #[test]
+    fn test_money() {
+        assert_eq!(
+            quantity(Language::English, "two million dollars"),
+            Some(("$2,000,000".into(), 3))
+        );
+        assert_eq!(
+            quantity(Language::English, "five dollars and fifty cents"),
+            Some(("$5.50".into(), 5))
+        );
+        assert_eq!(
+            quantity(Language::Spanish, "dos millones de euros"),
+            Some(("2.000.000 €".into(), 4))
+        );
+        assert_eq!(
+            quantity(Language::Catalan, "tres euros amb cinc cèntims"),
+            Some(("3,05 €".into(), 5))
+        );
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(
+            quantity(Language::English, "twenty five percent"),
+            Some(("25%".into(), 3))
+        );
+        assert_eq!(
+            quantity(Language::Spanish, "tres coma cinco por ciento"),
+            Some(("3,5 %".into(), 5))
+        );
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(
+            quantity(Language::English, "five kilometers per hour"),
+            Some(("5 km/h".into(), 4))
+        );
+        assert_eq!(
+            quantity(Language::Catalan, "vint graus"),
+            Some(("20°".into(), 2))
+        );
+        assert_eq!(quantity(Language::English, "five apples"), None);
+    }
+}
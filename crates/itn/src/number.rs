@@ -0,0 +1,366 @@
+//! Cardinals, ordinals and decimals.
+
+use crate::datetime;
+use crate::lexicon::{Gender, Language, Lexicon, NumberWord};
+use crate::{Match, SpanKind};
+
+/// Class of the last word of a number, which decides what may follow it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Class {
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cardinal {
+    pub value: u64,
+    /// Words consumed.
+    pub len: usize,
+    pub last: Class,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Ordinal {
+    pub value: u64,
+    pub gender: Gender,
+    pub len: usize,
+}
+
+/// A cardinal with an optional fractional part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Amount {
+    pub integer: u64,
+    /// Digits after the decimal separator.
+    pub fraction: Option<String>,
+    pub len: usize,
+    pub last: Class,
+}
+
+/// Parse the longest spoken cardinal at the start of `words`.
+///
+/// Words must follow the usual order (units after tens, decreasing
+/// scales), so "one two three" stops after "one".
+pub(crate) fn parse_cardinal(lex: &Lexicon, words: &[&str]) -> Option<Cardinal> {
+    let mut total = 0u64;
+    let mut group = 0u64;
+    let mut last: Option<Class> = None;
+    let mut last_scale = u64::MAX;
+    let mut len = 0;
+    let mut i = 0;
+
+    while i < words.len() {
+        let mut word = words[i];
+        let mut joined = false;
+        if word == lex.conjunction {
+            let allowed = match lex.language {
+                Language::English => matches!(last, Some(Class::Hundred | Class::Scale)),
+                Language::Spanish | Language::Catalan => last == Some(Class::Tens),
+            };
+            if !allowed || i + 1 >= words.len() {
+                break;
+            }
+            i += 1;
+            word = words[i];
+            joined = true;
+        }
+
+        let Some(number) = lex.number(word) else {
+            break;
+        };
+        let class = match number {
+            NumberWord::Unit(0) => {
+                if len == 0 {
+                    return Some(Cardinal {
+                        value: 0,
+                        len: 1,
+                        last: Class::Unit,
+                    });
+                }
+                break;
+            }
+            NumberWord::Unit(v) => {
+                if !matches!(
+                    last,
+                    None | Some(Class::Tens | Class::Hundred | Class::Scale)
+                ) {
+                    break;
+                }
+                group += v;
+                Class::Unit
+            }
+            NumberWord::Teen(v) | NumberWord::Tens(v) => {
+                if joined && lex.language != Language::English
+                    || !matches!(last, None | Some(Class::Hundred | Class::Scale))
+                {
+                    break;
+                }
+                group += v;
+                if matches!(number, NumberWord::Tens(_)) {
+                    Class::Tens
+                } else {
+                    Class::Teen
+                }
+            }
+            NumberWord::Hundreds(v) => {
+                if !matches!(last, None | Some(Class::Scale)) {
+                    break;
+                }
+                group += v;
+                Class::Hundred
+            }
+            NumberWord::Hundred => {
+                if !matches!(last, Some(Class::Unit | Class::Teen | Class::Tens)) || group >= 100 {
+                    break;
+                }
+                group *= 100;
+                Class::Hundred
+            }
+            NumberWord::Scale(scale) => {
+                if scale >= last_scale {
+                    break;
+                }
+                // "mil" on its own is a thousand in Spanish and Catalan.
+                let multiplier = if group > 0 {
+                    group
+                } else if last.is_none() && scale == 1_000 && lex.language != Language::English {
+                    1
+                } else {
+                    break;
+                };
+                total += multiplier * scale;
+                group = 0;
+                last_scale = scale;
+                Class::Scale
+            }
+        };
+
+        last = Some(class);
+        i += 1;
+        len = i;
+    }
+
+    last.map(|last| Cardinal {
+        value: total + group,
+        len,
+        last,
+    })
+}
+
+/// Parse a spoken ordinal: a single ordinal word, or a cardinal whose last
+/// word is ordinal ("twenty first", "vigésimo primero").
+pub(crate) fn parse_ordinal(lex: &Lexicon, words: &[&str]) -> Option<Ordinal> {
+    if let Some((value, gender)) = lex.ordinal(words.first()?) {
+        if (20..100).contains(&value) && value % 10 == 0 {
+            if let Some((unit, gender)) = words.get(1).and_then(|w| lex.ordinal(w)) {
+                if unit < 10 {
+                    return Some(Ordinal {
+                        value: value + unit,
+                        gender,
+                        len: 2,
+                    });
+                }
+            }
+        }
+        return Some(Ordinal {
+            value,
+            gender,
+            len: 1,
+        });
+    }
+
+    let cardinal = parse_cardinal(lex, words)?;
+    let (ordinal, gender) = lex.ordinal(words.get(cardinal.len)?)?;
+    let value = match (cardinal.last, ordinal) {
+        (Class::Tens, 1..=9) => cardinal.value + ordinal,
+        (Class::Hundred | Class::Scale, 1..=99) => cardinal.value + ordinal,
+        (Class::Unit | Class::Teen | Class::Tens, 100..) if cardinal.value < 100 => {
+            cardinal.value * ordinal
+        }
+        _ => return None,
+    };
+    Some(Ordinal {
+        value,
+        gender,
+        len: cardinal.len + 1,
+    })
+}
+
+/// Parse a cardinal followed by an optional decimal part ("three point
+/// five", "tres coma veinticinco").
+pub(crate) fn parse_amount(lex: &Lexicon, words: &[&str]) -> Option<Amount> {
+    let cardinal = parse_cardinal(lex, words)?;
+    let mut amount = Amount {
+        integer: cardinal.value,
+        fraction: None,
+        len: cardinal.len,
+        last: cardinal.last,
+    };
+
+    if words.get(cardinal.len) == Some(&lex.point) {
+        if let Some((digits, len)) = parse_fraction(lex, &words[cardinal.len + 1..]) {
+            amount.fraction = Some(digits);
+            amount.len += 1 + len;
+        }
+    }
+    Some(amount)
+}
+
+/// Digits after the decimal point.
+///
+/// English reads them digit by digit; Spanish and Catalan read them as a
+/// number unless they start with a zero.
+fn parse_fraction(lex: &Lexicon, words: &[&str]) -> Option<(String, usize)> {
+    let digit = |word: &&str| match lex.number(word) {
+        Some(NumberWord::Unit(d)) => char::from_digit(d as u32, 10),
+        _ => None,
+    };
+
+    let leading_zero = words.first().and_then(digit) == Some('0');
+    if lex.language == Language::English || leading_zero {
+        let digits: String = words.iter().map_while(digit).collect();
+        if !digits.is_empty() {
+            let len = digits.chars().count();
+            return Some((digits, len));
+        }
+    }
+
+    let cardinal = parse_cardinal(lex, words)?;
+    Some((cardinal.value.to_string(), cardinal.len))
+}
+
+pub(crate) fn format_amount(lex: &Lexicon, integer: u64, fraction: Option<&str>) -> String {
+    let integer = lex.format_integer(integer);
+    match fraction {
+        Some(fraction) => format!("{integer}{}{fraction}", lex.decimal_separator()),
+        None => integer,
+    }
+}
+
+/// A standalone number.
+///
+/// Single words below ten stay spelled out ("one of them", "the first
+/// time"), as do ambiguous words; a year read in pairs becomes one number
+/// and anything else larger or longer becomes digits.
+pub(crate) fn match_number(lex: &Lexicon, words: &[&str]) -> Option<Match> {
+    if let Some(ordinal) = parse_ordinal(lex, words) {
+        if ordinal.value >= 10 {
+            return Some(Match {
+                len: ordinal.len,
+                text: lex.format_ordinal(ordinal.value, ordinal.gender),
+                kind: SpanKind::Ordinal,
+            });
+        }
+    }
+
+    let amount = parse_amount(lex, words)?;
+    let text = format_amount(lex, amount.integer, amount.fraction.as_deref());
+    if amount.fraction.is_some() {
+        return Some(Match {
+            len: amount.len,
+            text,
+            kind: SpanKind::Decimal,
+        });
+    }
+    // "nineteen ninety nine" is one year, not two numbers. Centuries that
+    // are also clock hours are left alone so "ten fifteen" is not a year.
+    if amount.len == 1 && (13..30).contains(&amount.integer) {
+        if let Some((year, len)) = datetime::year(lex, words).filter(|&(_, len)| len > 1) {
+            return Some(Match {
+                len,
+                text: year.to_string(),
+                kind: SpanKind::Date,
+            });
+        }
+    }
+    if amount.len == 1 && (amount.integer < 10 || lex.is_ambiguous(words[0])) {
+        return None;
+    }
+    Some(Match {
+        len: amount.len,
+        text,
+        kind: SpanKind::Cardinal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cardinal(language: Language, text: &str) -> Option<(u64, usize)> {
+        let lex = Lexicon::new(language);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        parse_cardinal(&lex, &words).map(|c| (c.value, c.len))
+    }
+
+    #[test]
+    fn test_english_cardinals() {
+        let en = Language::English;
+        assert_eq!(cardinal(en, "twenty five"), Some((25, 2)));
+        assert_eq!(cardinal(en, "three hundred and five"), Some((305, 4)));
+        assert_eq!(cardinal(en, "fifteen hundred"), Some((1500, 2)));
+        assert_eq!(
+            cardinal(en, "two million three hundred thousand"),
+            Some((2_300_000, 5))
+        );
+        assert_eq!(cardinal(en, "one two three"), Some((1, 1)));
+        assert_eq!(cardinal(en, "five hundred and counting"), Some((500, 2)));
+        assert_eq!(cardinal(en, "hundred"), None);
+    }
+
+    #[test]
+    fn test_spanish_and_catalan_cardinals() {
+        assert_eq!(
+            cardinal(Language::Spanish, "treinta y cinco"),
+            Some((35, 3))
+        );
+        assert_eq!(
+            cardinal(Language::Spanish, "mil doscientos veintidós"),
+            Some((1222, 3))
+        );
+        assert_eq!(
+            cardinal(Language::Spanish, "dos millones"),
+            Some((2_000_000, 2))
+        );
+        assert_eq!(
+            cardinal(Language::Catalan, "dos mil vint-i-quatre"),
+            Some((2024, 3))
+        );
+        assert_eq!(
+            cardinal(Language::Catalan, "tres-cents quaranta-dos"),
+            Some((342, 2))
+        );
+    }
+
+    #[test]
+    fn test_ordinals() {
+        let lex = Lexicon::new(Language::English);
+        let ordinal = parse_ordinal(&lex, &["twenty", "first"]).unwrap();
+        assert_eq!((ordinal.value, ordinal.len), (21, 2));
+        let ordinal = parse_ordinal(&lex, &["one", "hundredth"]).unwrap();
+        assert_eq!(ordinal.value, 100);
+
+        let lex = Lexicon::new(Language::Spanish);
+        let ordinal = parse_ordinal(&lex, &["vigésima", "segunda"]).unwrap();
+        assert_eq!((ordinal.value, ordinal.gender), (22, Gender::Feminine));
+    }
+
+    #[test]
+    fn test_decimals() {
+        let lex = Lexicon::new(Language::English);
+        let amount = parse_amount(&lex, &["three", "point", "one", "four"]).unwrap();
+        assert_eq!(
+            (amount.integer, amount.fraction.as_deref()),
+            (3, Some("14"))
+        );
+
+        let lex = Lexicon::new(Language::Spanish);
+        let amount = parse_amount(&lex, &["tres", "coma", "veinticinco"]).unwrap();
+        assert_eq!(
+            format_amount(&lex, amount.integer, amount.fraction.as_deref()),
+            "3,25"
+        );
+    }
+}
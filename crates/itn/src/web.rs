@@ -0,0 +1,260 @@
+//! Spoken email addresses and URLs.
+
+use crate::lexicon::{Language, Lexicon};
+use crate::{Match, SpanKind};
+
+/// Top-level domains accepted at the end of a spoken address. Requiring
+/// one keeps "dot" in ordinary speech from being read as a domain.
+const TLDS: &[&str] = &[
+    "com", "org", "net", "io", "ai", "dev", "app", "edu", "gov", "co", "uk", "us", "es", "cat",
+    "eu", "de", "fr", "it", "info", "me", "tv",
+];
+
+/// English words that are never the local part of an address, so "look at
+/// example dot com" is read as a URL, not an email.
+const EN_NOT_LOCAL: &[&str] = &[
+    "look",
+    "looking",
+    "looked",
+    "me",
+    "us",
+    "him",
+    "her",
+    "them",
+    "it",
+    "is",
+    "are",
+    "was",
+    "were",
+    "be",
+    "been",
+    "arrive",
+    "arrived",
+    "stay",
+    "stayed",
+    "work",
+    "works",
+    "live",
+    "lives",
+    "go",
+    "went",
+    "get",
+    "got",
+    "see",
+    "check",
+    "available",
+    "find",
+    "found",
+    "visit",
+    "reach",
+    "reached",
+    "meet",
+    "there",
+    "here",
+    "and",
+    "or",
+    "but",
+    "the",
+    "a",
+    "an",
+];
+
+struct WebWords {
+    at: &'static [&'static str],
+    dot: &'static str,
+    slash: &'static str,
+    dash: &'static [&'static str],
+    underscore: &'static [&'static str],
+}
+
+const EN_WEB: WebWords = WebWords {
+    at: &["at"],
+    dot: "dot",
+    slash: "slash",
+    dash: &["dash", "hyphen"],
+    underscore: &["underscore"],
+};
+
+const ES_WEB: WebWords = WebWords {
+    at: &["arroba"],
+    dot: "punto",
+    slash: "barra",
+    dash: &["guion", "guión"],
+    underscore: &[],
+};
+
+const CA_WEB: WebWords = WebWords {
+    at: &["arrova", "arroba"],
+    dot: "punt",
+    slash: "barra",
+    dash: &["guió", "guio"],
+    underscore: &[],
+};
+
+impl WebWords {
+    fn for_language(language: Language) -> &'static Self {
+        match language {
+            Language::English => &EN_WEB,
+            Language::Spanish => &ES_WEB,
+            Language::Catalan => &CA_WEB,
+        }
+    }
+
+    fn is_connector(&self, word: &str) -> bool {
+        self.at.contains(&word)
+            || word == self.dot
+            || word == self.slash
+            || self.dash.contains(&word)
+            || self.underscore.contains(&word)
+    }
+
+    /// A word usable as an address label.
+    fn is_label(&self, word: &str) -> bool {
+        !word.is_empty()
+            && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !self.is_connector(word)
+    }
+
+    /// "example dot co dot uk": labels joined by "dot", ending in a known
+    /// top-level domain.
+    fn domain(&self, words: &[&str]) -> Option<(String, usize)> {
+        if !self.is_label(words.first()?) {
+            return None;
+        }
+        let mut labels = vec![words[0]];
+        let mut best = None;
+        let mut i = 1;
+        while i + 1 < words.len() && words[i] == self.dot && self.is_label(words[i + 1]) {
+            labels.push(words[i + 1]);
+            i += 2;
+            if TLDS.contains(&words[i - 1]) {
+                best = Some((labels.join("."), i));
+            }
+        }
+        best
+    }
+}
+
+/// "john dot smith at example dot com".
+pub(crate) fn match_email(lex: &Lexicon, words: &[&str]) -> Option<Match> {
+    let web = WebWords::for_language(lex.language);
+    let first = *words.first()?;
+    if !web.is_label(first) || lex.language == Language::English && EN_NOT_LOCAL.contains(&first) {
+        return None;
+    }
+
+    let mut local = first.to_string();
+    let mut i = 1;
+    while i + 1 < words.len() && web.is_label(words[i + 1]) {
+        let separator = if words[i] == web.dot {
+            '.'
+        } else if web.underscore.contains(&words[i]) {
+            '_'
+        } else if web.dash.contains(&words[i]) {
+            '-'
+        } else {
+            break;
+        };
+        local.push(separator);
+        local.push_str(words[i + 1]);
+        i += 2;
+    }
+
+    if !web.at.contains(words.get(i)?) {
+        return None;
+    }
+    let (domain, n) = web.domain(&words[i + 1..])?;
+    Some(Match {
+        len: i + 1 + n,
+        text: format!("{local}@{domain}"),
+        kind: SpanKind::Email,
+    })
+}
+
+/// "w w w dot example dot com slash docs".
+pub(crate) fn match_url(lex: &Lexicon, words: &[&str]) -> Option<Match> {
+    let web = WebWords::for_language(lex.language);
+
+    let (prefix, mut len) = match words {
+        ["www", dot, ..] if *dot == web.dot => ("www.", 2),
+        ["w", "w", "w", dot, ..] if *dot == web.dot => ("www.", 4),
+        _ => ("", 0),
+    };
+    let (domain, n) = web.domain(&words[len..])?;
+    len += n;
+
+    let mut text = format!("{prefix}{domain}");
+    while len + 1 < words.len() && words[len] == web.slash && web.is_label(words[len + 1]) {
+        text.push('/');
+        text.push_str(words[len + 1]);
+        len += 2;
+    }
+    Some(Match {
+        len,
+        text,
+        kind: SpanKind::Url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        language: Language,
+        text: &str,
+        matcher: fn(&Lexicon, &[&str]) -> Option<Match>,
+    ) -> Option<(String, usize)> {
+        let lex = Lexicon::new(language);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        matcher(&lex, &words).map(|m| (m.text, m.len))
+    }
+
+    #[test]
+    fn test_email() {
+        assert_eq!(
+            run(
+                Language::English,
+                "john dot smith at example dot co dot uk",
+                match_email
+            ),
+            Some(("john.smith@example.co.uk".into(), 9))
+        );
+        assert_eq!(
+            run(
+                Language::Spanish,
+                "ana arroba empresa punto es",
+                match_email
+            ),
+            Some(("ana@empresa.es".into(), 5))
+        );
+        assert_eq!(
+            run(Language::English, "look at example dot com", match_email),
+            None
+        );
+        assert_eq!(
+            run(Language::English, "me at the office", match_email),
+            None
+        );
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(
+            run(
+                Language::English,
+                "w w w dot example dot com slash docs",
+                match_url
+            ),
+            Some(("www.example.com/docs".into(), 9))
+        );
+        assert_eq!(
+            run(Language::Catalan, "gibberish punt cat", match_url),
+            Some(("gibberish.cat".into(), 3))
+        );
+        assert_eq!(
+            run(Language::English, "connect the dot later", match_url),
+            None
+        );
+    }
+}
@@ -191,8 +191,6 @@ async fn download_whisper_onnx_model<F>(
 where
     F: Fn(u64, u64),
 {
-    use std::io::Read;
-
     // Model name to archive name mapping
     let archive_name = match model {
         SttModel::WhisperOnnxSmall => "sherpa-onnx-whisper-small",
@@ -217,10 +215,25 @@ where
     .await?;
 
     tracing::info!("Extracting Whisper ONNX model archive");
+    extract_tar_bz2(&temp_archive, model_dir, &["test_wavs"])?;
 
-    // Extract the archive
-    let archive_file = std::fs::File::open(&temp_archive)
-        .map_err(|e| ModelError::DownloadFailed(e.to_string()))?;
+    // Clean up temp archive
+    let _ = std::fs::remove_file(&temp_archive);
+
+    tracing::info!("Whisper ONNX model extracted successfully");
+
+    Ok(())
+}
+
+/// Extract a `.tar.bz2` archive into `dest_dir`.
+///
+/// The archive's top-level directory is stripped, and entries under any of
+/// the `skip` subdirectories are ignored.
+pub(crate) fn extract_tar_bz2(archive_path: &Path, dest_dir: &Path, skip: &[&str]) -> Result<()> {
+    use std::io::Read;
+
+    let archive_file =
+        std::fs::File::open(archive_path).map_err(|e| ModelError::DownloadFailed(e.to_string()))?;
 
     let decoder = bzip2::read::BzDecoder::new(archive_file);
     let mut archive = tar::Archive::new(decoder);
@@ -244,12 +257,11 @@ where
         // Build path without the first component
         let relative_path: PathBuf = components[1..].iter().collect();
 
-        // Skip test_wavs directory
-        if relative_path.starts_with("test_wavs") {
+        if skip.iter().any(|dir| relative_path.starts_with(dir)) {
             continue;
         }
 
-        let dest_path = model_dir.join(&relative_path);
+        let dest_path = dest_dir.join(&relative_path);
 
         // Create parent directories
         if let Some(parent) = dest_path.parent() {
@@ -267,11 +279,6 @@ where
         }
     }
 
-    // Clean up temp archive
-    let _ = std::fs::remove_file(&temp_archive);
-
-    tracing::info!("Whisper ONNX model extracted successfully");
-
     Ok(())
 }
 
@@ -361,7 +368,12 @@ where
         )));
     }
 
-    let mut file = tokio::fs::File::create(dest)
+    // Written under a `.part` name and renamed once complete, so an
+    // interrupted download never passes for the finished file.
+    let mut part = dest.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let mut file = tokio::fs::File::create(&part)
         .await
         .map_err(ModelError::IoError)?;
 
@@ -376,6 +388,10 @@ where
     }
 
     file.flush().await.map_err(ModelError::IoError)?;
+    drop(file);
+    tokio::fs::rename(&part, dest)
+        .await
+        .map_err(ModelError::IoError)?;
 
     Ok(total)
 }
@@ -1,11 +1,25 @@
 mod download;
 mod metadata;
+mod punctuation;
+mod speaker;
+mod store;
 mod turn;
 
 use std::path::PathBuf;
 
 pub use download::download_model;
 pub use metadata::{get_metadata, ModelCategory, ModelMetadata};
+pub use punctuation::{
+    download_punctuation_model, is_punctuation_model_downloaded, punctuation_model_path,
+    PunctuationModel,
+};
+pub use speaker::{
+    download_speaker_model, is_speaker_model_downloaded, speaker_model_path, SpeakerModel,
+};
+pub use store::{
+    functiongemma_dir, mark_used, GcReport, InstallState, InstalledModel, ModelKind, ModelStore,
+    StoreFootprint,
+};
 pub use turn::{download_turn_model, is_turn_model_downloaded, turn_model_path, TurnModel};
 
 #[derive(Debug, thiserror::Error)]
@@ -43,6 +57,24 @@ pub enum SttModel {
 }
 
 impl SttModel {
+    /// Every known STT model, in registry order.
+    pub const ALL: [SttModel; 9] = [
+        Self::WhisperSmall,
+        Self::WhisperSmallEn,
+        Self::WhisperLargeTurbo,
+        Self::WhisperOnnxSmall,
+        Self::ParakeetCtc,
+        Self::ParakeetTdt,
+        Self::ParakeetEou,
+        Self::SherpaZipformerEn,
+        Self::NemoConformerCatalan,
+    ];
+
+    /// Look up a model by its unique identifier.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
     /// Get the unique identifier for this model.
     pub fn name(&self) -> &'static str {
         match self {
@@ -83,6 +115,11 @@ impl SttModel {
         self.metadata().category
     }
 
+    /// Whether commits need punctuation and casing restored (delegates to metadata).
+    pub fn needs_punctuation(&self) -> bool {
+        self.metadata().needs_punctuation
+    }
+
     /// Check if this is a Whisper GGML model.
     pub fn is_whisper(&self) -> bool {
         self.category() == ModelCategory::WhisperGgml
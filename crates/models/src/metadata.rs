@@ -25,6 +25,9 @@ pub struct ModelMetadata {
     pub is_downloaded: fn(&Path) -> bool,
     /// Model category for grouping.
     pub category: ModelCategory,
+    /// Output is lowercase and unpunctuated, so commits need punctuation
+    /// and truecasing restored before they reach the user.
+    pub needs_punctuation: bool,
 }
 
 /// Model categories for organization and filtering.
@@ -56,6 +59,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 466_000_000,
             is_downloaded: is_whisper_ggml_downloaded,
             category: ModelCategory::WhisperGgml,
+            needs_punctuation: false,
         },
     );
     m.insert(
@@ -67,6 +71,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 466_000_000,
             is_downloaded: is_whisper_ggml_downloaded,
             category: ModelCategory::WhisperGgml,
+            needs_punctuation: false,
         },
     );
     m.insert(
@@ -78,6 +83,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 1_600_000_000,
             is_downloaded: is_whisper_ggml_downloaded,
             category: ModelCategory::WhisperGgml,
+            needs_punctuation: false,
         },
     );
 
@@ -91,6 +97,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 490_000_000,
             is_downloaded: is_whisper_onnx_small_downloaded,
             category: ModelCategory::WhisperOnnx,
+            needs_punctuation: false,
         },
     );
 
@@ -104,6 +111,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 1_220_000_000,
             is_downloaded: is_parakeet_ctc_downloaded,
             category: ModelCategory::Parakeet,
+            needs_punctuation: true,
         },
     );
     m.insert(
@@ -115,6 +123,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 700_000_000,
             is_downloaded: is_parakeet_tdt_downloaded,
             category: ModelCategory::Parakeet,
+            needs_punctuation: false,
         },
     );
     m.insert(
@@ -126,6 +135,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 140_000_000,
             is_downloaded: is_parakeet_eou_downloaded,
             category: ModelCategory::Parakeet,
+            needs_punctuation: true,
         },
     );
 
@@ -139,6 +149,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 250_000_000,
             is_downloaded: is_sherpa_zipformer_downloaded,
             category: ModelCategory::SherpaStreaming,
+            needs_punctuation: true,
         },
     );
 
@@ -152,6 +163,7 @@ static MODEL_REGISTRY: LazyLock<HashMap<&'static str, ModelMetadata>> = LazyLock
             size_bytes: 507_000_000,
             is_downloaded: is_nemo_ctc_downloaded,
             category: ModelCategory::NemoCtc,
+            needs_punctuation: true,
         },
     );
 
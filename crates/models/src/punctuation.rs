@@ -0,0 +1,111 @@
+use crate::download::{download_file, extract_tar_bz2};
+use crate::{models_dir, Result};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PunctuationModel {
+    /// sherpa-onnx CNN-BiLSTM online punctuation and casing model (English)
+    OnlinePunctEn,
+}
+
+impl PunctuationModel {
+    /// Every known punctuation model.
+    pub const ALL: [PunctuationModel; 1] = [Self::OnlinePunctEn];
+
+    /// Look up a punctuation model by its unique identifier.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OnlinePunctEn => "online-punct-en",
+        }
+    }
+
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            Self::OnlinePunctEn => "sherpa-onnx-online-punct-en-2024-08-06",
+        }
+    }
+
+    /// Release archive on the sherpa-onnx GitHub releases page.
+    pub fn archive_url(&self) -> &'static str {
+        match self {
+            Self::OnlinePunctEn => "https://github.com/k2-fsa/sherpa-onnx/releases/download/punctuation-models/sherpa-onnx-online-punct-en-2024-08-06.tar.bz2",
+        }
+    }
+
+    /// ONNX graph inside the model directory.
+    pub fn model_filename(&self) -> &'static str {
+        match self {
+            Self::OnlinePunctEn => "model.onnx",
+        }
+    }
+
+    /// BPE vocabulary inside the model directory.
+    pub fn vocab_filename(&self) -> &'static str {
+        match self {
+            Self::OnlinePunctEn => "bpe.vocab",
+        }
+    }
+
+    /// ISO 639-1 codes of the languages the model was trained on.
+    pub fn languages(&self) -> &'static [&'static str] {
+        match self {
+            Self::OnlinePunctEn => &["en"],
+        }
+    }
+
+    pub fn size_bytes(&self) -> u64 {
+        match self {
+            Self::OnlinePunctEn => 36_000_000,
+        }
+    }
+}
+
+fn punctuation_models_dir() -> PathBuf {
+    models_dir().join("punctuation")
+}
+
+pub fn punctuation_model_path(model: PunctuationModel) -> PathBuf {
+    punctuation_models_dir().join(model.dir_name())
+}
+
+pub fn is_punctuation_model_downloaded(model: PunctuationModel) -> bool {
+    let dir = punctuation_model_path(model);
+    dir.join(model.model_filename()).exists() && dir.join(model.vocab_filename()).exists()
+}
+
+pub async fn download_punctuation_model<F>(
+    model: PunctuationModel,
+    on_progress: F,
+) -> Result<PathBuf>
+where
+    F: Fn(u64, u64),
+{
+    let model_dir = punctuation_model_path(model);
+
+    if is_punctuation_model_downloaded(model) {
+        return Ok(model_dir);
+    }
+
+    std::fs::create_dir_all(&model_dir)?;
+
+    let total = model.size_bytes();
+    let mut downloaded = 0u64;
+
+    tracing::info!("Downloading punctuation model from {}", model.archive_url());
+
+    let temp_archive = model_dir.join("model.tar.bz2");
+    download_file(model.archive_url(), &temp_archive, |chunk| {
+        downloaded = downloaded.saturating_add(chunk);
+        on_progress(downloaded, total.max(downloaded));
+    })
+    .await?;
+
+    extract_tar_bz2(&temp_archive, &model_dir, &["test_wavs"])?;
+    let _ = std::fs::remove_file(&temp_archive);
+
+    Ok(model_dir)
+}
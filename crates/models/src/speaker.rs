@@ -0,0 +1,103 @@
+use crate::download::download_file;
+use crate::{models_dir, ModelError, Result};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeakerModel {
+    /// WeSpeaker ResNet34 (VoxCeleb, large-margin fine-tuned) speaker embeddings
+    WespeakerResnet34,
+}
+
+impl SpeakerModel {
+    /// Every known speaker-embedding model.
+    pub const ALL: [SpeakerModel; 1] = [Self::WespeakerResnet34];
+
+    /// Look up a speaker model by its unique identifier.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WespeakerResnet34 => "wespeaker-resnet34",
+        }
+    }
+
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            Self::WespeakerResnet34 => "wespeaker-resnet34",
+        }
+    }
+
+    pub fn huggingface_repo(&self) -> &'static str {
+        match self {
+            Self::WespeakerResnet34 => "Wespeaker/wespeaker-voxceleb-resnet34-LM",
+        }
+    }
+
+    pub fn remote_path(&self) -> &'static str {
+        match self {
+            Self::WespeakerResnet34 => "voxceleb_resnet34_LM.onnx",
+        }
+    }
+
+    pub fn local_filename(&self) -> &'static str {
+        match self {
+            Self::WespeakerResnet34 => "voxceleb_resnet34_LM.onnx",
+        }
+    }
+
+    pub fn size_bytes(&self) -> u64 {
+        match self {
+            Self::WespeakerResnet34 => 26_500_000,
+        }
+    }
+}
+
+fn speaker_models_dir() -> PathBuf {
+    models_dir().join("speaker")
+}
+
+pub fn speaker_model_path(model: SpeakerModel) -> PathBuf {
+    speaker_models_dir().join(model.dir_name())
+}
+
+pub fn is_speaker_model_downloaded(model: SpeakerModel) -> bool {
+    let dir = speaker_model_path(model);
+    dir.join(model.local_filename()).exists()
+}
+
+pub async fn download_speaker_model<F>(model: SpeakerModel, on_progress: F) -> Result<PathBuf>
+where
+    F: Fn(u64, u64),
+{
+    let model_dir = speaker_model_path(model);
+
+    if is_speaker_model_downloaded(model) {
+        return Ok(model_dir);
+    }
+
+    std::fs::create_dir_all(&model_dir)?;
+
+    let url = format!(
+        "https://huggingface.co/{}/resolve/main/{}",
+        model.huggingface_repo(),
+        model.remote_path()
+    );
+    let dest = model_dir.join(model.local_filename());
+
+    let total = model.size_bytes();
+    let mut downloaded = 0u64;
+
+    download_file(&url, &dest, |chunk| {
+        downloaded = downloaded.saturating_add(chunk);
+        on_progress(downloaded, total.max(downloaded));
+    })
+    .await
+    .map_err(|e| match e {
+        ModelError::DownloadFailed(msg) => ModelError::DownloadFailed(msg),
+        other => other,
+    })?;
+
+    Ok(model_dir)
+}
@@ -0,0 +1,674 @@
+//! Local model store: inventory, disk usage and cleanup.
+//!
+//! Downloads only ever add to `models_dir()`. The store answers the
+//! questions the download path never asks: what is on disk, how big it is,
+//! when it was last used, and which directories are leftovers from
+//! abandoned or cancelled downloads.
+
+use crate::{models_dir, ModelError, PunctuationModel, Result, SpeakerModel, SttModel, TurnModel};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Marker file written into a model directory each time the model is loaded.
+const LAST_USED_MARKER: &str = ".last_used";
+
+/// Subdirectory of `models_dir()` holding turn (endpoint) models.
+const TURN_SUBDIR: &str = "turn";
+
+/// Subdirectory of `models_dir()` holding speaker-embedding models.
+const SPEAKER_SUBDIR: &str = "speaker";
+
+/// Subdirectory of `models_dir()` holding punctuation/truecasing models.
+const PUNCTUATION_SUBDIR: &str = "punctuation";
+
+/// Subdirectory of the app data dir holding FunctionGemma variants.
+const FUNCTIONGEMMA_SUBDIR: &str = "functiongemma";
+
+/// File name fragments left behind by interrupted downloads.
+const TEMP_FILE_SUFFIXES: &[&str] = &[".part", ".tar.bz2"];
+
+/// Minimum size for a FunctionGemma file to count as downloaded.
+const MIN_FUNCTIONGEMMA_FILE_BYTES: u64 = 1024;
+
+/// Which family a stored model belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModelKind {
+    /// Speech-to-text engines (Whisper, Parakeet, Sherpa, NeMo).
+    Stt,
+    /// Semantic turn / endpoint detectors.
+    Turn,
+    /// Speaker-embedding models used for diarization.
+    Speaker,
+    /// Punctuation and truecasing models.
+    Punctuation,
+    /// FunctionGemma router variants.
+    FunctionGemma,
+}
+
+impl ModelKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stt => "stt",
+            Self::Turn => "turn",
+            Self::Speaker => "speaker",
+            Self::Punctuation => "punctuation",
+            Self::FunctionGemma => "functiongemma",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "stt" => Some(Self::Stt),
+            "turn" => Some(Self::Turn),
+            "speaker" => Some(Self::Speaker),
+            "punctuation" => Some(Self::Punctuation),
+            "functiongemma" => Some(Self::FunctionGemma),
+            _ => None,
+        }
+    }
+}
+
+/// Install state of a model directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallState {
+    /// All required files are present.
+    Complete,
+    /// A known model whose required files are missing (interrupted download).
+    Partial,
+    /// A directory that no known model maps to.
+    Orphaned,
+}
+
+/// A model directory found on disk.
+#[derive(Debug, Clone)]
+pub struct InstalledModel {
+    /// Model name (e.g. "parakeet-tdt"), FunctionGemma variant, or the raw
+    /// directory name for orphaned entries.
+    pub id: String,
+    pub kind: ModelKind,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// When the model was last loaded, if it has been loaded since the
+    /// store started tracking usage.
+    pub last_used: Option<SystemTime>,
+    pub state: InstallState,
+}
+
+/// Result of a garbage collection pass.
+#[derive(Debug, Clone, Default)]
+pub struct GcReport {
+    /// Paths (directories or stray files) that were removed.
+    pub removed: Vec<PathBuf>,
+    pub freed_bytes: u64,
+}
+
+/// Disk usage per model kind.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StoreFootprint {
+    pub stt_bytes: u64,
+    pub turn_bytes: u64,
+    pub speaker_bytes: u64,
+    pub punctuation_bytes: u64,
+    pub functiongemma_bytes: u64,
+    /// Bytes held by partial and orphaned entries (reclaimable by GC).
+    pub reclaimable_bytes: u64,
+}
+
+impl StoreFootprint {
+    pub fn total_bytes(&self) -> u64 {
+        self.stt_bytes
+            + self.turn_bytes
+            + self.speaker_bytes
+            + self.punctuation_bytes
+            + self.functiongemma_bytes
+    }
+}
+
+/// View over the on-disk model directories.
+///
+/// STT, turn, speaker and punctuation models live under a single root (`models_dir()` by
+/// default). FunctionGemma variants are owned by the tools plugin and live
+/// under the app data dir, so either root may be absent.
+#[derive(Debug, Clone)]
+pub struct ModelStore {
+    root: Option<PathBuf>,
+    functiongemma_root: Option<PathBuf>,
+}
+
+impl Default for ModelStore {
+    fn default() -> Self {
+        Self::new(models_dir())
+    }
+}
+
+impl ModelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Some(root.into()),
+            functiongemma_root: None,
+        }
+    }
+
+    /// A store that only covers FunctionGemma variants under `dir`.
+    pub fn functiongemma_only(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root: None,
+            functiongemma_root: Some(dir.into()),
+        }
+    }
+
+    /// Include FunctionGemma variants stored under `dir`.
+    pub fn with_functiongemma_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.functiongemma_root = Some(dir.into());
+        self
+    }
+
+    fn kind_root(&self, kind: ModelKind) -> Option<PathBuf> {
+        match kind {
+            ModelKind::Stt => self.root.clone(),
+            ModelKind::Turn => self.root.as_ref().map(|r| r.join(TURN_SUBDIR)),
+            ModelKind::Speaker => self.root.as_ref().map(|r| r.join(SPEAKER_SUBDIR)),
+            ModelKind::Punctuation => self.root.as_ref().map(|r| r.join(PUNCTUATION_SUBDIR)),
+            ModelKind::FunctionGemma => self.functiongemma_root.clone(),
+        }
+    }
+
+    /// Enumerate every model directory, complete or not.
+    pub fn list(&self) -> Result<Vec<InstalledModel>> {
+        let mut out = Vec::new();
+
+        if let Some(root) = &self.root {
+            for dir_name in subdirectories(root)? {
+                if is_reserved_subdir(&dir_name) {
+                    continue;
+                }
+                let path = root.join(&dir_name);
+                let (id, state) = match SttModel::ALL.iter().find(|m| m.dir_name() == dir_name) {
+                    Some(model) => (model.name().to_string(), stt_state(*model, &path)),
+                    None => (dir_name, InstallState::Orphaned),
+                };
+                out.push(installed(id, ModelKind::Stt, path, state));
+            }
+
+            let turn_root = root.join(TURN_SUBDIR);
+            for dir_name in subdirectories(&turn_root)? {
+                let path = turn_root.join(&dir_name);
+                let (id, state) = match TurnModel::ALL.iter().find(|m| m.dir_name() == dir_name) {
+                    Some(model) => (model.name().to_string(), turn_state(*model, &path)),
+                    None => (dir_name, InstallState::Orphaned),
+                };
+                out.push(installed(id, ModelKind::Turn, path, state));
+            }
+
+            let speaker_root = root.join(SPEAKER_SUBDIR);
+            for dir_name in subdirectories(&speaker_root)? {
+                let path = speaker_root.join(&dir_name);
+                let (id, state) = match SpeakerModel::ALL.iter().find(|m| m.dir_name() == dir_name)
+                {
+                    Some(model) => (model.name().to_string(), speaker_state(*model, &path)),
+                    None => (dir_name, InstallState::Orphaned),
+                };
+                out.push(installed(id, ModelKind::Speaker, path, state));
+            }
+
+            let punctuation_root = root.join(PUNCTUATION_SUBDIR);
+            for dir_name in subdirectories(&punctuation_root)? {
+                let path = punctuation_root.join(&dir_name);
+                let (id, state) = match PunctuationModel::ALL
+                    .iter()
+                    .find(|m| m.dir_name() == dir_name)
+                {
+                    Some(model) => (model.name().to_string(), punctuation_state(*model, &path)),
+                    None => (dir_name, InstallState::Orphaned),
+                };
+                out.push(installed(id, ModelKind::Punctuation, path, state));
+            }
+        }
+
+        if let Some(fg_root) = &self.functiongemma_root {
+            for variant in subdirectories(fg_root)? {
+                let path = fg_root.join(&variant);
+                let state = if is_functiongemma_complete(&path, &variant) {
+                    InstallState::Complete
+                } else {
+                    InstallState::Partial
+                };
+                out.push(installed(variant, ModelKind::FunctionGemma, path, state));
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Total disk usage, split by kind.
+    pub fn footprint(&self) -> Result<StoreFootprint> {
+        let mut fp = StoreFootprint::default();
+        for m in self.list()? {
+            match m.kind {
+                ModelKind::Stt => fp.stt_bytes += m.size_bytes,
+                ModelKind::Turn => fp.turn_bytes += m.size_bytes,
+                ModelKind::Speaker => fp.speaker_bytes += m.size_bytes,
+                ModelKind::Punctuation => fp.punctuation_bytes += m.size_bytes,
+                ModelKind::FunctionGemma => fp.functiongemma_bytes += m.size_bytes,
+            }
+            if m.state != InstallState::Complete {
+                fp.reclaimable_bytes += m.size_bytes;
+            }
+        }
+        Ok(fp)
+    }
+
+    /// Resolve the directory for a model id of the given kind.
+    ///
+    /// Known model names map to their registry directory; anything else is
+    /// treated as a raw directory name so orphaned entries can be removed.
+    pub fn path_for(&self, kind: ModelKind, id: &str) -> Result<PathBuf> {
+        let root = self
+            .kind_root(kind)
+            .ok_or_else(|| ModelError::NotFound(format!("{} store", kind.as_str())))?;
+
+        let dir_name = match kind {
+            ModelKind::Stt => SttModel::from_name(id).map(|m| m.dir_name()),
+            ModelKind::Turn => TurnModel::from_name(id).map(|m| m.dir_name()),
+            ModelKind::Speaker => SpeakerModel::from_name(id).map(|m| m.dir_name()),
+            ModelKind::Punctuation => PunctuationModel::from_name(id).map(|m| m.dir_name()),
+            ModelKind::FunctionGemma => None,
+        }
+        .unwrap_or(id);
+
+        if !is_plain_dir_name(dir_name) || (kind == ModelKind::Stt && is_reserved_subdir(dir_name))
+        {
+            return Err(ModelError::NotFound(id.to_string()));
+        }
+
+        Ok(root.join(dir_name))
+    }
+
+    /// Delete a model directory. Returns the number of bytes freed.
+    pub fn remove(&self, kind: ModelKind, id: &str) -> Result<u64> {
+        let path = self.path_for(kind, id)?;
+        if !path.is_dir() {
+            return Err(ModelError::NotFound(id.to_string()));
+        }
+        let size = dir_size(&path);
+        std::fs::remove_dir_all(&path)?;
+        tracing::info!(
+            kind = kind.as_str(),
+            id,
+            freed_bytes = size,
+            "Removed model"
+        );
+        Ok(size)
+    }
+
+    /// Remove partial and orphaned directories, plus stray temp files inside
+    /// complete ones.
+    ///
+    /// `in_use` lists model ids that must not be touched (active downloads),
+    /// since an in-flight download looks exactly like a partial one.
+    pub fn collect_garbage(&self, in_use: &[(ModelKind, &str)]) -> Result<GcReport> {
+        let mut report = GcReport::default();
+
+        for m in self.list()? {
+            if in_use.iter().any(|(k, id)| *k == m.kind && *id == m.id) {
+                continue;
+            }
+            match m.state {
+                InstallState::Complete => {
+                    for file in temp_files(&m.path) {
+                        let size = std::fs::metadata(&file).map(|md| md.len()).unwrap_or(0);
+                        if std::fs::remove_file(&file).is_ok() {
+                            report.freed_bytes += size;
+                            report.removed.push(file);
+                        }
+                    }
+                }
+                InstallState::Partial | InstallState::Orphaned => {
+                    match std::fs::remove_dir_all(&m.path) {
+                        Ok(()) => {
+                            report.freed_bytes += m.size_bytes;
+                            report.removed.push(m.path);
+                        }
+                        Err(e) => {
+                            tracing::warn!(path = ?m.path, error = %e, "Failed to remove model dir");
+                        }
+                    }
+                }
+            }
+        }
+
+        if !report.removed.is_empty() {
+            tracing::info!(
+                removed = report.removed.len(),
+                freed_bytes = report.freed_bytes,
+                "Model store garbage collected"
+            );
+        }
+        Ok(report)
+    }
+}
+
+/// FunctionGemma storage root for a given app data dir.
+pub fn functiongemma_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(FUNCTIONGEMMA_SUBDIR)
+}
+
+/// Record that the model stored at `dir` was just loaded.
+///
+/// Best-effort: failures are logged and otherwise ignored, since usage
+/// tracking must never prevent a model from loading.
+pub fn mark_used(dir: &Path) {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if let Err(e) = std::fs::write(dir.join(LAST_USED_MARKER), now.to_string()) {
+        tracing::debug!(dir = ?dir, error = %e, "Failed to write last-used marker");
+    }
+}
+
+fn last_used(dir: &Path) -> Option<SystemTime> {
+    std::fs::metadata(dir.join(LAST_USED_MARKER))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+fn installed(id: String, kind: ModelKind, path: PathBuf, state: InstallState) -> InstalledModel {
+    InstalledModel {
+        id,
+        kind,
+        size_bytes: dir_size(&path),
+        last_used: last_used(&path),
+        path,
+        state,
+    }
+}
+
+fn stt_state(model: SttModel, dir: &Path) -> InstallState {
+    if (model.metadata().is_downloaded)(dir) {
+        InstallState::Complete
+    } else {
+        InstallState::Partial
+    }
+}
+
+fn turn_state(model: TurnModel, dir: &Path) -> InstallState {
+    if dir.join(model.local_filename()).exists() {
+        InstallState::Complete
+    } else {
+        InstallState::Partial
+    }
+}
+
+fn speaker_state(model: SpeakerModel, dir: &Path) -> InstallState {
+    if dir.join(model.local_filename()).exists() {
+        InstallState::Complete
+    } else {
+        InstallState::Partial
+    }
+}
+
+fn punctuation_state(model: PunctuationModel, dir: &Path) -> InstallState {
+    if dir.join(model.model_filename()).exists() && dir.join(model.vocab_filename()).exists() {
+        InstallState::Complete
+    } else {
+        InstallState::Partial
+    }
+}
+
+fn is_functiongemma_complete(dir: &Path, variant: &str) -> bool {
+    let ok = |p: PathBuf| {
+        std::fs::metadata(p)
+            .map(|m| m.is_file() && m.len() > MIN_FUNCTIONGEMMA_FILE_BYTES)
+            .unwrap_or(false)
+    };
+    ok(dir.join(format!("{variant}.onnx")))
+        && ok(dir.join(format!("{variant}.onnx_data")))
+        && ok(dir.join("tokenizer.json"))
+}
+
+/// Subdirectories of the STT root that hold other model kinds.
+fn is_reserved_subdir(name: &str) -> bool {
+    name == TURN_SUBDIR || name == SPEAKER_SUBDIR || name == PUNCTUATION_SUBDIR
+}
+
+fn is_plain_dir_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains('/') && !name.contains('\\')
+}
+
+/// Names of immediate subdirectories (missing root = empty).
+fn subdirectories(root: &Path) -> Result<Vec<String>> {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Recursive size of a directory in bytes (unreadable entries count as 0).
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&e.path()),
+            Ok(_) => e.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Leftover download artifacts directly inside a model directory.
+fn temp_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| TEMP_FILE_SUFFIXES.iter().any(|s| n.ends_with(s)))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(path: &Path, bytes: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; bytes]).unwrap();
+    }
+
+    fn complete_nemo(root: &Path) -> PathBuf {
+        let dir = root.join(SttModel::NemoConformerCatalan.dir_name());
+        write(&dir.join("model.onnx"), 4000);
+        write(&dir.join("tokens.txt"), 100);
+        dir
+    }
+
+    #[test]
+    fn test_missing_root_lists_nothing() {
+        let tmp = tempdir().unwrap();
+        let store = ModelStore::new(tmp.path().join("missing"));
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_classifies_entries() {
+        let tmp = tempdir().unwrap();
+        complete_nemo(tmp.path());
+        write(
+            &tmp.path()
+                .join(SttModel::ParakeetTdt.dir_name())
+                .join("vocab.txt"),
+            10,
+        );
+        write(&tmp.path().join("old-model").join("weights.bin"), 10);
+        write(
+            &tmp.path()
+                .join(TURN_SUBDIR)
+                .join(TurnModel::SmartTurnV31Cpu.dir_name())
+                .join(TurnModel::SmartTurnV31Cpu.local_filename()),
+            50,
+        );
+        write(
+            &tmp.path()
+                .join(SPEAKER_SUBDIR)
+                .join(SpeakerModel::WespeakerResnet34.dir_name())
+                .join("download.part"),
+            30,
+        );
+        let punct_dir = tmp
+            .path()
+            .join(PUNCTUATION_SUBDIR)
+            .join(PunctuationModel::OnlinePunctEn.dir_name());
+        write(
+            &punct_dir.join(PunctuationModel::OnlinePunctEn.model_filename()),
+            40,
+        );
+        write(
+            &punct_dir.join(PunctuationModel::OnlinePunctEn.vocab_filename()),
+            5,
+        );
+
+        let store = ModelStore::new(tmp.path());
+        let models = store.list().unwrap();
+        let find = |id: &str| models.iter().find(|m| m.id == id).unwrap();
+
+        assert_eq!(models.len(), 6);
+        assert_eq!(find("nemo-conformer-ca").state, InstallState::Complete);
+        assert_eq!(find("nemo-conformer-ca").size_bytes, 4100);
+        assert_eq!(find("parakeet-tdt").state, InstallState::Partial);
+        assert_eq!(find("old-model").state, InstallState::Orphaned);
+        assert_eq!(find("smart-turn-v3.1-cpu").kind, ModelKind::Turn);
+        assert_eq!(find("smart-turn-v3.1-cpu").state, InstallState::Complete);
+        assert_eq!(find("wespeaker-resnet34").kind, ModelKind::Speaker);
+        assert_eq!(find("wespeaker-resnet34").state, InstallState::Partial);
+        assert_eq!(store.footprint().unwrap().speaker_bytes, 30);
+        assert_eq!(find("online-punct-en").kind, ModelKind::Punctuation);
+        assert_eq!(find("online-punct-en").state, InstallState::Complete);
+        assert_eq!(store.footprint().unwrap().punctuation_bytes, 45);
+    }
+
+    #[test]
+    fn test_functiongemma_variants() {
+        let tmp = tempdir().unwrap();
+        let fg = functiongemma_dir(tmp.path());
+        write(&fg.join("model_fp16").join("model_fp16.onnx"), 2048);
+        write(&fg.join("model_fp16").join("model_fp16.onnx_data"), 2048);
+        write(&fg.join("model_fp16").join("tokenizer.json"), 2048);
+        write(&fg.join("model_q4").join("model_q4.onnx.part"), 2048);
+
+        let store = ModelStore::new(tmp.path().join("models")).with_functiongemma_dir(&fg);
+        let models = store.list().unwrap();
+        assert_eq!(models.len(), 2);
+        assert!(models
+            .iter()
+            .any(|m| m.id == "model_fp16" && m.state == InstallState::Complete));
+        assert!(models
+            .iter()
+            .any(|m| m.id == "model_q4" && m.state == InstallState::Partial));
+
+        let fp = store.footprint().unwrap();
+        assert_eq!(fp.functiongemma_bytes, 4 * 2048);
+        assert_eq!(fp.reclaimable_bytes, 2048);
+
+        let fg_only = ModelStore::functiongemma_only(&fg);
+        assert_eq!(fg_only.list().unwrap().len(), 2);
+        assert!(fg_only.remove(ModelKind::Stt, "model_fp16").is_err());
+        assert_eq!(
+            fg_only
+                .remove(ModelKind::FunctionGemma, "model_q4")
+                .unwrap(),
+            2048
+        );
+    }
+
+    #[test]
+    fn test_remove_known_and_orphaned() {
+        let tmp = tempdir().unwrap();
+        let nemo = complete_nemo(tmp.path());
+        write(&tmp.path().join("old-model").join("weights.bin"), 10);
+
+        let store = ModelStore::new(tmp.path());
+        assert_eq!(
+            store.remove(ModelKind::Stt, "nemo-conformer-ca").unwrap(),
+            4100
+        );
+        assert!(!nemo.exists());
+        assert_eq!(store.remove(ModelKind::Stt, "old-model").unwrap(), 10);
+        assert!(matches!(
+            store.remove(ModelKind::Stt, "old-model"),
+            Err(ModelError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_remove_rejects_path_traversal() {
+        let tmp = tempdir().unwrap();
+        let store = ModelStore::new(tmp.path().join("models"));
+        assert!(store.remove(ModelKind::Stt, "..").is_err());
+        assert!(store.remove(ModelKind::Stt, "../models").is_err());
+        assert!(store.remove(ModelKind::Stt, TURN_SUBDIR).is_err());
+        assert!(store.remove(ModelKind::Stt, SPEAKER_SUBDIR).is_err());
+        assert!(store.remove(ModelKind::Stt, PUNCTUATION_SUBDIR).is_err());
+        assert!(store
+            .remove(ModelKind::FunctionGemma, "model_fp16")
+            .is_err());
+    }
+
+    #[test]
+    fn test_collect_garbage() {
+        let tmp = tempdir().unwrap();
+        let nemo = complete_nemo(tmp.path());
+        write(&nemo.join("model.tar.bz2"), 300);
+        let partial = tmp.path().join(SttModel::ParakeetTdt.dir_name());
+        write(&partial.join("vocab.txt"), 10);
+        let downloading = tmp.path().join(SttModel::SherpaZipformerEn.dir_name());
+        write(&downloading.join("encoder.onnx"), 10);
+        write(&tmp.path().join("old-model").join("weights.bin"), 20);
+
+        let store = ModelStore::new(tmp.path());
+        let report = store
+            .collect_garbage(&[(ModelKind::Stt, "sherpa-zipformer-en")])
+            .unwrap();
+
+        assert_eq!(report.freed_bytes, 330);
+        assert_eq!(report.removed.len(), 3);
+        assert!(nemo.join("model.onnx").exists());
+        assert!(!nemo.join("model.tar.bz2").exists());
+        assert!(!partial.exists());
+        assert!(downloading.exists());
+    }
+
+    #[test]
+    fn test_mark_used_sets_last_used() {
+        let tmp = tempdir().unwrap();
+        let nemo = complete_nemo(tmp.path());
+        let store = ModelStore::new(tmp.path());
+        assert!(store.list().unwrap()[0].last_used.is_none());
+
+        mark_used(&nemo);
+        let models = store.list().unwrap();
+        assert!(models[0].last_used.is_some());
+        assert_eq!(models[0].state, InstallState::Complete);
+    }
+}
@@ -9,6 +9,14 @@ pub enum TurnModel {
 }
 
 impl TurnModel {
+    /// Every known turn model.
+    pub const ALL: [TurnModel; 1] = [Self::SmartTurnV31Cpu];
+
+    /// Look up a turn model by its unique identifier.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Self::SmartTurnV31Cpu => "smart-turn-v3.1-cpu",
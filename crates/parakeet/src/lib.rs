@@ -4,7 +4,7 @@ use std::sync::Mutex;
 
 use gibberish_stt::EngineLoader;
 pub use parakeet_rs::{
-    ExecutionProvider, ParakeetTDT, TimestampMode, Transcriber, TranscriptionResult,
+    ExecutionProvider, Parakeet, ParakeetTDT, TimestampMode, Transcriber, TranscriptionResult,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -167,6 +167,7 @@ fn transcription_result_to_segment(result: TranscriptionResult) -> gibberish_stt
         end_ms,
         words,
         speaker: None,
+        words_unaligned: false,
     }
 }
 
@@ -201,3 +202,99 @@ impl EngineLoader for ParakeetTdtLoader {
         false // TDT is batch-only
     }
 }
+
+/// Parakeet CTC (English) engine. Its word timestamps come from the CTC
+/// frames, which makes it the alignment reference for English Whisper
+/// transcripts.
+pub struct ParakeetCtcEngine {
+    model: Mutex<Parakeet>,
+    model_name: String,
+}
+
+impl ParakeetCtcEngine {
+    pub fn new(model_dir: impl AsRef<Path>) -> Result<Self> {
+        let model_dir = model_dir.as_ref();
+
+        // CTC model files: model_fp16.onnx (+ external data), tokenizer.json
+        if !model_dir.join("tokenizer.json").exists() {
+            return Err(ParakeetError::TranscriptionFailed(
+                "CTC model files not found (tokenizer.json required)".to_string(),
+            ));
+        }
+
+        let model = Parakeet::from_pretrained(model_dir, None)
+            .map_err(|e| ParakeetError::TranscriptionFailed(e.to_string()))?;
+
+        let model_name = model_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("parakeet-ctc")
+            .to_string();
+
+        Ok(Self {
+            model: Mutex::new(model),
+            model_name,
+        })
+    }
+
+    pub fn transcribe_samples(
+        &self,
+        audio: &[f32],
+        sample_rate: u32,
+    ) -> Result<TranscriptionResult> {
+        let mut model = self.model.lock().map_err(|_| ParakeetError::LockPoisoned)?;
+
+        model
+            .transcribe_samples(audio.to_vec(), sample_rate, 1, Some(TimestampMode::Words))
+            .map_err(|e| ParakeetError::TranscriptionFailed(e.to_string()))
+    }
+}
+
+impl gibberish_stt::SttEngine for ParakeetCtcEngine {
+    fn transcribe(&self, audio: &[f32]) -> gibberish_stt::Result<Vec<gibberish_stt::Segment>> {
+        let result = self
+            .transcribe_samples(audio, 16000)
+            .map_err(|e| gibberish_stt::SttError::TranscriptionFailed(e.to_string()))?;
+
+        Ok(vec![transcription_result_to_segment(result)])
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    fn supported_languages(&self) -> Vec<&'static str> {
+        vec!["en"]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+unsafe impl Send for ParakeetCtcEngine {}
+unsafe impl Sync for ParakeetCtcEngine {}
+
+/// Loader for the Parakeet CTC model.
+pub struct ParakeetCtcLoader;
+
+impl EngineLoader for ParakeetCtcLoader {
+    fn name(&self) -> &str {
+        "Parakeet CTC"
+    }
+
+    fn can_load(&self, model_id: &str) -> bool {
+        model_id == "parakeet-ctc"
+    }
+
+    fn load(
+        &self,
+        _model_id: &str,
+        model_path: &Path,
+        _language: &str,
+    ) -> gibberish_stt::Result<Box<dyn gibberish_stt::SttEngine>> {
+        let engine = ParakeetCtcEngine::new(model_path)
+            .map_err(|e| gibberish_stt::SttError::TranscriptionFailed(e.to_string()))?;
+        Ok(Box::new(engine))
+    }
+}
@@ -0,0 +1,256 @@
+//! CNN-BiLSTM punctuation and casing model (sherpa-onnx "online punct").
+//!
+//! The model reads BPE token ids and predicts, for every word, a casing
+//! class and the punctuation mark that follows it. Predictions are made per
+//! word, so the output maps one-to-one onto the input words.
+
+mod vocab;
+
+use gibberish_punctuation::{capitalize, PunctuationError, Punctuator};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::Path;
+use std::sync::Mutex;
+use vocab::BpeVocab;
+
+/// Punctuation classes, indexed by model output.
+const PUNCTUATION: [&str; 4] = ["", ",", ".", "?"];
+
+/// Casing classes, indexed by model output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Upper,
+    Capitalized,
+    Mixed,
+}
+
+const CASING: [Casing; 4] = [
+    Casing::Lower,
+    Casing::Upper,
+    Casing::Capitalized,
+    Casing::Mixed,
+];
+
+#[derive(Debug)]
+pub struct OnnxPunctuator {
+    session: Mutex<Session>,
+    vocab: BpeVocab,
+    token_ids_name: String,
+    valid_ids_name: String,
+    label_lens_name: String,
+    case_output: String,
+    punct_output: String,
+}
+
+impl OnnxPunctuator {
+    /// Load the model graph and its `bpe.vocab`.
+    pub fn load(
+        model_path: impl AsRef<Path>,
+        vocab_path: impl AsRef<Path>,
+    ) -> Result<Self, PunctuationError> {
+        let vocab = std::fs::read_to_string(vocab_path.as_ref())
+            .map_err(|e| PunctuationError::Model(format!("failed to read vocab: {e}")))?;
+        let vocab = BpeVocab::parse(&vocab);
+
+        let session = Session::builder()
+            .map_err(|e| PunctuationError::Model(e.to_string()))?
+            .with_inter_threads(1)
+            .map_err(|e| PunctuationError::Model(e.to_string()))?
+            .with_intra_threads(1)
+            .map_err(|e| PunctuationError::Model(e.to_string()))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| PunctuationError::Model(e.to_string()))?
+            .commit_from_file(model_path.as_ref())
+            .map_err(|e| PunctuationError::Model(e.to_string()))?;
+
+        let inputs: Vec<String> = session.inputs.iter().map(|i| i.name.clone()).collect();
+        let outputs: Vec<String> = session.outputs.iter().map(|o| o.name.clone()).collect();
+        if inputs.len() != 3 || outputs.len() != 2 {
+            return Err(PunctuationError::Model(format!(
+                "expected 3 inputs and 2 outputs, found {inputs:?} and {outputs:?}"
+            )));
+        }
+
+        // Inputs are (token ids, word-start mask, word count); outputs are
+        // (casing logits, punctuation logits) unless the names say otherwise.
+        let case_index = outputs
+            .iter()
+            .position(|n| n.contains("case") || n.contains("cap"))
+            .or_else(|| {
+                outputs
+                    .iter()
+                    .position(|n| n.contains("punc"))
+                    .map(|i| 1 - i)
+            })
+            .unwrap_or(0);
+
+        Ok(Self {
+            session: Mutex::new(session),
+            vocab,
+            token_ids_name: inputs[0].clone(),
+            valid_ids_name: inputs[1].clone(),
+            label_lens_name: inputs[2].clone(),
+            case_output: outputs[case_index].clone(),
+            punct_output: outputs[1 - case_index].clone(),
+        })
+    }
+
+    fn run(&self, words: &[&str]) -> Result<(Vec<usize>, Vec<usize>), PunctuationError> {
+        let mut token_ids = Vec::new();
+        let mut valid_ids = Vec::new();
+        for word in words {
+            for (i, id) in self.vocab.encode_word(word).into_iter().enumerate() {
+                token_ids.push(id);
+                valid_ids.push(i32::from(i == 0));
+            }
+        }
+        let num_tokens = token_ids.len() as i64;
+
+        let token_ids = Tensor::from_array(([1i64, num_tokens], token_ids))
+            .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+        let valid_ids_tensor = Tensor::from_array(([1i64, num_tokens], valid_ids.clone()))
+            .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+        let label_lens = Tensor::from_array(([1i64], vec![words.len() as i32]))
+            .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| PunctuationError::Inference("lock poisoned".to_string()))?;
+
+        let outputs = session
+            .run(ort::inputs![
+                self.token_ids_name.as_str() => token_ids,
+                self.valid_ids_name.as_str() => valid_ids_tensor,
+                self.label_lens_name.as_str() => label_lens,
+            ])
+            .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+
+        let classes = |name: &str, num_classes: usize| {
+            let output = outputs
+                .get(name)
+                .ok_or_else(|| PunctuationError::Inference(format!("missing output {name}")))?;
+            let (shape, data) = output
+                .try_extract_tensor::<f32>()
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+            per_word_argmax(shape, data, &valid_ids, words.len(), num_classes)
+        };
+
+        let cases = classes(&self.case_output, CASING.len())?;
+        let puncts = classes(&self.punct_output, PUNCTUATION.len())?;
+        Ok((cases, puncts))
+    }
+}
+
+impl Punctuator for OnnxPunctuator {
+    fn name(&self) -> &'static str {
+        "onnx-cnn-bilstm"
+    }
+
+    fn punctuate(&self, words: &[&str]) -> Result<Vec<String>, PunctuationError> {
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (cases, puncts) = self.run(words)?;
+
+        Ok(words
+            .iter()
+            .zip(cases.iter().zip(puncts.iter()))
+            .map(|(word, (&case, &punct))| {
+                let cased = match CASING[case] {
+                    Casing::Lower => word.to_string(),
+                    Casing::Upper => word.to_uppercase(),
+                    Casing::Capitalized | Casing::Mixed => capitalize(word),
+                };
+                format!("{cased}{}", PUNCTUATION[punct])
+            })
+            .collect())
+    }
+}
+
+/// Argmax class per word from `[1, rows, classes]` logits.
+///
+/// Rows are either one per word or one per token; in the latter case only
+/// the first token of each word (marked in `valid_ids`) is read.
+fn per_word_argmax(
+    shape: &[i64],
+    data: &[f32],
+    valid_ids: &[i32],
+    num_words: usize,
+    num_classes: usize,
+) -> Result<Vec<usize>, PunctuationError> {
+    let (rows, classes) = match shape {
+        [1, rows, classes] => (*rows as usize, *classes as usize),
+        _ => {
+            return Err(PunctuationError::InvalidOutput(format!(
+                "unexpected logits shape {shape:?}"
+            )))
+        }
+    };
+    if classes != num_classes || data.len() != rows * classes {
+        return Err(PunctuationError::InvalidOutput(format!(
+            "expected {num_classes} classes, got shape {shape:?}"
+        )));
+    }
+
+    let row_indices: Vec<usize> = if rows == num_words {
+        (0..num_words).collect()
+    } else if rows == valid_ids.len() {
+        valid_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v == 1)
+            .map(|(i, _)| i)
+            .collect()
+    } else {
+        return Err(PunctuationError::InvalidOutput(format!(
+            "{rows} rows for {num_words} words"
+        )));
+    };
+
+    Ok(row_indices
+        .into_iter()
+        .map(|row| {
+            let logits = &data[row * classes..(row + 1) * classes];
+            logits
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i)
+                .unwrap_or(0)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_word_rows() {
+        let data = [0.1, 0.9, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        let out = per_word_argmax(&[1, 2, 4], &data, &[1, 1], 2, 4).unwrap();
+        assert_eq!(out, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_per_token_rows() {
+        // Three tokens for two words: the second token is a continuation.
+        let data = [
+            0.0, 0.0, 1.0, 0.0, //
+            1.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0,
+        ];
+        let out = per_word_argmax(&[1, 3, 4], &data, &[1, 0, 1], 2, 4).unwrap();
+        assert_eq!(out, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_rejects_wrong_class_count() {
+        let data = [0.0; 6];
+        assert!(per_word_argmax(&[1, 2, 3], &data, &[1, 1], 2, 4).is_err());
+    }
+}
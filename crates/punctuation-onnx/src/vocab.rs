@@ -0,0 +1,100 @@
+//! SentencePiece BPE vocabulary (`bpe.vocab`: one `piece<TAB>score` per line).
+
+use std::collections::HashMap;
+
+/// Word-boundary marker prefixed to the first piece of every word.
+const WORD_START: char = '▁';
+
+#[derive(Debug)]
+pub struct BpeVocab {
+    ids: HashMap<String, i32>,
+    unk_id: i32,
+    max_piece_chars: usize,
+}
+
+impl BpeVocab {
+    pub fn parse(contents: &str) -> Self {
+        let mut ids = HashMap::new();
+        let mut max_piece_chars = 1;
+        for (id, line) in contents.lines().enumerate() {
+            let piece = line.split('\t').next().unwrap_or_default();
+            if piece.is_empty() {
+                continue;
+            }
+            max_piece_chars = max_piece_chars.max(piece.chars().count());
+            ids.entry(piece.to_string()).or_insert(id as i32);
+        }
+        let unk_id = ids.get("<unk>").copied().unwrap_or(0);
+        Self {
+            ids,
+            unk_id,
+            max_piece_chars,
+        }
+    }
+
+    /// Split one word into piece ids.
+    ///
+    /// Uses greedy longest-match over the vocabulary, which agrees with BPE
+    /// merges on the vast majority of words. Characters that are not in the
+    /// vocabulary become `<unk>`.
+    pub fn encode_word(&self, word: &str) -> Vec<i32> {
+        let chars: Vec<char> = std::iter::once(WORD_START).chain(word.chars()).collect();
+        let mut ids = Vec::new();
+        let mut start = 0;
+
+        while start < chars.len() {
+            let longest = (start + 1..=chars.len().min(start + self.max_piece_chars))
+                .rev()
+                .find_map(|end| {
+                    let piece: String = chars[start..end].iter().collect();
+                    self.ids.get(&piece).map(|&id| (id, end))
+                });
+            match longest {
+                Some((id, end)) => {
+                    ids.push(id);
+                    start = end;
+                }
+                None => {
+                    // A bare "▁" may be missing; fold it into the next piece.
+                    if start == 0 && chars.len() > 1 {
+                        start = 1;
+                        continue;
+                    }
+                    ids.push(self.unk_id);
+                    start += 1;
+                }
+            }
+        }
+
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab() -> BpeVocab {
+        BpeVocab::parse("<unk>\t0\n▁hel\t-1\n▁he\t-2\nlo\t-3\nl\t-4\no\t-5\n▁\t-6\n▁a\t-7\n")
+    }
+
+    #[test]
+    fn test_parse() {
+        let v = vocab();
+        assert_eq!(v.ids.len(), 8);
+        assert_eq!(v.unk_id, 0);
+    }
+
+    #[test]
+    fn test_encode_longest_match() {
+        let v = vocab();
+        assert_eq!(v.encode_word("hello"), vec![1, 3]);
+        assert_eq!(v.encode_word("a"), vec![7]);
+    }
+
+    #[test]
+    fn test_encode_unknown() {
+        let v = vocab();
+        assert_eq!(v.encode_word("zo"), vec![6, 0, 5]);
+    }
+}
@@ -0,0 +1,82 @@
+//! Punctuation and truecasing for unpunctuated STT output.
+//!
+//! CTC and transducer engines emit bare lowercase (or uppercase) words. A
+//! [`Punctuator`] restores sentence punctuation and casing word by word, so
+//! the output maps one-to-one onto the input words and their timestamps.
+//! [`PunctuationStage`] runs a punctuator over successive streaming commits,
+//! carrying sentence state from one commit to the next.
+
+mod rules;
+mod stage;
+
+pub use rules::RuleBasedPunctuator;
+pub use stage::PunctuationStage;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PunctuationError {
+    #[error("failed to load model: {0}")]
+    Model(String),
+    #[error("inference failed: {0}")]
+    Inference(String),
+    #[error("invalid output: {0}")]
+    InvalidOutput(String),
+}
+
+pub type Result<T> = std::result::Result<T, PunctuationError>;
+
+pub trait Punctuator: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Punctuate and case a run of lowercase words.
+    ///
+    /// Returns exactly one output word per input word: punctuation is
+    /// attached to the word it follows (or precedes, for `¿`), never emitted
+    /// as a token of its own.
+    fn punctuate(&self, words: &[&str]) -> Result<Vec<String>>;
+}
+
+/// Characters that close a sentence.
+const SENTENCE_END: &[char] = &['.', '?', '!', '…'];
+
+/// Whether a punctuated word closes its sentence.
+pub fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')', '»'])
+        .ends_with(SENTENCE_END)
+}
+
+/// Uppercase the first letter of a word, skipping leading `¿`/`¡`/quotes.
+pub fn capitalize(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut done = false;
+    for c in word.chars() {
+        if !done && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            done = true;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ends_sentence() {
+        assert!(ends_sentence("done."));
+        assert!(ends_sentence("really?"));
+        assert!(ends_sentence("\"stop!\""));
+        assert!(!ends_sentence("well,"));
+        assert!(!ends_sentence("word"));
+    }
+
+    #[test]
+    fn test_capitalize() {
+        assert_eq!(capitalize("hello"), "Hello");
+        assert_eq!(capitalize("¿qué"), "¿Qué");
+        assert_eq!(capitalize("àlex"), "Àlex");
+        assert_eq!(capitalize(""), "");
+    }
+}
@@ -0,0 +1,145 @@
+//! Rule-based fallback punctuator.
+
+use crate::{capitalize, ends_sentence, Punctuator, Result};
+
+/// English words that open a question when they start a sentence.
+const QUESTION_WORDS_EN: &[&str] = &[
+    "what", "why", "how", "who", "whom", "whose", "where", "when", "which", "is", "are", "am",
+    "was", "were", "do", "does", "did", "can", "could", "would", "should", "will", "shall", "may",
+    "might", "have", "has", "isn't", "aren't", "don't", "doesn't", "didn't", "can't", "won't",
+];
+
+/// Spanish interrogatives (only the accented forms are unambiguous).
+const QUESTION_WORDS_ES: &[&str] = &[
+    "qué", "quién", "quiénes", "cómo", "dónde", "cuándo", "cuál", "cuáles", "cuánto", "cuánta",
+    "cuántos", "cuántas",
+];
+
+/// Catalan interrogatives.
+const QUESTION_WORDS_CA: &[&str] = &[
+    "què", "qui", "on", "quan", "quin", "quina", "quins", "quines", "quant", "quanta", "quants",
+    "quantes",
+];
+
+/// English words that are always capitalized.
+const ALWAYS_CAPITALIZED_EN: &[&str] = &["i", "i'm", "i've", "i'll", "i'd"];
+
+/// Restores casing and sentence-final punctuation without a model.
+///
+/// Each call is treated as one sentence: the first word is capitalized and
+/// the last gets a full stop, or a question mark when the sentence opens
+/// with an interrogative. No commas are inserted.
+#[derive(Debug, Clone)]
+pub struct RuleBasedPunctuator {
+    language: String,
+}
+
+impl RuleBasedPunctuator {
+    /// Create a punctuator for an ISO 639-1 language code.
+    ///
+    /// Unknown codes get casing and full stops only.
+    pub fn new(language: &str) -> Self {
+        Self {
+            language: language.to_lowercase(),
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Whether `word` is capitalized wherever it appears in a sentence.
+    pub fn is_always_capitalized(&self, word: &str) -> bool {
+        self.language == "en" && ALWAYS_CAPITALIZED_EN.contains(&word.to_lowercase().as_str())
+    }
+
+    fn is_question_word(&self, word: &str) -> bool {
+        let list = match self.language.as_str() {
+            "en" => QUESTION_WORDS_EN,
+            "es" => QUESTION_WORDS_ES,
+            "ca" => QUESTION_WORDS_CA,
+            _ => return false,
+        };
+        let word = word.trim_start_matches(['¿', '¡']).to_lowercase();
+        list.contains(&word.as_str())
+    }
+}
+
+impl Punctuator for RuleBasedPunctuator {
+    fn name(&self) -> &'static str {
+        "rules"
+    }
+
+    fn punctuate(&self, words: &[&str]) -> Result<Vec<String>> {
+        let mut out: Vec<String> = Vec::with_capacity(words.len());
+        let mut sentence_start = 0;
+
+        for (i, word) in words.iter().enumerate() {
+            let at_start = out.last().is_none_or(|prev| ends_sentence(prev));
+            if at_start {
+                sentence_start = i;
+            }
+            let word = if at_start || self.is_always_capitalized(word) {
+                capitalize(word)
+            } else {
+                (*word).to_string()
+            };
+            out.push(word);
+        }
+
+        if let Some(last) = out.last_mut() {
+            if !ends_sentence(last) {
+                let last_word = last.trim_end_matches([',', ';', ':']).to_string();
+                let is_question = self.is_question_word(words[sentence_start]);
+                *last = format!("{last_word}{}", if is_question { "?" } else { "." });
+
+                if is_question && self.language == "es" && !out[sentence_start].starts_with('¿') {
+                    out[sentence_start] = format!("¿{}", out[sentence_start]);
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(language: &str, text: &str) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        RuleBasedPunctuator::new(language)
+            .punctuate(&words)
+            .unwrap()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_statement() {
+        assert_eq!(
+            run("en", "so i think we should ship it"),
+            "So I think we should ship it."
+        );
+    }
+
+    #[test]
+    fn test_question() {
+        assert_eq!(run("en", "what time is it"), "What time is it?");
+        assert_eq!(run("es", "qué hora es"), "¿Qué hora es?");
+        assert_eq!(run("ca", "quina hora és"), "Quina hora és?");
+    }
+
+    #[test]
+    fn test_keeps_existing_punctuation() {
+        assert_eq!(run("en", "okay. thanks"), "Okay. Thanks.");
+        assert_eq!(run("en", "done!"), "Done!");
+    }
+
+    #[test]
+    fn test_preserves_word_count() {
+        let words = ["one", "two", "three"];
+        let out = RuleBasedPunctuator::new("xx").punctuate(&words).unwrap();
+        assert_eq!(out, vec!["One", "two", "three."]);
+    }
+}
@@ -0,0 +1,240 @@
+//! Punctuation over a stream of commits.
+
+use std::sync::Arc;
+
+use crate::{capitalize, ends_sentence, Punctuator, RuleBasedPunctuator};
+
+/// Trailing characters dropped from a commit that stops mid-sentence.
+const OPEN_SENTENCE_TRIM: &[char] = &['.', '?', '!', '…', ',', ';', ':'];
+
+/// Applies a [`Punctuator`] to successive commits of one stream.
+///
+/// Commits are short and often split a sentence, so the stage remembers
+/// whether the previous commit left a sentence open. A continuation does not
+/// get its first word capitalized, and a commit that does not end at a
+/// pause does not get a full stop. If the punctuator fails, or breaks the
+/// one-word-per-word contract, the rule-based fallback is used instead.
+#[derive(Clone)]
+pub struct PunctuationStage {
+    punctuator: Arc<dyn Punctuator>,
+    fallback: RuleBasedPunctuator,
+    sentence_open: bool,
+}
+
+impl std::fmt::Debug for PunctuationStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PunctuationStage")
+            .field("punctuator", &self.punctuator.name())
+            .field("language", &self.fallback.language())
+            .field("sentence_open", &self.sentence_open)
+            .finish()
+    }
+}
+
+impl PunctuationStage {
+    pub fn new(punctuator: Arc<dyn Punctuator>, language: &str) -> Self {
+        Self {
+            punctuator,
+            fallback: RuleBasedPunctuator::new(language),
+            sentence_open: false,
+        }
+    }
+
+    /// A stage that only uses the rule-based punctuator.
+    pub fn rule_based(language: &str) -> Self {
+        Self::new(Arc::new(RuleBasedPunctuator::new(language)), language)
+    }
+
+    pub fn punctuator_name(&self) -> &'static str {
+        self.punctuator.name()
+    }
+
+    /// Punctuate the words of one commit.
+    ///
+    /// `ends_sentence` is true when the commit ends at a pause, so the last
+    /// sentence can be closed. The output has one word per input word.
+    pub fn process_words(&mut self, words: &[&str], ends_sentence: bool) -> Vec<String> {
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let lowered: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        let input: Vec<&str> = lowered.iter().map(String::as_str).collect();
+
+        let mut out = match self.punctuator.punctuate(&input) {
+            Ok(out) if out.len() == input.len() => out,
+            Ok(out) => {
+                tracing::warn!(
+                    punctuator = self.punctuator.name(),
+                    expected = input.len(),
+                    got = out.len(),
+                    "Punctuator changed the word count, using rules"
+                );
+                self.fallback_punctuate(&input)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    punctuator = self.punctuator.name(),
+                    error = %e,
+                    "Punctuation failed, using rules"
+                );
+                self.fallback_punctuate(&input)
+            }
+        };
+
+        // Undo sentence-initial capitalization when continuing a sentence.
+        if self.sentence_open && !self.fallback.is_always_capitalized(input[0]) {
+            if let Some(suffix) = out[0].strip_prefix(&capitalize(input[0])) {
+                out[0] = format!("{}{suffix}", input[0]);
+            }
+        }
+
+        if ends_sentence {
+            close_sentence(&mut out);
+        } else {
+            open_sentence(&mut out);
+        }
+
+        self.sentence_open = out.last().is_some_and(|w| !crate::ends_sentence(w));
+        out
+    }
+
+    /// Punctuate a whitespace-separated commit.
+    pub fn process_text(&mut self, text: &str, ends_sentence: bool) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        self.process_words(&words, ends_sentence).join(" ")
+    }
+
+    /// Forget sentence state for a new stream.
+    pub fn reset(&mut self) {
+        self.sentence_open = false;
+    }
+
+    fn fallback_punctuate(&self, words: &[&str]) -> Vec<String> {
+        self.fallback
+            .punctuate(words)
+            .unwrap_or_else(|_| words.iter().map(|w| (*w).to_string()).collect())
+    }
+}
+
+/// End the last sentence with a full stop unless it is already closed.
+fn close_sentence(words: &mut [String]) {
+    if let Some(last) = words.last_mut() {
+        if !ends_sentence(last) {
+            let trimmed = last.trim_end_matches([',', ';', ':']).to_string();
+            *last = format!("{trimmed}.");
+        }
+    }
+}
+
+/// Drop trailing punctuation the punctuator added at the commit boundary.
+fn open_sentence(words: &mut [String]) {
+    let Some(last) = words.last_mut() else {
+        return;
+    };
+    let was_question = last.ends_with('?');
+    let trimmed = last.trim_end_matches(OPEN_SENTENCE_TRIM);
+    if trimmed.is_empty() || trimmed.len() == last.len() {
+        return;
+    }
+    *last = trimmed.to_string();
+
+    // An inverted question mark opening the now-unfinished sentence goes too.
+    if was_question {
+        let sentence_start = words
+            .iter()
+            .rposition(|w| ends_sentence(w))
+            .map_or(0, |i| i + 1);
+        if let Some(word) = words[sentence_start..]
+            .iter_mut()
+            .find(|w| w.starts_with('¿'))
+        {
+            *word = word.trim_start_matches('¿').to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PunctuationError, Result};
+
+    /// Appends a comma to every word and a full stop to the last.
+    struct CommaPunctuator;
+
+    impl Punctuator for CommaPunctuator {
+        fn name(&self) -> &'static str {
+            "comma"
+        }
+
+        fn punctuate(&self, words: &[&str]) -> Result<Vec<String>> {
+            let mut out: Vec<String> = words.iter().map(|w| format!("{w},")).collect();
+            if let Some(last) = out.last_mut() {
+                *last = format!("{}.", last.trim_end_matches(','));
+            }
+            Ok(out)
+        }
+    }
+
+    struct BrokenPunctuator;
+
+    impl Punctuator for BrokenPunctuator {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn punctuate(&self, _words: &[&str]) -> Result<Vec<String>> {
+            Err(PunctuationError::Inference("boom".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_uppercase_input_is_truecased() {
+        let mut stage = PunctuationStage::rule_based("en");
+        assert_eq!(
+            stage.process_text("HELLO THERE I AM HERE", true),
+            "Hello there I am here."
+        );
+    }
+
+    #[test]
+    fn test_sentence_continues_across_commits() {
+        let mut stage = PunctuationStage::rule_based("en");
+        assert_eq!(
+            stage.process_text("we went to the", false),
+            "We went to the"
+        );
+        assert_eq!(
+            stage.process_text("park and i ran", true),
+            "park and I ran."
+        );
+        assert_eq!(stage.process_text("then we left", true), "Then we left.");
+    }
+
+    #[test]
+    fn test_open_commit_drops_question_marks() {
+        let mut stage = PunctuationStage::rule_based("es");
+        assert_eq!(stage.process_text("qué vamos a", false), "Qué vamos a");
+    }
+
+    #[test]
+    fn test_model_output_keeps_word_count() {
+        let mut stage = PunctuationStage::new(Arc::new(CommaPunctuator), "en");
+        let out = stage.process_words(&["one", "two", "three"], false);
+        assert_eq!(out, vec!["one,", "two,", "three"]);
+    }
+
+    #[test]
+    fn test_failure_falls_back_to_rules() {
+        let mut stage = PunctuationStage::new(Arc::new(BrokenPunctuator), "en");
+        assert_eq!(stage.process_text("how are you", true), "How are you?");
+    }
+
+    #[test]
+    fn test_reset_closes_sentence() {
+        let mut stage = PunctuationStage::rule_based("en");
+        stage.process_text("and then", false);
+        stage.reset();
+        assert_eq!(stage.process_text("hello", true), "Hello.");
+    }
+}
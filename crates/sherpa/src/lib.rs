@@ -514,6 +514,7 @@ impl SttEngine for SherpaStreamingEngine {
             end_ms,
             words: Vec::<Word>::new(),
             speaker: None,
+            words_unaligned: false,
         }])
     }
 
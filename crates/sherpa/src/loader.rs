@@ -36,6 +36,10 @@ impl EngineLoader for SherpaZipformerLoader {
     fn is_streaming(&self, _model_id: &str) -> bool {
         true
     }
+
+    fn has_word_timestamps(&self, _model_id: &str) -> bool {
+        false
+    }
 }
 
 /// Loader for Sherpa Whisper ONNX models.
@@ -69,6 +73,10 @@ impl EngineLoader for SherpaWhisperLoader {
     fn is_streaming(&self, _model_id: &str) -> bool {
         false // Whisper is batch-only
     }
+
+    fn has_word_timestamps(&self, _model_id: &str) -> bool {
+        false // Text only; timings come from forced alignment
+    }
 }
 
 /// Loader for NeMo CTC models (e.g., Catalan Conformer).
@@ -4,7 +4,7 @@
 //! Used for language-specific models like Catalan.
 
 use std::any::Any;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::mem;
 use std::path::Path;
 use std::sync::Mutex;
@@ -145,12 +145,18 @@ impl SherpaNemoCtcEngine {
 
     /// Transcribe audio samples.
     pub fn transcribe_samples(&self, samples: &[f32], sample_rate: u32) -> Result<String> {
+        self.decode(samples, sample_rate).map(|(text, _)| text)
+    }
+
+    /// Decode audio, returning the text and word timings built from the
+    /// per-token timestamps of the CTC output.
+    fn decode(&self, samples: &[f32], sample_rate: u32) -> Result<(String, Vec<Word>)> {
         let recognizer = self
             .recognizer
             .lock()
             .map_err(|_| SherpaError::TranscriptionFailed("lock poisoned".to_string()))?;
 
-        let text = unsafe {
+        let (text, tokens) = unsafe {
             let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(*recognizer);
             if stream.is_null() {
                 return Err(SherpaError::TranscriptionFailed(
@@ -171,27 +177,96 @@ impl SherpaNemoCtcEngine {
             let text = if result_ptr.is_null() || (*result_ptr).text.is_null() {
                 String::new()
             } else {
-                std::ffi::CStr::from_ptr((*result_ptr).text)
+                CStr::from_ptr((*result_ptr).text)
                     .to_string_lossy()
                     .to_string()
             };
 
+            // Token texts and their start times (seconds), when the model reports them.
+            let mut tokens = Vec::new();
             if !result_ptr.is_null() {
+                let result = &*result_ptr;
+                if !result.timestamps.is_null() && !result.tokens_arr.is_null() {
+                    for i in 0..result.count.max(0) as usize {
+                        let token_ptr = *result.tokens_arr.add(i);
+                        if token_ptr.is_null() {
+                            continue;
+                        }
+                        let token = CStr::from_ptr(token_ptr).to_string_lossy().to_string();
+                        tokens.push((token, *result.timestamps.add(i)));
+                    }
+                }
                 sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
             }
             sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
 
-            text
+            (text, tokens)
         };
 
-        Ok(text.trim().to_string())
+        let audio_end_ms = (samples.len() as f64 / sample_rate as f64 * 1000.0).round() as u64;
+        Ok((
+            text.trim().to_string(),
+            tokens_to_words(&tokens, audio_end_ms),
+        ))
+    }
+}
+
+/// Upper bound on how long a word extends past its last token's start.
+///
+/// CTC only reports token start times, so word ends are inferred from the
+/// next word's start; this keeps pauses from being attributed to a word.
+const MAX_TOKEN_TAIL_MS: u64 = 400;
+
+/// Group SentencePiece tokens into words.
+///
+/// A token starting with `▁` opens a new word; other tokens continue the
+/// current one.
+fn tokens_to_words(tokens: &[(String, f32)], audio_end_ms: u64) -> Vec<Word> {
+    // (word, start of its last token)
+    let mut words: Vec<(Word, u64)> = Vec::new();
+    for (token, start_s) in tokens {
+        let start_ms = (start_s.max(0.0) * 1000.0).round() as u64;
+        let piece = token.trim_start_matches('\u{2581}');
+
+        match words.last_mut() {
+            Some((current, last_token_ms)) if !token.starts_with('\u{2581}') => {
+                current.text.push_str(piece);
+                *last_token_ms = start_ms;
+            }
+            _ => {
+                if let Some((prev, last_token_ms)) = words.last_mut() {
+                    prev.end_ms = start_ms.min(*last_token_ms + MAX_TOKEN_TAIL_MS);
+                }
+                words.push((
+                    Word {
+                        text: piece.to_string(),
+                        start_ms,
+                        end_ms: start_ms,
+                        confidence: 1.0,
+                    },
+                    start_ms,
+                ));
+            }
+        }
+    }
+
+    if let Some((last, last_token_ms)) = words.last_mut() {
+        last.end_ms = audio_end_ms
+            .min(*last_token_ms + MAX_TOKEN_TAIL_MS)
+            .max(last.start_ms);
     }
+
+    words
+        .into_iter()
+        .map(|(word, _)| word)
+        .filter(|w| !w.text.is_empty())
+        .collect()
 }
 
 impl SttEngine for SherpaNemoCtcEngine {
     fn transcribe(&self, audio: &[f32]) -> gibberish_stt::Result<Vec<Segment>> {
-        let text = self
-            .transcribe_samples(audio, 16000)
+        let (text, words) = self
+            .decode(audio, 16000)
             .map_err(|e| gibberish_stt::SttError::TranscriptionFailed(e.to_string()))?;
 
         let end_ms = (audio.len() as f64 / 16000.0 * 1000.0).round() as u64;
@@ -199,8 +274,9 @@ impl SttEngine for SherpaNemoCtcEngine {
             text,
             start_ms: 0,
             end_ms,
-            words: Vec::<Word>::new(),
+            words,
             speaker: None,
+            words_unaligned: false,
         }])
     }
 
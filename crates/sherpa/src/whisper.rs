@@ -134,6 +134,7 @@ impl SttEngine for SherpaWhisperEngine {
             end_ms,
             words: Vec::<Word>::new(),
             speaker: None,
+            words_unaligned: false,
         }])
     }
 
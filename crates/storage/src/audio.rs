@@ -0,0 +1,249 @@
+//! Session recordings: compressed when attached, sliced on demand.
+//!
+//! [`Database::attach_session_audio`] replaces the recorder's WAV file with
+//! a FLAC file, sealed when the database is encrypted, and attaches it to
+//! the session. [`Database::session_audio_clip`] cuts the audio of a time
+//! range, segment or word out of it, for playback and for running a model
+//! again over part of a session. Recordings attached before compression
+//! are read as WAV.
+
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::{flac, retention, Database, Result, StorageError, TranscriptRepository};
+
+/// Part of a session recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AudioSpan {
+    /// Milliseconds from the start of the recording.
+    Range {
+        start_ms: u64,
+        end_ms: u64,
+    },
+    Segment {
+        segment_id: Uuid,
+    },
+    /// Word `word` (0-based) of a segment.
+    Word {
+        segment_id: Uuid,
+        word: usize,
+    },
+}
+
+/// Mono 16-bit audio cut from a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioClip {
+    pub sample_rate: u32,
+    /// Where the clip starts in the recording.
+    pub start_ms: u64,
+    pub samples: Vec<i16>,
+}
+
+impl AudioClip {
+    pub fn duration_ms(&self) -> u64 {
+        self.samples.len() as u64 * 1000 / u64::from(self.sample_rate)
+    }
+
+    /// Samples in [-1, 1], as the speech models take them.
+    pub fn to_f32(&self) -> Vec<f32> {
+        self.samples
+            .iter()
+            .map(|&s| f32::from(s) / f32::from(i16::MAX))
+            .collect()
+    }
+
+    /// The clip as a WAV file, e.g. for playback.
+    pub fn to_wav(&self) -> Result<Vec<u8>> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec).map_err(audio_error)?;
+        for &sample in &self.samples {
+            writer.write_sample(sample).map_err(audio_error)?;
+        }
+        writer.finalize().map_err(audio_error)?;
+        Ok(wav.into_inner())
+    }
+}
+
+fn audio_error(e: impl std::fmt::Display) -> StorageError {
+    StorageError::Audio(e.to_string())
+}
+
+impl Database {
+    /// Compress the WAV recording at `wav_path` and attach it to a session.
+    /// The FLAC file takes its place (same name, `.flac`) and is sealed when
+    /// the database is encrypted. Returns its path.
+    pub fn attach_session_audio(&self, id: &Uuid, wav_path: &Path) -> Result<PathBuf> {
+        let cipher = self.file_cipher()?;
+        let data = match &cipher {
+            Some(cipher) => cipher.read_file(wav_path)?,
+            None => std::fs::read(wav_path)
+                .map_err(|e| audio_error(format!("{}: {e}", wav_path.display())))?,
+        };
+        let recording = read_wav(&data)?;
+        let mut flac = flac::encode(&recording.samples, recording.sample_rate);
+        if let Some(cipher) = &cipher {
+            flac = cipher.seal(&flac)?;
+        }
+
+        let path = wav_path.with_extension("flac");
+        let tmp = wav_path.with_extension("flac.tmp");
+        std::fs::write(&tmp, &flac)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&tmp);
+                audio_error(format!("{}: {e}", path.display()))
+            })?;
+        if let Err(e) = self.set_session_audio(id, Some(&path)) {
+            retention::remove_audio(&path);
+            return Err(e);
+        }
+        if path != wav_path {
+            retention::remove_audio(wav_path);
+        }
+        tracing::debug!(
+            "Recording of session {} compressed from {} to {} bytes",
+            id,
+            data.len(),
+            flac.len()
+        );
+        Ok(path)
+    }
+
+    /// The audio of `span` in a session's recording, widened by
+    /// `padding_ms` on both sides and clamped to the recording.
+    pub fn session_audio_clip(
+        &self,
+        id: &Uuid,
+        span: &AudioSpan,
+        padding_ms: u64,
+    ) -> Result<AudioClip> {
+        let (start_ms, end_ms) = match *span {
+            AudioSpan::Range { start_ms, end_ms } => (start_ms, end_ms),
+            AudioSpan::Segment { segment_id } => {
+                let transcript = self.get(id)?;
+                let segment = transcript
+                    .segments
+                    .iter()
+                    .find(|s| s.id == segment_id)
+                    .ok_or_else(|| StorageError::NotFound(format!("segment {segment_id}")))?;
+                (segment.start_ms, segment.end_ms)
+            }
+            AudioSpan::Word { segment_id, word } => {
+                let transcript = self.get(id)?;
+                let word = transcript
+                    .segments
+                    .iter()
+                    .find(|s| s.id == segment_id)
+                    .and_then(|s| s.words.get(word))
+                    .ok_or_else(|| {
+                        StorageError::NotFound(format!("word {word} of segment {segment_id}"))
+                    })?;
+                (word.start_ms, word.end_ms)
+            }
+        };
+        if end_ms <= start_ms {
+            return Err(StorageError::Audio(format!(
+                "empty span {start_ms}..{end_ms} ms"
+            )));
+        }
+
+        let path = self
+            .session_audio(id)?
+            .ok_or_else(|| StorageError::NotFound(format!("recording of transcript {id}")))?;
+        let data = match self.file_cipher()? {
+            Some(cipher) => cipher.read_file(&path)?,
+            None => {
+                std::fs::read(&path).map_err(|e| audio_error(format!("{}: {e}", path.display())))?
+            }
+        };
+        slice(
+            &data,
+            start_ms.saturating_sub(padding_ms),
+            end_ms.saturating_add(padding_ms),
+        )
+    }
+}
+
+fn slice(data: &[u8], start_ms: u64, end_ms: u64) -> Result<AudioClip> {
+    let (sample_rate, total, start, samples) = if flac::is_flac(data) {
+        let stream = flac::Stream::open(data)?;
+        let rate = u64::from(stream.sample_rate);
+        let start = (start_ms * rate / 1000).min(stream.total_samples);
+        let samples = stream.decode(start, end_ms.saturating_mul(rate) / 1000)?;
+        (stream.sample_rate, stream.total_samples, start, samples)
+    } else {
+        let recording = read_wav(data)?;
+        let rate = u64::from(recording.sample_rate);
+        let total = recording.samples.len() as u64;
+        let start = (start_ms * rate / 1000).min(total);
+        let end = (end_ms.saturating_mul(rate) / 1000).clamp(start, total);
+        let samples = recording.samples[start as usize..end as usize].to_vec();
+        (recording.sample_rate, total, start, samples)
+    };
+    if samples.is_empty() {
+        return Err(StorageError::Audio(format!(
+            "{start_ms} ms is past the end of the recording ({} ms)",
+            total * 1000 / u64::from(sample_rate)
+        )));
+    }
+    Ok(AudioClip {
+        sample_rate,
+        start_ms: start * 1000 / u64::from(sample_rate),
+        samples,
+    })
+}
+
+/// A WAV recording as mono 16-bit samples; channels are averaged.
+fn read_wav(data: &[u8]) -> Result<AudioClip> {
+    let mut reader = hound::WavReader::new(Cursor::new(data)).map_err(audio_error)?;
+    let spec = reader.spec();
+    let samples: Vec<i16> = match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Int, 16) => reader
+            .samples::<i16>()
+            .collect::<std::result::Result<_, _>>()
+            .map_err(audio_error)?,
+        (hound::SampleFormat::Int, bits) => reader
+            .samples::<i32>()
+            .map(|s| {
+                s.map(|s| {
+                    if bits > 16 {
+                        (s >> (bits - 16)) as i16
+                    } else {
+                        (s << (16 - bits)) as i16
+                    }
+                })
+            })
+            .collect::<std::result::Result<_, _>>()
+            .map_err(audio_error)?,
+        (hound::SampleFormat::Float, _) => reader
+            .samples::<f32>()
+            .map(|s| s.map(|s| (s.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(audio_error)?,
+    };
+    let channels = usize::from(spec.channels.max(1));
+    let samples = if channels == 1 {
+        samples
+    } else {
+        samples
+            .chunks(channels)
+            .map(|frame| {
+                (frame.iter().map(|&s| i32::from(s)).sum::<i32>() / frame.len() as i32) as i16
+            })
+            .collect()
+    };
+    Ok(AudioClip {
+        sample_rate: spec.sample_rate,
+        start_ms: 0,
+        samples,
+    })
+}
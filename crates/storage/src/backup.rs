@@ -0,0 +1,813 @@
+//! Backup and restore of the whole local archive.
+//!
+//! A backup is one gzip-compressed tar file:
+//!
+//! - `sessions/<id>.json`: every transcript, with its segments and words
+//! - `recordings/<id>.<ext>`: their recordings, unsealed
+//! - `activities.jsonl`: the activity feed, one activity per line
+//! - `settings.toml`: the settings, laid out as the config file
+//! - `files/<name>/...`: user directories such as skills and tool packs
+//! - `manifest.json`, written last: the format version, counts, and the
+//!   size and SHA-256 of every other entry
+//!
+//! With a passphrase in the [`BackupScope`] the whole file is encrypted:
+//! AES-256-GCM over 64 KiB chunks in the STREAM construction, so chunks
+//! cannot be dropped, reordered or cut off unnoticed, under a key derived
+//! with Argon2id. An encrypted database is only exported without one when
+//! the scope allows plaintext.
+//!
+//! [`Database::import_backup`] checks every checksum before it changes
+//! anything, then merges: what is missing here is added, identical items
+//! are skipped, and items whose local copy differs keep the local copy and
+//! are reported as conflicts.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::aead::{KeyInit, OsRng, Payload};
+use aes_gcm::Aes256Gcm;
+use argon2::{Algorithm, Argon2, Params, Version};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use gibberish_events::Activity;
+use gibberish_transcript::Transcript;
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use crate::{
+    activity_from_row, ActivityRepository, Database, Result, Settings, StorageError,
+    TranscriptRepository, ACTIVITY_COLUMNS,
+};
+
+/// Version of the backup format written by this build.
+pub const BACKUP_VERSION: u32 = 1;
+const BACKUP_FORMAT: &str = "gibberish-backup";
+
+const MANIFEST: &str = "manifest.json";
+const ACTIVITIES: &str = "activities.jsonl";
+const SETTINGS: &str = "settings.toml";
+
+/// Start of an encrypted backup; a plain one starts with the gzip magic.
+const SEALED_MAGIC: &[u8; 8] = b"GIBBBAK1";
+const SALT_LEN: usize = 16;
+/// The AES-GCM nonce less STREAM's 32-bit counter and last-chunk flag.
+const NONCE_PREFIX_LEN: usize = 7;
+/// Magic, salt, Argon2id memory, time and parallelism costs, nonce prefix.
+const SEALED_HEADER_LEN: usize = SEALED_MAGIC.len() + SALT_LEN + 3 * 4 + NONCE_PREFIX_LEN;
+/// Plaintext bytes per encrypted chunk.
+const CHUNK_LEN: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+/// Largest Argon2id memory cost accepted from a backup, in KiB (1 GiB).
+const MAX_MEMORY_COST: u32 = 1 << 20;
+
+/// What a backup covers besides the database.
+#[derive(Default)]
+pub struct BackupScope<'a> {
+    pub settings: Option<&'a Settings>,
+    /// User directories by archive name, e.g. `("skills", <config>/skills)`.
+    pub dirs: Vec<(String, PathBuf)>,
+    /// Where restored recordings are written. Without it they are skipped.
+    pub recordings_dir: Option<PathBuf>,
+    /// Encrypts an export, and opens an encrypted backup on import.
+    pub passphrase: Option<&'a str>,
+    /// Export an encrypted database without a passphrase.
+    pub allow_plaintext: bool,
+}
+
+/// A file in a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at_ms: i64,
+    /// Schema of the database the backup was made from.
+    pub schema_version: u32,
+    pub sessions: usize,
+    pub recordings: usize,
+    pub activities: usize,
+    pub files: usize,
+    pub entries: Vec<BackupEntry>,
+}
+
+/// Kind of a backed-up item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupItem {
+    Session,
+    Recording,
+    Activity,
+    Setting,
+    File,
+}
+
+/// An item whose local copy differs from the backup; the local copy is kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupConflict {
+    pub item: BackupItem,
+    /// Session or activity id, setting key, or file path in the backup.
+    pub id: String,
+}
+
+/// What an import added.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub sessions: usize,
+    pub recordings: usize,
+    pub activities: usize,
+    /// Keys of the settings taken from the backup.
+    pub settings: Vec<String>,
+    pub files: usize,
+    /// Items that were already here, identical.
+    pub unchanged: usize,
+    pub conflicts: Vec<BackupConflict>,
+}
+
+fn backup_error(e: impl std::fmt::Display) -> StorageError {
+    StorageError::Backup(e.to_string())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+impl Database {
+    /// Write a backup to `path` at `now_ms` (Unix ms). The file appears
+    /// only once it is complete.
+    pub fn export_backup(
+        &self,
+        path: &Path,
+        scope: &BackupScope,
+        now_ms: i64,
+    ) -> Result<BackupManifest> {
+        match scope.passphrase {
+            Some("") => return Err(backup_error("the backup passphrase is empty")),
+            None if self.is_encrypted() && !scope.allow_plaintext => {
+                return Err(backup_error(
+                    "the database is encrypted; give the backup a passphrase or allow a plaintext backup",
+                ))
+            }
+            _ => {}
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let manifest = self
+            .write_backup(&tmp, scope, now_ms)
+            .and_then(|manifest| {
+                std::fs::rename(&tmp, path).map_err(backup_error)?;
+                Ok(manifest)
+            })
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&tmp);
+            })?;
+        tracing::info!(
+            "Backup written to {}: {} sessions, {} recordings, {} activities, {} files",
+            path.display(),
+            manifest.sessions,
+            manifest.recordings,
+            manifest.activities,
+            manifest.files
+        );
+        Ok(manifest)
+    }
+
+    fn write_backup(
+        &self,
+        path: &Path,
+        scope: &BackupScope,
+        now_ms: i64,
+    ) -> Result<BackupManifest> {
+        let file = File::create(path).map_err(backup_error)?;
+        let sink = match scope.passphrase {
+            Some(passphrase) => Sink::Sealed(Box::new(SealedWriter::new(file, passphrase)?)),
+            None => Sink::Plain(file),
+        };
+        let mut archive = ArchiveWriter::new(sink, now_ms);
+        let mut manifest = BackupManifest {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at_ms: now_ms,
+            schema_version: self.schema_version()?,
+            sessions: 0,
+            recordings: 0,
+            activities: 0,
+            files: 0,
+            entries: Vec::new(),
+        };
+
+        let cipher = self.file_cipher()?;
+        for transcript in self.list()? {
+            let id = transcript.id;
+            archive.add(
+                &format!("sessions/{id}.json"),
+                &serde_json::to_vec(&transcript)?,
+            )?;
+            manifest.sessions += 1;
+
+            let Some(audio) = self.session_audio(&id)? else {
+                continue;
+            };
+            let data = match &cipher {
+                Some(cipher) => cipher.read_file(&audio),
+                None => std::fs::read(&audio).map_err(backup_error),
+            };
+            match data {
+                Ok(data) => {
+                    let ext = audio.extension().and_then(|e| e.to_str()).unwrap_or("wav");
+                    archive.add(&format!("recordings/{id}.{ext}"), &data)?;
+                    manifest.recordings += 1;
+                }
+                Err(e) => tracing::warn!("Recording {:?} left out of the backup: {}", audio, e),
+            }
+        }
+
+        let mut activities = Vec::new();
+        for activity in self.all_activities()? {
+            serde_json::to_writer(&mut activities, &activity)?;
+            activities.push(b'\n');
+            manifest.activities += 1;
+        }
+        archive.add(ACTIVITIES, &activities)?;
+
+        if let Some(settings) = scope.settings {
+            archive.add(SETTINGS, settings.to_toml()?.as_bytes())?;
+        }
+
+        for (name, dir) in &scope.dirs {
+            for file in files_in(dir)? {
+                let relative = file
+                    .strip_prefix(dir)
+                    .map_err(backup_error)?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let data = std::fs::read(&file).map_err(backup_error)?;
+                archive.add(&format!("files/{name}/{relative}"), &data)?;
+                manifest.files += 1;
+            }
+        }
+
+        manifest.entries = archive.entries.clone();
+        archive.add(MANIFEST, &serde_json::to_vec_pretty(&manifest)?)?;
+        archive.finish()?;
+        Ok(manifest)
+    }
+
+    /// Merge the backup at `path` into the local archive. Nothing is
+    /// changed unless every entry matches its checksum.
+    pub fn import_backup(&self, path: &Path, scope: &BackupScope) -> Result<ImportReport> {
+        let manifest = verify_backup(path, scope.passphrase)?;
+        tracing::info!(
+            "Importing backup from {} made at {}",
+            path.display(),
+            manifest.created_at_ms
+        );
+
+        let cipher = self.file_cipher()?;
+        let mut report = ImportReport::default();
+        // Sessions added by this import; only their recordings are restored.
+        let mut added = HashSet::new();
+        let mut archive = open_archive(path, scope.passphrase)?;
+        for entry in archive.entries().map_err(backup_error)? {
+            let mut entry = entry.map_err(backup_error)?;
+            let name = entry_name(&entry)?;
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).map_err(backup_error)?;
+
+            match name.split_once('/') {
+                Some(("sessions", _)) => {
+                    let transcript: Transcript = serde_json::from_slice(&data)?;
+                    let id = transcript.id;
+                    match self.get(&id) {
+                        Ok(local) => {
+                            if serde_json::to_value(&local)? == serde_json::to_value(&transcript)? {
+                                report.unchanged += 1;
+                            } else {
+                                report.conflict(BackupItem::Session, id.to_string());
+                            }
+                        }
+                        Err(StorageError::NotFound(_)) => {
+                            self.save(&transcript)?;
+                            added.insert(id);
+                            report.sessions += 1;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Some(("recordings", file)) => {
+                    let Some((id, ext)) = file
+                        .split_once('.')
+                        .and_then(|(id, ext)| Some((Uuid::parse_str(id).ok()?, ext)))
+                    else {
+                        continue;
+                    };
+                    if !added.contains(&id) {
+                        continue;
+                    }
+                    let Some(dir) = &scope.recordings_dir else {
+                        tracing::warn!("No recordings directory; recording {} skipped", id);
+                        continue;
+                    };
+                    let target = dir.join(format!("recording_{id}.{ext}"));
+                    if target.exists() {
+                        report.conflict(BackupItem::Recording, id.to_string());
+                        continue;
+                    }
+                    if let Some(cipher) = &cipher {
+                        data = cipher.seal(&data)?;
+                    }
+                    std::fs::create_dir_all(dir).map_err(backup_error)?;
+                    std::fs::write(&target, &data).map_err(backup_error)?;
+                    self.set_session_audio(&id, Some(&target))?;
+                    report.recordings += 1;
+                }
+                Some(("files", file)) => {
+                    let Some((name, relative)) = file.split_once('/') else {
+                        continue;
+                    };
+                    let Some((_, dir)) = scope.dirs.iter().find(|(dir, _)| dir == name) else {
+                        continue;
+                    };
+                    let target = dir.join(relative);
+                    match std::fs::read(&target) {
+                        Ok(local) if local == data => report.unchanged += 1,
+                        Ok(_) => report.conflict(BackupItem::File, file.to_string()),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                            if let Some(parent) = target.parent() {
+                                std::fs::create_dir_all(parent).map_err(backup_error)?;
+                            }
+                            std::fs::write(&target, &data).map_err(backup_error)?;
+                            report.files += 1;
+                        }
+                        Err(e) => return Err(backup_error(format!("{}: {e}", target.display()))),
+                    }
+                }
+                _ if name == ACTIVITIES => self.import_activities(&data, &mut report)?,
+                _ if name == SETTINGS => {
+                    let Some(settings) = scope.settings else {
+                        continue;
+                    };
+                    let text = String::from_utf8(data).map_err(backup_error)?;
+                    let (applied, conflicts) = settings.merge_toml(&text)?;
+                    report.settings = applied.into_iter().map(str::to_string).collect();
+                    for key in conflicts {
+                        report.conflict(BackupItem::Setting, key.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        tracing::info!(
+            "Backup imported: {} sessions, {} recordings, {} activities, {} files added, {} conflicts",
+            report.sessions,
+            report.recordings,
+            report.activities,
+            report.files,
+            report.conflicts.len()
+        );
+        Ok(report)
+    }
+
+    fn import_activities(&self, data: &[u8], report: &mut ImportReport) -> Result<()> {
+        for line in data.split(|&b| b == b'\n').filter(|line| !line.is_empty()) {
+            let activity: Activity = serde_json::from_slice(line)?;
+            match self.activity(&activity.id)? {
+                Some(local) => {
+                    if serde_json::to_value(&local)? == serde_json::to_value(&activity)? {
+                        report.unchanged += 1;
+                    } else {
+                        report.conflict(BackupItem::Activity, activity.id);
+                    }
+                }
+                None => {
+                    self.save_activity(&activity)?;
+                    report.activities += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn activity(&self, id: &str) -> Result<Option<Activity>> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        Ok(conn
+            .query_row(
+                &format!("SELECT {ACTIVITY_COLUMNS} FROM activities WHERE id = ?1"),
+                [id],
+                activity_from_row,
+            )
+            .optional()?)
+    }
+
+    fn all_activities(&self) -> Result<Vec<Activity>> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ACTIVITY_COLUMNS} FROM activities ORDER BY timestamp, id"
+        ))?;
+        let activities = stmt
+            .query_map([], activity_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(activities)
+    }
+}
+
+impl ImportReport {
+    fn conflict(&mut self, item: BackupItem, id: String) {
+        self.conflicts.push(BackupConflict { item, id });
+    }
+}
+
+/// Read the manifest of a backup and check every entry against it. An
+/// encrypted backup needs its `passphrase`, and is authenticated to its end.
+pub fn verify_backup(path: &Path, passphrase: Option<&str>) -> Result<BackupManifest> {
+    let mut archive = open_archive(path, passphrase)?;
+    let mut found = BTreeMap::new();
+    let mut manifest = None;
+    for entry in archive.entries().map_err(backup_error)? {
+        let mut entry = entry.map_err(backup_error)?;
+        let name = entry_name(&entry)?;
+        if name == MANIFEST {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).map_err(backup_error)?;
+            let parsed: BackupManifest = serde_json::from_slice(&data)
+                .map_err(|e| backup_error(format!("invalid manifest: {e}")))?;
+            manifest = Some(parsed);
+            continue;
+        }
+        let mut hasher = HashingWriter::default();
+        std::io::copy(&mut entry, &mut hasher).map_err(backup_error)?;
+        found.insert(
+            name,
+            (hasher.size, format!("{:x}", hasher.sha256.finalize())),
+        );
+    }
+    // The tar reader stops at its end marker; the last chunk must still
+    // authenticate for a sealed backup to be whole.
+    std::io::copy(&mut archive.into_inner().into_inner(), &mut std::io::sink())
+        .map_err(backup_error)?;
+
+    let manifest = manifest.ok_or_else(|| backup_error("no manifest; not a complete backup"))?;
+    if manifest.format != BACKUP_FORMAT {
+        return Err(backup_error(format!(
+            "unknown format {:?}",
+            manifest.format
+        )));
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err(backup_error(format!(
+            "backup format {} is newer than this build supports ({BACKUP_VERSION})",
+            manifest.version
+        )));
+    }
+    for entry in &manifest.entries {
+        match found.remove(&entry.path) {
+            Some((size, sha256)) if size == entry.size && sha256 == entry.sha256 => {}
+            Some(_) => {
+                return Err(backup_error(format!(
+                    "{} does not match its checksum",
+                    entry.path
+                )))
+            }
+            None => return Err(backup_error(format!("{} is missing", entry.path))),
+        }
+    }
+    if let Some(path) = found.keys().next() {
+        return Err(backup_error(format!("{path} is not in the manifest")));
+    }
+    Ok(manifest)
+}
+
+fn open_archive(
+    path: &Path,
+    passphrase: Option<&str>,
+) -> Result<tar::Archive<GzDecoder<Box<dyn Read>>>> {
+    let mut file = BufReader::new(File::open(path).map_err(backup_error)?);
+    let sealed = file
+        .fill_buf()
+        .map_err(backup_error)?
+        .starts_with(SEALED_MAGIC);
+    let reader: Box<dyn Read> = match (sealed, passphrase) {
+        (false, _) => Box::new(file),
+        (true, Some(passphrase)) => Box::new(SealedReader::new(file, passphrase)?),
+        (true, None) => {
+            return Err(backup_error(
+                "the backup is encrypted; enter its passphrase",
+            ))
+        }
+    };
+    Ok(tar::Archive::new(GzDecoder::new(reader)))
+}
+
+/// Path of an archive entry. Absolute paths and `..` are rejected.
+fn entry_name<R: Read>(entry: &tar::Entry<R>) -> Result<String> {
+    let path = entry.path().map_err(backup_error)?;
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(backup_error(format!("unsafe path {}", path.display())));
+    }
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| backup_error(format!("invalid path {}", path.display())))
+}
+
+/// Files under `dir`, sorted, skipping hidden ones. A missing directory
+/// has none.
+fn files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(backup_error(e)),
+    };
+    for entry in entries {
+        let entry = entry.map_err(backup_error)?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type().map_err(backup_error)?;
+        if file_type.is_dir() {
+            files.extend(files_in(&entry.path())?);
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Writes entries and records their checksums for the manifest.
+struct ArchiveWriter {
+    tar: tar::Builder<GzEncoder<Sink>>,
+    entries: Vec<BackupEntry>,
+    mtime: u64,
+}
+
+impl ArchiveWriter {
+    fn new(sink: Sink, now_ms: i64) -> Self {
+        Self {
+            tar: tar::Builder::new(GzEncoder::new(sink, Compression::default())),
+            entries: Vec::new(),
+            mtime: (now_ms / 1000).max(0) as u64,
+        }
+    }
+
+    fn add(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(self.mtime);
+        self.tar
+            .append_data(&mut header, path, data)
+            .map_err(backup_error)?;
+        if path != MANIFEST {
+            self.entries.push(BackupEntry {
+                path: path.to_string(),
+                size: data.len() as u64,
+                sha256: sha256_hex(data),
+            });
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        let file = self
+            .tar
+            .into_inner()
+            .and_then(GzEncoder::finish)
+            .and_then(Sink::finish)
+            .map_err(backup_error)?;
+        file.sync_all().map_err(backup_error)
+    }
+}
+
+#[derive(Default)]
+struct HashingWriter {
+    sha256: Sha256,
+    size: u64,
+}
+
+impl Write for HashingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.sha256.update(buf);
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The file an archive is written to, directly or encrypted.
+enum Sink {
+    Plain(File),
+    Sealed(Box<SealedWriter<File>>),
+}
+
+impl Sink {
+    fn finish(self) -> std::io::Result<File> {
+        match self {
+            Self::Plain(file) => Ok(file),
+            Self::Sealed(writer) => writer.finish(),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Sealed(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Sealed(writer) => writer.flush(),
+        }
+    }
+}
+
+/// Derive the backup key from `passphrase` with the salt and costs in
+/// `header`.
+fn backup_cipher(passphrase: &str, header: &[u8]) -> Result<Aes256Gcm> {
+    let salt = &header[SEALED_MAGIC.len()..][..SALT_LEN];
+    let costs = &header[SEALED_MAGIC.len() + SALT_LEN..];
+    let cost = |i: usize| u32::from_le_bytes(costs[i * 4..][..4].try_into().expect("4 bytes"));
+    if cost(0) > MAX_MEMORY_COST {
+        return Err(backup_error("the backup asks for too much memory to open"));
+    }
+    let params = Params::new(cost(0), cost(1), cost(2), Some(32)).map_err(backup_error)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(backup_error)?;
+    Aes256Gcm::new_from_slice(key.as_ref()).map_err(backup_error)
+}
+
+fn wrong_passphrase() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "wrong passphrase, or the backup is damaged",
+    )
+}
+
+/// Encrypts what is written to it, one [`CHUNK_LEN`] chunk at a time. The
+/// header goes first and is bound to every chunk.
+struct SealedWriter<W: Write> {
+    inner: W,
+    header: Vec<u8>,
+    /// Taken by [`Self::finish`], which seals the last chunk.
+    encryptor: Option<EncryptorBE32<Aes256Gcm>>,
+    chunk: Vec<u8>,
+}
+
+impl<W: Write> SealedWriter<W> {
+    fn new(mut inner: W, passphrase: &str) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce_prefix);
+        let mut header = Vec::with_capacity(SEALED_HEADER_LEN);
+        header.extend_from_slice(SEALED_MAGIC);
+        header.extend_from_slice(&salt);
+        for cost in [
+            Params::DEFAULT_M_COST,
+            Params::DEFAULT_T_COST,
+            Params::DEFAULT_P_COST,
+        ] {
+            header.extend_from_slice(&cost.to_le_bytes());
+        }
+        header.extend_from_slice(&nonce_prefix);
+
+        let cipher = backup_cipher(passphrase, &header)?;
+        inner.write_all(&header).map_err(backup_error)?;
+        Ok(Self {
+            inner,
+            header,
+            encryptor: Some(EncryptorBE32::from_aead(cipher, &nonce_prefix.into())),
+            chunk: Vec::with_capacity(CHUNK_LEN),
+        })
+    }
+
+    fn finish(mut self) -> std::io::Result<W> {
+        let encryptor = self.encryptor.take().expect("sealed writer finished twice");
+        let sealed = encryptor
+            .encrypt_last(Payload {
+                msg: &self.chunk,
+                aad: &self.header,
+            })
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        self.inner.write_all(&sealed)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for SealedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // A full chunk is sealed only once more follows, so the last one
+        // is always left for `finish`.
+        if self.chunk.len() == CHUNK_LEN {
+            let encryptor = self.encryptor.as_mut().expect("sealed writer finished");
+            let sealed = encryptor
+                .encrypt_next(Payload {
+                    msg: &self.chunk,
+                    aad: &self.header,
+                })
+                .map_err(|e| std::io::Error::other(e.to_string()))?;
+            self.inner.write_all(&sealed)?;
+            self.chunk.clear();
+        }
+        let n = buf.len().min(CHUNK_LEN - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decrypts a backup written by [`SealedWriter`].
+struct SealedReader<R: BufRead> {
+    inner: R,
+    header: Vec<u8>,
+    /// Gone once the last chunk has been read.
+    decryptor: Option<DecryptorBE32<Aes256Gcm>>,
+    chunk: Zeroizing<Vec<u8>>,
+    pos: usize,
+}
+
+impl<R: BufRead> SealedReader<R> {
+    /// Read the header and the first chunk, which checks the passphrase.
+    fn new(mut inner: R, passphrase: &str) -> Result<Self> {
+        let mut header = vec![0u8; SEALED_HEADER_LEN];
+        inner
+            .read_exact(&mut header)
+            .map_err(|e| backup_error(format!("invalid backup header: {e}")))?;
+        let cipher = backup_cipher(passphrase, &header)?;
+        let nonce_prefix = &header[SEALED_HEADER_LEN - NONCE_PREFIX_LEN..];
+        let decryptor = DecryptorBE32::from_aead(cipher, nonce_prefix.into());
+        let mut reader = Self {
+            inner,
+            header,
+            decryptor: Some(decryptor),
+            chunk: Zeroizing::new(Vec::new()),
+            pos: 0,
+        };
+        reader.next_chunk().map_err(backup_error)?;
+        Ok(reader)
+    }
+
+    fn next_chunk(&mut self) -> std::io::Result<()> {
+        let mut sealed = Vec::with_capacity(CHUNK_LEN + TAG_LEN);
+        (&mut self.inner)
+            .take((CHUNK_LEN + TAG_LEN) as u64)
+            .read_to_end(&mut sealed)?;
+        let last = sealed.len() < CHUNK_LEN + TAG_LEN || self.inner.fill_buf()?.is_empty();
+        let payload = Payload {
+            msg: &sealed,
+            aad: &self.header,
+        };
+        let chunk = if last {
+            let decryptor = self.decryptor.take().expect("read past the last chunk");
+            decryptor.decrypt_last(payload)
+        } else {
+            let decryptor = self.decryptor.as_mut().expect("read past the last chunk");
+            decryptor.decrypt_next(payload)
+        };
+        self.chunk = Zeroizing::new(chunk.map_err(|_| wrong_passphrase())?);
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for SealedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.chunk.len() {
+            if self.decryptor.is_none() {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..][..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
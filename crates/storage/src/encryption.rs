@@ -0,0 +1,383 @@
+//! Encryption at rest.
+//!
+//! SQLCipher encrypts the database file as a whole, so segments, words and
+//! the search index are covered along with the JSON columns. Recordings are
+//! sealed with AES-256-GCM under a file key kept inside the encrypted
+//! database, so changing the database key never rewrites audio.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rusqlite::{Connection, DatabaseName, ErrorCode, OptionalExtension};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{migrations, Database, Result, StorageError};
+
+/// Header of a sealed file, also bound into its tag.
+const FILE_MAGIC: &[u8; 8] = b"GIBBENC1";
+const NONCE_LEN: usize = 12;
+/// Row in `secrets` holding the recordings file key.
+const FILE_KEY_NAME: &str = "recordings_key";
+
+/// Key for an encrypted database.
+pub enum DatabaseKey {
+    /// Stretched by SQLCipher (PBKDF2-HMAC-SHA512, salt in the file header).
+    Passphrase(String),
+    /// 256-bit key used as is, e.g. one kept in the OS keyring.
+    Raw([u8; 32]),
+}
+
+impl DatabaseKey {
+    /// A random raw key.
+    pub fn generate() -> Self {
+        Self::Raw(Aes256Gcm::generate_key(&mut OsRng).into())
+    }
+
+    /// Raw key from its hex form (see [`DatabaseKey::to_hex`]).
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+        let mut bytes = [0u8; 32];
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(StorageError::Encryption(
+                "a raw key is 64 hex digits".to_string(),
+            ));
+        }
+        for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).unwrap_or_default();
+            *byte = u8::from_str_radix(pair, 16)
+                .map_err(|_| StorageError::Encryption("a raw key is 64 hex digits".to_string()))?;
+        }
+        Ok(Self::Raw(bytes))
+    }
+
+    /// Hex form of a raw key, for a keyring. `None` for passphrases.
+    pub fn to_hex(&self) -> Option<String> {
+        match self {
+            Self::Passphrase(_) => None,
+            Self::Raw(bytes) => Some(bytes.iter().map(|b| format!("{b:02x}")).collect()),
+        }
+    }
+
+    /// Key material as SQLCipher reads it from `PRAGMA key` and
+    /// `ATTACH ... KEY`.
+    fn material(&self) -> Result<Zeroizing<String>> {
+        match self {
+            // An empty key means "not encrypted" to SQLCipher.
+            Self::Passphrase(passphrase) if passphrase.is_empty() => Err(StorageError::Encryption(
+                "the passphrase is empty".to_string(),
+            )),
+            Self::Passphrase(passphrase) => Ok(Zeroizing::new(passphrase.clone())),
+            Self::Raw(_) => Ok(Zeroizing::new(format!(
+                "x'{}'",
+                self.to_hex().unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+impl Drop for DatabaseKey {
+    fn drop(&mut self) {
+        match self {
+            Self::Passphrase(passphrase) => passphrase.zeroize(),
+            Self::Raw(bytes) => bytes.zeroize(),
+        }
+    }
+}
+
+impl std::fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("DatabaseKey::Passphrase(..)"),
+            Self::Raw(_) => f.write_str("DatabaseKey::Raw(..)"),
+        }
+    }
+}
+
+/// Open `path`, unlocking it with `key` if given.
+pub(crate) fn connect(path: &Path, key: Option<&DatabaseKey>) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", &*key.material()?)?;
+    }
+    // SQLCipher reads nothing until the first query: check the key now.
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    }) {
+        Ok(_) => {
+            // Deleted rows are overwritten instead of lingering in free pages.
+            conn.pragma_update(None, "secure_delete", true)?;
+            Ok(conn)
+        }
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::NotADatabase => {
+            Err(match key {
+                Some(_) => StorageError::KeyRejected,
+                None => StorageError::Locked,
+            })
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl Database {
+    /// Open an encrypted database, or create one at a new `path`.
+    pub fn open_encrypted(path: &Path, key: &DatabaseKey) -> Result<Self> {
+        let mut conn = connect(path, Some(key))?;
+        migrations::migrate(&mut conn)?;
+        Ok(Self::from_connection(conn, Some(path), true))
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted.load(Ordering::SeqCst)
+    }
+
+    /// Encrypt a plain database in place under `key`.
+    pub fn encrypt(&self, key: &DatabaseKey) -> Result<()> {
+        if self.is_encrypted() {
+            return Err(StorageError::Encryption(
+                "the database is already encrypted".to_string(),
+            ));
+        }
+        key.material()?;
+        self.export(None, Some(key))
+    }
+
+    /// Store the database in plain text again. `key` must be the current
+    /// key. The plain copy has no recordings file key, so unseal recordings
+    /// first; if the export fails, the encrypted database keeps it.
+    pub fn decrypt(&self, key: &DatabaseKey) -> Result<()> {
+        if !self.is_encrypted() {
+            return Err(StorageError::Encryption(
+                "the database is not encrypted".to_string(),
+            ));
+        }
+        self.check_key(key)?;
+        self.export(Some(key), None)
+    }
+
+    /// Fails with [`StorageError::KeyRejected`] unless `key` unlocks the
+    /// database file.
+    pub fn check_key(&self, key: &DatabaseKey) -> Result<()> {
+        drop(connect(self.file_path()?, Some(key))?);
+        Ok(())
+    }
+
+    /// Change the key of an encrypted database. Recordings keep their file
+    /// key and are not rewritten.
+    pub fn rekey(&self, new_key: &DatabaseKey) -> Result<()> {
+        if !self.is_encrypted() {
+            return Err(StorageError::Encryption(
+                "the database is not encrypted".to_string(),
+            ));
+        }
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        conn.pragma_update(None, "rekey", &*new_key.material()?)?;
+        Ok(())
+    }
+
+    /// Cipher for recordings, with the file key created on first use.
+    /// `None` unless the database is encrypted: the file key must never be
+    /// stored in plain text.
+    pub fn file_cipher(&self) -> Result<Option<FileCipher>> {
+        if !self.is_encrypted() {
+            return Ok(None);
+        }
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let stored: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT value FROM secrets WHERE name = ?1",
+                [FILE_KEY_NAME],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let key = match stored {
+            Some(key) => Zeroizing::new(key),
+            None => {
+                let key = Zeroizing::new(Aes256Gcm::generate_key(&mut OsRng).to_vec());
+                conn.execute(
+                    "INSERT INTO secrets (name, value) VALUES (?1, ?2)",
+                    (FILE_KEY_NAME, key.as_slice()),
+                )?;
+                key
+            }
+        };
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|_| StorageError::Encryption("the recordings key is corrupt".to_string()))?;
+        Ok(Some(FileCipher { cipher }))
+    }
+
+    fn file_path(&self) -> Result<&Path> {
+        self.path.as_deref().ok_or_else(|| {
+            StorageError::Encryption("an in-memory database has no file".to_string())
+        })
+    }
+
+    /// Rewrite the database file under `to` (plain text when `None`) with
+    /// `sqlcipher_export`, then swap it in under the connection lock. A
+    /// plain text copy is stripped of `secrets` before the swap.
+    fn export(&self, from: Option<&DatabaseKey>, to: Option<&DatabaseKey>) -> Result<()> {
+        let path = self.file_path()?;
+        let export_path = sibling(path, "export");
+        let _ = std::fs::remove_file(&export_path);
+
+        let mut conn = self.conn.lock().expect("database mutex poisoned");
+        let material = match to {
+            Some(key) => key.material()?,
+            None => Zeroizing::new(String::new()),
+        };
+        let version = migrations::schema_version(&conn)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS export KEY ?2",
+            (export_path.to_string_lossy(), material.as_str()),
+        )?;
+        let exported = conn
+            .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+            .and_then(|_| {
+                conn.pragma_update(
+                    Some(DatabaseName::Attached("export")),
+                    "user_version",
+                    version,
+                )
+            })
+            .and_then(|_| match to {
+                Some(_) => Ok(()),
+                None => conn
+                    .execute_batch("PRAGMA export.secure_delete = ON; DELETE FROM export.secrets;"),
+            });
+        conn.execute("DETACH DATABASE export", [])?;
+        if let Err(e) = exported {
+            let _ = std::fs::remove_file(&export_path);
+            return Err(e.into());
+        }
+
+        // Close the old file before replacing it.
+        *conn = Connection::open_in_memory()?;
+        if let Err(e) = std::fs::rename(&export_path, path) {
+            *conn = connect(path, from)?;
+            return Err(StorageError::Encryption(format!(
+                "failed to replace the database: {e}"
+            )));
+        }
+        *conn = connect(path, to)?;
+        self.encrypted.store(to.is_some(), Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Seals recordings with the file key of an encrypted database.
+///
+/// A sealed file is `GIBBENC1`, a 96-bit nonce and the AES-256-GCM
+/// ciphertext with its tag.
+pub struct FileCipher {
+    cipher: Aes256Gcm,
+}
+
+impl FileCipher {
+    pub fn is_sealed(data: &[u8]) -> bool {
+        data.starts_with(FILE_MAGIC)
+    }
+
+    /// Whether the file at `path` is sealed, reading only its header.
+    pub fn is_sealed_file(path: &Path) -> Result<bool> {
+        let mut header = Vec::with_capacity(FILE_MAGIC.len());
+        std::fs::File::open(path)
+            .and_then(|file| file.take(FILE_MAGIC.len() as u64).read_to_end(&mut header))
+            .map_err(|e| {
+                StorageError::Encryption(format!("failed to read {}: {e}", path.display()))
+            })?;
+        Ok(Self::is_sealed(&header))
+    }
+
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: FILE_MAGIC,
+                },
+            )
+            .map_err(|_| StorageError::Encryption("failed to seal data".to_string()))?;
+        let mut sealed = Vec::with_capacity(FILE_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(FILE_MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypt sealed data. Fails if it was altered or sealed under another
+    /// key.
+    pub fn unseal(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if !Self::is_sealed(sealed) || sealed.len() < FILE_MAGIC.len() + NONCE_LEN {
+            return Err(StorageError::Encryption("data is not sealed".to_string()));
+        }
+        let (nonce, ciphertext) = sealed[FILE_MAGIC.len()..].split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: FILE_MAGIC,
+                },
+            )
+            .map_err(|_| {
+                StorageError::Encryption("sealed data is damaged or from another key".to_string())
+            })
+    }
+
+    /// Contents of a file, unsealed if it is sealed.
+    pub fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
+        let data = read(path)?;
+        if Self::is_sealed(&data) {
+            self.unseal(&data)
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Seal a file in place. Returns `false` if it was already sealed.
+    pub fn seal_file(&self, path: &Path) -> Result<bool> {
+        let data = Zeroizing::new(read(path)?);
+        if Self::is_sealed(&data) {
+            return Ok(false);
+        }
+        replace(path, &self.seal(&data)?)?;
+        Ok(true)
+    }
+
+    /// Unseal a file in place. Returns `false` if it was not sealed.
+    pub fn unseal_file(&self, path: &Path) -> Result<bool> {
+        let data = read(path)?;
+        if !Self::is_sealed(&data) {
+            return Ok(false);
+        }
+        replace(path, &self.unseal(&data)?)?;
+        Ok(true)
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| StorageError::Encryption(format!("failed to read {}: {e}", path.display())))
+}
+
+/// Write through a sibling file so a crash never leaves half a recording.
+fn replace(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp = sibling(path, "tmp");
+    std::fs::write(&tmp, data)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            StorageError::Encryption(format!("failed to write {}: {e}", path.display()))
+        })
+}
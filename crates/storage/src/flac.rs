@@ -0,0 +1,478 @@
+//! FLAC for 16-bit mono recordings.
+//!
+//! The encoder writes fixed-predictor subframes (orders 0 to 4) with
+//! partitioned Rice residuals, a fixed block size, the MD5 of the audio and
+//! a seek table, which any FLAC player opens. Frames are decoded by claxon,
+//! starting at the nearest seek point (claxon itself does not seek), so a
+//! slice decodes only the frames around it.
+
+use claxon::frame::FrameReader;
+use md5::{Digest, Md5};
+
+use crate::{Result, StorageError};
+
+const MAGIC: &[u8; 4] = b"fLaC";
+/// Samples per frame: 256 ms at 16 kHz.
+const BLOCK_SIZE: usize = 4096;
+/// Frames between seek points.
+const SEEK_INTERVAL: usize = 16;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 6;
+/// Largest Rice parameter of the 4-bit coding method; 15 is the escape.
+const MAX_RICE_PARAM: u32 = 14;
+
+const METADATA_STREAMINFO: u8 = 0;
+const METADATA_SEEKTABLE: u8 = 3;
+const SEEK_POINT_LEN: usize = 18;
+const PLACEHOLDER_SEEK_POINT: u64 = u64::MAX;
+
+fn error(message: impl Into<String>) -> StorageError {
+    StorageError::Audio(message.into())
+}
+
+pub(crate) fn is_flac(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encode `samples` as a FLAC stream.
+pub(crate) fn encode(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let mut frames = Vec::new();
+    let mut seek_points = Vec::new();
+    let (mut min_frame, mut max_frame) = (u32::MAX, 0);
+    for (number, block) in samples.chunks(BLOCK_SIZE).enumerate() {
+        if number % SEEK_INTERVAL == 0 {
+            seek_points.push((
+                (number * BLOCK_SIZE) as u64,
+                frames.len() as u64,
+                block.len(),
+            ));
+        }
+        let start = frames.len();
+        let block: Vec<i32> = block.iter().map(|&s| i32::from(s)).collect();
+        encode_frame(&mut frames, number as u32, &block);
+        let size = (frames.len() - start) as u32;
+        min_frame = min_frame.min(size);
+        max_frame = max_frame.max(size);
+    }
+    if frames.is_empty() {
+        min_frame = 0;
+    }
+
+    let mut out = MAGIC.to_vec();
+    let mut info = BitWriter::default();
+    info.write(BLOCK_SIZE as u64, 16);
+    info.write(BLOCK_SIZE as u64, 16);
+    info.write(u64::from(min_frame), 24);
+    info.write(u64::from(max_frame), 24);
+    info.write(u64::from(sample_rate), 20);
+    info.write(0, 3); // one channel
+    info.write(u64::from(BITS_PER_SAMPLE - 1), 5);
+    info.write(samples.len() as u64 >> 32, 4);
+    info.write(samples.len() as u64 & 0xFFFF_FFFF, 32);
+    let mut info = info.finish();
+    let mut md5 = Md5::new();
+    for sample in samples {
+        md5.update(sample.to_le_bytes());
+    }
+    info.extend_from_slice(&md5.finalize());
+    write_metadata(&mut out, METADATA_STREAMINFO, seek_points.is_empty(), &info);
+
+    if !seek_points.is_empty() {
+        let mut table = Vec::with_capacity(seek_points.len() * SEEK_POINT_LEN);
+        for (sample, offset, len) in seek_points {
+            table.extend_from_slice(&sample.to_be_bytes());
+            table.extend_from_slice(&offset.to_be_bytes());
+            table.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        write_metadata(&mut out, METADATA_SEEKTABLE, true, &table);
+    }
+    out.extend_from_slice(&frames);
+    out
+}
+
+fn write_metadata(out: &mut Vec<u8>, kind: u8, last: bool, data: &[u8]) {
+    out.push(kind | if last { 0x80 } else { 0 });
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+    out.extend_from_slice(data);
+}
+
+fn encode_frame(out: &mut Vec<u8>, number: u32, block: &[i32]) {
+    let mut w = BitWriter::default();
+    w.write(0xFFF8, 16); // sync code, fixed block size
+    w.write(0b0111, 4); // block size in 16 bits after the frame number
+    w.write(0b0000, 4); // sample rate from STREAMINFO
+    w.write(0b0000, 4); // mono
+    w.write(0b100, 3); // 16 bits per sample
+    w.write(0, 1);
+    write_utf8(&mut w, number);
+    w.write(block.len() as u64 - 1, 16);
+    let crc = crc8(&w.bytes);
+    w.write(u64::from(crc), 8);
+
+    encode_subframe(&mut w, block);
+
+    let mut frame = w.finish();
+    let crc = crc16(&frame);
+    frame.extend_from_slice(&crc.to_be_bytes());
+    out.extend_from_slice(&frame);
+}
+
+/// Frame numbers use the extended UTF-8 coding of FLAC (up to 31 bits).
+fn write_utf8(w: &mut BitWriter, value: u32) {
+    if value < 0x80 {
+        w.write(u64::from(value), 8);
+        return;
+    }
+    let mut continuation = 1;
+    while u64::from(value) >= 1 << (6 * continuation + 6 - continuation) {
+        continuation += 1;
+    }
+    let prefix = !(0xFFu32 >> (continuation + 1)) & 0xFF;
+    w.write(u64::from(prefix | (value >> (6 * continuation))), 8);
+    for i in (0..continuation).rev() {
+        w.write(u64::from(0x80 | ((value >> (6 * i)) & 0x3F)), 8);
+    }
+}
+
+/// A fixed-predictor residual, its Rice partitioning and its size in bits.
+struct Candidate {
+    order: usize,
+    residual: Vec<u32>,
+    partition_order: u32,
+    params: Vec<u32>,
+    bits: u64,
+}
+
+fn encode_subframe(w: &mut BitWriter, samples: &[i32]) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        w.write(0b0000_0000, 8); // constant
+        w.write_signed(samples[0], BITS_PER_SAMPLE);
+        return;
+    }
+
+    let best = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residual: Vec<u32> = (order..samples.len())
+                .map(|n| zigzag(fixed_residual(samples, n, order)))
+                .collect();
+            let (partition_order, params, bits) = partition(&residual, samples.len(), order);
+            Candidate {
+                order,
+                residual,
+                partition_order,
+                params,
+                bits: bits + (order as u64) * u64::from(BITS_PER_SAMPLE) + 6,
+            }
+        })
+        .min_by_key(|candidate| candidate.bits)
+        .expect("at least one predictor order");
+
+    if best.bits >= samples.len() as u64 * u64::from(BITS_PER_SAMPLE) {
+        w.write(0b0000_0010, 8); // verbatim
+        for &sample in samples {
+            w.write_signed(sample, BITS_PER_SAMPLE);
+        }
+        return;
+    }
+
+    w.write(0b0001_0000 | (best.order as u64) << 1, 8); // fixed, order in the low bits
+    for &sample in &samples[..best.order] {
+        w.write_signed(sample, BITS_PER_SAMPLE);
+    }
+    w.write(0b00, 2); // Rice coding with 4-bit parameters
+    w.write(u64::from(best.partition_order), 4);
+    let mut residual = best.residual.iter();
+    let partition_len = samples.len() >> best.partition_order;
+    for (i, &param) in best.params.iter().enumerate() {
+        let count = if i == 0 {
+            partition_len - best.order
+        } else {
+            partition_len
+        };
+        w.write(u64::from(param), 4);
+        for &value in residual.by_ref().take(count) {
+            w.write_unary(value >> param);
+            w.write(u64::from(value), param);
+        }
+    }
+}
+
+fn fixed_residual(samples: &[i32], n: usize, order: usize) -> i32 {
+    let x = |back: usize| samples[n - back];
+    match order {
+        0 => x(0),
+        1 => x(0) - x(1),
+        2 => x(0) - 2 * x(1) + x(2),
+        3 => x(0) - 3 * x(1) + 3 * x(2) - x(3),
+        _ => x(0) - 4 * x(1) + 6 * x(2) - 4 * x(3) + x(4),
+    }
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+/// The partition order and Rice parameters that code `residual` (of a
+/// block of `block_len` samples, predicted at `order`) in the fewest bits,
+/// estimated from partition sums.
+fn partition(residual: &[u32], block_len: usize, order: usize) -> (u32, Vec<u32>, u64) {
+    let mut max_order = 0;
+    while max_order < MAX_PARTITION_ORDER
+        && block_len.is_multiple_of(1 << (max_order + 1))
+        && block_len >> (max_order + 1) > order
+    {
+        max_order += 1;
+    }
+
+    // (count, sum) per partition, finest first; merged pairwise below.
+    let partition_len = block_len >> max_order;
+    let mut sums: Vec<(u64, u64)> = (0..1usize << max_order)
+        .map(|i| {
+            let start = (i * partition_len).max(order) - order;
+            let end = (i + 1) * partition_len - order;
+            let values = &residual[start..end];
+            (
+                values.len() as u64,
+                values.iter().map(|&v| u64::from(v)).sum(),
+            )
+        })
+        .collect();
+
+    let mut best: Option<(u32, Vec<u32>, u64)> = None;
+    for partition_order in (0..=max_order).rev() {
+        let params: Vec<u32> = sums
+            .iter()
+            .map(|&(count, sum)| rice_param(count, sum))
+            .collect();
+        let bits = sums
+            .iter()
+            .zip(&params)
+            .map(|(&(count, sum), &k)| 4 + count * u64::from(k + 1) + (sum >> k))
+            .sum();
+        if best
+            .as_ref()
+            .is_none_or(|(_, _, best_bits)| bits < *best_bits)
+        {
+            best = Some((partition_order, params, bits));
+        }
+        sums = sums
+            .chunks(2)
+            .map(|pair| {
+                pair.iter()
+                    .fold((0, 0), |(c, s), &(count, sum)| (c + count, s + sum))
+            })
+            .collect();
+    }
+    best.expect("at least one partition order")
+}
+
+fn rice_param(count: u64, sum: u64) -> u32 {
+    let mut k = 0;
+    while k < MAX_RICE_PARAM && count << (k + 1) <= sum {
+        k += 1;
+    }
+    k
+}
+
+/// A FLAC stream as written by [`encode`].
+pub(crate) struct Stream<'a> {
+    data: &'a [u8],
+    pub sample_rate: u32,
+    pub total_samples: u64,
+    /// Offset of the first frame.
+    frames: usize,
+    /// (first sample, offset from the first frame)
+    seek_points: Vec<(u64, u64)>,
+}
+
+impl<'a> Stream<'a> {
+    pub fn open(data: &'a [u8]) -> Result<Self> {
+        if !is_flac(data) {
+            return Err(error("not a FLAC stream"));
+        }
+        let mut stream = Self {
+            data,
+            sample_rate: 0,
+            total_samples: 0,
+            frames: 0,
+            seek_points: Vec::new(),
+        };
+        let mut pos = MAGIC.len();
+        loop {
+            let header = data
+                .get(pos..pos + 4)
+                .ok_or_else(|| error("truncated FLAC metadata"))?;
+            let last = header[0] & 0x80 != 0;
+            let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+            let block = data
+                .get(pos + 4..pos + 4 + len)
+                .ok_or_else(|| error("truncated FLAC metadata"))?;
+            match header[0] & 0x7F {
+                METADATA_STREAMINFO => {
+                    // Sample rate, channels, bits and total samples share
+                    // the 64 bits after the block and frame sizes.
+                    let fields = block
+                        .get(10..18)
+                        .ok_or_else(|| error("truncated FLAC STREAMINFO"))?;
+                    let fields = u64::from_be_bytes(fields.try_into().unwrap());
+                    stream.sample_rate = (fields >> 44) as u32;
+                    let channels = (fields >> 41 & 0b111) + 1;
+                    let bits = (fields >> 36 & 0b1_1111) as u32 + 1;
+                    if channels != 1 || bits != BITS_PER_SAMPLE {
+                        return Err(error(format!(
+                            "unsupported FLAC stream: {channels} channels of {bits} bits"
+                        )));
+                    }
+                    stream.total_samples = fields & 0xF_FFFF_FFFF;
+                }
+                METADATA_SEEKTABLE => {
+                    stream.seek_points = block
+                        .chunks_exact(SEEK_POINT_LEN)
+                        .map(|point| {
+                            let sample = u64::from_be_bytes(point[..8].try_into().unwrap());
+                            let offset = u64::from_be_bytes(point[8..16].try_into().unwrap());
+                            (sample, offset)
+                        })
+                        .filter(|&(sample, _)| sample != PLACEHOLDER_SEEK_POINT)
+                        .collect();
+                }
+                _ => {}
+            }
+            pos += 4 + len;
+            if last {
+                break;
+            }
+        }
+        if stream.sample_rate == 0 {
+            return Err(error("FLAC stream without STREAMINFO"));
+        }
+        stream.frames = pos;
+        Ok(stream)
+    }
+
+    /// Samples `start..end`, clamped to the stream.
+    pub fn decode(&self, start: u64, end: u64) -> Result<Vec<i16>> {
+        let end = end.min(self.total_samples);
+        let (mut sample, offset) = self
+            .seek_points
+            .iter()
+            .rev()
+            .find(|&&(sample, _)| sample <= start)
+            .copied()
+            .unwrap_or((0, 0));
+        let frames = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| self.data.get(self.frames + offset..))
+            .ok_or_else(|| error("FLAC seek point is out of range"))?;
+        let mut reader = FrameReader::new(std::io::Cursor::new(frames));
+        let mut samples = Vec::with_capacity(end.saturating_sub(start) as usize);
+        let mut buffer = Vec::new();
+        // Positions are counted here: claxon derives a short last frame's
+        // position from its own size.
+        while sample < end {
+            let Some(block) = reader
+                .read_next_or_eof(buffer)
+                .map_err(|e| error(format!("FLAC frame is damaged: {e}")))?
+            else {
+                break;
+            };
+            let block_end = sample + u64::from(block.duration());
+            if block_end > start {
+                let from = start.saturating_sub(sample) as usize;
+                let to = (end.min(block_end) - sample) as usize;
+                samples.extend(block.channel(0)[from..to].iter().map(|&s| s as i16));
+            }
+            sample = block_end;
+            buffer = block.into_buffer();
+        }
+        Ok(samples)
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Bits not yet flushed to `bytes`; fewer than 8 between writes.
+    pending: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    /// Write the low `bits` (at most 32) bits of `value`.
+    fn write(&mut self, value: u64, bits: u32) {
+        if bits == 0 {
+            return;
+        }
+        self.pending = self.pending << bits | (value & ((1 << bits) - 1));
+        self.pending_bits += bits;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            self.bytes.push((self.pending >> self.pending_bits) as u8);
+        }
+        self.pending &= (1 << self.pending_bits) - 1;
+    }
+
+    fn write_signed(&mut self, value: i32, bits: u32) {
+        self.write(value as u64, bits);
+    }
+
+    /// `value` zeros and a one.
+    fn write_unary(&mut self, mut value: u32) {
+        while value >= 32 {
+            self.write(0, 32);
+            value -= 32;
+        }
+        self.write(1, value + 1);
+    }
+
+    /// Pad with zeros to a byte boundary and return the bytes.
+    fn finish(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+        self.bytes
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+const CRC16_TABLE: [u16; 256] = crc16_table();
+
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x8005
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, &byte| {
+        crc << 8 ^ CRC16_TABLE[usize::from((crc >> 8) as u8 ^ byte)]
+    })
+}
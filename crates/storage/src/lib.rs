@@ -1,7 +1,32 @@
-use gibberish_events::Activity;
-use gibberish_transcript::{Transcript, TranscriptRepository};
-use rusqlite::Connection;
-use std::path::Path;
+mod audio;
+mod backup;
+mod encryption;
+mod flac;
+mod migrations;
+mod retention;
+mod settings;
+
+pub use audio::{AudioClip, AudioSpan};
+pub use backup::{
+    verify_backup, BackupConflict, BackupEntry, BackupItem, BackupManifest, BackupScope,
+    ImportReport, BACKUP_VERSION,
+};
+pub use encryption::{DatabaseKey, FileCipher};
+pub use migrations::SCHEMA_VERSION;
+pub use retention::{
+    ModeRetention, PurgeReason, PurgedAudio, PurgedSession, RetentionReport, RetentionSettings,
+};
+pub use settings::{
+    Language, RouterSettings, Setting, Settings, SettingsSnapshot, SettingsWatcher, TurnSettings,
+    VadSettings,
+};
+
+use gibberish_events::{Activity, ActivityStatus, ActivityType};
+use gibberish_transcript::{Marker, MarkerKind, Segment, Transcript, TranscriptRepository, Word};
+use rusqlite::{params_from_iter, types::Value, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
 use uuid::Uuid;
 
@@ -13,81 +38,232 @@ pub enum StorageError {
     SerializationError(#[from] serde_json::Error),
     #[error("not found: {0}")]
     NotFound(String),
+    #[error("database schema version {found} is newer than this build supports ({supported})")]
+    SchemaTooNew { found: u32, supported: u32 },
+    #[error("invalid setting {key}: {reason}")]
+    InvalidSetting { key: &'static str, reason: String },
+    #[error("settings file error: {0}")]
+    SettingsFile(String),
+    #[error("database is encrypted and no key was given")]
+    Locked,
+    #[error("the key does not unlock the database")]
+    KeyRejected,
+    #[error("encryption error: {0}")]
+    Encryption(String),
+    #[error("audio error: {0}")]
+    Audio(String),
+    #[error("backup error: {0}")]
+    Backup(String),
 }
 
 pub type Result<T> = std::result::Result<T, StorageError>;
 
 pub struct Database {
     conn: Mutex<Connection>,
+    /// Database file; `None` in memory.
+    path: Option<PathBuf>,
+    encrypted: AtomicBool,
 }
 
 impl Database {
+    /// Open a plain database. Fails with [`StorageError::Locked`] if it is
+    /// encrypted (see [`Database::open_encrypted`]).
     pub fn open(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path)?;
-        let db = Self {
-            conn: Mutex::new(conn),
-        };
-        db.init_schema()?;
-        Ok(db)
+        let mut conn = encryption::connect(path, None)?;
+        migrations::migrate(&mut conn)?;
+        Ok(Self::from_connection(conn, Some(path), false))
     }
 
     pub fn open_in_memory() -> Result<Self> {
-        let conn = Connection::open_in_memory()?;
-        let db = Self {
+        let mut conn = Connection::open_in_memory()?;
+        migrations::migrate(&mut conn)?;
+        Ok(Self::from_connection(conn, None, false))
+    }
+
+    fn from_connection(conn: Connection, path: Option<&Path>, encrypted: bool) -> Self {
+        Self {
             conn: Mutex::new(conn),
-        };
-        db.init_schema()?;
-        Ok(db)
+            path: path.map(Path::to_path_buf),
+            encrypted: AtomicBool::new(encrypted),
+        }
     }
 
-    fn init_schema(&self) -> Result<()> {
+    /// Schema version of the open database (see [`SCHEMA_VERSION`]).
+    pub fn schema_version(&self) -> Result<u32> {
         let conn = self.conn.lock().expect("database mutex poisoned");
-        conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS sessions (
-                id TEXT PRIMARY KEY,
-                title TEXT,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL,
-                duration_ms INTEGER,
-                audio_path TEXT,
-                transcript_json TEXT
-            );
+        migrations::schema_version(&conn)
+    }
+}
 
-            CREATE TABLE IF NOT EXISTS settings (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            );
+/// Transcript JSON as stored in `sessions`: segments live in their own
+/// table.
+fn transcript_json(transcript: &Transcript) -> Result<String> {
+    let mut value = serde_json::to_value(transcript)?;
+    value["segments"] = serde_json::Value::Array(Vec::new());
+    Ok(serde_json::to_string(&value)?)
+}
 
-            CREATE TABLE IF NOT EXISTS activities (
-                id TEXT PRIMARY KEY,
-                activity_type TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                status TEXT NOT NULL,
-                parent_id TEXT,
-                content_json TEXT NOT NULL,
-                FOREIGN KEY (parent_id) REFERENCES activities(id)
-            );
+/// A stored transcript with its segments.
+fn load_transcript(conn: &Connection, json: &str) -> Result<Transcript> {
+    let mut transcript: Transcript = serde_json::from_str(json)?;
+    let session_id = transcript.id.to_string();
 
-            CREATE INDEX IF NOT EXISTS idx_sessions_created_at ON sessions(created_at);
-            CREATE INDEX IF NOT EXISTS idx_activities_timestamp ON activities(timestamp DESC);
-            CREATE INDEX IF NOT EXISTS idx_activities_parent ON activities(parent_id);
-            "#,
-        )?;
-        Ok(())
+    let mut words: Vec<Vec<Word>> = Vec::new();
+    let mut stmt = conn.prepare(
+        "SELECT segment_position, text, start_ms, end_ms, confidence FROM words WHERE session_id = ?1 ORDER BY segment_position, position",
+    )?;
+    let rows = stmt.query_map([&session_id], |row| {
+        let segment_position: usize = row.get(0)?;
+        let word = Word {
+            text: row.get(1)?,
+            start_ms: row.get::<_, i64>(2)? as u64,
+            end_ms: row.get::<_, i64>(3)? as u64,
+            confidence: row.get(4)?,
+        };
+        Ok((segment_position, word))
+    })?;
+    for row in rows {
+        let (segment_position, word) = row?;
+        if words.len() <= segment_position {
+            words.resize_with(segment_position + 1, Vec::new);
+        }
+        words[segment_position].push(word);
     }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, text, raw_text, start_ms, end_ms, speaker, is_final FROM segments WHERE session_id = ?1 ORDER BY position",
+    )?;
+    let rows = stmt.query_map([&session_id], |row| {
+        let id: String = row.get(0)?;
+        let segment = Segment {
+            id: Uuid::nil(),
+            text: row.get(1)?,
+            raw_text: row.get(2)?,
+            start_ms: row.get::<_, i64>(3)? as u64,
+            end_ms: row.get::<_, i64>(4)? as u64,
+            words: Vec::new(),
+            speaker: row.get(5)?,
+            is_final: row.get(6)?,
+        };
+        Ok((id, segment))
+    })?;
+    for row in rows {
+        let (id, mut segment) = row?;
+        // Edits, splits and the revision log refer to segments by id, so
+        // one that does not parse is an error rather than a new id.
+        segment.id = Uuid::parse_str(&id).map_err(|e| {
+            StorageError::SerializationError(serde::de::Error::custom(format!(
+                "segment id {id:?} of session {session_id}: {e}"
+            )))
+        })?;
+        transcript.segments.push(segment);
+    }
+    for (segment, words) in transcript.segments.iter_mut().zip(&mut words) {
+        segment.words = std::mem::take(words);
+    }
+    Ok(transcript)
+}
+
+/// Replace the segment and word rows of `transcript`.
+fn insert_segments(conn: &Connection, transcript: &Transcript) -> Result<()> {
+    let session_id = transcript.id.to_string();
+    conn.execute("DELETE FROM words WHERE session_id = ?1", [&session_id])?;
+    conn.execute("DELETE FROM segments WHERE session_id = ?1", [&session_id])?;
+    let mut insert_segment = conn.prepare(
+        "INSERT INTO segments (session_id, position, id, text, raw_text, start_ms, end_ms, speaker, is_final) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    let mut insert_word = conn.prepare(
+        "INSERT INTO words (session_id, segment_position, position, text, start_ms, end_ms, confidence) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for (position, segment) in transcript.segments.iter().enumerate() {
+        insert_segment.execute((
+            &session_id,
+            position as i64,
+            segment.id.to_string(),
+            &segment.text,
+            &segment.raw_text,
+            segment.start_ms as i64,
+            segment.end_ms as i64,
+            segment.speaker,
+            segment.is_final,
+        ))?;
+        for (word_position, word) in segment.words.iter().enumerate() {
+            insert_word.execute((
+                &session_id,
+                position as i64,
+                word_position as i64,
+                &word.text,
+                word.start_ms as i64,
+                word.end_ms as i64,
+                word.confidence,
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace the search index rows of `transcript`.
+fn index_transcript(conn: &Connection, transcript: &Transcript) -> Result<()> {
+    let session_id = transcript.id.to_string();
+    unindex_session(conn, &session_id)?;
+    let mut insert = conn.prepare(
+        "INSERT INTO transcript_fts (text, session_id, segment_id, start_ms, speaker, mode) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    let mut insert_row =
+        conn.prepare("INSERT INTO search_rows (fts_rowid, session_id) VALUES (?1, ?2)")?;
+    if let Some(title) = &transcript.title {
+        let fts_rowid = insert.insert((
+            title,
+            &session_id,
+            None::<String>,
+            None::<i64>,
+            None::<i32>,
+            &transcript.mode,
+        ))?;
+        insert_row.execute((fts_rowid, &session_id))?;
+    }
+    for segment in &transcript.segments {
+        let fts_rowid = insert.insert((
+            &segment.text,
+            &session_id,
+            segment.id.to_string(),
+            segment.start_ms as i64,
+            segment.speaker,
+            &transcript.mode,
+        ))?;
+        insert_row.execute((fts_rowid, &session_id))?;
+    }
+    Ok(())
+}
+
+/// Remove the search index rows of a session, found by rowid through
+/// `search_rows`.
+fn unindex_session(conn: &Connection, session_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM transcript_fts WHERE rowid IN (SELECT fts_rowid FROM search_rows WHERE session_id = ?1)",
+        [session_id],
+    )?;
+    conn.execute(
+        "DELETE FROM search_rows WHERE session_id = ?1",
+        [session_id],
+    )?;
+    Ok(())
 }
 
 impl TranscriptRepository for Database {
     type Error = StorageError;
 
     fn save(&self, transcript: &Transcript) -> Result<()> {
-        let json = serde_json::to_string(transcript)?;
-        let conn = self.conn.lock().expect("database mutex poisoned");
-        conn.execute(
-            "INSERT OR REPLACE INTO sessions (id, title, created_at, updated_at, duration_ms, transcript_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        let json = transcript_json(transcript)?;
+        let session_id = transcript.id.to_string();
+        let mut conn = self.conn.lock().expect("database mutex poisoned");
+        let tx = conn.transaction()?;
+        // An upsert, not a replace: the row keeps its `audio_path`.
+        tx.execute(
+            "INSERT INTO sessions (id, title, created_at, updated_at, duration_ms, transcript_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET title = excluded.title, created_at = excluded.created_at, updated_at = excluded.updated_at, duration_ms = excluded.duration_ms, transcript_json = excluded.transcript_json",
             (
-                transcript.id.to_string(),
+                &session_id,
                 &transcript.title,
                 transcript.created_at.timestamp(),
                 transcript.updated_at.timestamp(),
@@ -95,6 +271,27 @@ impl TranscriptRepository for Database {
                 json,
             ),
         )?;
+
+        // Markers are kept in the transcript JSON and mirrored into their
+        // own table so they can be queried across sessions.
+        tx.execute("DELETE FROM markers WHERE session_id = ?1", [&session_id])?;
+        let session_start_ms = transcript.created_at.timestamp_millis();
+        for marker in &transcript.markers {
+            tx.execute(
+                "INSERT OR REPLACE INTO markers (id, session_id, kind, occurred_at, done, marker_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (
+                    marker.id.to_string(),
+                    &session_id,
+                    marker.kind.as_str(),
+                    session_start_ms + marker.timestamp_ms as i64,
+                    marker.done,
+                    serde_json::to_string(marker)?,
+                ),
+            )?;
+        }
+        insert_segments(&tx, transcript)?;
+        index_transcript(&tx, transcript)?;
+        tx.commit()?;
         Ok(())
     }
 
@@ -112,7 +309,7 @@ impl TranscriptRepository for Database {
                 }
                 other => StorageError::DatabaseError(other),
             })?;
-        Ok(serde_json::from_str(&json)?)
+        load_transcript(&conn, &json)
     }
 
     fn list(&self) -> Result<Vec<Transcript>> {
@@ -124,24 +321,489 @@ impl TranscriptRepository for Database {
             Ok(json)
         })?;
 
+        let jsons: Vec<String> = rows.collect::<rusqlite::Result<_>>()?;
+
         let mut transcripts = Vec::new();
-        for row in rows {
-            let json = row?;
-            if let Ok(t) = serde_json::from_str(&json) {
+        for json in jsons {
+            if let Ok(t) = load_transcript(&conn, &json) {
                 transcripts.push(t);
             }
         }
         Ok(transcripts)
     }
 
+    /// Delete the session and its recording, if one is attached.
     fn delete(&self, id: &Uuid) -> Result<()> {
+        let mut conn = self.conn.lock().expect("database mutex poisoned");
+        let tx = conn.transaction()?;
+        let audio_path = delete_session(&tx, &id.to_string())?;
+        tx.commit()?;
+        drop(conn);
+        if let Some(path) = audio_path {
+            retention::remove_audio(&path);
+        }
+        Ok(())
+    }
+}
+
+/// Delete a session's rows. Returns its recording, which is left on disk.
+fn delete_session(conn: &Connection, id: &str) -> Result<Option<PathBuf>> {
+    let audio_path: Option<String> = conn
+        .query_row(
+            "SELECT audio_path FROM sessions WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                StorageError::NotFound(format!("transcript {id}"))
+            }
+            other => StorageError::DatabaseError(other),
+        })?;
+    conn.execute("DELETE FROM markers WHERE session_id = ?1", [id])?;
+    unindex_session(conn, id)?;
+    conn.execute("DELETE FROM words WHERE session_id = ?1", [id])?;
+    conn.execute("DELETE FROM segments WHERE session_id = ?1", [id])?;
+    conn.execute("DELETE FROM sessions WHERE id = ?1", [id])?;
+    Ok(audio_path.map(PathBuf::from))
+}
+
+impl Database {
+    /// Attach (or detach) a session's recording. An attached recording is
+    /// deleted with the session and by [retention](RetentionSettings) rules.
+    pub fn set_session_audio(&self, id: &Uuid, path: Option<&Path>) -> Result<()> {
         let conn = self.conn.lock().expect("database mutex poisoned");
-        let affected = conn.execute("DELETE FROM sessions WHERE id = ?1", [id.to_string()])?;
+        let affected = conn.execute(
+            "UPDATE sessions SET audio_path = ?1 WHERE id = ?2",
+            (
+                path.map(|p| p.to_string_lossy().into_owned()),
+                id.to_string(),
+            ),
+        )?;
         if affected == 0 {
             return Err(StorageError::NotFound(format!("transcript {id}")));
         }
         Ok(())
     }
+
+    pub fn session_audio(&self, id: &Uuid) -> Result<Option<PathBuf>> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let audio_path: Option<String> = conn
+            .query_row(
+                "SELECT audio_path FROM sessions WHERE id = ?1",
+                [id.to_string()],
+                |row| row.get(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    StorageError::NotFound(format!("transcript {id}"))
+                }
+                other => StorageError::DatabaseError(other),
+            })?;
+        Ok(audio_path.map(PathBuf::from))
+    }
+
+    /// Rebuild the database file without its free pages, returning the
+    /// space of deleted rows to the file system.
+    pub fn vacuum(&self) -> Result<()> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+}
+
+/// Filter for [`Database::query_markers`]. Empty fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkerQuery {
+    /// Marker kinds to include.
+    pub kinds: Vec<MarkerKind>,
+    /// Only markers not marked done.
+    pub open_only: bool,
+    /// Earliest time the marker was placed (Unix ms, inclusive).
+    pub since_ms: Option<i64>,
+    /// Latest time the marker was placed (Unix ms, exclusive).
+    pub until_ms: Option<i64>,
+    pub session_id: Option<Uuid>,
+    pub limit: Option<usize>,
+}
+
+/// A marker found by [`Database::query_markers`], with its session.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMarker {
+    pub session_id: Uuid,
+    pub session_title: Option<String>,
+    /// When the marker was placed (Unix ms): session start plus the
+    /// marker's timeline position.
+    pub occurred_at_ms: i64,
+    pub marker: Marker,
+}
+
+impl Database {
+    /// Markers across sessions, newest first.
+    pub fn query_markers(&self, query: &MarkerQuery) -> Result<Vec<SessionMarker>> {
+        let mut sql = String::from(
+            "SELECT m.session_id, s.title, m.occurred_at, m.marker_json FROM markers m JOIN sessions s ON s.id = m.session_id WHERE 1 = 1",
+        );
+        let mut params: Vec<Value> = Vec::new();
+        if !query.kinds.is_empty() {
+            let placeholders = vec!["?"; query.kinds.len()].join(", ");
+            sql.push_str(&format!(" AND m.kind IN ({placeholders})"));
+            params.extend(
+                query
+                    .kinds
+                    .iter()
+                    .map(|kind| Value::Text(kind.as_str().to_string())),
+            );
+        }
+        if query.open_only {
+            sql.push_str(" AND m.done = 0");
+        }
+        if let Some(since_ms) = query.since_ms {
+            sql.push_str(" AND m.occurred_at >= ?");
+            params.push(Value::Integer(since_ms));
+        }
+        if let Some(until_ms) = query.until_ms {
+            sql.push_str(" AND m.occurred_at < ?");
+            params.push(Value::Integer(until_ms));
+        }
+        if let Some(session_id) = query.session_id {
+            sql.push_str(" AND m.session_id = ?");
+            params.push(Value::Text(session_id.to_string()));
+        }
+        sql.push_str(" ORDER BY m.occurred_at DESC");
+        if let Some(limit) = query.limit {
+            sql.push_str(" LIMIT ?");
+            params.push(Value::Integer(limit as i64));
+        }
+
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params), |row| {
+            let session_id: String = row.get(0)?;
+            let title: Option<String> = row.get(1)?;
+            let occurred_at_ms: i64 = row.get(2)?;
+            let marker_json: String = row.get(3)?;
+            Ok((session_id, title, occurred_at_ms, marker_json))
+        })?;
+
+        let mut markers = Vec::new();
+        for row in rows {
+            let (session_id, session_title, occurred_at_ms, marker_json) = row?;
+            let Ok(session_id) = Uuid::parse_str(&session_id) else {
+                continue;
+            };
+            markers.push(SessionMarker {
+                session_id,
+                session_title,
+                occurred_at_ms,
+                marker: serde_json::from_str(&marker_json)?,
+            });
+        }
+        Ok(markers)
+    }
+}
+
+/// Start and end of matched terms in [`SearchHit::snippet`].
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+/// Full-text query for [`Database::search`]. Empty filters match everything.
+///
+/// `text` accepts `"quoted phrases"`, `prefix*` terms, `AND`, `OR`, `NOT`
+/// and parentheses; adjacent terms must all match. Words are matched by
+/// stem, ignoring case and diacritics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub text: String,
+    /// Earliest session start (Unix ms, inclusive).
+    pub since_ms: Option<i64>,
+    /// Latest session start (Unix ms, exclusive).
+    pub until_ms: Option<i64>,
+    /// Context mode the session was recorded in.
+    pub mode: Option<String>,
+    /// Diarized speaker id. Title hits never match a speaker filter.
+    pub speaker: Option<i32>,
+    pub session_id: Option<Uuid>,
+    pub limit: Option<usize>,
+}
+
+/// A segment or session title matching a [`SearchQuery`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub session_id: Uuid,
+    pub session_title: Option<String>,
+    /// Session start (Unix seconds).
+    pub created_at: i64,
+    pub duration_ms: u64,
+    /// Matching segment; `None` when the title matched.
+    pub segment_id: Option<Uuid>,
+    pub start_ms: Option<u64>,
+    pub speaker: Option<i32>,
+    /// Matching text around the hit, terms wrapped in [`HIGHLIGHT_START`]
+    /// and [`HIGHLIGHT_END`].
+    pub snippet: String,
+    /// Relevance (BM25); higher is better.
+    pub score: f64,
+}
+
+impl Database {
+    /// Segments and titles matching `query`, best first. A query without
+    /// any search terms matches nothing.
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+        let Some(expression) = match_expression(&query.text) else {
+            return Ok(Vec::new());
+        };
+        let mut sql = format!(
+            "SELECT transcript_fts.session_id, s.title, s.created_at, s.duration_ms, transcript_fts.segment_id, transcript_fts.start_ms, transcript_fts.speaker, snippet(transcript_fts, 0, '{HIGHLIGHT_START}', '{HIGHLIGHT_END}', '…', 16), bm25(transcript_fts) FROM transcript_fts JOIN sessions s ON s.id = transcript_fts.session_id WHERE transcript_fts MATCH ?",
+        );
+        let mut params: Vec<Value> = vec![Value::Text(expression)];
+        // `created_at` is in seconds; the bounds are rounded up so the
+        // comparison stays on the bare, indexed column.
+        if let Some(since_ms) = query.since_ms {
+            sql.push_str(" AND s.created_at >= ?");
+            params.push(Value::Integer(ceil_secs(since_ms)));
+        }
+        if let Some(until_ms) = query.until_ms {
+            sql.push_str(" AND s.created_at < ?");
+            params.push(Value::Integer(ceil_secs(until_ms)));
+        }
+        if let Some(mode) = &query.mode {
+            sql.push_str(" AND transcript_fts.mode = ?");
+            params.push(Value::Text(mode.clone()));
+        }
+        if let Some(speaker) = query.speaker {
+            sql.push_str(" AND transcript_fts.speaker = ?");
+            params.push(Value::Integer(speaker.into()));
+        }
+        if let Some(session_id) = query.session_id {
+            sql.push_str(" AND transcript_fts.session_id = ?");
+            params.push(Value::Text(session_id.to_string()));
+        }
+        sql.push_str(" ORDER BY bm25(transcript_fts)");
+        if let Some(limit) = query.limit {
+            sql.push_str(" LIMIT ?");
+            params.push(Value::Integer(limit as i64));
+        }
+
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params), |row| {
+            let session_id: String = row.get(0)?;
+            let segment_id: Option<String> = row.get(4)?;
+            let start_ms: Option<i64> = row.get(5)?;
+            let duration_ms: Option<i64> = row.get(3)?;
+            let bm25: f64 = row.get(8)?;
+            Ok((
+                session_id,
+                segment_id,
+                SearchHit {
+                    session_id: Uuid::nil(),
+                    session_title: row.get(1)?,
+                    created_at: row.get(2)?,
+                    duration_ms: duration_ms.unwrap_or(0) as u64,
+                    segment_id: None,
+                    start_ms: start_ms.map(|ms| ms as u64),
+                    speaker: row.get(6)?,
+                    snippet: row.get(7)?,
+                    score: -bm25,
+                },
+            ))
+        })?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            let (session_id, segment_id, mut hit) = row?;
+            let Ok(session_id) = Uuid::parse_str(&session_id) else {
+                continue;
+            };
+            hit.session_id = session_id;
+            hit.segment_id = segment_id.and_then(|id| Uuid::parse_str(&id).ok());
+            hits.push(hit);
+        }
+        Ok(hits)
+    }
+
+    /// Re-index every saved session. Returns the number of sessions indexed.
+    pub fn rebuild_search_index(&self) -> Result<usize> {
+        let mut conn = self.conn.lock().expect("database mutex poisoned");
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM transcript_fts", [])?;
+        tx.execute("DELETE FROM search_rows", [])?;
+        let transcripts: Vec<String> = {
+            let mut stmt = tx.prepare("SELECT transcript_json FROM sessions")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut indexed = 0;
+        for json in transcripts {
+            let Ok(transcript) = load_transcript(&tx, &json) else {
+                continue;
+            };
+            index_transcript(&tx, &transcript)?;
+            indexed += 1;
+        }
+        tx.commit()?;
+        Ok(indexed)
+    }
+}
+
+/// Unix ms as Unix seconds, rounded up.
+fn ceil_secs(ms: i64) -> i64 {
+    -(-ms).div_euclid(1000)
+}
+
+#[derive(Debug, PartialEq)]
+enum QueryToken {
+    Term(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+/// Turn user input into a well-formed FTS5 expression, or `None` when it
+/// has no terms. Every term is quoted so punctuation can't be read as FTS5
+/// syntax; stray operators and parentheses are dropped and implicit ANDs
+/// made explicit.
+fn match_expression(input: &str) -> Option<String> {
+    let quote = |text: &str, prefix: bool| {
+        format!(
+            "\"{}\"{}",
+            text.replace('"', "\"\""),
+            if prefix { "*" } else { "" }
+        )
+    };
+
+    let mut raw = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' {
+            chars.next();
+            raw.push(if c == '(' {
+                QueryToken::Open
+            } else {
+                QueryToken::Close
+            });
+        } else if c == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            let prefix = chars.next_if_eq(&'*').is_some();
+            if !phrase.trim().is_empty() {
+                raw.push(QueryToken::Term(quote(phrase.trim(), prefix)));
+            }
+        } else {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && !"()\"".contains(c)) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "AND" => raw.push(QueryToken::Operator("AND")),
+                "OR" => raw.push(QueryToken::Operator("OR")),
+                "NOT" => raw.push(QueryToken::Operator("NOT")),
+                _ => {
+                    let term = word.trim_end_matches('*');
+                    if !term.is_empty() {
+                        raw.push(QueryToken::Term(quote(term, term.len() < word.len())));
+                    }
+                }
+            }
+        }
+    }
+
+    // Operators need an operand on the left; a closing parenthesis needs
+    // an open group with something in it.
+    let mut tokens: Vec<QueryToken> = Vec::new();
+    let mut depth = 0;
+    for token in raw {
+        let after_operand = matches!(tokens.last(), Some(QueryToken::Term(_) | QueryToken::Close));
+        match token {
+            QueryToken::Operator(_) if !after_operand => continue,
+            QueryToken::Close if depth == 0 => continue,
+            QueryToken::Close if !after_operand => {
+                while let Some(QueryToken::Operator(_)) = tokens.last() {
+                    tokens.pop();
+                }
+                if tokens.last() == Some(&QueryToken::Open) {
+                    tokens.pop();
+                    depth -= 1;
+                    continue;
+                }
+            }
+            QueryToken::Term(_) | QueryToken::Open if after_operand => {
+                tokens.push(QueryToken::Operator("AND"));
+            }
+            _ => {}
+        }
+        match token {
+            QueryToken::Open => depth += 1,
+            QueryToken::Close => depth -= 1,
+            _ => {}
+        }
+        tokens.push(token);
+    }
+    while let Some(QueryToken::Operator(_) | QueryToken::Open) = tokens.last() {
+        if tokens.pop() == Some(QueryToken::Open) {
+            depth -= 1;
+        }
+    }
+    if !tokens.iter().any(|t| matches!(t, QueryToken::Term(_))) {
+        return None;
+    }
+
+    let mut expression: Vec<String> = tokens
+        .into_iter()
+        .map(|token| match token {
+            QueryToken::Term(term) => term,
+            QueryToken::Operator(op) => op.to_string(),
+            QueryToken::Open => "(".to_string(),
+            QueryToken::Close => ")".to_string(),
+        })
+        .collect();
+    expression.extend((0..depth).map(|_| ")".to_string()));
+    Some(expression.join(" "))
+}
+
+/// Filter and page for [`ActivityRepository::query_activities`]. Empty
+/// fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityQuery {
+    pub types: Vec<ActivityType>,
+    pub statuses: Vec<ActivityStatus>,
+    /// Tool named in the content (voice commands and their results).
+    pub tool: Option<String>,
+    /// Mode named in the content.
+    pub mode: Option<String>,
+    /// Earliest timestamp (Unix ms, inclusive).
+    pub since_ms: Option<i64>,
+    /// Latest timestamp (Unix ms, exclusive).
+    pub until_ms: Option<i64>,
+    /// Continue after this position: the `next_cursor` of the previous page.
+    pub cursor: Option<ActivityCursor>,
+    /// Page size; [`DEFAULT_ACTIVITY_PAGE`] when unset.
+    pub limit: Option<usize>,
+}
+
+/// Page size of an [`ActivityQuery`] without a limit.
+pub const DEFAULT_ACTIVITY_PAGE: usize = 50;
+
+/// Position in the activity feed. Activities are ordered newest first, with
+/// the id breaking ties between equal timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityCursor {
+    pub timestamp: i64,
+    pub id: String,
+}
+
+/// One page of the activity feed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityPage {
+    pub activities: Vec<Activity>,
+    /// Cursor for the next page; `None` on the last page.
+    pub next_cursor: Option<ActivityCursor>,
 }
 
 /// Repository for activity persistence.
@@ -149,26 +811,70 @@ pub trait ActivityRepository {
     type Error;
     fn save_activity(&self, activity: &Activity) -> std::result::Result<(), Self::Error>;
     fn get_activities(&self, limit: usize) -> std::result::Result<Vec<Activity>, Self::Error>;
+    /// A page of activities matching `query`, newest first.
+    fn query_activities(
+        &self,
+        query: &ActivityQuery,
+    ) -> std::result::Result<ActivityPage, Self::Error>;
+    /// Every activity in the thread of `id` (its root, following
+    /// `parent_id`, and all of the root's replies), oldest first.
+    fn get_thread(&self, id: &str) -> std::result::Result<Vec<Activity>, Self::Error>;
+    /// Set the status of an activity, and its duration when given.
+    fn update_activity_status(
+        &self,
+        id: &str,
+        status: ActivityStatus,
+        duration_ms: Option<u64>,
+    ) -> std::result::Result<(), Self::Error>;
     fn delete_activity(&self, id: &str) -> std::result::Result<(), Self::Error>;
     fn clear_activities(&self) -> std::result::Result<(), Self::Error>;
 }
 
+const ACTIVITY_COLUMNS: &str = "id, activity_type, timestamp, status, parent_id, content_json";
+
+/// An enum as stored in its column: its serde name.
+fn enum_column<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string(value)?.trim_matches('"').to_string())
+}
+
+/// An activity from a row of [`ACTIVITY_COLUMNS`]. Unknown types, statuses
+/// and content fall back to defaults rather than hiding the row.
+fn activity_from_row(row: &rusqlite::Row) -> rusqlite::Result<Activity> {
+    let activity_type_str: String = row.get(1)?;
+    let status_str: String = row.get(3)?;
+    let content_json: String = row.get(5)?;
+
+    let activity_type = serde_json::from_str(&format!("\"{}\"", activity_type_str))
+        .unwrap_or(ActivityType::Transcript);
+    let status =
+        serde_json::from_str(&format!("\"{}\"", status_str)).unwrap_or(ActivityStatus::Completed);
+    let content = serde_json::from_str(&content_json).unwrap_or_default();
+
+    Ok(Activity {
+        id: row.get(0)?,
+        activity_type,
+        timestamp: row.get(2)?,
+        status,
+        parent_id: row.get(4)?,
+        content,
+        expanded: None,
+    })
+}
+
 impl ActivityRepository for Database {
     type Error = StorageError;
 
     fn save_activity(&self, activity: &Activity) -> Result<()> {
         let content_json = serde_json::to_string(&activity.content)?;
-        let activity_type = serde_json::to_string(&activity.activity_type)?;
-        let status = serde_json::to_string(&activity.status)?;
 
         let conn = self.conn.lock().expect("database mutex poisoned");
         conn.execute(
             "INSERT OR REPLACE INTO activities (id, activity_type, timestamp, status, parent_id, content_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             (
                 &activity.id,
-                activity_type.trim_matches('"'),
+                enum_column(&activity.activity_type)?,
                 activity.timestamp,
-                status.trim_matches('"'),
+                enum_column(&activity.status)?,
                 &activity.parent_id,
                 content_json,
             ),
@@ -178,48 +884,132 @@ impl ActivityRepository for Database {
 
     fn get_activities(&self, limit: usize) -> Result<Vec<Activity>> {
         let conn = self.conn.lock().expect("database mutex poisoned");
-        let mut stmt = conn.prepare(
-            "SELECT id, activity_type, timestamp, status, parent_id, content_json FROM activities ORDER BY timestamp DESC LIMIT ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ACTIVITY_COLUMNS} FROM activities ORDER BY timestamp DESC LIMIT ?1"
+        ))?;
+        let activities = stmt
+            .query_map([limit as i64], activity_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(activities)
+    }
+
+    fn query_activities(&self, query: &ActivityQuery) -> Result<ActivityPage> {
+        let mut sql = format!("SELECT {ACTIVITY_COLUMNS} FROM activities WHERE 1 = 1");
+        let mut params: Vec<Value> = Vec::new();
+        if !query.types.is_empty() {
+            let placeholders = vec!["?"; query.types.len()].join(", ");
+            sql.push_str(&format!(" AND activity_type IN ({placeholders})"));
+            for activity_type in &query.types {
+                params.push(Value::Text(enum_column(activity_type)?));
+            }
+        }
+        if !query.statuses.is_empty() {
+            let placeholders = vec!["?"; query.statuses.len()].join(", ");
+            sql.push_str(&format!(" AND status IN ({placeholders})"));
+            for status in &query.statuses {
+                params.push(Value::Text(enum_column(status)?));
+            }
+        }
+        if let Some(tool) = &query.tool {
+            sql.push_str(" AND json_extract(content_json, '$.tool') = ?");
+            params.push(Value::Text(tool.clone()));
+        }
+        if let Some(mode) = &query.mode {
+            sql.push_str(" AND json_extract(content_json, '$.mode') = ?");
+            params.push(Value::Text(mode.clone()));
+        }
+        if let Some(since_ms) = query.since_ms {
+            sql.push_str(" AND timestamp >= ?");
+            params.push(Value::Integer(since_ms));
+        }
+        if let Some(until_ms) = query.until_ms {
+            sql.push_str(" AND timestamp < ?");
+            params.push(Value::Integer(until_ms));
+        }
+        if let Some(cursor) = &query.cursor {
+            sql.push_str(" AND (timestamp < ? OR (timestamp = ? AND id < ?))");
+            params.push(Value::Integer(cursor.timestamp));
+            params.push(Value::Integer(cursor.timestamp));
+            params.push(Value::Text(cursor.id.clone()));
+        }
+        // One extra row tells whether there is a next page.
+        let limit = query.limit.unwrap_or(DEFAULT_ACTIVITY_PAGE).max(1);
+        sql.push_str(" ORDER BY timestamp DESC, id DESC LIMIT ?");
+        params.push(Value::Integer(limit as i64 + 1));
 
-        let rows = stmt.query_map([limit as i64], |row| {
-            let id: String = row.get(0)?;
-            let activity_type_str: String = row.get(1)?;
-            let timestamp: i64 = row.get(2)?;
-            let status_str: String = row.get(3)?;
-            let parent_id: Option<String> = row.get(4)?;
-            let content_json: String = row.get(5)?;
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let mut activities: Vec<Activity> = stmt
+            .query_map(params_from_iter(params), activity_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
 
-            Ok((id, activity_type_str, timestamp, status_str, parent_id, content_json))
-        })?;
+        let next_cursor = if activities.len() > limit {
+            activities.truncate(limit);
+            activities.last().map(|last| ActivityCursor {
+                timestamp: last.timestamp,
+                id: last.id.clone(),
+            })
+        } else {
+            None
+        };
+        Ok(ActivityPage {
+            activities,
+            next_cursor,
+        })
+    }
 
-        let mut activities = Vec::new();
-        for row in rows {
-            let (id, activity_type_str, timestamp, status_str, parent_id, content_json) = row?;
-
-            // Parse activity_type
-            let activity_type = serde_json::from_str(&format!("\"{}\"", activity_type_str))
-                .unwrap_or(gibberish_events::ActivityType::Transcript);
-
-            // Parse status
-            let status = serde_json::from_str(&format!("\"{}\"", status_str))
-                .unwrap_or(gibberish_events::ActivityStatus::Completed);
-
-            // Parse content
-            let content = serde_json::from_str(&content_json)
-                .unwrap_or_default();
-
-            activities.push(Activity {
-                id,
-                activity_type,
-                timestamp,
-                status,
-                parent_id,
-                content,
-                expanded: None,
-            });
+    fn get_thread(&self, id: &str) -> Result<Vec<Activity>> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        // Walk up to the root, then down from every ancestor to every reply.
+        // Both walks carry only ids, so UNION drops a row seen before and a
+        // parent_id cycle ends them.
+        let mut stmt = conn.prepare(&format!(
+            "WITH RECURSIVE
+                ancestors(id, parent_id) AS (
+                    SELECT id, parent_id FROM activities WHERE id = ?1
+                    UNION
+                    SELECT a.id, a.parent_id
+                    FROM activities a JOIN ancestors ON a.id = ancestors.parent_id
+                ),
+                thread(id) AS (
+                    SELECT id FROM ancestors
+                    UNION
+                    SELECT a.id FROM activities a JOIN thread ON a.parent_id = thread.id
+                )
+             SELECT {ACTIVITY_COLUMNS} FROM activities
+             WHERE id IN (SELECT id FROM thread)
+             ORDER BY timestamp, id"
+        ))?;
+        let thread: Vec<Activity> = stmt
+            .query_map([id], activity_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        if thread.is_empty() {
+            return Err(StorageError::NotFound(format!("activity {id}")));
         }
-        Ok(activities)
+        Ok(thread)
+    }
+
+    fn update_activity_status(
+        &self,
+        id: &str,
+        status: ActivityStatus,
+        duration_ms: Option<u64>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        let affected = match duration_ms {
+            Some(duration_ms) => conn.execute(
+                "UPDATE activities SET status = ?1, content_json = json_set(content_json, '$.duration', ?2) WHERE id = ?3",
+                (enum_column(&status)?, duration_ms as i64, id),
+            )?,
+            None => conn.execute(
+                "UPDATE activities SET status = ?1 WHERE id = ?2",
+                (enum_column(&status)?, id),
+            )?,
+        };
+        if affected == 0 {
+            return Err(StorageError::NotFound(format!("activity {id}")));
+        }
+        Ok(())
     }
 
     fn delete_activity(&self, id: &str) -> Result<()> {
@@ -0,0 +1,360 @@
+//! Versioned schema migrations.
+//!
+//! The schema version is kept in `PRAGMA user_version`. Each migration runs
+//! once, in order, in its own transaction together with the version bump,
+//! so an interrupted upgrade resumes where it stopped. Append new
+//! migrations to [`MIGRATIONS`]; never edit or reorder released ones.
+//!
+//! Migrations are frozen: they read the JSON and write the tables as they
+//! were at their version, with their own SQL and types, and never call the
+//! live repository code.
+
+use rusqlite::{Connection, Transaction};
+use serde::Deserialize;
+
+use crate::{Result, StorageError};
+
+struct Migration {
+    description: &'static str,
+    up: fn(&Transaction) -> Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "initial schema",
+        up: initial_schema,
+    },
+    Migration {
+        description: "full-text search index",
+        up: search_index,
+    },
+    Migration {
+        description: "segments and words in their own tables",
+        up: normalized_segments,
+    },
+    Migration {
+        description: "secrets of encrypted databases",
+        up: secrets,
+    },
+    Migration {
+        description: "activity feed paging index",
+        up: activity_paging,
+    },
+    Migration {
+        description: "secure delete in the search index",
+        up: search_secure_delete,
+    },
+    Migration {
+        description: "search index rows by session",
+        up: search_rows,
+    },
+];
+
+/// Schema version of a fully migrated database.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+pub(crate) fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Bring the database up to [`SCHEMA_VERSION`].
+pub(crate) fn migrate(conn: &mut Connection) -> Result<()> {
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        return Err(StorageError::SchemaTooNew {
+            found: current,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = version as u32 + 1;
+        tracing::info!(
+            "Migrating database to version {}: {}",
+            version,
+            migration.description
+        );
+        let tx = conn.transaction()?;
+        (migration.up)(&tx)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Tables as they were before versioning. Databases from that time are at
+/// version 0 but already have them, hence `IF NOT EXISTS`.
+fn initial_schema(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            title TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            duration_ms INTEGER,
+            audio_path TEXT,
+            transcript_json TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS activities (
+            id TEXT PRIMARY KEY,
+            activity_type TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            parent_id TEXT,
+            content_json TEXT NOT NULL,
+            FOREIGN KEY (parent_id) REFERENCES activities(id)
+        );
+
+        CREATE TABLE IF NOT EXISTS markers (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            occurred_at INTEGER NOT NULL,
+            done INTEGER NOT NULL DEFAULT 0,
+            marker_json TEXT NOT NULL,
+            FOREIGN KEY (session_id) REFERENCES sessions(id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_sessions_created_at ON sessions(created_at);
+        CREATE INDEX IF NOT EXISTS idx_markers_session ON markers(session_id);
+        CREATE INDEX IF NOT EXISTS idx_markers_kind_occurred_at ON markers(kind, occurred_at);
+        CREATE INDEX IF NOT EXISTS idx_activities_timestamp ON activities(timestamp DESC);
+        CREATE INDEX IF NOT EXISTS idx_activities_parent ON activities(parent_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// FTS5 index over titles and segment text, filled from the saved sessions.
+fn search_index(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        -- One row per segment plus one for the title (segment_id NULL).
+        CREATE VIRTUAL TABLE IF NOT EXISTS transcript_fts USING fts5(
+            text,
+            session_id UNINDEXED,
+            segment_id UNINDEXED,
+            start_ms UNINDEXED,
+            speaker UNINDEXED,
+            mode UNINDEXED,
+            tokenize = 'porter unicode61 remove_diacritics 2'
+        );
+        DELETE FROM transcript_fts;
+        "#,
+    )?;
+    // Segments are still inline in the JSON at this version.
+    let mut insert = tx.prepare(
+        "INSERT INTO transcript_fts (text, session_id, segment_id, start_ms, speaker, mode) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (session_id, transcript) in legacy_transcripts(tx)? {
+        if let Some(title) = &transcript.title {
+            insert.execute((
+                title,
+                &session_id,
+                None::<String>,
+                None::<i64>,
+                None::<i32>,
+                &transcript.mode,
+            ))?;
+        }
+        for segment in &transcript.segments {
+            insert.execute((
+                &segment.text,
+                &session_id,
+                &segment.id,
+                segment.start_ms as i64,
+                segment.speaker,
+                &transcript.mode,
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Move segments and their words out of `sessions.transcript_json`.
+fn normalized_segments(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE segments (
+            session_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            id TEXT NOT NULL,
+            text TEXT NOT NULL,
+            raw_text TEXT,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            speaker INTEGER,
+            is_final INTEGER NOT NULL,
+            PRIMARY KEY (session_id, position),
+            FOREIGN KEY (session_id) REFERENCES sessions(id)
+        ) WITHOUT ROWID;
+
+        CREATE TABLE words (
+            session_id TEXT NOT NULL,
+            segment_position INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            confidence REAL NOT NULL,
+            PRIMARY KEY (session_id, segment_position, position),
+            FOREIGN KEY (session_id, segment_position) REFERENCES segments(session_id, position)
+        ) WITHOUT ROWID;
+        "#,
+    )?;
+    let mut insert_segment = tx.prepare(
+        "INSERT INTO segments (session_id, position, id, text, raw_text, start_ms, end_ms, speaker, is_final) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    let mut insert_word = tx.prepare(
+        "INSERT INTO words (session_id, segment_position, position, text, start_ms, end_ms, confidence) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    let mut update_json = tx.prepare("UPDATE sessions SET transcript_json = ?1 WHERE id = ?2")?;
+    for (session_id, transcript) in legacy_transcripts(tx)? {
+        for (position, segment) in transcript.segments.iter().enumerate() {
+            insert_segment.execute((
+                &session_id,
+                position as i64,
+                &segment.id,
+                &segment.text,
+                &segment.raw_text,
+                segment.start_ms as i64,
+                segment.end_ms as i64,
+                segment.speaker,
+                segment.is_final,
+            ))?;
+            for (word_position, word) in segment.words.iter().enumerate() {
+                insert_word.execute((
+                    &session_id,
+                    position as i64,
+                    word_position as i64,
+                    &word.text,
+                    word.start_ms as i64,
+                    word.end_ms as i64,
+                    word.confidence,
+                ))?;
+            }
+        }
+        let mut json = transcript.json;
+        json["segments"] = serde_json::Value::Array(Vec::new());
+        update_json.execute((serde_json::to_string(&json)?, &session_id))?;
+    }
+    Ok(())
+}
+
+/// Keys that may only be stored encrypted, such as the recordings file key.
+fn secrets(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE secrets (
+            name TEXT PRIMARY KEY,
+            value BLOB NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Activities are paged by (timestamp, id), newest first.
+fn activity_paging(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        DROP INDEX IF EXISTS idx_activities_timestamp;
+        CREATE INDEX idx_activities_timestamp_id ON activities(timestamp DESC, id DESC);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Deleted text is removed from the FTS5 index instead of lingering in its
+/// segments until a merge; `PRAGMA secure_delete` does not reach there. The
+/// rebuild drops what earlier deletes left behind.
+fn search_secure_delete(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        INSERT INTO transcript_fts (transcript_fts, rank) VALUES ('secure-delete', 1);
+        INSERT INTO transcript_fts (transcript_fts) VALUES ('rebuild');
+        "#,
+    )?;
+    Ok(())
+}
+
+/// The search index rows of each session, so saves and deletes find them
+/// by rowid instead of scanning the UNINDEXED `session_id` column.
+fn search_rows(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+        CREATE TABLE search_rows (
+            fts_rowid INTEGER PRIMARY KEY,
+            session_id TEXT NOT NULL
+        );
+        CREATE INDEX idx_search_rows_session ON search_rows(session_id);
+        INSERT INTO search_rows (fts_rowid, session_id) SELECT rowid, session_id FROM transcript_fts;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// A session as stored in `sessions.transcript_json` before segments
+/// moved to their own tables.
+#[derive(Deserialize)]
+struct LegacyTranscript {
+    title: Option<String>,
+    #[serde(default)]
+    mode: Option<String>,
+    segments: Vec<LegacySegment>,
+    /// The row's JSON, kept whole so other fields survive a rewrite.
+    #[serde(skip)]
+    json: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct LegacySegment {
+    id: String,
+    text: String,
+    #[serde(default)]
+    raw_text: Option<String>,
+    start_ms: u64,
+    end_ms: u64,
+    words: Vec<LegacyWord>,
+    speaker: Option<i32>,
+    is_final: bool,
+}
+
+#[derive(Deserialize)]
+struct LegacyWord {
+    text: String,
+    start_ms: u64,
+    end_ms: u64,
+    confidence: f32,
+}
+
+/// Sessions whose JSON still holds the whole transcript, by session id.
+/// Unreadable rows are skipped, as `list` skips them.
+fn legacy_transcripts(tx: &Transaction) -> Result<Vec<(String, LegacyTranscript)>> {
+    let mut stmt = tx.prepare("SELECT id, transcript_json FROM sessions")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+    let mut transcripts = Vec::new();
+    for row in rows {
+        let (session_id, Some(json)) = row? else {
+            continue;
+        };
+        let parsed = serde_json::from_str::<serde_json::Value>(&json).and_then(|json| {
+            let mut transcript = LegacyTranscript::deserialize(&json)?;
+            transcript.json = json;
+            Ok(transcript)
+        });
+        match parsed {
+            Ok(transcript) => transcripts.push((session_id, transcript)),
+            Err(e) => tracing::warn!("Skipping unreadable session during migration: {}", e),
+        }
+    }
+    Ok(transcripts)
+}
@@ -0,0 +1,311 @@
+//! Retention rules and the purge that applies them.
+//!
+//! The rules are the `retention` [`Setting`]. [`Database::plan_retention`]
+//! reports what they would delete without touching anything (a dry run);
+//! [`Database::apply_retention`] deletes it, removes the recordings of the
+//! deleted sessions and vacuums the database. Every limit of 0 means "no
+//! limit", so the default rules keep everything.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::{delete_session, Database, Result, Setting};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// What to keep, and for how long.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// Delete sessions older than this many days.
+    pub session_max_age_days: u32,
+    /// Keep only this many of the newest sessions.
+    pub keep_last_sessions: u32,
+    /// Delete recordings older than this many days. Their sessions stay.
+    pub audio_max_age_days: u32,
+    /// Keep no recordings at all.
+    pub discard_audio: bool,
+    /// Delete activities older than this many days.
+    pub activity_max_age_days: u32,
+    /// Rules for sessions recorded in a context mode ("Meeting", "Dev",
+    /// ...), replacing the ones above where set.
+    pub modes: BTreeMap<String, ModeRetention>,
+}
+
+/// Per-mode overrides. A mode with its own `keep_last_sessions` counts
+/// only its own sessions; the others share the global count.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModeRetention {
+    pub session_max_age_days: Option<u32>,
+    pub keep_last_sessions: Option<u32>,
+    pub audio_max_age_days: Option<u32>,
+    pub discard_audio: Option<bool>,
+}
+
+impl Setting for RetentionSettings {
+    const KEY: &'static str = "retention";
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.modes.keys().any(|mode| mode.trim().is_empty()) {
+            return Err("mode names must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The rules that apply to one session.
+struct Rule {
+    session_max_age_days: u32,
+    keep_last_sessions: u32,
+    audio_max_age_days: u32,
+    discard_audio: bool,
+}
+
+impl RetentionSettings {
+    fn rule(&self, mode: Option<&str>) -> Rule {
+        let overrides = mode.and_then(|mode| self.modes.get(mode));
+        let overrides = overrides.cloned().unwrap_or_default();
+        Rule {
+            session_max_age_days: overrides
+                .session_max_age_days
+                .unwrap_or(self.session_max_age_days),
+            keep_last_sessions: overrides
+                .keep_last_sessions
+                .unwrap_or(self.keep_last_sessions),
+            audio_max_age_days: overrides
+                .audio_max_age_days
+                .unwrap_or(self.audio_max_age_days),
+            discard_audio: overrides.discard_audio.unwrap_or(self.discard_audio),
+        }
+    }
+
+    /// Recordings of sessions in `mode` are not kept at all.
+    pub fn discards_audio(&self, mode: Option<&str>) -> bool {
+        self.rule(mode).discard_audio
+    }
+
+    /// Sessions of `mode` are counted separately for `keep_last_sessions`.
+    fn counts_separately(&self, mode: &str) -> bool {
+        self.modes
+            .get(mode)
+            .is_some_and(|overrides| overrides.keep_last_sessions.is_some())
+    }
+}
+
+/// Why a session is deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeReason {
+    /// Older than `session_max_age_days`.
+    Expired,
+    /// Beyond the newest `keep_last_sessions`.
+    OverLimit,
+}
+
+/// A session the rules delete, with its recording.
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgedSession {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub created_at_ms: i64,
+    pub mode: Option<String>,
+    pub reason: PurgeReason,
+    pub audio_path: Option<PathBuf>,
+}
+
+/// A recording the rules delete from a session that is kept.
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgedAudio {
+    pub session_id: Uuid,
+    pub path: PathBuf,
+}
+
+/// What a retention run deletes (or, in a dry run, would delete).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub sessions: Vec<PurgedSession>,
+    pub audio: Vec<PurgedAudio>,
+    pub activities: usize,
+    /// Size of the recordings on disk.
+    pub audio_bytes: u64,
+}
+
+impl RetentionReport {
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty() && self.audio.is_empty() && self.activities == 0
+    }
+
+    fn audio_paths(&self) -> impl Iterator<Item = &Path> {
+        self.sessions
+            .iter()
+            .filter_map(|session| session.audio_path.as_deref())
+            .chain(self.audio.iter().map(|audio| audio.path.as_path()))
+    }
+}
+
+impl Database {
+    /// What `rules` would delete at `now_ms` (Unix ms). Nothing is changed.
+    pub fn plan_retention(
+        &self,
+        rules: &RetentionSettings,
+        now_ms: i64,
+    ) -> Result<RetentionReport> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        plan(&conn, rules, now_ms)
+    }
+
+    /// Delete what `rules` select at `now_ms` (Unix ms): sessions with
+    /// their recordings, recordings of kept sessions, and activities (with
+    /// their replies). The database is vacuumed afterwards.
+    pub fn apply_retention(
+        &self,
+        rules: &RetentionSettings,
+        now_ms: i64,
+    ) -> Result<RetentionReport> {
+        let mut conn = self.conn.lock().expect("database mutex poisoned");
+        let mut report = plan(&conn, rules, now_ms)?;
+        report.dry_run = false;
+        if report.is_empty() {
+            return Ok(report);
+        }
+
+        let tx = conn.transaction()?;
+        for session in &report.sessions {
+            delete_session(&tx, &session.id.to_string())?;
+        }
+        for audio in &report.audio {
+            tx.execute(
+                "UPDATE sessions SET audio_path = NULL WHERE id = ?1",
+                [audio.session_id.to_string()],
+            )?;
+        }
+        if let Some(cutoff) = cutoff(now_ms, rules.activity_max_age_days) {
+            tx.execute(
+                &format!("{EXPIRED_ACTIVITIES} DELETE FROM activities WHERE id IN (SELECT id FROM expired)"),
+                [cutoff],
+            )?;
+        }
+        tx.commit()?;
+        conn.execute_batch("VACUUM")?;
+        drop(conn);
+
+        for path in report.audio_paths() {
+            remove_audio(path);
+        }
+        tracing::info!(
+            "Retention deleted {} sessions, {} recordings and {} activities",
+            report.sessions.len(),
+            report.audio.len(),
+            report.activities
+        );
+        Ok(report)
+    }
+}
+
+/// Activities older than `?1` and their replies at any depth, as the
+/// `expired` table. `UNION` stops at a cycle in `parent_id`.
+const EXPIRED_ACTIVITIES: &str = "WITH RECURSIVE expired(id) AS (
+    SELECT id FROM activities WHERE timestamp < ?1
+    UNION SELECT activities.id FROM activities JOIN expired ON activities.parent_id = expired.id
+)";
+
+/// Unix ms before which something `max_age_days` old expires.
+fn cutoff(now_ms: i64, max_age_days: u32) -> Option<i64> {
+    (max_age_days > 0).then(|| now_ms - i64::from(max_age_days) * DAY_MS)
+}
+
+fn plan(conn: &Connection, rules: &RetentionSettings, now_ms: i64) -> Result<RetentionReport> {
+    let mut report = RetentionReport {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT id, title, created_at, json_extract(transcript_json, '$.mode'), audio_path FROM sessions ORDER BY created_at DESC, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?;
+
+    // Sessions seen so far per `keep_last_sessions` group (None: global).
+    let mut ranks: HashMap<Option<String>, u32> = HashMap::new();
+    for row in rows {
+        let (id, title, created_at, mode, audio_path) = row?;
+        let Ok(id) = Uuid::parse_str(&id) else {
+            continue;
+        };
+        let created_at_ms = created_at * 1000;
+        let audio_path = audio_path.map(PathBuf::from);
+        let rule = rules.rule(mode.as_deref());
+
+        let group = mode.clone().filter(|mode| rules.counts_separately(mode));
+        let rank = ranks.entry(group).or_default();
+        let newer = *rank;
+        *rank += 1;
+
+        let expired =
+            |max_age_days| cutoff(now_ms, max_age_days).is_some_and(|c| created_at_ms < c);
+        let reason = if rule.keep_last_sessions > 0 && newer >= rule.keep_last_sessions {
+            Some(PurgeReason::OverLimit)
+        } else if expired(rule.session_max_age_days) {
+            Some(PurgeReason::Expired)
+        } else {
+            None
+        };
+
+        match (reason, audio_path) {
+            (Some(reason), audio_path) => {
+                report.audio_bytes += audio_path.as_deref().map_or(0, file_size);
+                report.sessions.push(PurgedSession {
+                    id,
+                    title,
+                    created_at_ms,
+                    mode,
+                    reason,
+                    audio_path,
+                });
+            }
+            (None, Some(path)) if rule.discard_audio || expired(rule.audio_max_age_days) => {
+                report.audio_bytes += file_size(&path);
+                report.audio.push(PurgedAudio {
+                    session_id: id,
+                    path,
+                });
+            }
+            (None, _) => {}
+        }
+    }
+
+    if let Some(cutoff) = cutoff(now_ms, rules.activity_max_age_days) {
+        report.activities = conn.query_row(
+            &format!("{EXPIRED_ACTIVITIES} SELECT count(*) FROM expired"),
+            [cutoff],
+            |row| row.get::<_, i64>(0),
+        )? as usize;
+    }
+    Ok(report)
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |meta| meta.len())
+}
+
+/// Delete a recording. One that is already gone is not an error.
+pub(crate) fn remove_audio(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => tracing::debug!("Deleted recording {:?}", path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("Failed to delete recording {:?}: {}", path, e),
+    }
+}
@@ -0,0 +1,504 @@
+//! Typed application settings.
+//!
+//! Each [`Setting`] is one row of the `settings` table (JSON, keyed by
+//! [`Setting::KEY`]) and one section of the TOML config file. Values are
+//! validated before they are stored, and every change is published on the
+//! [`EventBus`](gibberish_events::EventBus) as [`SettingsChangedEvent`],
+//! whether it came from a command or from an edit to the config file.
+
+use gibberish_events::{event_names, EventBusRef, SettingsChangedEvent};
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::{Database, Result, RetentionSettings, StorageError};
+
+/// A group of settings stored under one key.
+pub trait Setting: Serialize + DeserializeOwned + Default + Clone + PartialEq {
+    /// Row in the `settings` table and section of the config file.
+    const KEY: &'static str;
+
+    /// Why the value can't be used, if it can't.
+    fn validate(&self) -> std::result::Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Transcription language: "auto" or a language code ("en", "ca", ...).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Language(pub String);
+
+impl Default for Language {
+    fn default() -> Self {
+        Self("auto".to_string())
+    }
+}
+
+impl Setting for Language {
+    const KEY: &'static str = "language";
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        let code = self.0.as_str();
+        if code == "auto"
+            || ((2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase()))
+        {
+            Ok(())
+        } else {
+            Err(format!("\"{code}\" is not \"auto\" or a language code"))
+        }
+    }
+}
+
+/// Voice activity detection timing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VadSettings {
+    /// Silence (ms) after speech before the speech segment ends.
+    pub redemption_time_ms: u32,
+    /// Shortest speech (ms) that counts as speech.
+    pub min_speech_time_ms: u32,
+}
+
+impl Default for VadSettings {
+    fn default() -> Self {
+        Self {
+            redemption_time_ms: 500,
+            min_speech_time_ms: 100,
+        }
+    }
+}
+
+impl Setting for VadSettings {
+    const KEY: &'static str = "vad";
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if !(100..=5000).contains(&self.redemption_time_ms) {
+            return Err("redemption_time_ms must be between 100 and 5000".to_string());
+        }
+        if self.min_speech_time_ms > 2000 {
+            return Err("min_speech_time_ms must be at most 2000".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Semantic end-of-turn detection.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TurnSettings {
+    pub enabled: bool,
+    /// End-of-turn probability above which a turn ends.
+    pub threshold: f32,
+}
+
+impl Default for TurnSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 0.5,
+        }
+    }
+}
+
+impl Setting for TurnSettings {
+    const KEY: &'static str = "turn";
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err("threshold must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Action router policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RouterSettings {
+    pub enabled: bool,
+    /// Run read-only tools without asking.
+    pub auto_run_read_only: bool,
+    /// Run every tool without asking (for testing only).
+    pub auto_run_all: bool,
+    pub default_lang: String,
+    /// Proposals below this confidence are dropped.
+    pub min_confidence: f32,
+    /// Proposals below this confidence ask for clarification.
+    pub clarification_threshold: f32,
+}
+
+impl Default for RouterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_run_read_only: true,
+            auto_run_all: false,
+            default_lang: "en".to_string(),
+            min_confidence: 0.35,
+            clarification_threshold: 0.5,
+        }
+    }
+}
+
+impl Setting for RouterSettings {
+    const KEY: &'static str = "router";
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err("min_confidence must be between 0 and 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.clarification_threshold) {
+            return Err("clarification_threshold must be between 0 and 1".to_string());
+        }
+        if self.default_lang.trim().is_empty() {
+            return Err("default_lang must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Every setting, laid out as the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsSnapshot {
+    pub language: Language,
+    pub vad: VadSettings,
+    pub turn: TurnSettings,
+    pub router: RouterSettings,
+    pub retention: RetentionSettings,
+}
+
+impl SettingsSnapshot {
+    fn validate(&self) -> Result<()> {
+        check(&self.language)?;
+        check(&self.vad)?;
+        check(&self.turn)?;
+        check(&self.router)?;
+        check(&self.retention)
+    }
+}
+
+fn check<S: Setting>(value: &S) -> Result<()> {
+    value
+        .validate()
+        .map_err(|reason| StorageError::InvalidSetting {
+            key: S::KEY,
+            reason,
+        })
+}
+
+/// Settings store over the `settings` table, optionally mirrored to a TOML
+/// file.
+pub struct Settings {
+    db: Arc<Database>,
+    bus: EventBusRef,
+    /// Config file kept in sync, with the contents last written or read.
+    file: Mutex<Option<(PathBuf, String)>>,
+}
+
+impl Settings {
+    pub fn new(db: Arc<Database>, bus: EventBusRef) -> Self {
+        Self {
+            db,
+            bus,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Stored value, or the default when unset or no longer valid.
+    pub fn get<S: Setting>(&self) -> S {
+        let stored = match self.db.get_setting(S::KEY) {
+            Ok(stored) => stored,
+            Err(e) => {
+                tracing::warn!("Failed to read setting {}: {}", S::KEY, e);
+                None
+            }
+        };
+        stored
+            .and_then(|json| match serde_json::from_str::<S>(&json) {
+                Ok(value) if value.validate().is_ok() => Some(value),
+                _ => {
+                    tracing::warn!("Ignoring invalid stored setting {}", S::KEY);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    /// Validate and store `value`, publish the change and update the config
+    /// file. Storing the current value again is a no-op.
+    pub fn set<S: Setting>(&self, value: S) -> Result<()> {
+        check(&value)?;
+        if self.store(&value)? {
+            self.write_file()?;
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> SettingsSnapshot {
+        SettingsSnapshot {
+            language: self.get(),
+            vad: self.get(),
+            turn: self.get(),
+            router: self.get(),
+            retention: self.get(),
+        }
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(&self.snapshot())
+            .map_err(|e| StorageError::SettingsFile(e.to_string()))
+    }
+
+    /// Apply a config file. Sections left out go back to their defaults.
+    /// Nothing is applied unless the whole file is valid. Returns the keys
+    /// that changed.
+    pub fn import_toml(&self, text: &str) -> Result<Vec<&'static str>> {
+        let snapshot: SettingsSnapshot =
+            toml::from_str(text).map_err(|e| StorageError::SettingsFile(e.to_string()))?;
+        snapshot.validate()?;
+
+        let mut changed = Vec::new();
+        if self.store(&snapshot.language)? {
+            changed.push(Language::KEY);
+        }
+        if self.store(&snapshot.vad)? {
+            changed.push(VadSettings::KEY);
+        }
+        if self.store(&snapshot.turn)? {
+            changed.push(TurnSettings::KEY);
+        }
+        if self.store(&snapshot.router)? {
+            changed.push(RouterSettings::KEY);
+        }
+        if self.store(&snapshot.retention)? {
+            changed.push(RetentionSettings::KEY);
+        }
+        Ok(changed)
+    }
+
+    /// Merge a config file from elsewhere, e.g. a backup. A section is
+    /// taken only where the local one is still at its default; where both
+    /// were changed, the local one is kept and its key returned as a
+    /// conflict. Returns the keys taken and the conflicting keys.
+    pub fn merge_toml(&self, text: &str) -> Result<(Vec<&'static str>, Vec<&'static str>)> {
+        let snapshot: SettingsSnapshot =
+            toml::from_str(text).map_err(|e| StorageError::SettingsFile(e.to_string()))?;
+        snapshot.validate()?;
+
+        let mut applied = Vec::new();
+        let mut conflicts = Vec::new();
+        self.merge(&snapshot.language, &mut applied, &mut conflicts)?;
+        self.merge(&snapshot.vad, &mut applied, &mut conflicts)?;
+        self.merge(&snapshot.turn, &mut applied, &mut conflicts)?;
+        self.merge(&snapshot.router, &mut applied, &mut conflicts)?;
+        self.merge(&snapshot.retention, &mut applied, &mut conflicts)?;
+        if !applied.is_empty() {
+            self.write_file()?;
+        }
+        Ok((applied, conflicts))
+    }
+
+    fn merge<S: Setting>(
+        &self,
+        value: &S,
+        applied: &mut Vec<&'static str>,
+        conflicts: &mut Vec<&'static str>,
+    ) -> Result<()> {
+        let local = self.get::<S>();
+        if local == *value || *value == S::default() {
+            return Ok(());
+        }
+        if local == S::default() {
+            self.store(value)?;
+            applied.push(S::KEY);
+        } else {
+            conflicts.push(S::KEY);
+        }
+        Ok(())
+    }
+
+    /// Keep `path` in sync: settings from an existing file are applied,
+    /// otherwise the file is created from the current settings.
+    pub fn sync_file(&self, path: &Path) -> Result<Vec<&'static str>> {
+        let changed = match std::fs::read_to_string(path) {
+            Ok(text) => {
+                let changed = self.import_toml(&text)?;
+                *self.file.lock().expect("settings mutex poisoned") =
+                    Some((path.to_path_buf(), text));
+                changed
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                *self.file.lock().expect("settings mutex poisoned") =
+                    Some((path.to_path_buf(), String::new()));
+                self.write_file()?;
+                Vec::new()
+            }
+            Err(e) => return Err(StorageError::SettingsFile(e.to_string())),
+        };
+        Ok(changed)
+    }
+
+    /// Re-read the synced config file if it changed since it was last read
+    /// or written. Returns the keys that changed.
+    pub fn reload_file(&self) -> Result<Vec<&'static str>> {
+        let Some((path, last)) = self.file.lock().expect("settings mutex poisoned").clone() else {
+            return Ok(Vec::new());
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            // Mid-save by an editor, or deleted: keep the current settings.
+            Err(_) => return Ok(Vec::new()),
+        };
+        if text == last {
+            return Ok(Vec::new());
+        }
+        let changed = self.import_toml(&text)?;
+        *self.file.lock().expect("settings mutex poisoned") = Some((path, text));
+        Ok(changed)
+    }
+
+    /// Publish every current value, so listeners can start from the stored
+    /// settings.
+    pub fn publish_all(&self) {
+        let snapshot = self.snapshot();
+        self.publish(&snapshot.language);
+        self.publish(&snapshot.vad);
+        self.publish(&snapshot.turn);
+        self.publish(&snapshot.router);
+        self.publish(&snapshot.retention);
+    }
+
+    /// Store a validated value. Returns whether it changed.
+    fn store<S: Setting>(&self, value: &S) -> Result<bool> {
+        if *value == self.get::<S>() {
+            return Ok(false);
+        }
+        self.db
+            .set_setting(S::KEY, &serde_json::to_string(value)?)?;
+        self.publish(value);
+        Ok(true)
+    }
+
+    fn publish<S: Setting>(&self, value: &S) {
+        match serde_json::to_value(value) {
+            Ok(value) => self.bus.emit(
+                event_names::SETTINGS_CHANGED,
+                serde_json::to_value(SettingsChangedEvent {
+                    key: S::KEY.to_string(),
+                    value,
+                })
+                .unwrap_or_default(),
+            ),
+            Err(e) => tracing::warn!("Failed to publish setting {}: {}", S::KEY, e),
+        }
+    }
+
+    fn write_file(&self) -> Result<()> {
+        let mut file = self.file.lock().expect("settings mutex poisoned");
+        let Some((path, last)) = file.as_mut() else {
+            return Ok(());
+        };
+        let text = self.to_toml()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| StorageError::SettingsFile(e.to_string()))?;
+        }
+        std::fs::write(&*path, &text).map_err(|e| StorageError::SettingsFile(e.to_string()))?;
+        *last = text;
+        Ok(())
+    }
+}
+
+impl Database {
+    pub(crate) fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        match conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+            row.get(0)
+        }) {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub(crate) fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.conn.lock().expect("database mutex poisoned");
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            (key, value),
+        )?;
+        Ok(())
+    }
+}
+
+/// Reloads the synced config file when it changes on disk.
+pub struct SettingsWatcher {
+    /// The watcher instance (kept alive).
+    _watcher: RecommendedWatcher,
+}
+
+impl SettingsWatcher {
+    /// Watch the file passed to [`Settings::sync_file`]. Invalid edits are
+    /// logged and leave the settings unchanged.
+    pub fn new(settings: Arc<Settings>) -> Result<Self> {
+        let Some((path, _)) = settings
+            .file
+            .lock()
+            .expect("settings mutex poisoned")
+            .clone()
+        else {
+            return Err(StorageError::SettingsFile(
+                "no settings file to watch".to_string(),
+            ));
+        };
+        // Editors often save by replacing the file, so watch its directory.
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = RecommendedWatcher::new(
+            move |result: std::result::Result<Event, notify::Error>| {
+                let _ = tx.send(result);
+            },
+            Config::default().with_poll_interval(Duration::from_secs(2)),
+        )
+        .map_err(|e| StorageError::SettingsFile(e.to_string()))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| StorageError::SettingsFile(e.to_string()))?;
+
+        thread::spawn(move || {
+            for result in rx {
+                match result {
+                    Ok(event)
+                        if event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == path.file_name()) =>
+                    {
+                        match settings.reload_file() {
+                            Ok(changed) if !changed.is_empty() => {
+                                tracing::info!(keys = ?changed, "Settings reloaded from file");
+                            }
+                            Ok(_) => {}
+                            Err(e) => tracing::warn!("Ignoring invalid settings file: {}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(error = %e, "Settings watcher error"),
+                }
+            }
+        });
+
+        Ok(Self { _watcher: watcher })
+    }
+}
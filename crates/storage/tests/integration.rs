@@ -88,6 +88,221 @@ mod initialization {
     }
 }
 
+// =============================================================================
+// Schema Migration Tests
+// =============================================================================
+
+mod migrations {
+    use super::*;
+    use gibberish_storage::{SearchQuery, SCHEMA_VERSION};
+    use gibberish_transcript::{Segment, Word};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_new_database_is_at_latest_version() {
+        let db = create_test_db();
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrates_unversioned_database() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("legacy.db");
+
+        // A database from before versioning: whole transcripts as JSON.
+        let mut transcript = create_test_transcript();
+        transcript.segments.push(Segment {
+            id: Uuid::new_v4(),
+            text: "Hello world".to_string(),
+            raw_text: None,
+            start_ms: 0,
+            end_ms: 1000,
+            words: vec![
+                Word {
+                    text: "Hello".to_string(),
+                    start_ms: 0,
+                    end_ms: 400,
+                    confidence: 0.9,
+                },
+                Word {
+                    text: "world".to_string(),
+                    start_ms: 500,
+                    end_ms: 1000,
+                    confidence: 0.8,
+                },
+            ],
+            is_final: true,
+            speaker: Some(1),
+        });
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sessions (id TEXT PRIMARY KEY, title TEXT, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL, duration_ms INTEGER, audio_path TEXT, transcript_json TEXT);",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO sessions (id, title, created_at, updated_at, duration_ms, transcript_json) VALUES (?1, ?2, 0, 0, 5000, ?3)",
+                (
+                    transcript.id.to_string(),
+                    &transcript.title,
+                    serde_json::to_string(&transcript).unwrap(),
+                ),
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+
+        let migrated = db.get(&transcript.id).unwrap();
+        assert_eq!(migrated.segments.len(), 1);
+        assert_eq!(migrated.segments[0].id, transcript.segments[0].id);
+        assert_eq!(migrated.segments[0].speaker, Some(1));
+        let words: Vec<&str> = migrated.segments[0]
+            .words
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect();
+        assert_eq!(words, vec!["Hello", "world"]);
+
+        let hits = db
+            .search(&SearchQuery {
+                text: "world".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        // Segments no longer live in the JSON.
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let json: String = conn
+            .query_row("SELECT transcript_json FROM sessions", [], |row| row.get(0))
+            .unwrap();
+        assert!(!json.contains("Hello world"));
+    }
+
+    #[test]
+    fn test_migrates_legacy_json_as_written() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("legacy.db");
+        let id = Uuid::new_v4();
+        let segment_id = Uuid::new_v4();
+
+        // JSON as written before versioning, not by today's `Transcript`.
+        let json = serde_json::json!({
+            "id": id,
+            "title": "Standup",
+            "segments": [{
+                "id": segment_id,
+                "text": "Ship it",
+                "start_ms": 0,
+                "end_ms": 800,
+                "words": [{ "text": "Ship", "start_ms": 0, "end_ms": 300, "confidence": 0.9 }],
+                "speaker": null,
+                "is_final": true
+            }],
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "duration_ms": 800,
+            "mode": "meeting",
+            "legacy_field": 1
+        });
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sessions (id TEXT PRIMARY KEY, title TEXT, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL, duration_ms INTEGER, audio_path TEXT, transcript_json TEXT);",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO sessions (id, title, created_at, updated_at, duration_ms, transcript_json) VALUES (?1, 'Standup', 0, 0, 800, ?2)",
+                (id.to_string(), json.to_string()),
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+        let migrated = db.get(&id).unwrap();
+        assert_eq!(migrated.segments.len(), 1);
+        assert_eq!(migrated.segments[0].id, segment_id);
+        assert_eq!(migrated.segments[0].words[0].text, "Ship");
+
+        let hits = db
+            .search(&SearchQuery {
+                text: "standup".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let stored: String = conn
+            .query_row("SELECT transcript_json FROM sessions", [], |row| row.get(0))
+            .unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&stored).unwrap();
+        assert_eq!(stored["segments"], serde_json::json!([]));
+        assert_eq!(stored["legacy_field"], 1);
+    }
+
+    #[test]
+    fn test_invalid_segment_id_is_an_error() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("legacy.db");
+        let id = Uuid::new_v4();
+        let json = serde_json::json!({
+            "id": id,
+            "title": "Standup",
+            "segments": [{
+                "id": "not-a-uuid",
+                "text": "Ship it",
+                "start_ms": 0,
+                "end_ms": 800,
+                "words": [],
+                "speaker": null,
+                "is_final": true
+            }],
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "duration_ms": 800
+        });
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sessions (id TEXT PRIMARY KEY, title TEXT, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL, duration_ms INTEGER, audio_path TEXT, transcript_json TEXT);",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO sessions (id, title, created_at, updated_at, duration_ms, transcript_json) VALUES (?1, 'Standup', 0, 0, 800, ?2)",
+                (id.to_string(), json.to_string()),
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+        let result = db.get(&id);
+        assert!(
+            matches!(result, Err(StorageError::SerializationError(_))),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("future.db");
+        drop(Database::open(&db_path).unwrap());
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+                .unwrap();
+        }
+
+        assert!(matches!(
+            Database::open(&db_path),
+            Err(StorageError::SchemaTooNew { found, .. }) if found == SCHEMA_VERSION + 1
+        ));
+    }
+}
+
 // =============================================================================
 // Transcript Repository Tests
 // =============================================================================
@@ -209,6 +424,7 @@ mod transcripts {
         transcript.segments.push(Segment {
             id: Uuid::new_v4(),
             text: "Hello world".to_string(),
+            raw_text: None,
             start_ms: 0,
             end_ms: 1000,
             words: Vec::new(),
@@ -219,6 +435,7 @@ mod transcripts {
         transcript.segments.push(Segment {
             id: Uuid::new_v4(),
             text: "How are you".to_string(),
+            raw_text: None,
             start_ms: 1000,
             end_ms: 2000,
             words: Vec::new(),
@@ -234,159 +451,1982 @@ mod transcripts {
         assert_eq!(retrieved.segments[0].text, "Hello world");
         assert_eq!(retrieved.segments[1].text, "How are you");
     }
+
+    #[test]
+    fn test_save_transcript_with_speakers() {
+        use gibberish_transcript::Segment;
+
+        let db = create_test_db();
+        let mut transcript = create_test_transcript();
+
+        for (text, speaker) in [
+            ("Budget is tight", 1),
+            ("Agreed", 0),
+            ("Let's cut travel", 1),
+        ] {
+            transcript.segments.push(Segment {
+                id: Uuid::new_v4(),
+                text: text.to_string(),
+                raw_text: None,
+                start_ms: 0,
+                end_ms: 1000,
+                words: Vec::new(),
+                is_final: true,
+                speaker: Some(speaker),
+            });
+        }
+        transcript.rename_speaker(1, "Anna");
+
+        let id = transcript.id;
+        db.save(&transcript).unwrap();
+
+        let retrieved = db.get(&id).unwrap();
+        assert_eq!(retrieved.speakers(), vec![1, 0]);
+        assert_eq!(retrieved.speaker_name(1), "Anna");
+        assert_eq!(retrieved.speaker_name(0), "Speaker 1");
+        assert_eq!(retrieved.segments[2].speaker, Some(1));
+    }
+
+    #[test]
+    fn test_save_transcript_keeps_raw_text() {
+        use gibberish_transcript::Segment;
+
+        let db = create_test_db();
+        let mut transcript = create_test_transcript();
+
+        transcript.segments.push(Segment {
+            id: Uuid::new_v4(),
+            text: "It costs $25".to_string(),
+            raw_text: Some("It costs twenty five dollars".to_string()),
+            start_ms: 0,
+            end_ms: 1000,
+            words: Vec::new(),
+            is_final: true,
+            speaker: None,
+        });
+
+        let id = transcript.id;
+        db.save(&transcript).unwrap();
+
+        let retrieved = db.get(&id).unwrap();
+        assert_eq!(retrieved.segments[0].text, "It costs $25");
+        assert_eq!(
+            retrieved.segments[0].raw_text.as_deref(),
+            Some("It costs twenty five dollars")
+        );
+    }
 }
 
 // =============================================================================
-// Activity Repository Tests
+// Marker Tests
 // =============================================================================
 
-mod activities {
+mod markers {
     use super::*;
+    use gibberish_storage::MarkerQuery;
+    use gibberish_transcript::{Marker, MarkerKind};
+
+    fn transcript_with_markers(
+        created_at_ms: i64,
+        markers: &[(MarkerKind, u64, bool)],
+    ) -> Transcript {
+        let mut transcript = create_test_transcript();
+        transcript.created_at = chrono::DateTime::from_timestamp_millis(created_at_ms).unwrap();
+        for &(kind, timestamp_ms, done) in markers {
+            transcript.add_marker(Marker {
+                done,
+                ..Marker::new(kind, timestamp_ms)
+            });
+        }
+        transcript
+    }
 
     #[test]
-    fn test_save_and_get_activity() {
+    fn test_query_open_action_items_in_range() {
         let db = create_test_db();
-        let activity = create_test_activity(ActivityType::Transcript);
-
-        db.save_activity(&activity).unwrap();
+        let last_week = transcript_with_markers(1_000_000, &[(MarkerKind::ActionItem, 0, false)]);
+        let this_week = transcript_with_markers(
+            9_000_000,
+            &[
+                (MarkerKind::ActionItem, 1000, false),
+                (MarkerKind::ActionItem, 2000, true),
+                (MarkerKind::Decision, 3000, false),
+                (MarkerKind::ActionItem, 4000, false),
+            ],
+        );
+        db.save(&last_week).unwrap();
+        db.save(&this_week).unwrap();
+
+        let found = db
+            .query_markers(&MarkerQuery {
+                kinds: vec![MarkerKind::ActionItem],
+                open_only: true,
+                since_ms: Some(5_000_000),
+                ..Default::default()
+            })
+            .unwrap();
 
-        let activities = db.get_activities(10).unwrap();
-        assert_eq!(activities.len(), 1);
-        assert_eq!(activities[0].id, activity.id);
-        assert_eq!(activities[0].activity_type, ActivityType::Transcript);
+        let times: Vec<i64> = found.iter().map(|m| m.occurred_at_ms).collect();
+        assert_eq!(times, vec![9_004_000, 9_001_000]);
+        assert!(found.iter().all(|m| m.session_id == this_week.id));
+        assert_eq!(found[0].session_title.as_deref(), Some("Test Session"));
     }
 
     #[test]
-    fn test_get_activities_empty() {
+    fn test_markers_follow_session_saves_and_deletes() {
         let db = create_test_db();
-        let activities = db.get_activities(10).unwrap();
-        assert!(activities.is_empty());
+        let mut transcript = transcript_with_markers(0, &[(MarkerKind::Question, 500, false)]);
+        db.save(&transcript).unwrap();
+
+        let id = transcript.markers[0].id;
+        transcript.marker_mut(&id).unwrap().done = true;
+        db.save(&transcript).unwrap();
+
+        let open = MarkerQuery {
+            open_only: true,
+            ..Default::default()
+        };
+        assert!(db.query_markers(&open).unwrap().is_empty());
+        let all = db.query_markers(&MarkerQuery::default()).unwrap();
+        assert_eq!(all.len(), 1);
+        assert!(all[0].marker.done);
+        assert_eq!(db.get(&transcript.id).unwrap().markers, transcript.markers);
+
+        db.delete(&transcript.id).unwrap();
+        assert!(db
+            .query_markers(&MarkerQuery::default())
+            .unwrap()
+            .is_empty());
     }
+}
 
-    #[test]
-    fn test_get_activities_with_limit() {
-        let db = create_test_db();
+// =============================================================================
+// Full-Text Search Tests
+// =============================================================================
 
-        // Save 5 activities
-        for _ in 0..5 {
-            let activity = create_test_activity(ActivityType::Transcript);
-            std::thread::sleep(std::time::Duration::from_millis(5));
-            db.save_activity(&activity).unwrap();
+mod search {
+    use super::*;
+    use gibberish_storage::SearchQuery;
+    use gibberish_transcript::Segment;
+
+    fn transcript_with_segments(
+        created_at_secs: i64,
+        mode: &str,
+        segments: &[(&str, Option<i32>)],
+    ) -> Transcript {
+        let mut transcript = create_test_transcript();
+        transcript.created_at = chrono::DateTime::from_timestamp(created_at_secs, 0).unwrap();
+        transcript.mode = Some(mode.to_string());
+        for (i, &(text, speaker)) in segments.iter().enumerate() {
+            transcript.segments.push(Segment {
+                id: Uuid::new_v4(),
+                text: text.to_string(),
+                raw_text: None,
+                start_ms: i as u64 * 1000,
+                end_ms: i as u64 * 1000 + 900,
+                words: Vec::new(),
+                is_final: true,
+                speaker,
+            });
         }
+        transcript
+    }
 
-        // Request only 3
-        let activities = db.get_activities(3).unwrap();
-        assert_eq!(activities.len(), 3);
+    fn search(db: &Database, text: &str) -> Vec<gibberish_storage::SearchHit> {
+        db.search(&SearchQuery {
+            text: text.to_string(),
+            ..Default::default()
+        })
+        .unwrap()
     }
 
     #[test]
-    fn test_get_activities_ordered_by_timestamp() {
+    fn test_phrase_prefix_and_boolean_queries() {
         let db = create_test_db();
+        let transcript = transcript_with_segments(
+            1_000,
+            "Meeting",
+            &[
+                ("We should migrate the billing service", Some(0)),
+                ("The service migration is blocked on billing", Some(1)),
+                ("Lunch is at noon", Some(0)),
+            ],
+        );
+        db.save(&transcript).unwrap();
 
-        let mut a1 = create_test_activity(ActivityType::Transcript);
-        a1.content.text = Some("First".to_string());
-        a1.timestamp = 1000;
-        db.save_activity(&a1).unwrap();
-
-        let mut a2 = create_test_activity(ActivityType::VoiceCommand);
-        a2.content.text = Some("Second".to_string());
-        a2.timestamp = 2000;
-        db.save_activity(&a2).unwrap();
+        let phrase = search(&db, "\"billing service\"");
+        assert_eq!(phrase.len(), 1);
+        assert_eq!(phrase[0].segment_id, Some(transcript.segments[0].id));
+        assert_eq!(phrase[0].start_ms, Some(0));
+        assert_eq!(
+            phrase[0].snippet,
+            "We should migrate the <mark>billing service</mark>"
+        );
 
-        let mut a3 = create_test_activity(ActivityType::ToolResult);
-        a3.content.text = Some("Third".to_string());
-        a3.timestamp = 3000;
-        db.save_activity(&a3).unwrap();
+        // Stemming and prefixes: "migrate" and "migration" both match.
+        assert_eq!(search(&db, "migrat*").len(), 2);
+        assert_eq!(search(&db, "billing NOT blocked").len(), 1);
+        assert_eq!(search(&db, "lunch OR blocked").len(), 2);
 
-        // Should be ordered newest first
-        let activities = db.get_activities(10).unwrap();
-        assert_eq!(activities.len(), 3);
-        assert_eq!(activities[0].content.text, Some("Third".to_string()));
-        assert_eq!(activities[1].content.text, Some("Second".to_string()));
-        assert_eq!(activities[2].content.text, Some("First".to_string()));
+        let title = search(&db, "test session");
+        assert_eq!(title.len(), 1);
+        assert_eq!(title[0].segment_id, None);
+        assert_eq!(title[0].duration_ms, 5000);
     }
 
     #[test]
-    fn test_save_activity_types() {
+    fn test_filters() {
         let db = create_test_db();
+        let old = transcript_with_segments(1_000, "Meeting", &[("roadmap review", Some(0))]);
+        let new = transcript_with_segments(
+            9_000,
+            "Dev",
+            &[("roadmap draft", Some(0)), ("roadmap dates", Some(1))],
+        );
+        db.save(&old).unwrap();
+        db.save(&new).unwrap();
 
-        let types = [
-            ActivityType::Transcript,
-            ActivityType::VoiceCommand,
-            ActivityType::ToolResult,
-            ActivityType::ToolError,
-        ];
+        let query = |query: SearchQuery| {
+            db.search(&SearchQuery {
+                text: "roadmap".to_string(),
+                ..query
+            })
+            .unwrap()
+        };
+        assert_eq!(query(SearchQuery::default()).len(), 3);
 
-        for activity_type in types {
-            let activity = create_test_activity(activity_type.clone());
-            db.save_activity(&activity).unwrap();
+        let recent = query(SearchQuery {
+            since_ms: Some(5_000_000),
+            ..Default::default()
+        });
+        assert!(recent.iter().all(|hit| hit.session_id == new.id));
+        assert_eq!(recent.len(), 2);
+        let bounds = |since_ms, until_ms| {
+            query(SearchQuery {
+                since_ms: Some(since_ms),
+                until_ms: Some(until_ms),
+                ..Default::default()
+            })
+            .len()
+        };
+        assert_eq!(bounds(9_000_000, 9_000_001), 2);
+        assert_eq!(bounds(8_999_999, 9_000_000), 0);
+        assert_eq!(bounds(9_000_001, 10_000_000), 0);
+
+        let meetings = query(SearchQuery {
+            mode: Some("Meeting".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].session_id, old.id);
 
-            let retrieved = db.get_activities(100).unwrap();
-            let found = retrieved.iter().find(|a| a.id == activity.id);
-            assert!(found.is_some(), "Activity should be found");
-            assert_eq!(found.unwrap().activity_type, activity_type);
-        }
+        let speaker = query(SearchQuery {
+            speaker: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(speaker.len(), 1);
+        assert_eq!(speaker[0].snippet, "<mark>roadmap</mark> dates");
     }
 
     #[test]
-    fn test_save_activity_with_parent() {
+    fn test_index_follows_saves_and_deletes() {
         let db = create_test_db();
+        let mut transcript = transcript_with_segments(0, "Global", &[("first draft", None)]);
+        db.save(&transcript).unwrap();
+        assert_eq!(search(&db, "draft").len(), 1);
 
-        let parent = create_test_activity(ActivityType::VoiceCommand);
-        let parent_id = parent.id.clone();
-        db.save_activity(&parent).unwrap();
+        transcript.segments[0].text = "final version".to_string();
+        db.save(&transcript).unwrap();
+        assert!(search(&db, "draft").is_empty());
+        assert_eq!(search(&db, "final").len(), 1);
 
-        let mut child = create_test_activity(ActivityType::ToolResult);
-        child.parent_id = Some(parent_id.clone());
-        db.save_activity(&child).unwrap();
+        assert_eq!(db.rebuild_search_index().unwrap(), 1);
+        assert_eq!(search(&db, "final").len(), 1);
 
-        let activities = db.get_activities(10).unwrap();
-        let retrieved_child = activities.iter().find(|a| a.id == child.id).unwrap();
-        assert_eq!(retrieved_child.parent_id, Some(parent_id));
+        db.delete(&transcript.id).unwrap();
+        assert!(search(&db, "final").is_empty());
     }
 
     #[test]
-    fn test_delete_activity() {
+    fn test_malformed_queries_do_not_fail() {
         let db = create_test_db();
-        let activity = create_test_activity(ActivityType::Transcript);
-        let id = activity.id.clone();
+        let transcript = transcript_with_segments(0, "Global", &[("don't panic (really)", None)]);
+        db.save(&transcript).unwrap();
 
-        db.save_activity(&activity).unwrap();
-        assert_eq!(db.get_activities(10).unwrap().len(), 1);
+        for text in [
+            "\"don't",
+            "OR panic AND",
+            "(panic",
+            "really)",
+            "NOT",
+            "()",
+            "*",
+            "  ",
+        ] {
+            db.search(&SearchQuery {
+                text: text.to_string(),
+                ..Default::default()
+            })
+            .unwrap_or_else(|e| panic!("query {text:?} failed: {e}"));
+        }
+        assert_eq!(search(&db, "\"don't").len(), 1);
+        assert_eq!(search(&db, "(panic").len(), 1);
+        assert!(search(&db, "()").is_empty());
+    }
+}
 
-        db.delete_activity(&id).unwrap();
-        assert!(db.get_activities(10).unwrap().is_empty());
+// =============================================================================
+// Settings Tests
+// =============================================================================
+
+mod settings {
+    use super::*;
+    use gibberish_events::{event_names, InMemoryEventBus};
+    use gibberish_storage::{Language, RouterSettings, Settings, TurnSettings, VadSettings};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn create_settings() -> (Settings, Arc<InMemoryEventBus>) {
+        let bus = Arc::new(InMemoryEventBus::new());
+        let settings = Settings::new(Arc::new(create_test_db()), bus.clone());
+        (settings, bus)
     }
 
     #[test]
-    fn test_delete_nonexistent_activity() {
-        let db = create_test_db();
+    fn test_defaults_and_persistence() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("settings.db");
+        let bus = Arc::new(InMemoryEventBus::new());
+        {
+            let settings = Settings::new(Arc::new(Database::open(&db_path).unwrap()), bus.clone());
+            assert_eq!(settings.get::<TurnSettings>(), TurnSettings::default());
+            settings
+                .set(TurnSettings {
+                    enabled: false,
+                    threshold: 0.7,
+                })
+                .unwrap();
+        }
 
-        let result = db.delete_activity("nonexistent-id");
-        assert!(matches!(result, Err(StorageError::NotFound(_))));
+        let settings = Settings::new(Arc::new(Database::open(&db_path).unwrap()), bus.clone());
+        assert_eq!(
+            settings.get::<TurnSettings>(),
+            TurnSettings {
+                enabled: false,
+                threshold: 0.7,
+            }
+        );
+        assert_eq!(settings.get::<Language>(), Language::default());
     }
 
     #[test]
-    fn test_clear_activities() {
-        let db = create_test_db();
-
-        // Save multiple activities
-        for _ in 0..5 {
-            let activity = create_test_activity(ActivityType::Transcript);
-            db.save_activity(&activity).unwrap();
-        }
+    fn test_changes_are_validated_and_published() {
+        let (settings, bus) = create_settings();
 
-        assert_eq!(db.get_activities(10).unwrap().len(), 5);
+        let invalid = settings.set(VadSettings {
+            redemption_time_ms: 10,
+            ..Default::default()
+        });
+        assert!(matches!(
+            invalid,
+            Err(StorageError::InvalidSetting { key: "vad", .. })
+        ));
+        assert!(settings.set(Language("english".to_string())).is_err());
+        assert!(bus.is_empty());
+
+        settings.set(Language("ca".to_string())).unwrap();
+        settings.set(Language("ca".to_string())).unwrap();
+        let events = bus.events_for(event_names::SETTINGS_CHANGED);
+        assert_eq!(events.len(), 1, "unchanged values are not republished");
+        assert_eq!(events[0].payload["key"], "language");
+        assert_eq!(events[0].payload["value"], "ca");
+    }
 
-        db.clear_activities().unwrap();
-        assert!(db.get_activities(10).unwrap().is_empty());
+    #[test]
+    fn test_toml_import_is_all_or_nothing() {
+        let (settings, bus) = create_settings();
+
+        let changed = settings
+            .import_toml(
+                "language = \"es\"\n\n[router]\nmin_confidence = 0.4\nclarification_threshold = 0.6\n",
+            )
+            .unwrap();
+        assert_eq!(changed, vec!["language", "router"]);
+        assert_eq!(bus.len(), 2);
+        assert_eq!(settings.get::<RouterSettings>().min_confidence, 0.4);
+        assert!(settings.get::<RouterSettings>().auto_run_read_only);
+
+        // A typo or an invalid value rejects the whole file.
+        assert!(settings
+            .import_toml("language = \"en\"\n[vad]\nredemtion_time_ms = 300\n")
+            .is_err());
+        assert!(settings
+            .import_toml("language = \"en\"\n[turn]\nthreshold = 2.0\n")
+            .is_err());
+        assert_eq!(settings.get::<Language>(), Language("es".to_string()));
+
+        let exported = settings.to_toml().unwrap();
+        let (other, _) = create_settings();
+        other.import_toml(&exported).unwrap();
+        assert_eq!(other.snapshot(), settings.snapshot());
     }
 
     #[test]
-    fn test_clear_activities_empty_db() {
-        let db = create_test_db();
-        // Should not error on empty database
-        db.clear_activities().unwrap();
+    fn test_file_sync_and_reload() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config").join("settings.toml");
+        let (settings, bus) = create_settings();
+
+        // A missing file is created from the current settings.
+        assert!(settings.sync_file(&path).unwrap().is_empty());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("redemption_time_ms = 500"));
+
+        settings
+            .set(VadSettings {
+                redemption_time_ms: 800,
+                ..Default::default()
+            })
+            .unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("redemption_time_ms = 800"));
+        assert!(settings.reload_file().unwrap().is_empty());
+
+        bus.clear();
+        std::fs::write(&path, text.replace("enabled = true", "enabled = false")).unwrap();
+        assert_eq!(settings.reload_file().unwrap(), vec!["turn", "router"]);
+        assert!(!settings.get::<TurnSettings>().enabled);
+        assert_eq!(bus.len(), 2);
+
+        std::fs::write(&path, "not toml [").unwrap();
+        assert!(settings.reload_file().is_err());
+        assert!(!settings.get::<RouterSettings>().enabled);
+    }
+}
+
+// =============================================================================
+// Encryption Tests
+// =============================================================================
+
+mod encryption {
+    use super::*;
+    use gibberish_storage::{DatabaseKey, FileCipher, SearchQuery, SCHEMA_VERSION};
+    use gibberish_transcript::Segment;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    const SECRET: &str = "quarterly layoffs";
+
+    fn save_secret_session(db: &Database) -> Uuid {
+        let mut transcript = create_test_transcript();
+        transcript.segments.push(Segment {
+            id: Uuid::new_v4(),
+            text: format!("We discussed the {SECRET}"),
+            raw_text: None,
+            start_ms: 0,
+            end_ms: 2000,
+            words: Vec::new(),
+            speaker: None,
+            is_final: true,
+        });
+        db.save(&transcript).unwrap();
+        transcript.id
+    }
+
+    fn file_contains(path: &Path, needle: &str) -> bool {
+        let data = std::fs::read(path).unwrap();
+        data.windows(needle.len()).any(|w| w == needle.as_bytes())
+    }
+
+    fn passphrase(p: &str) -> DatabaseKey {
+        DatabaseKey::Passphrase(p.to_string())
+    }
+
+    #[test]
+    fn test_encrypted_database_needs_its_key() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let id = {
+            let db = Database::open_encrypted(&db_path, &passphrase("hunter2")).unwrap();
+            assert!(db.is_encrypted());
+            save_secret_session(&db)
+        };
+        assert!(!file_contains(&db_path, SECRET));
+
+        assert!(matches!(
+            Database::open(&db_path),
+            Err(StorageError::Locked)
+        ));
+        assert!(matches!(
+            Database::open_encrypted(&db_path, &passphrase("hunter3")),
+            Err(StorageError::KeyRejected)
+        ));
+        assert!(Database::open_encrypted(&db_path, &passphrase("")).is_err());
+
+        let db = Database::open_encrypted(&db_path, &passphrase("hunter2")).unwrap();
+        assert!(db.get(&id).is_ok());
+        let hits = db
+            .search(&SearchQuery {
+                text: "layoffs".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[test]
+    fn test_encrypt_rekey_and_decrypt_in_place() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+        let id = save_secret_session(&db);
+        assert!(file_contains(&db_path, SECRET));
+
+        let key = DatabaseKey::generate();
+        db.encrypt(&key).unwrap();
+        assert!(db.is_encrypted());
+        assert!(!file_contains(&db_path, SECRET));
+        let sealed = db
+            .file_cipher()
+            .unwrap()
+            .unwrap()
+            .seal(b"recording")
+            .unwrap();
+        assert!(db.get(&id).is_ok(), "the open handle keeps working");
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+        assert!(db.encrypt(&key).is_err());
+
+        let stored = DatabaseKey::from_hex(&key.to_hex().unwrap()).unwrap();
+        let reopened = Database::open_encrypted(&db_path, &stored).unwrap();
+        assert_eq!(reopened.schema_version().unwrap(), SCHEMA_VERSION);
+        assert!(reopened.get(&id).is_ok());
+        drop(reopened);
+
+        db.rekey(&passphrase("rotated")).unwrap();
+        assert!(matches!(
+            Database::open_encrypted(&db_path, &key),
+            Err(StorageError::KeyRejected)
+        ));
+        assert!(Database::open_encrypted(&db_path, &passphrase("rotated")).is_ok());
+
+        assert!(matches!(db.check_key(&key), Err(StorageError::KeyRejected)));
+        db.check_key(&passphrase("rotated")).unwrap();
+        assert!(matches!(db.decrypt(&key), Err(StorageError::KeyRejected)));
+        let cipher = db.file_cipher().unwrap().unwrap();
+        assert_eq!(
+            cipher.unseal(&sealed).unwrap(),
+            b"recording",
+            "a failed decrypt keeps the file key"
+        );
+        db.decrypt(&passphrase("rotated")).unwrap();
+        assert!(!db.is_encrypted());
+        let plain = Database::open(&db_path).unwrap();
+        assert!(plain.get(&id).is_ok());
+        assert!(plain.file_cipher().unwrap().is_none());
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let secrets: i64 = conn
+            .query_row("SELECT COUNT(*) FROM secrets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(secrets, 0);
+    }
+
+    #[test]
+    fn test_recordings_are_sealed_with_the_stored_file_key() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let wav = dir.path().join("recording.wav");
+        let audio = b"RIFF....WAVEfmt audio samples".to_vec();
+        std::fs::write(&wav, &audio).unwrap();
+
+        assert!(create_test_db().file_cipher().unwrap().is_none());
+
+        let key = passphrase("hunter2");
+        {
+            let db = Database::open_encrypted(&db_path, &key).unwrap();
+            let cipher = db.file_cipher().unwrap().unwrap();
+            assert!(!FileCipher::is_sealed_file(&wav).unwrap());
+            assert!(cipher.seal_file(&wav).unwrap());
+            assert!(FileCipher::is_sealed_file(&wav).unwrap());
+            assert!(!cipher.seal_file(&wav).unwrap(), "sealing twice is a no-op");
+        }
+        let sealed = std::fs::read(&wav).unwrap();
+        assert!(FileCipher::is_sealed(&sealed));
+        assert!(!file_contains(&wav, "audio samples"));
+
+        // The file key survives reopening and changing the database key.
+        let db = Database::open_encrypted(&db_path, &key).unwrap();
+        db.rekey(&passphrase("rotated")).unwrap();
+        let cipher = db.file_cipher().unwrap().unwrap();
+        assert_eq!(cipher.read_file(&wav).unwrap(), audio);
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(cipher.unseal(&tampered).is_err());
+
+        assert!(cipher.unseal_file(&wav).unwrap());
+        assert_eq!(std::fs::read(&wav).unwrap(), audio);
+        assert_eq!(cipher.read_file(&wav).unwrap(), audio);
+    }
+
+    #[test]
+    fn test_key_hex_round_trip() {
+        let key = DatabaseKey::generate();
+        let hex = key.to_hex().unwrap();
+        assert_eq!(hex.len(), 64);
+        assert_eq!(DatabaseKey::from_hex(&hex).unwrap().to_hex(), Some(hex));
+        assert!(DatabaseKey::from_hex("not hex").is_err());
+        assert!(passphrase("secret").to_hex().is_none());
+        assert_eq!(
+            format!("{:?}", passphrase("secret")),
+            "DatabaseKey::Passphrase(..)"
+        );
+    }
+}
+
+// =============================================================================
+// Retention Tests
+// =============================================================================
+
+mod retention {
+    use super::*;
+    use gibberish_events::InMemoryEventBus;
+    use gibberish_storage::{ModeRetention, PurgeReason, RetentionSettings, Settings};
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    const NOW_MS: i64 = 1_700_000_000_000;
+    const DAY_SECS: i64 = 24 * 60 * 60;
+
+    /// Save a session `days_old` days before `NOW_MS`, with a recording in
+    /// `dir` when given.
+    fn save_session(db: &Database, days_old: i64, mode: &str, dir: Option<&Path>) -> Uuid {
+        let mut transcript = create_test_transcript();
+        transcript.created_at =
+            chrono::DateTime::from_timestamp(NOW_MS / 1000 - days_old * DAY_SECS, 0).unwrap();
+        transcript.mode = Some(mode.to_string());
+        db.save(&transcript).unwrap();
+        if let Some(dir) = dir {
+            let path = audio_path(dir, &transcript.id);
+            std::fs::write(&path, vec![0u8; 100]).unwrap();
+            db.set_session_audio(&transcript.id, Some(&path)).unwrap();
+        }
+        transcript.id
+    }
+
+    fn audio_path(dir: &Path, id: &Uuid) -> PathBuf {
+        dir.join(format!("{id}.wav"))
+    }
+
+    fn save_activity(db: &Database, days_old: i64, parent_id: Option<String>) -> String {
+        let mut activity = create_test_activity(ActivityType::VoiceCommand);
+        activity.timestamp = NOW_MS - days_old * DAY_SECS * 1000;
+        activity.parent_id = parent_id;
+        db.save_activity(&activity).unwrap();
+        activity.id
+    }
+
+    #[test]
+    fn test_dry_run_then_apply() {
+        let dir = tempdir().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).unwrap();
+        let fresh = save_session(&db, 1, "Dev", Some(dir.path()));
+        let week_old = save_session(&db, 10, "Dev", Some(dir.path()));
+        let expired = save_session(&db, 40, "Dev", Some(dir.path()));
+        let old_command = save_activity(&db, 45, None);
+        let reply = save_activity(&db, 1, Some(old_command));
+        save_activity(&db, 1, Some(reply));
+        save_activity(&db, 1, None);
+
+        let rules = RetentionSettings {
+            session_max_age_days: 30,
+            audio_max_age_days: 7,
+            activity_max_age_days: 30,
+            ..Default::default()
+        };
+        let plan = db.plan_retention(&rules, NOW_MS).unwrap();
+        assert!(plan.dry_run);
+        assert_eq!(plan.sessions.len(), 1);
+        assert_eq!(plan.sessions[0].id, expired);
+        assert_eq!(plan.sessions[0].reason, PurgeReason::Expired);
+        assert_eq!(plan.audio.len(), 1);
+        assert_eq!(plan.audio[0].session_id, week_old);
+        assert_eq!(
+            plan.activities, 3,
+            "an expired command takes its replies along, at any depth"
+        );
+        assert_eq!(plan.audio_bytes, 200);
+        assert_eq!(db.list().unwrap().len(), 3, "a dry run changes nothing");
+        assert!(audio_path(dir.path(), &expired).exists());
+
+        let report = db.apply_retention(&rules, NOW_MS).unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.sessions.len(), 1);
+        assert!(matches!(db.get(&expired), Err(StorageError::NotFound(_))));
+        assert!(!audio_path(dir.path(), &expired).exists());
+        assert!(db.get(&week_old).is_ok());
+        assert!(!audio_path(dir.path(), &week_old).exists());
+        assert_eq!(db.session_audio(&week_old).unwrap(), None);
+        assert!(audio_path(dir.path(), &fresh).exists());
+        assert_eq!(db.get_activities(10).unwrap().len(), 1);
+
+        assert!(db.plan_retention(&rules, NOW_MS).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_purged_text_leaves_the_database_file() {
+        let dir = tempdir().unwrap();
+        let rules = RetentionSettings {
+            session_max_age_days: 30,
+            ..Default::default()
+        };
+        // Few enough writes that FTS5 does not merge the deletion away.
+        for (purge, word) in [("retention", "zqxjvbkw"), ("delete", "zqxjvbqm")] {
+            let db_path = dir.path().join(format!("{purge}.db"));
+            let db = Database::open(&db_path).unwrap();
+            save_session(&db, 1, "Dev", None);
+            let mut transcript = create_test_transcript();
+            transcript.created_at =
+                chrono::DateTime::from_timestamp(NOW_MS / 1000 - 40 * DAY_SECS, 0).unwrap();
+            transcript.title = Some(format!("{word} plans"));
+            db.save(&transcript).unwrap();
+
+            if purge == "retention" {
+                assert_eq!(
+                    db.apply_retention(&rules, NOW_MS).unwrap().sessions.len(),
+                    1
+                );
+            } else {
+                db.delete(&transcript.id).unwrap();
+            }
+            let query = gibberish_storage::SearchQuery {
+                text: "plans".to_string(),
+                ..Default::default()
+            };
+            assert!(db.search(&query).unwrap().is_empty());
+            drop(db);
+
+            let data = std::fs::read(&db_path).unwrap();
+            assert!(
+                !data.windows(word.len()).any(|w| w == word.as_bytes()),
+                "{purge}: {word} is still in the file"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mode_overrides() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let old_meeting = save_session(&db, 400, "Meeting", Some(dir.path()));
+        let new_dev = save_session(&db, 1, "Dev", None);
+        let old_dev = save_session(&db, 2, "Dev", None);
+        let writer = save_session(&db, 3, "Writer", None);
+        let older_writer = save_session(&db, 5, "Writer", None);
+
+        let rules = RetentionSettings {
+            session_max_age_days: 90,
+            keep_last_sessions: 3,
+            modes: [
+                (
+                    "Meeting".to_string(),
+                    ModeRetention {
+                        session_max_age_days: Some(0),
+                        discard_audio: Some(true),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "Dev".to_string(),
+                    ModeRetention {
+                        keep_last_sessions: Some(1),
+                        ..Default::default()
+                    },
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let plan = db.plan_retention(&rules, NOW_MS).unwrap();
+        let purged: Vec<(Uuid, PurgeReason)> =
+            plan.sessions.iter().map(|s| (s.id, s.reason)).collect();
+        // Dev keeps one of its own; Writer and Meeting share the global three.
+        assert_eq!(purged, vec![(old_dev, PurgeReason::OverLimit)]);
+        assert_eq!(plan.audio.len(), 1);
+        assert_eq!(plan.audio[0].session_id, old_meeting);
+        assert!(rules.discards_audio(Some("Meeting")));
+        assert!(!rules.discards_audio(Some("Dev")));
+        assert!(!rules.discards_audio(None));
+
+        db.apply_retention(&rules, NOW_MS).unwrap();
+        for id in [old_meeting, new_dev, writer, older_writer] {
+            assert!(db.get(&id).is_ok());
+        }
+        assert!(!audio_path(dir.path(), &old_meeting).exists());
+    }
+
+    #[test]
+    fn test_deleting_a_session_deletes_its_recording() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let id = save_session(&db, 0, "Dev", Some(dir.path()));
+
+        // Saving the session again keeps the recording attached.
+        let mut transcript = db.get(&id).unwrap();
+        transcript.title = Some("Renamed".to_string());
+        db.save(&transcript).unwrap();
+        assert_eq!(
+            db.session_audio(&id).unwrap(),
+            Some(audio_path(dir.path(), &id))
+        );
+
+        db.delete(&id).unwrap();
+        assert!(!audio_path(dir.path(), &id).exists());
+        assert!(db
+            .set_session_audio(&id, Some(&audio_path(dir.path(), &id)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_rules_are_a_setting() {
+        let settings = Settings::new(
+            Arc::new(create_test_db()),
+            Arc::new(InMemoryEventBus::new()),
+        );
+        assert_eq!(
+            settings.get::<RetentionSettings>(),
+            RetentionSettings::default()
+        );
+
+        let changed = settings
+            .import_toml(
+                "[retention]\nkeep_last_sessions = 50\n\n[retention.modes.Meeting]\ndiscard_audio = true\n",
+            )
+            .unwrap();
+        assert_eq!(changed, vec!["retention"]);
+        let rules = settings.get::<RetentionSettings>();
+        assert_eq!(rules.keep_last_sessions, 50);
+        assert_eq!(rules.modes["Meeting"].discard_audio, Some(true));
+        assert_eq!(rules.modes["Meeting"].session_max_age_days, None);
+
+        let exported = settings.to_toml().unwrap();
+        let other = Settings::new(
+            Arc::new(create_test_db()),
+            Arc::new(InMemoryEventBus::new()),
+        );
+        other.import_toml(&exported).unwrap();
+        assert_eq!(other.get::<RetentionSettings>(), rules);
+    }
+}
+
+// =============================================================================
+// Activity Repository Tests
+// =============================================================================
+
+mod activities {
+    use super::*;
+    use gibberish_storage::ActivityQuery;
+
+    #[test]
+    fn test_save_and_get_activity() {
+        let db = create_test_db();
+        let activity = create_test_activity(ActivityType::Transcript);
+
+        db.save_activity(&activity).unwrap();
+
+        let activities = db.get_activities(10).unwrap();
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].id, activity.id);
+        assert_eq!(activities[0].activity_type, ActivityType::Transcript);
+    }
+
+    #[test]
+    fn test_get_activities_empty() {
+        let db = create_test_db();
+        let activities = db.get_activities(10).unwrap();
+        assert!(activities.is_empty());
+    }
+
+    #[test]
+    fn test_get_activities_with_limit() {
+        let db = create_test_db();
+
+        // Save 5 activities
+        for _ in 0..5 {
+            let activity = create_test_activity(ActivityType::Transcript);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            db.save_activity(&activity).unwrap();
+        }
+
+        // Request only 3
+        let activities = db.get_activities(3).unwrap();
+        assert_eq!(activities.len(), 3);
+    }
+
+    #[test]
+    fn test_get_activities_ordered_by_timestamp() {
+        let db = create_test_db();
+
+        let mut a1 = create_test_activity(ActivityType::Transcript);
+        a1.content.text = Some("First".to_string());
+        a1.timestamp = 1000;
+        db.save_activity(&a1).unwrap();
+
+        let mut a2 = create_test_activity(ActivityType::VoiceCommand);
+        a2.content.text = Some("Second".to_string());
+        a2.timestamp = 2000;
+        db.save_activity(&a2).unwrap();
+
+        let mut a3 = create_test_activity(ActivityType::ToolResult);
+        a3.content.text = Some("Third".to_string());
+        a3.timestamp = 3000;
+        db.save_activity(&a3).unwrap();
+
+        // Should be ordered newest first
+        let activities = db.get_activities(10).unwrap();
+        assert_eq!(activities.len(), 3);
+        assert_eq!(activities[0].content.text, Some("Third".to_string()));
+        assert_eq!(activities[1].content.text, Some("Second".to_string()));
+        assert_eq!(activities[2].content.text, Some("First".to_string()));
+    }
+
+    #[test]
+    fn test_save_activity_types() {
+        let db = create_test_db();
+
+        let types = [
+            ActivityType::Transcript,
+            ActivityType::VoiceCommand,
+            ActivityType::ToolResult,
+            ActivityType::ToolError,
+        ];
+
+        for activity_type in types {
+            let activity = create_test_activity(activity_type.clone());
+            db.save_activity(&activity).unwrap();
+
+            let retrieved = db.get_activities(100).unwrap();
+            let found = retrieved.iter().find(|a| a.id == activity.id);
+            assert!(found.is_some(), "Activity should be found");
+            assert_eq!(found.unwrap().activity_type, activity_type);
+        }
+    }
+
+    #[test]
+    fn test_save_activity_with_parent() {
+        let db = create_test_db();
+
+        let parent = create_test_activity(ActivityType::VoiceCommand);
+        let parent_id = parent.id.clone();
+        db.save_activity(&parent).unwrap();
+
+        let mut child = create_test_activity(ActivityType::ToolResult);
+        child.parent_id = Some(parent_id.clone());
+        db.save_activity(&child).unwrap();
+
+        let activities = db.get_activities(10).unwrap();
+        let retrieved_child = activities.iter().find(|a| a.id == child.id).unwrap();
+        assert_eq!(retrieved_child.parent_id, Some(parent_id));
+    }
+
+    #[test]
+    fn test_delete_activity() {
+        let db = create_test_db();
+        let activity = create_test_activity(ActivityType::Transcript);
+        let id = activity.id.clone();
+
+        db.save_activity(&activity).unwrap();
+        assert_eq!(db.get_activities(10).unwrap().len(), 1);
+
+        db.delete_activity(&id).unwrap();
+        assert!(db.get_activities(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_nonexistent_activity() {
+        let db = create_test_db();
+
+        let result = db.delete_activity("nonexistent-id");
+        assert!(matches!(result, Err(StorageError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_clear_activities() {
+        let db = create_test_db();
+
+        // Save multiple activities
+        for _ in 0..5 {
+            let activity = create_test_activity(ActivityType::Transcript);
+            db.save_activity(&activity).unwrap();
+        }
+
+        assert_eq!(db.get_activities(10).unwrap().len(), 5);
+
+        db.clear_activities().unwrap();
+        assert!(db.get_activities(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clear_activities_empty_db() {
+        let db = create_test_db();
+        // Should not error on empty database
+        db.clear_activities().unwrap();
+    }
+
+    fn save_at(db: &Database, activity_type: ActivityType, timestamp: i64) -> Activity {
+        let mut activity = create_test_activity(activity_type);
+        activity.timestamp = timestamp;
+        db.save_activity(&activity).unwrap();
+        activity
+    }
+
+    #[test]
+    fn test_query_pages_with_cursor() {
+        let db = create_test_db();
+        // Equal timestamps must not be skipped or repeated across pages.
+        for timestamp in [1000, 2000, 2000, 2000, 3000, 4000, 4000] {
+            save_at(&db, ActivityType::Transcript, timestamp);
+        }
+
+        let mut query = ActivityQuery {
+            limit: Some(3),
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        let mut pages = 0;
+        loop {
+            let page = db.query_activities(&query).unwrap();
+            pages += 1;
+            seen.extend(page.activities);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(seen.len(), 7);
+        let mut ids: Vec<&str> = seen.iter().map(|a| a.id.as_str()).collect();
+        ids.dedup();
+        assert_eq!(ids.len(), 7);
+        assert!(seen.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+
+        let all = db.query_activities(&ActivityQuery::default()).unwrap();
+        assert_eq!(all.activities.len(), 7);
+        assert!(all.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let db = create_test_db();
+        let mut command = create_test_activity(ActivityType::VoiceCommand);
+        command.timestamp = 1000;
+        command.status = ActivityStatus::Running;
+        command.content.tool = Some("wikipedia".to_string());
+        db.save_activity(&command).unwrap();
+        let mut change = create_test_activity(ActivityType::ContextChange);
+        change.timestamp = 2000;
+        change.content.mode = Some("Dev".to_string());
+        db.save_activity(&change).unwrap();
+        save_at(&db, ActivityType::Transcript, 3000);
+
+        let query = |query: ActivityQuery| -> Vec<String> {
+            let page = db.query_activities(&query).unwrap();
+            page.activities.into_iter().map(|a| a.id).collect()
+        };
+        assert_eq!(
+            query(ActivityQuery {
+                types: vec![ActivityType::VoiceCommand, ActivityType::ContextChange],
+                ..Default::default()
+            }),
+            vec![change.id.clone(), command.id.clone()]
+        );
+        assert_eq!(
+            query(ActivityQuery {
+                statuses: vec![ActivityStatus::Running],
+                ..Default::default()
+            }),
+            vec![command.id.clone()]
+        );
+        assert_eq!(
+            query(ActivityQuery {
+                tool: Some("wikipedia".to_string()),
+                ..Default::default()
+            }),
+            vec![command.id.clone()]
+        );
+        assert_eq!(
+            query(ActivityQuery {
+                mode: Some("Dev".to_string()),
+                ..Default::default()
+            }),
+            vec![change.id.clone()]
+        );
+        assert_eq!(
+            query(ActivityQuery {
+                since_ms: Some(1000),
+                until_ms: Some(3000),
+                ..Default::default()
+            }),
+            vec![change.id.clone(), command.id.clone()]
+        );
+    }
+
+    #[test]
+    fn test_get_thread() {
+        let db = create_test_db();
+        let command = save_at(&db, ActivityType::VoiceCommand, 1000);
+        let mut result = create_test_activity(ActivityType::ToolResult);
+        result.timestamp = 2000;
+        result.parent_id = Some(command.id.clone());
+        db.save_activity(&result).unwrap();
+        let mut followup = create_test_activity(ActivityType::VoiceCommand);
+        followup.timestamp = 3000;
+        followup.parent_id = Some(result.id.clone());
+        db.save_activity(&followup).unwrap();
+        save_at(&db, ActivityType::VoiceCommand, 1500);
+
+        let expected = vec![command.id.clone(), result.id.clone(), followup.id.clone()];
+        for id in &expected {
+            let thread: Vec<String> = db
+                .get_thread(id)
+                .unwrap()
+                .into_iter()
+                .map(|a| a.id)
+                .collect();
+            assert_eq!(thread, expected);
+        }
+        assert!(matches!(
+            db.get_thread("nonexistent-id"),
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_thread_survives_parent_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+        let mut own_parent = create_test_activity(ActivityType::VoiceCommand);
+        own_parent.timestamp = 1000;
+        own_parent.parent_id = Some(own_parent.id.clone());
+        db.save_activity(&own_parent).unwrap();
+        let thread: Vec<String> = db
+            .get_thread(&own_parent.id)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(thread, vec![own_parent.id.clone()]);
+
+        // a -> b -> a, closed behind the foreign key's back.
+        let mut a = create_test_activity(ActivityType::VoiceCommand);
+        let mut b = create_test_activity(ActivityType::ToolResult);
+        let mut reply = create_test_activity(ActivityType::VoiceCommand);
+        (a.timestamp, b.timestamp, reply.timestamp) = (2000, 3000, 4000);
+        b.parent_id = Some(a.id.clone());
+        reply.parent_id = Some(b.id.clone());
+        for activity in [&a, &b, &reply] {
+            db.save_activity(activity).unwrap();
+        }
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute(
+            "UPDATE activities SET parent_id = ?1 WHERE id = ?2",
+            [&b.id, &a.id],
+        )
+        .unwrap();
+        let expected = vec![a.id.clone(), b.id.clone(), reply.id.clone()];
+        for id in &expected {
+            let thread: Vec<String> = db
+                .get_thread(id)
+                .unwrap()
+                .into_iter()
+                .map(|a| a.id)
+                .collect();
+            assert_eq!(thread, expected);
+        }
+    }
+
+    #[test]
+    fn test_update_activity_status() {
+        let db = create_test_db();
+        let mut command = create_test_activity(ActivityType::VoiceCommand);
+        command.status = ActivityStatus::Running;
+        command.content.tool = Some("wikipedia".to_string());
+        db.save_activity(&command).unwrap();
+
+        db.update_activity_status(&command.id, ActivityStatus::Completed, Some(420))
+            .unwrap();
+        let stored = &db.get_activities(1).unwrap()[0];
+        assert_eq!(stored.status, ActivityStatus::Completed);
+        assert_eq!(stored.content.duration, Some(420));
+        assert_eq!(stored.content.tool.as_deref(), Some("wikipedia"));
+
+        db.update_activity_status(&command.id, ActivityStatus::Error, None)
+            .unwrap();
+        let stored = &db.get_activities(1).unwrap()[0];
+        assert_eq!(stored.status, ActivityStatus::Error);
+        assert_eq!(stored.content.duration, Some(420));
+
+        assert!(matches!(
+            db.update_activity_status("nonexistent-id", ActivityStatus::Completed, None),
+            Err(StorageError::NotFound(_))
+        ));
+    }
+}
+
+// =============================================================================
+// Session Audio Tests
+// =============================================================================
+
+mod audio {
+    use super::*;
+    use gibberish_storage::{AudioSpan, DatabaseKey, FileCipher};
+    use gibberish_transcript::{Segment, Word};
+    use std::path::{Path, PathBuf};
+    use tempfile::tempdir;
+
+    const RATE: u32 = 16_000;
+
+    /// One second each of silence, a tone and full-scale noise, repeated
+    /// to `seconds`, so every kind of FLAC subframe is written.
+    fn recording(seconds: usize) -> Vec<i16> {
+        let mut seed = 0x2545_f491_u32;
+        (0..seconds * RATE as usize)
+            .map(|n| match (n / RATE as usize) % 3 {
+                0 => 0,
+                1 => {
+                    ((n as f32 * 440.0 * std::f32::consts::TAU / RATE as f32).sin() * 8000.0) as i16
+                }
+                _ => {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    seed as i16
+                }
+            })
+            .collect()
+    }
+
+    fn write_wav(path: &Path, samples: &[i16]) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    fn ms(ms: u64) -> usize {
+        (ms * u64::from(RATE) / 1000) as usize
+    }
+
+    /// A session with one segment over 1.0-2.0 s and its words.
+    fn save_session(db: &Database) -> (Uuid, Uuid) {
+        let mut transcript = create_test_transcript();
+        let segment_id = Uuid::new_v4();
+        let word = |text: &str, start_ms, end_ms| Word {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            confidence: 0.9,
+        };
+        transcript.segments.push(Segment {
+            id: segment_id,
+            text: "hello world".to_string(),
+            raw_text: None,
+            start_ms: 1000,
+            end_ms: 2000,
+            words: vec![word("hello", 1000, 1400), word("world", 1500, 2000)],
+            speaker: None,
+            is_final: true,
+        });
+        db.save(&transcript).unwrap();
+        (transcript.id, segment_id)
+    }
+
+    fn attach(db: &Database, dir: &Path, samples: &[i16]) -> (Uuid, Uuid, PathBuf) {
+        let (id, segment_id) = save_session(db);
+        let wav = dir.join("recording.wav");
+        write_wav(&wav, samples);
+        let path = db.attach_session_audio(&id, &wav).unwrap();
+        (id, segment_id, path)
+    }
+
+    #[test]
+    fn test_attached_recording_is_compressed() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(3);
+        let (id, _, path) = attach(&db, dir.path(), &samples);
+
+        assert_eq!(path, dir.path().join("recording.flac"));
+        assert_eq!(db.session_audio(&id).unwrap(), Some(path.clone()));
+        assert!(!dir.path().join("recording.wav").exists());
+        let data = std::fs::read(&path).unwrap();
+        assert!(data.starts_with(b"fLaC"));
+        assert!(data.len() < samples.len() * 2 * 3 / 4);
+
+        let whole = AudioSpan::Range {
+            start_ms: 0,
+            end_ms: 3000,
+        };
+        let clip = db.session_audio_clip(&id, &whole, 0).unwrap();
+        assert_eq!(clip.sample_rate, RATE);
+        assert_eq!(clip.samples, samples);
+    }
+
+    #[test]
+    fn test_recording_is_standard_flac() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(3);
+        let (_, _, path) = attach(&db, dir.path(), &samples);
+
+        // An independent reader of the whole file, seek table included.
+        let mut reader = claxon::FlacReader::open(&path).unwrap();
+        let info = reader.streaminfo();
+        assert_eq!(info.sample_rate, RATE);
+        assert_eq!(info.channels, 1);
+        assert_eq!(info.bits_per_sample, 16);
+        assert_eq!(info.samples, Some(samples.len() as u64));
+        let decoded: Vec<i16> = reader
+            .samples()
+            .map(|sample| sample.unwrap() as i16)
+            .collect();
+        assert_eq!(decoded, samples);
+
+        use md5::Digest;
+        let mut md5 = md5::Md5::new();
+        for sample in &samples {
+            md5.update(sample.to_le_bytes());
+        }
+        assert_eq!(info.md5sum, <[u8; 16]>::from(md5.finalize()));
+    }
+
+    #[test]
+    fn test_flac_round_trip_of_extremes_and_partial_frames() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        // Full-scale swings and a length that ends mid-frame.
+        let samples: Vec<i16> = (0..2 * 4096 + 1001)
+            .map(|n| match n % 4 {
+                0 => i16::MIN,
+                1 => i16::MAX,
+                2 => (n as i16).wrapping_mul(7919),
+                _ => -1,
+            })
+            .collect();
+        let (id, _, _) = attach(&db, dir.path(), &samples);
+
+        let whole = AudioSpan::Range {
+            start_ms: 0,
+            end_ms: 1000,
+        };
+        let clip = db.session_audio_clip(&id, &whole, 0).unwrap();
+        assert_eq!(clip.samples, samples);
+
+        let tail = AudioSpan::Range {
+            start_ms: 500,
+            end_ms: 1000,
+        };
+        let clip = db.session_audio_clip(&id, &tail, 0).unwrap();
+        assert_eq!(clip.samples, samples[ms(500)..]);
+    }
+
+    #[test]
+    fn test_clip_of_range_segment_and_word() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(3);
+        let (id, segment_id, _) = attach(&db, dir.path(), &samples);
+
+        let range = AudioSpan::Range {
+            start_ms: 500,
+            end_ms: 1500,
+        };
+        let clip = db.session_audio_clip(&id, &range, 0).unwrap();
+        assert_eq!(clip.start_ms, 500);
+        assert_eq!(clip.duration_ms(), 1000);
+        assert_eq!(clip.samples, samples[ms(500)..ms(1500)]);
+
+        let segment = AudioSpan::Segment { segment_id };
+        let clip = db.session_audio_clip(&id, &segment, 0).unwrap();
+        assert_eq!(clip.samples, samples[ms(1000)..ms(2000)]);
+
+        let word = AudioSpan::Word {
+            segment_id,
+            word: 1,
+        };
+        let clip = db.session_audio_clip(&id, &word, 100).unwrap();
+        assert_eq!(clip.start_ms, 1400);
+        assert_eq!(clip.samples, samples[ms(1400)..ms(2100)]);
+
+        let missing = AudioSpan::Word {
+            segment_id,
+            word: 2,
+        };
+        assert!(matches!(
+            db.session_audio_clip(&id, &missing, 0),
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_clip_is_clamped_to_the_recording() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(3);
+        let (id, segment_id, _) = attach(&db, dir.path(), &samples);
+
+        let segment = AudioSpan::Segment { segment_id };
+        let clip = db.session_audio_clip(&id, &segment, 5000).unwrap();
+        assert_eq!(clip.start_ms, 0);
+        assert_eq!(clip.samples, samples);
+
+        let past_end = AudioSpan::Range {
+            start_ms: 4000,
+            end_ms: 5000,
+        };
+        assert!(matches!(
+            db.session_audio_clip(&id, &past_end, 0),
+            Err(StorageError::Audio(_))
+        ));
+    }
+
+    #[test]
+    fn test_clip_from_long_recording() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(60);
+        let (id, _, _) = attach(&db, dir.path(), &samples);
+
+        let range = AudioSpan::Range {
+            start_ms: 45_100,
+            end_ms: 47_300,
+        };
+        let clip = db.session_audio_clip(&id, &range, 0).unwrap();
+        assert_eq!(clip.samples, samples[ms(45_100)..ms(47_300)]);
+    }
+
+    #[test]
+    fn test_clip_to_wav() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(3);
+        let (id, segment_id, _) = attach(&db, dir.path(), &samples);
+
+        let segment = AudioSpan::Segment { segment_id };
+        let clip = db.session_audio_clip(&id, &segment, 0).unwrap();
+        let wav = clip.to_wav().unwrap();
+        let reader = hound::WavReader::new(std::io::Cursor::new(wav)).unwrap();
+        assert_eq!(reader.spec().sample_rate, RATE);
+        let decoded: Vec<i16> = reader.into_samples().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, clip.samples);
+    }
+
+    #[test]
+    fn test_recording_attached_as_wav_is_sliced() {
+        let dir = tempdir().unwrap();
+        let db = create_test_db();
+        let samples = recording(3);
+        let (id, segment_id) = save_session(&db);
+        let wav = dir.path().join("recording.wav");
+        write_wav(&wav, &samples);
+        db.set_session_audio(&id, Some(&wav)).unwrap();
+
+        let segment = AudioSpan::Segment { segment_id };
+        let clip = db.session_audio_clip(&id, &segment, 0).unwrap();
+        assert_eq!(clip.samples, samples[ms(1000)..ms(2000)]);
+    }
+
+    #[test]
+    fn test_recording_of_encrypted_database_is_sealed() {
+        let dir = tempdir().unwrap();
+        let db = Database::open_encrypted(
+            &dir.path().join("test.db"),
+            &DatabaseKey::Passphrase("hunter2".to_string()),
+        )
+        .unwrap();
+        let samples = recording(3);
+        let (id, segment_id, path) = attach(&db, dir.path(), &samples);
+
+        assert!(FileCipher::is_sealed(&std::fs::read(&path).unwrap()));
+        let segment = AudioSpan::Segment { segment_id };
+        let clip = db.session_audio_clip(&id, &segment, 0).unwrap();
+        assert_eq!(clip.samples, samples[ms(1000)..ms(2000)]);
+    }
+
+    #[test]
+    fn test_session_without_recording() {
+        let db = create_test_db();
+        let (id, segment_id) = save_session(&db);
+
+        let segment = AudioSpan::Segment { segment_id };
+        assert!(matches!(
+            db.session_audio_clip(&id, &segment, 0),
+            Err(StorageError::NotFound(_))
+        ));
+    }
+}
+
+// =============================================================================
+// Backup Tests
+// =============================================================================
+
+mod backup {
+    use super::*;
+    use gibberish_events::InMemoryEventBus;
+    use gibberish_storage::{
+        verify_backup, BackupConflict, BackupItem, BackupScope, DatabaseKey, FileCipher, Language,
+        Settings, VadSettings, BACKUP_VERSION,
+    };
+    use std::io::Read;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    const NOW_MS: i64 = 1_760_000_000_000;
+
+    /// A database, its settings, and skills and tools directories under
+    /// `root`.
+    struct Archive {
+        db: Arc<Database>,
+        settings: Settings,
+        root: PathBuf,
+    }
+
+    impl Archive {
+        fn new(root: &Path) -> Self {
+            Self::with_db(root, Arc::new(create_test_db()))
+        }
+
+        fn with_db(root: &Path, db: Arc<Database>) -> Self {
+            let settings = Settings::new(db.clone(), Arc::new(InMemoryEventBus::new()));
+            Self {
+                db,
+                settings,
+                root: root.to_path_buf(),
+            }
+        }
+
+        fn scope(&self) -> BackupScope<'_> {
+            BackupScope {
+                settings: Some(&self.settings),
+                dirs: vec![
+                    ("skills".to_string(), self.root.join("skills")),
+                    ("tools".to_string(), self.root.join("tools")),
+                ],
+                recordings_dir: Some(self.root.join("recordings")),
+                ..Default::default()
+            }
+        }
+
+        fn scope_with<'a>(&'a self, passphrase: &'a str) -> BackupScope<'a> {
+            BackupScope {
+                passphrase: Some(passphrase),
+                ..self.scope()
+            }
+        }
+
+        fn write(&self, relative: &str, contents: &str) {
+            let path = self.root.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        fn read(&self, relative: &str) -> String {
+            std::fs::read_to_string(self.root.join(relative)).unwrap()
+        }
+    }
+
+    /// A session with a recording, two activities, a changed setting and
+    /// a skill and a tool pack.
+    fn populate(archive: &Archive) -> Uuid {
+        let transcript = create_test_transcript();
+        archive.db.save(&transcript).unwrap();
+        archive.write("recordings/recording.wav", "RIFF....WAVE audio");
+        archive
+            .db
+            .set_session_audio(
+                &transcript.id,
+                Some(&archive.root.join("recordings/recording.wav")),
+            )
+            .unwrap();
+
+        for activity_type in [ActivityType::Transcript, ActivityType::VoiceCommand] {
+            archive
+                .db
+                .save_activity(&create_test_activity(activity_type))
+                .unwrap();
+        }
+        archive.settings.set(Language("de".to_string())).unwrap();
+        archive.write("skills/summarize.toml", "name = \"summarize\"");
+        archive.write("tools/weather/pack.toml", "name = \"weather\"");
+        archive.write("tools/.DS_Store", "junk");
+        transcript.id
+    }
+
+    type Entries = Vec<(String, Vec<u8>)>;
+    type Edit = dyn Fn(&mut Entries);
+    type Damage = dyn Fn(&mut Vec<u8>);
+
+    /// Rewrite the backup at `path` with `edit` applied to its entries.
+    fn rewrite(path: &Path, edit: impl Fn(&mut Entries)) {
+        let file = std::fs::File::open(path).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let mut entries = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_str().unwrap().to_string();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            entries.push((name, data));
+        }
+        edit(&mut entries);
+
+        let file = std::fs::File::create(path).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
+        ));
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(&mut header, name, data.as_slice())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_into_empty_archive() {
+        let dir = tempdir().unwrap();
+        let source = Archive::new(&dir.path().join("source"));
+        let id = populate(&source);
+        let backup = dir.path().join("backup.tar.gz");
+
+        let manifest = source
+            .db
+            .export_backup(&backup, &source.scope(), NOW_MS)
+            .unwrap();
+        assert_eq!(manifest.version, BACKUP_VERSION);
+        assert_eq!(manifest.created_at_ms, NOW_MS);
+        assert_eq!(
+            (manifest.sessions, manifest.recordings, manifest.activities),
+            (1, 1, 2)
+        );
+        assert_eq!(manifest.files, 2, "hidden files are left out");
+        assert!(!dir.path().join("backup.tar.gz.tmp").exists());
+        assert_eq!(
+            verify_backup(&backup, None).unwrap().entries,
+            manifest.entries
+        );
+
+        let target = Archive::new(&dir.path().join("target"));
+        let report = target.db.import_backup(&backup, &target.scope()).unwrap();
+        assert_eq!(
+            (
+                report.sessions,
+                report.recordings,
+                report.activities,
+                report.files
+            ),
+            (1, 1, 2, 2)
+        );
+        assert_eq!(report.settings, vec!["language"]);
+        assert_eq!(report.unchanged, 0);
+        assert!(report.conflicts.is_empty());
+
+        assert_eq!(
+            serde_json::to_value(target.db.get(&id).unwrap()).unwrap(),
+            serde_json::to_value(source.db.get(&id).unwrap()).unwrap()
+        );
+        let audio = target.db.session_audio(&id).unwrap().unwrap();
+        assert!(audio.starts_with(target.root.join("recordings")));
+        assert_eq!(std::fs::read(audio).unwrap(), b"RIFF....WAVE audio");
+        assert_eq!(target.db.get_activities(10).unwrap().len(), 2);
+        assert_eq!(target.settings.get::<Language>().0, "de");
+        assert_eq!(target.read("tools/weather/pack.toml"), "name = \"weather\"");
+        assert_eq!(target.read("skills/summarize.toml"), "name = \"summarize\"");
+
+        // Importing again adds nothing.
+        let again = target.db.import_backup(&backup, &target.scope()).unwrap();
+        assert_eq!(
+            (
+                again.sessions,
+                again.recordings,
+                again.activities,
+                again.files
+            ),
+            (0, 0, 0, 0)
+        );
+        assert!(again.settings.is_empty());
+        assert_eq!(again.unchanged, 5);
+        assert!(again.conflicts.is_empty());
+        assert_eq!(target.db.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_conflicts_keep_the_local_copy() {
+        let dir = tempdir().unwrap();
+        let source = Archive::new(&dir.path().join("source"));
+        let id = populate(&source);
+        let backup = dir.path().join("backup.tar.gz");
+        source
+            .db
+            .export_backup(&backup, &source.scope(), NOW_MS)
+            .unwrap();
+
+        // The same session and skill, changed locally, plus a local setting
+        // and a session of its own.
+        let target = Archive::new(&dir.path().join("target"));
+        let mut transcript = source.db.get(&id).unwrap();
+        transcript.title = Some("Renamed here".to_string());
+        target.db.save(&transcript).unwrap();
+        let own = create_test_transcript();
+        target.db.save(&own).unwrap();
+        target.settings.set(Language("fr".to_string())).unwrap();
+        let vad = VadSettings {
+            redemption_time_ms: 800,
+            ..Default::default()
+        };
+        target.settings.set(vad).unwrap();
+        target.write("skills/summarize.toml", "name = \"mine\"");
+
+        let report = target.db.import_backup(&backup, &target.scope()).unwrap();
+        assert_eq!((report.sessions, report.recordings), (0, 0));
+        assert_eq!((report.activities, report.files), (2, 1));
+        assert!(report.settings.is_empty());
+        assert_eq!(
+            report.conflicts,
+            vec![
+                BackupConflict {
+                    item: BackupItem::Session,
+                    id: id.to_string(),
+                },
+                BackupConflict {
+                    item: BackupItem::Setting,
+                    id: "language".to_string(),
+                },
+                BackupConflict {
+                    item: BackupItem::File,
+                    id: "skills/summarize.toml".to_string(),
+                },
+            ]
+        );
+
+        assert_eq!(
+            target.db.get(&id).unwrap().title.as_deref(),
+            Some("Renamed here")
+        );
+        assert!(target.db.session_audio(&id).unwrap().is_none());
+        assert!(target.db.get(&own.id).is_ok());
+        assert_eq!(target.settings.get::<Language>().0, "fr");
+        assert_eq!(target.settings.get::<VadSettings>(), vad);
+        assert_eq!(target.read("skills/summarize.toml"), "name = \"mine\"");
+    }
+
+    #[test]
+    fn test_damaged_backups_are_refused() {
+        let dir = tempdir().unwrap();
+        let source = Archive::new(&dir.path().join("source"));
+        populate(&source);
+        let backup = dir.path().join("backup.tar.gz");
+        let target = Archive::new(&dir.path().join("target"));
+
+        let cases: [(&str, &Edit); 5] = [
+            ("changed", &|entries| {
+                let (_, data) = entries
+                    .iter_mut()
+                    .find(|(name, _)| name == "activities.jsonl")
+                    .unwrap();
+                data[0] = b' ';
+            }),
+            ("missing", &|entries| {
+                entries.retain(|(name, _)| !name.starts_with("recordings/"))
+            }),
+            ("unlisted", &|entries| {
+                entries.insert(0, ("files/skills/extra.toml".to_string(), b"x".to_vec()))
+            }),
+            ("no manifest", &|entries| {
+                entries.retain(|(name, _)| name != "manifest.json")
+            }),
+            ("newer", &|entries| {
+                let (_, data) = entries.last_mut().unwrap();
+                let mut manifest: serde_json::Value = serde_json::from_slice(data).unwrap();
+                manifest["version"] = (BACKUP_VERSION + 1).into();
+                *data = serde_json::to_vec(&manifest).unwrap();
+            }),
+        ];
+        for (case, edit) in cases {
+            source
+                .db
+                .export_backup(&backup, &source.scope(), NOW_MS)
+                .unwrap();
+            rewrite(&backup, edit);
+            let result = target.db.import_backup(&backup, &target.scope());
+            assert!(
+                matches!(result, Err(StorageError::Backup(_))),
+                "{case}: {result:?}"
+            );
+        }
+        assert!(target.db.list().unwrap().is_empty());
+        assert!(target.db.get_activities(10).unwrap().is_empty());
+        assert!(!target.root.join("skills").exists());
+    }
+
+    #[test]
+    fn test_encrypted_recordings_are_unsealed_and_resealed() {
+        let dir = tempdir().unwrap();
+        let key = DatabaseKey::Passphrase("hunter2".to_string());
+        let source = Archive::with_db(
+            &dir.path().join("source"),
+            Arc::new(Database::open_encrypted(&dir.path().join("source.db"), &key).unwrap()),
+        );
+        let id = populate(&source);
+        let recording = source.root.join("recordings/recording.wav");
+        source
+            .db
+            .file_cipher()
+            .unwrap()
+            .unwrap()
+            .seal_file(&recording)
+            .unwrap();
+        let backup = dir.path().join("backup.tar.gz");
+        let result = source.db.export_backup(&backup, &source.scope(), NOW_MS);
+        assert!(matches!(result, Err(StorageError::Backup(_))), "{result:?}");
+        assert!(!backup.exists());
+        source
+            .db
+            .export_backup(&backup, &source.scope_with("backup pass"), NOW_MS)
+            .unwrap();
+
+        let plain = Archive::new(&dir.path().join("plain"));
+        plain
+            .db
+            .import_backup(&backup, &plain.scope_with("backup pass"))
+            .unwrap();
+        let audio = plain.db.session_audio(&id).unwrap().unwrap();
+        assert_eq!(std::fs::read(audio).unwrap(), b"RIFF....WAVE audio");
+
+        let encrypted = Archive::with_db(
+            &dir.path().join("encrypted"),
+            Arc::new(Database::open_encrypted(&dir.path().join("target.db"), &key).unwrap()),
+        );
+        encrypted
+            .db
+            .import_backup(&backup, &encrypted.scope_with("backup pass"))
+            .unwrap();
+        let audio = encrypted.db.session_audio(&id).unwrap().unwrap();
+        assert!(FileCipher::is_sealed(&std::fs::read(&audio).unwrap()));
+        assert_eq!(
+            encrypted
+                .db
+                .file_cipher()
+                .unwrap()
+                .unwrap()
+                .read_file(&audio)
+                .unwrap(),
+            b"RIFF....WAVE audio"
+        );
+    }
+
+    #[test]
+    fn test_encrypted_backups_need_their_passphrase() {
+        let dir = tempdir().unwrap();
+        let source = Archive::new(&dir.path().join("source"));
+        populate(&source);
+        // Incompressible, so the backup spans several chunks.
+        let mut state = 1u32;
+        let noise: String = (0..400_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                char::from(b'a' + (state >> 24) as u8 % 26)
+            })
+            .collect();
+        source.write("skills/noise.toml", &noise);
+        let backup = dir.path().join("backup.tar.gz");
+        let manifest = source
+            .db
+            .export_backup(&backup, &source.scope_with("backup pass"), NOW_MS)
+            .unwrap();
+
+        let sealed = std::fs::read(&backup).unwrap();
+        assert!(sealed.starts_with(b"GIBBBAK1"));
+        assert!(sealed.len() > 2 * 64 * 1024);
+        assert!(!sealed.windows(7).any(|w| w == b"summari"));
+        assert_eq!(
+            verify_backup(&backup, Some("backup pass")).unwrap().entries,
+            manifest.entries
+        );
+        for passphrase in [None, Some("wrong pass")] {
+            let result = verify_backup(&backup, passphrase);
+            assert!(matches!(result, Err(StorageError::Backup(_))), "{result:?}");
+        }
+
+        let target = Archive::new(&dir.path().join("target"));
+        // Header, then chunks of 64 KiB plus a 16-byte tag.
+        const FIRST_CHUNK_END: usize = 43 + 64 * 1024 + 16;
+        let cases: [(&str, &Damage); 3] = [
+            ("flipped", &|data| data[FIRST_CHUNK_END + 100] ^= 1),
+            ("cut at a chunk", &|data| data.truncate(FIRST_CHUNK_END)),
+            ("cut short", &|data| data.truncate(data.len() - 1)),
+        ];
+        for (case, edit) in cases {
+            let mut data = sealed.clone();
+            edit(&mut data);
+            std::fs::write(&backup, data).unwrap();
+            let result = target
+                .db
+                .import_backup(&backup, &target.scope_with("backup pass"));
+            assert!(
+                matches!(result, Err(StorageError::Backup(_))),
+                "{case}: {result:?}"
+            );
+        }
+        assert!(target.db.list().unwrap().is_empty());
+
+        std::fs::write(&backup, &sealed).unwrap();
+        let report = target
+            .db
+            .import_backup(&backup, &target.scope_with("backup pass"))
+            .unwrap();
+        assert_eq!((report.sessions, report.files), (1, 3));
+        assert_eq!(target.read("skills/noise.toml"), noise);
+    }
+
+    #[test]
+    fn test_plaintext_export_of_encrypted_database_is_opt_in() {
+        let dir = tempdir().unwrap();
+        let key = DatabaseKey::Passphrase("hunter2".to_string());
+        let source = Archive::with_db(
+            &dir.path().join("source"),
+            Arc::new(Database::open_encrypted(&dir.path().join("source.db"), &key).unwrap()),
+        );
+        populate(&source);
+        let backup = dir.path().join("backup.tar.gz");
+
+        let result = source
+            .db
+            .export_backup(&backup, &source.scope_with(""), NOW_MS);
+        assert!(matches!(result, Err(StorageError::Backup(_))), "{result:?}");
+        let plaintext = BackupScope {
+            allow_plaintext: true,
+            ..source.scope()
+        };
+        source
+            .db
+            .export_backup(&backup, &plaintext, NOW_MS)
+            .unwrap();
+        assert_eq!(verify_backup(&backup, None).unwrap().sessions, 1);
     }
 }
 
@@ -0,0 +1,611 @@
+//! Word-level forced alignment for engines that only return segment text.
+//!
+//! Whisper decodes whole utterances without usable word timings, but the
+//! streaming pipeline (`WordTracker`, turn-boundary splitting) and transcript
+//! exports need `Word { start_ms, end_ms }`. [`ForcedAligner`] recovers them
+//! after decoding:
+//!
+//! - With a CTC reference engine (e.g. the NeMo Conformer CTC model) the same
+//!   audio is decoded again and the hypothesis is aligned character-by-character
+//!   onto the reference's timed words using an edit-distance backtrace.
+//! - Without a reference, words are spread over the voiced frames of the audio
+//!   in proportion to their length, so pauses do not swallow word time. These
+//!   are estimates, not an alignment, and are flagged as unaligned.
+//!
+//! [`AlignedEngine`] wraps any [`SttEngine`] and fills in missing words,
+//! setting [`Segment::words_unaligned`] where they were only estimated.
+
+use std::any::Any;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::engine::read_wav_mono_f32_16k;
+use crate::{Segment, SttEngine, Word, STT_SAMPLE_RATE};
+
+/// Analysis frame for the energy-based fallback (20ms at 16kHz).
+const FRAME_SAMPLES: usize = 320;
+
+/// Frame duration in milliseconds.
+const FRAME_MS: u64 = (FRAME_SAMPLES as u64 * 1000) / STT_SAMPLE_RATE as u64;
+
+/// Fraction of the noise-to-peak energy range a frame must exceed to count as voiced.
+const VOICED_THRESHOLD_RATIO: f32 = 0.1;
+
+/// Upper bound on the edit-distance matrix size (hypothesis x reference chars).
+///
+/// Longer inputs fall back to a proportional character mapping, which is
+/// still monotonic but ignores substitutions.
+const MAX_ALIGNMENT_CELLS: usize = 4_000_000;
+
+/// Word timings for a decoded text.
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    pub words: Vec<Word>,
+    /// The reference was missing or failed, so the words were estimated
+    /// with [`estimate_word_timings`].
+    pub unaligned: bool,
+}
+
+/// Aligns decoded text to audio, producing word timings.
+#[derive(Clone, Default)]
+pub struct ForcedAligner {
+    reference: Option<Arc<dyn SttEngine>>,
+}
+
+impl ForcedAligner {
+    /// Aligner without a reference: every result is an unaligned estimate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aligner that decodes the audio with `reference` (a model that emits
+    /// word timestamps, typically CTC) and aligns onto its output.
+    pub fn with_reference(reference: Arc<dyn SttEngine>) -> Self {
+        Self {
+            reference: Some(reference),
+        }
+    }
+
+    /// Name of the reference model, if any.
+    pub fn reference_name(&self) -> Option<&str> {
+        self.reference.as_deref().map(|r| r.model_name())
+    }
+
+    /// Produce word timings for `text` spoken in `audio` (16kHz mono).
+    ///
+    /// Timestamps are relative to the start of `audio`.
+    pub fn align(&self, audio: &[f32], text: &str) -> Alignment {
+        if text.split_whitespace().next().is_none() {
+            return Alignment {
+                words: Vec::new(),
+                unaligned: false,
+            };
+        }
+
+        if let Some(reference) = &self.reference {
+            match reference.transcribe(audio) {
+                Ok(segments) => {
+                    let timed: Vec<Word> = segments.into_iter().flat_map(|s| s.words).collect();
+                    if !timed.is_empty() {
+                        return Alignment {
+                            words: align_to_reference(text, &timed),
+                            unaligned: false,
+                        };
+                    }
+                    tracing::debug!("Alignment reference returned no words, estimating");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Alignment reference failed, estimating");
+                }
+            }
+        }
+
+        Alignment {
+            words: estimate_word_timings(text, audio),
+            unaligned: true,
+        }
+    }
+}
+
+/// Map the words of `text` onto timed `reference` words.
+///
+/// Both sides are reduced to lowercase alphanumeric characters and aligned
+/// with a minimum edit-distance backtrace. Each hypothesis word takes the time
+/// span of the reference characters it aligned with; words that aligned with
+/// nothing are interpolated between their neighbours. Confidence is the
+/// fraction of the word's characters that matched exactly.
+pub fn align_to_reference(text: &str, reference: &[Word]) -> Vec<Word> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.is_empty() || reference.is_empty() {
+        return Vec::new();
+    }
+
+    // Hypothesis characters, tagged with their word index.
+    let mut hyp: Vec<(char, usize)> = Vec::new();
+    for (w, token) in tokens.iter().enumerate() {
+        hyp.extend(normalized_chars(token).map(|c| (c, w)));
+    }
+
+    // Reference characters, each owning an even slice of its word's span.
+    let mut refc: Vec<(char, u64, u64)> = Vec::new();
+    for word in reference {
+        let chars: Vec<char> = normalized_chars(&word.text).collect();
+        let n = chars.len() as u64;
+        let span = word.end_ms.saturating_sub(word.start_ms);
+        for (i, c) in chars.into_iter().enumerate() {
+            let i = i as u64;
+            refc.push((
+                c,
+                word.start_ms + span * i / n,
+                word.start_ms + span * (i + 1) / n,
+            ));
+        }
+    }
+
+    let mut spans: Vec<Option<(u64, u64)>> = vec![None; tokens.len()];
+    let mut exact = vec![0usize; tokens.len()];
+
+    if !hyp.is_empty() && !refc.is_empty() {
+        for (hi, ri) in char_alignment(&hyp, &refc) {
+            let (hc, w) = hyp[hi];
+            let (rc, start, end) = refc[ri];
+            if hc == rc {
+                exact[w] += 1;
+            }
+            spans[w] = Some(match spans[w] {
+                Some((s, e)) => (s.min(start), e.max(end)),
+                None => (start, end),
+            });
+        }
+    }
+
+    let first_ms = reference.first().map(|w| w.start_ms).unwrap_or(0);
+    let last_ms = reference.last().map(|w| w.end_ms).unwrap_or(first_ms);
+    let weights: Vec<usize> = tokens.iter().map(|t| t.chars().count()).collect();
+    fill_gaps(&mut spans, &weights, first_ms, last_ms);
+
+    let words = tokens
+        .iter()
+        .zip(spans)
+        .enumerate()
+        .map(|(w, (token, span))| {
+            let (start_ms, end_ms) = span.unwrap_or((first_ms, first_ms));
+            let len = normalized_chars(token).count();
+            let confidence = if len == 0 {
+                1.0
+            } else {
+                exact[w] as f32 / len as f32
+            };
+            Word {
+                text: (*token).to_string(),
+                start_ms,
+                end_ms,
+                confidence,
+            }
+        })
+        .collect();
+
+    make_monotonic(words)
+}
+
+/// Estimate word timings from signal energy alone.
+///
+/// Frames above an adaptive energy threshold are treated as speech and the
+/// words are laid out across them proportionally to their character count.
+pub fn estimate_word_timings(text: &str, audio: &[f32]) -> Vec<Word> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+
+    let energies: Vec<f32> = audio
+        .chunks(FRAME_SAMPLES)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect();
+
+    let mut voiced: Vec<usize> = if energies.is_empty() {
+        Vec::new()
+    } else {
+        let peak = energies.iter().cloned().fold(0.0f32, f32::max);
+        let floor = energies.iter().cloned().fold(f32::MAX, f32::min);
+        let threshold = floor + (peak - floor) * VOICED_THRESHOLD_RATIO;
+        (0..energies.len())
+            .filter(|&i| peak > 0.0 && energies[i] >= threshold)
+            .collect()
+    };
+    if voiced.is_empty() {
+        voiced = (0..energies.len().max(1)).collect();
+    }
+
+    // One extra unit per word approximates the inter-word gap.
+    let weights: Vec<usize> = tokens.iter().map(|t| t.chars().count() + 1).collect();
+    let total: usize = weights.iter().sum();
+    let frames = voiced.len();
+
+    let mut cumulative = 0usize;
+    let words = tokens
+        .iter()
+        .zip(&weights)
+        .map(|(token, weight)| {
+            let first = (cumulative * frames / total).min(frames - 1);
+            cumulative += weight;
+            let last = cumulative.saturating_mul(frames).div_ceil(total);
+            let last = last.clamp(first + 1, frames);
+            Word {
+                text: (*token).to_string(),
+                start_ms: voiced[first] as u64 * FRAME_MS,
+                end_ms: (voiced[last - 1] as u64 + 1) * FRAME_MS,
+                confidence: 1.0,
+            }
+        })
+        .collect();
+
+    make_monotonic(words)
+}
+
+/// Engine decorator that adds word timings to segments that lack them.
+///
+/// Segments that already carry words are passed through untouched, so this
+/// is safe to wrap around any engine. [`SttEngine::as_any`] delegates to the
+/// inner engine so engine-specific downcasts keep working.
+pub struct AlignedEngine {
+    inner: Box<dyn SttEngine>,
+    aligner: ForcedAligner,
+}
+
+impl AlignedEngine {
+    pub fn new(inner: Box<dyn SttEngine>, aligner: ForcedAligner) -> Self {
+        Self { inner, aligner }
+    }
+
+    pub fn aligner(&self) -> &ForcedAligner {
+        &self.aligner
+    }
+
+    fn align_segments(&self, audio: &[f32], mut segments: Vec<Segment>) -> Vec<Segment> {
+        for segment in segments.iter_mut().filter(|s| s.words.is_empty()) {
+            let from = ms_to_sample(segment.start_ms).min(audio.len());
+            let to = if segment.end_ms > segment.start_ms {
+                ms_to_sample(segment.end_ms).clamp(from, audio.len())
+            } else {
+                audio.len()
+            };
+
+            let alignment = self.aligner.align(&audio[from..to], &segment.text);
+            segment.words_unaligned = alignment.unaligned;
+            segment.words = alignment
+                .words
+                .into_iter()
+                .map(|mut w| {
+                    w.start_ms += segment.start_ms;
+                    w.end_ms += segment.start_ms;
+                    w
+                })
+                .collect();
+        }
+        segments
+    }
+}
+
+impl SttEngine for AlignedEngine {
+    fn transcribe(&self, audio: &[f32]) -> crate::Result<Vec<Segment>> {
+        let segments = self.inner.transcribe(audio)?;
+        Ok(self.align_segments(audio, segments))
+    }
+
+    fn transcribe_file(&self, path: &Path) -> crate::Result<Vec<Segment>> {
+        let samples = read_wav_mono_f32_16k(path)?;
+        self.transcribe(&samples)
+    }
+
+    fn is_streaming_capable(&self) -> bool {
+        self.inner.is_streaming_capable()
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn supported_languages(&self) -> Vec<&'static str> {
+        self.inner.supported_languages()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self.inner.as_any()
+    }
+}
+
+fn ms_to_sample(ms: u64) -> usize {
+    (ms * STT_SAMPLE_RATE as u64 / 1000) as usize
+}
+
+fn normalized_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+}
+
+/// Pairs of (hypothesis index, reference index) on a minimum edit path.
+fn char_alignment(hyp: &[(char, usize)], refc: &[(char, u64, u64)]) -> Vec<(usize, usize)> {
+    let n = hyp.len();
+    let m = refc.len();
+
+    if n.saturating_mul(m) > MAX_ALIGNMENT_CELLS {
+        return (0..n).map(|i| (i, i * m / n)).collect();
+    }
+
+    let cols = m + 1;
+    let mut dist = vec![0u32; (n + 1) * cols];
+    for (j, cell) in dist.iter_mut().enumerate().take(cols) {
+        *cell = j as u32;
+    }
+    for i in 1..=n {
+        dist[i * cols] = i as u32;
+        for j in 1..=m {
+            let sub = u32::from(hyp[i - 1].0 != refc[j - 1].0);
+            dist[i * cols + j] = (dist[(i - 1) * cols + j - 1] + sub)
+                .min(dist[(i - 1) * cols + j] + 1)
+                .min(dist[i * cols + j - 1] + 1);
+        }
+    }
+
+    let mut pairs = Vec::with_capacity(n.min(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 && j > 0 {
+        let here = dist[i * cols + j];
+        let sub = u32::from(hyp[i - 1].0 != refc[j - 1].0);
+        if here == dist[(i - 1) * cols + j - 1] + sub {
+            pairs.push((i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if here == dist[(i - 1) * cols + j] + 1 {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// Interpolate spans for words that aligned with nothing, splitting the gap
+/// between their neighbours by word length.
+fn fill_gaps(spans: &mut [Option<(u64, u64)>], weights: &[usize], first_ms: u64, last_ms: u64) {
+    let mut i = 0;
+    while i < spans.len() {
+        if spans[i].is_some() {
+            i += 1;
+            continue;
+        }
+        let run_start = i;
+        while i < spans.len() && spans[i].is_none() {
+            i += 1;
+        }
+
+        let left = spans[..run_start]
+            .iter()
+            .rev()
+            .flatten()
+            .map(|s| s.1)
+            .next()
+            .unwrap_or(first_ms);
+        let right = spans[i..]
+            .iter()
+            .flatten()
+            .map(|s| s.0)
+            .next()
+            .unwrap_or(last_ms)
+            .max(left);
+
+        let run_weights: Vec<u64> = weights[run_start..i]
+            .iter()
+            .map(|w| (*w).max(1) as u64)
+            .collect();
+        let total: u64 = run_weights.iter().sum();
+        let mut acc = 0u64;
+        for (k, weight) in run_weights.iter().enumerate() {
+            let start = left + (right - left) * acc / total;
+            acc += weight;
+            let end = left + (right - left) * acc / total;
+            spans[run_start + k] = Some((start, end));
+        }
+    }
+}
+
+/// Ensure words never start before the previous word ends.
+fn make_monotonic(mut words: Vec<Word>) -> Vec<Word> {
+    let mut prev_end = 0;
+    for word in &mut words {
+        word.start_ms = word.start_ms.max(prev_end);
+        word.end_ms = word.end_ms.max(word.start_ms);
+        prev_end = word.end_ms;
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> Word {
+        Word {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            confidence: 1.0,
+        }
+    }
+
+    fn times(words: &[Word]) -> Vec<(u64, u64)> {
+        words.iter().map(|w| (w.start_ms, w.end_ms)).collect()
+    }
+
+    struct FixedEngine {
+        segments: Vec<Segment>,
+    }
+
+    impl SttEngine for FixedEngine {
+        fn transcribe(&self, _audio: &[f32]) -> crate::Result<Vec<Segment>> {
+            Ok(self.segments.clone())
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64, words: Vec<Word>) -> Segment {
+        Segment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            words,
+            speaker: None,
+            words_unaligned: false,
+        }
+    }
+
+    #[test]
+    fn identical_text_takes_reference_times() {
+        let reference = vec![word("hello", 100, 400), word("world", 500, 900)];
+        let words = align_to_reference("Hello, world!", &reference);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello,");
+        assert_eq!(times(&words), vec![(100, 400), (500, 900)]);
+        assert!(words
+            .iter()
+            .all(|w| (w.confidence - 1.0).abs() < f32::EPSILON));
+    }
+
+    #[test]
+    fn different_word_boundaries_are_realigned() {
+        // Reference splits "cannot" into two words.
+        let reference = vec![
+            word("i", 0, 100),
+            word("can", 200, 400),
+            word("not", 400, 600),
+            word("go", 700, 900),
+        ];
+        let words = align_to_reference("I cannot go", &reference);
+
+        assert_eq!(times(&words), vec![(0, 100), (200, 600), (700, 900)]);
+    }
+
+    #[test]
+    fn substitutions_lower_confidence() {
+        let reference = vec![word("their", 0, 500), word("house", 600, 1000)];
+        let words = align_to_reference("there house", &reference);
+
+        assert_eq!(words[0].start_ms, 0);
+        assert!(words[0].confidence < 1.0);
+        assert_eq!(times(&words)[1], (600, 1000));
+    }
+
+    #[test]
+    fn unmatched_words_are_interpolated() {
+        let reference = vec![word("one", 0, 300), word("three", 1000, 1300)];
+        let words = align_to_reference("one — three", &reference);
+
+        assert_eq!(words.len(), 3);
+        assert_eq!(words[1].text, "—");
+        assert_eq!(times(&words)[1], (300, 1000));
+    }
+
+    #[test]
+    fn aligned_words_are_monotonic() {
+        let reference = vec![word("a", 0, 100), word("b", 100, 200), word("a", 200, 300)];
+        let words = align_to_reference("a a b b a", &reference);
+
+        for pair in words.windows(2) {
+            assert!(pair[1].start_ms >= pair[0].end_ms);
+            assert!(pair[0].end_ms >= pair[0].start_ms);
+        }
+    }
+
+    #[test]
+    fn empty_inputs_yield_no_words() {
+        assert!(align_to_reference("", &[word("a", 0, 10)]).is_empty());
+        assert!(align_to_reference("a", &[]).is_empty());
+        assert!(estimate_word_timings("  ", &[0.0; 1600]).is_empty());
+    }
+
+    #[test]
+    fn estimate_skips_silence() {
+        // 500ms silence, 500ms tone, 500ms silence, 500ms tone.
+        let mut audio = vec![0.0f32; 8000];
+        audio.extend((0..8000).map(|i| (i as f32 * 0.1).sin() * 0.5));
+        audio.extend(vec![0.0f32; 8000]);
+        audio.extend((0..8000).map(|i| (i as f32 * 0.1).sin() * 0.5));
+
+        let words = estimate_word_timings("abcd efgh", &audio);
+
+        assert_eq!(times(&words), vec![(500, 1000), (1500, 2000)]);
+    }
+
+    #[test]
+    fn estimate_covers_silent_audio() {
+        let words = estimate_word_timings("one two", &[0.0; 16000]);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].start_ms, 0);
+        assert_eq!(words[1].end_ms, 1000);
+    }
+
+    #[test]
+    fn aligner_uses_reference_engine() {
+        let reference = FixedEngine {
+            segments: vec![segment(
+                "hola món",
+                0,
+                1000,
+                vec![word("hola", 100, 400), word("món", 500, 800)],
+            )],
+        };
+        let aligner = ForcedAligner::with_reference(Arc::new(reference));
+
+        let alignment = aligner.align(&[0.0; 16000], "Hola, món.");
+
+        assert_eq!(aligner.reference_name(), Some("fixed"));
+        assert_eq!(times(&alignment.words), vec![(100, 400), (500, 800)]);
+        assert!(!alignment.unaligned);
+    }
+
+    #[test]
+    fn aligner_falls_back_without_reference_words() {
+        let reference = FixedEngine {
+            segments: vec![segment("hola", 0, 1000, Vec::new())],
+        };
+        let aligner = ForcedAligner::with_reference(Arc::new(reference));
+
+        let alignment = aligner.align(&[0.0; 16000], "hola");
+
+        assert_eq!(times(&alignment.words), vec![(0, 1000)]);
+        assert!(alignment.unaligned);
+    }
+
+    #[test]
+    fn aligned_engine_fills_missing_words_with_segment_offset() {
+        let inner = FixedEngine {
+            segments: vec![
+                segment("first", 0, 500, Vec::new()),
+                segment("second", 500, 1000, vec![word("second", 600, 900)]),
+                segment("third", 1000, 1500, Vec::new()),
+            ],
+        };
+        let engine = AlignedEngine::new(Box::new(inner), ForcedAligner::new());
+
+        let segments = engine.transcribe(&[0.0; 24000]).unwrap();
+
+        assert_eq!(times(&segments[0].words), vec![(0, 500)]);
+        assert_eq!(times(&segments[1].words), vec![(600, 900)]);
+        assert_eq!(times(&segments[2].words), vec![(1000, 1500)]);
+        assert!(segments[0].words_unaligned && segments[2].words_unaligned);
+        assert!(!segments[1].words_unaligned);
+        assert!(engine.as_any().downcast_ref::<FixedEngine>().is_some());
+    }
+}
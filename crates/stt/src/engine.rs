@@ -17,6 +17,9 @@ pub struct Segment {
     pub end_ms: u64,
     pub words: Vec<Word>,
     pub speaker: Option<i32>,
+    /// The word timings were estimated from signal energy because no
+    /// alignment reference was available; they are not aligned to speech.
+    pub words_unaligned: bool,
 }
 
 /// Standard sample rate for STT processing.
@@ -88,6 +91,14 @@ pub trait EngineLoader: Send + Sync {
     fn is_streaming(&self, _model_id: &str) -> bool {
         false
     }
+
+    /// Check if the engine fills `Segment::words` with timestamps.
+    ///
+    /// Batch engines that return only text are wrapped in an
+    /// [`AlignedEngine`](crate::AlignedEngine) at load time.
+    fn has_word_timestamps(&self, _model_id: &str) -> bool {
+        true
+    }
 }
 
 /// Resample audio using linear interpolation.
@@ -115,7 +126,7 @@ fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Cow<'_, [f3
 }
 
 /// Read a WAV file and return mono f32 samples at 16kHz.
-fn read_wav_mono_f32_16k(path: &Path) -> crate::Result<Vec<f32>> {
+pub fn read_wav_mono_f32_16k(path: &Path) -> crate::Result<Vec<f32>> {
     let mut reader = hound::WavReader::open(path)
         .map_err(|e| crate::SttError::TranscriptionFailed(e.to_string()))?;
     let spec = reader.spec();
@@ -1,8 +1,12 @@
+mod alignment;
 mod engine;
 
+pub use alignment::{
+    align_to_reference, estimate_word_timings, AlignedEngine, Alignment, ForcedAligner,
+};
 pub use engine::{
-    EngineLoader, Segment, SttEngine, Word, SILENCE_INJECTION_MS, SILENCE_INJECTION_SAMPLES,
-    STT_SAMPLE_RATE,
+    read_wav_mono_f32_16k, EngineLoader, Segment, SttEngine, Word, SILENCE_INJECTION_MS,
+    SILENCE_INJECTION_SAMPLES, STT_SAMPLE_RATE,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,435 @@
+//! Topic segmentation into chapters (TextTiling) and keyword titles.
+//!
+//! The transcript's content words are grouped into fixed-size windows.
+//! At each gap between windows, the blocks of windows on either side are
+//! compared; a topic shift shows up as a dip in similarity. Gaps whose dip
+//! is deep relative to its neighbouring peaks become chapter boundaries,
+//! snapped to the nearest segment start.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::lexical::{cosine, is_term, words};
+use crate::Transcript;
+
+/// Keywords kept per chapter.
+const CHAPTER_KEYWORDS: usize = 5;
+
+/// Shallowest similarity dip that counts as a topic shift, whatever the
+/// rest of the transcript looks like.
+const MIN_DEPTH: f32 = 0.1;
+
+/// A bigram needs this many occurrences to be a title phrase.
+const MIN_PHRASE_COUNT: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChapterOptions {
+    /// Content words per window.
+    pub window_terms: usize,
+    /// Windows compared on each side of a gap.
+    pub block_windows: usize,
+    /// Shortest chapter (ms).
+    pub min_chapter_ms: u64,
+}
+
+impl Default for ChapterOptions {
+    fn default() -> Self {
+        Self {
+            window_terms: 20,
+            block_windows: 6,
+            min_chapter_ms: 90_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub title: String,
+    /// Most distinctive words of the chapter, best first.
+    pub keywords: Vec<String>,
+}
+
+/// A content word with its position.
+struct Token {
+    term: String,
+    ms: u64,
+}
+
+impl Transcript {
+    /// Split the transcript into chapters. Chapter boundaries fall on
+    /// segment starts; the chapters cover the whole transcript.
+    pub fn segment_chapters(&self, options: &ChapterOptions) -> Vec<Chapter> {
+        let Some(first) = self.segments.first() else {
+            return Vec::new();
+        };
+        let start_ms = first.start_ms;
+        let end_ms = self
+            .segments
+            .iter()
+            .map(|s| s.end_ms)
+            .max()
+            .unwrap_or(start_ms);
+
+        let mut bounds = vec![start_ms];
+        bounds.extend(self.boundaries(options, start_ms, end_ms));
+        bounds.push(end_ms);
+
+        let texts: Vec<String> = bounds
+            .windows(2)
+            .map(|range| {
+                self.segments
+                    .iter()
+                    .filter(|s| s.start_ms >= range[0] && s.start_ms < range[1].max(range[0] + 1))
+                    .map(|s| s.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        let titles = titles(&texts);
+
+        bounds
+            .windows(2)
+            .zip(titles)
+            .map(|(range, (title, keywords))| Chapter {
+                start_ms: range[0],
+                end_ms: range[1],
+                title,
+                keywords,
+            })
+            .collect()
+    }
+
+    /// A title from the longest chapter, with `context` (meeting app,
+    /// project) appended: "Mobile app launch · Zoom".
+    pub fn suggest_title(&self, context: Option<&str>) -> Option<String> {
+        let context = context.map(str::trim).filter(|c| !c.is_empty());
+        let topic = self
+            .chapters
+            .iter()
+            .filter(|c| !c.title.is_empty())
+            .max_by_key(|c| c.end_ms - c.start_ms)
+            .map(|c| c.title.as_str());
+        match (topic, context) {
+            (Some(topic), Some(context)) => Some(format!("{topic} · {context}")),
+            (Some(topic), None) => Some(topic.to_string()),
+            (None, context) => context.map(str::to_string),
+        }
+    }
+
+    /// Topic boundaries (ms), in order, excluding the transcript edges.
+    fn boundaries(&self, options: &ChapterOptions, start_ms: u64, end_ms: u64) -> Vec<u64> {
+        let tokens = self.tokens();
+        let window = options.window_terms.max(1);
+        let block = options.block_windows.max(1);
+        let windows: Vec<&[Token]> = tokens.chunks(window).collect();
+        if windows.len() < 2 * block {
+            return Vec::new();
+        }
+
+        // Similarity across each gap between windows i - 1 and i.
+        let counts: Vec<HashMap<String, f32>> = windows
+            .iter()
+            .map(|w| {
+                let mut counts = HashMap::new();
+                for token in *w {
+                    *counts.entry(token.term.clone()).or_default() += 1.0;
+                }
+                counts
+            })
+            .collect();
+        let block_vector = |range: std::ops::Range<usize>| {
+            let mut vector: HashMap<String, f32> = HashMap::new();
+            for counts in &counts[range] {
+                for (term, count) in counts {
+                    *vector.entry(term.clone()).or_default() += count;
+                }
+            }
+            let norm = vector.values().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                vector.values_mut().for_each(|v| *v /= norm);
+            }
+            vector
+        };
+        let gaps: Vec<usize> = (1..windows.len()).collect();
+        let scores: Vec<f32> = gaps
+            .iter()
+            .map(|&gap| {
+                let left = block_vector(gap.saturating_sub(block)..gap);
+                let right = block_vector(gap..(gap + block).min(windows.len()));
+                cosine(&left, &right)
+            })
+            .collect();
+
+        let depths = depth_scores(&scores);
+        let mean = depths.iter().sum::<f32>() / depths.len() as f32;
+        let variance = depths.iter().map(|d| (d - mean).powi(2)).sum::<f32>() / depths.len() as f32;
+        let cutoff = mean - variance.sqrt() / 2.0;
+
+        // Only valley bottoms: the slopes of one valley are one shift.
+        let is_valley = |i: usize| {
+            (i == 0 || scores[i] <= scores[i - 1])
+                && (i + 1 == scores.len() || scores[i] <= scores[i + 1])
+        };
+        let mut candidates: Vec<(f32, u64)> = (0..gaps.len())
+            .filter(|&i| is_valley(i) && depths[i] > cutoff && depths[i] >= MIN_DEPTH)
+            .filter_map(|i| {
+                let ms = self.nearest_segment_start(windows[gaps[i]][0].ms)?;
+                Some((depths[i], ms))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        // Deepest first, as long as every chapter stays long enough.
+        let mut accepted: Vec<u64> = Vec::new();
+        for (_, ms) in candidates {
+            let fits = [start_ms, end_ms]
+                .iter()
+                .chain(&accepted)
+                .all(|&other| ms.abs_diff(other) >= options.min_chapter_ms);
+            if fits {
+                accepted.push(ms);
+            }
+        }
+        accepted.sort_unstable();
+        accepted
+    }
+
+    /// Content words with times interpolated across their segment.
+    fn tokens(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        for segment in &self.segments {
+            let words = words(&segment.text);
+            let span = segment.end_ms.saturating_sub(segment.start_ms);
+            for (i, word) in words.iter().enumerate() {
+                if is_term(word) {
+                    tokens.push(Token {
+                        term: word.clone(),
+                        ms: segment.start_ms + span * i as u64 / words.len() as u64,
+                    });
+                }
+            }
+        }
+        tokens
+    }
+
+    fn nearest_segment_start(&self, ms: u64) -> Option<u64> {
+        self.segments
+            .iter()
+            .skip(1)
+            .map(|s| s.start_ms)
+            .min_by_key(|start| start.abs_diff(ms))
+    }
+}
+
+/// TextTiling depth: how far a gap's score sits below the highest peaks
+/// reached by climbing left and right from it.
+fn depth_scores(scores: &[f32]) -> Vec<f32> {
+    (0..scores.len())
+        .map(|i| {
+            let mut left = scores[i];
+            for &score in scores[..i].iter().rev() {
+                if score < left {
+                    break;
+                }
+                left = score;
+            }
+            let mut right = scores[i];
+            for &score in &scores[i + 1..] {
+                if score < right {
+                    break;
+                }
+                right = score;
+            }
+            (left - scores[i]) + (right - scores[i])
+        })
+        .collect()
+}
+
+/// Title and keywords of each chapter text. Words are weighted by how
+/// specific they are to the chapter (TF-IDF across chapters); a repeated
+/// two-word phrase beats its words alone.
+fn titles(texts: &[String]) -> Vec<(String, Vec<String>)> {
+    let docs: Vec<Vec<String>> = texts.iter().map(|t| words(t)).collect();
+    let mut df: HashMap<&str, usize> = HashMap::new();
+    for doc in &docs {
+        let mut seen: Vec<&str> = doc
+            .iter()
+            .map(String::as_str)
+            .filter(|w| is_term(w))
+            .collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for term in seen {
+            *df.entry(term).or_default() += 1;
+        }
+    }
+    let n = docs.len() as f32;
+    let idf = |term: &str| (n / df.get(term).copied().unwrap_or(1) as f32).ln() + 1.0;
+
+    docs.iter()
+        .map(|doc| {
+            let mut unigrams: HashMap<&str, usize> = HashMap::new();
+            let mut bigrams: HashMap<(&str, &str), usize> = HashMap::new();
+            for (i, word) in doc.iter().enumerate() {
+                if !is_term(word) {
+                    continue;
+                }
+                *unigrams.entry(word).or_default() += 1;
+                if let Some(next) = doc.get(i + 1).filter(|next| is_term(next)) {
+                    if next != word {
+                        *bigrams.entry((word, next)).or_default() += 1;
+                    }
+                }
+            }
+
+            let mut keywords: Vec<(&str, f32)> = unigrams
+                .iter()
+                .map(|(&term, &count)| (term, count as f32 * idf(term)))
+                .collect();
+            keywords.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+
+            let mut phrases: Vec<(String, f32)> = bigrams
+                .iter()
+                .filter(|(_, &count)| count >= MIN_PHRASE_COUNT)
+                .map(|(&(a, b), &count)| (format!("{a} {b}"), count as f32 * (idf(a) + idf(b))))
+                .chain(
+                    keywords
+                        .iter()
+                        .map(|&(term, score)| (term.to_string(), score)),
+                )
+                .collect();
+            phrases.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+            // Best phrase, plus the next one that doesn't repeat its words.
+            let mut title: Vec<&str> = Vec::new();
+            for (phrase, _) in &phrases {
+                let overlaps = title
+                    .iter()
+                    .any(|picked| picked.split(' ').any(|w| phrase.split(' ').any(|p| p == w)));
+                if !overlaps {
+                    title.push(phrase);
+                }
+                if title.len() == 2 {
+                    break;
+                }
+            }
+
+            (
+                capitalize(&title.join(" and ")),
+                keywords
+                    .into_iter()
+                    .take(CHAPTER_KEYWORDS)
+                    .map(|(term, _)| term.to_string())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Segment;
+    use uuid::Uuid;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> Segment {
+        Segment {
+            id: Uuid::new_v4(),
+            text: text.to_string(),
+            raw_text: None,
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker: None,
+            is_final: true,
+        }
+    }
+
+    /// Two topics, ten 30 s segments each.
+    fn two_topics() -> Transcript {
+        let budget = "The budget review shows marketing spend above the forecast. \
+                      Budget cuts to marketing spend help.";
+        let hiring = "Hiring for the backend team is slow. \
+                      The backend team needs more engineers on the hiring panel.";
+        let mut t = Transcript::new();
+        t.segments = (0..20)
+            .map(|i| {
+                let text = if i < 10 { budget } else { hiring };
+                segment(text, i * 30_000, i * 30_000 + 29_000)
+            })
+            .collect();
+        t
+    }
+
+    #[test]
+    fn test_chapters_split_at_topic_shift() {
+        let t = two_topics();
+        let options = ChapterOptions {
+            window_terms: 10,
+            block_windows: 3,
+            min_chapter_ms: 60_000,
+        };
+        let chapters = t.segment_chapters(&options);
+
+        let ranges: Vec<(u64, u64)> = chapters.iter().map(|c| (c.start_ms, c.end_ms)).collect();
+        assert_eq!(ranges, vec![(0, 300_000), (300_000, 599_000)]);
+        assert_eq!(chapters[0].title, "Marketing spend and budget");
+        assert_eq!(chapters[1].title, "Backend team and hiring");
+        assert_eq!(chapters[0].keywords[0], "budget");
+    }
+
+    #[test]
+    fn test_short_transcript_is_one_chapter() {
+        let mut t = Transcript::new();
+        t.segments = vec![
+            segment("Release notes for the mobile app.", 0, 3000),
+            segment("The mobile app release is Friday.", 3000, 6000),
+        ];
+        let chapters = t.segment_chapters(&ChapterOptions::default());
+        assert_eq!(chapters.len(), 1);
+        assert_eq!((chapters[0].start_ms, chapters[0].end_ms), (0, 6000));
+        assert_eq!(chapters[0].title, "Mobile app and release");
+        assert!(Transcript::new()
+            .segment_chapters(&ChapterOptions::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_suggest_title() {
+        let mut t = two_topics();
+        assert_eq!(t.suggest_title(Some("Zoom")).as_deref(), Some("Zoom"));
+        assert_eq!(t.suggest_title(None), None);
+
+        t.chapters = vec![
+            Chapter {
+                start_ms: 0,
+                end_ms: 100_000,
+                title: "Budget".to_string(),
+                keywords: Vec::new(),
+            },
+            Chapter {
+                start_ms: 100_000,
+                end_ms: 600_000,
+                title: "Hiring".to_string(),
+                keywords: Vec::new(),
+            },
+        ];
+        assert_eq!(
+            t.suggest_title(Some(" Zoom ")).as_deref(),
+            Some("Hiring · Zoom")
+        );
+        assert_eq!(t.suggest_title(Some("")).as_deref(), Some("Hiring"));
+    }
+}
@@ -0,0 +1,522 @@
+//! Offline meeting digest: extractive summary, action items, decisions and
+//! participants, computed from the transcript alone.
+//!
+//! The summary ranks sentences by centrality (LexRank): sentences are
+//! TF-IDF vectors, and a sentence scores high when it is similar to many
+//! other high-scoring sentences. Action items and decisions come from
+//! stored markers plus sentences matching cue phrases ("I'll send...",
+//! "we decided...").
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::lexical::{cosine, terms};
+use crate::{MarkerKind, Transcript};
+
+/// Sentences shorter than this (in words) are not summary candidates.
+const MIN_SUMMARY_WORDS: usize = 5;
+
+/// Sentences at least this similar to one already picked are skipped.
+const DUPLICATE_SIMILARITY: f32 = 0.7;
+
+const DAMPING: f32 = 0.85;
+const RANK_ITERATIONS: usize = 50;
+
+const ACTION_CUES: &[&str] = &[
+    "i'll",
+    "i will",
+    "i can take",
+    "i'm going to",
+    "we'll need to",
+    "we need to",
+    "you need to",
+    "needs to",
+    "can you",
+    "could you",
+    "please",
+    "make sure",
+    "action item",
+    "todo",
+    "to do",
+    "follow up",
+    "take care of",
+    "by tomorrow",
+    "by monday",
+    "by tuesday",
+    "by wednesday",
+    "by thursday",
+    "by friday",
+    "by next week",
+    "by end of",
+];
+
+const DECISION_CUES: &[&str] = &[
+    "we decided",
+    "we've decided",
+    "we have decided",
+    "decided to",
+    "decision is",
+    "let's go with",
+    "we'll go with",
+    "we're going with",
+    "going forward we",
+    "agreed",
+    "we agree",
+    "settled on",
+    "approved",
+    "final answer",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestOptions {
+    /// Maximum sentences in the extractive summary.
+    pub summary_sentences: usize,
+}
+
+impl Default for DigestOptions {
+    fn default() -> Self {
+        Self {
+            summary_sentences: 5,
+        }
+    }
+}
+
+/// A sentence quoted from the transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestSentence {
+    pub text: String,
+    pub start_ms: u64,
+    pub speaker: Option<i32>,
+}
+
+/// Where an action item or decision was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestSource {
+    /// A marker placed during or after the meeting.
+    Marker,
+    /// A sentence matching a cue phrase. Candidates, not certainties.
+    Cue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestItem {
+    pub text: String,
+    pub start_ms: u64,
+    pub speaker: Option<i32>,
+    pub source: DigestSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Participant {
+    pub speaker: i32,
+    pub name: String,
+    pub talk_time_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Digest {
+    /// Highest-ranked sentences, in transcript order.
+    pub summary: Vec<DigestSentence>,
+    pub action_items: Vec<DigestItem>,
+    pub decisions: Vec<DigestItem>,
+    /// Diarized speakers by talk time; empty without speaker labels.
+    pub participants: Vec<Participant>,
+    pub generated_at: DateTime<Utc>,
+}
+
+struct Sentence {
+    text: String,
+    start_ms: u64,
+    end_ms: u64,
+    speaker: Option<i32>,
+}
+
+impl Transcript {
+    /// Build a digest of the transcript. Runs locally; nothing leaves the
+    /// machine.
+    pub fn summarize(&self, options: &DigestOptions) -> Digest {
+        let sentences = self.sentences();
+        Digest {
+            summary: summary(&sentences, options.summary_sentences),
+            action_items: self.items(&sentences, &[MarkerKind::ActionItem, MarkerKind::FollowUp]),
+            decisions: self.items(&sentences, &[MarkerKind::Decision]),
+            participants: self.participants(),
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// Segments split into sentences, with times interpolated by character
+    /// position inside each segment.
+    fn sentences(&self) -> Vec<Sentence> {
+        let mut sentences = Vec::new();
+        for segment in &self.segments {
+            let text = segment.text.trim();
+            let total = text.chars().count().max(1) as u64;
+            let span = segment.end_ms.saturating_sub(segment.start_ms);
+            let at = |chars: usize| segment.start_ms + span * chars as u64 / total;
+
+            let mut start = 0;
+            let mut chars_before = 0;
+            let mut sentence_chars = 0;
+            let mut chars = text.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                chars_before += 1;
+                let boundary = matches!(c, '.' | '?' | '!')
+                    && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+                if boundary || chars.peek().is_none() {
+                    let end = i + c.len_utf8();
+                    let sentence = text[start..end].trim();
+                    if !sentence.is_empty() {
+                        sentences.push(Sentence {
+                            text: sentence.to_string(),
+                            start_ms: at(sentence_chars),
+                            end_ms: at(chars_before),
+                            speaker: segment.speaker,
+                        });
+                    }
+                    start = end;
+                    sentence_chars = chars_before;
+                }
+            }
+        }
+        sentences
+    }
+
+    /// Items from markers of `kinds`, then cue-phrase matches that no
+    /// marker already covers. Sorted by time.
+    fn items(&self, sentences: &[Sentence], kinds: &[MarkerKind]) -> Vec<DigestItem> {
+        let markers: Vec<_> = self
+            .markers
+            .iter()
+            .filter(|m| kinds.contains(&m.kind))
+            .collect();
+        let cues = if kinds.contains(&MarkerKind::Decision) {
+            DECISION_CUES
+        } else {
+            ACTION_CUES
+        };
+
+        let mut items: Vec<DigestItem> = markers
+            .iter()
+            .map(|marker| {
+                let span = marker.span.as_ref();
+                DigestItem {
+                    text: marker
+                        .note
+                        .clone()
+                        .or_else(|| span.map(|s| s.text.clone()))
+                        .unwrap_or_default(),
+                    start_ms: span.map_or(marker.timestamp_ms, |s| s.start_ms),
+                    speaker: self
+                        .segments
+                        .iter()
+                        .find(|s| span.is_some_and(|span| s.start_ms == span.start_ms))
+                        .and_then(|s| s.speaker),
+                    source: DigestSource::Marker,
+                    marker_id: Some(marker.id),
+                }
+            })
+            .collect();
+
+        for sentence in sentences {
+            let covered = markers.iter().any(|m| {
+                m.span.as_ref().map_or(
+                    (sentence.start_ms..=sentence.end_ms).contains(&m.timestamp_ms),
+                    |span| span.start_ms <= sentence.start_ms && sentence.end_ms <= span.end_ms,
+                )
+            });
+            if !covered && matches_cue(&sentence.text, cues) {
+                items.push(DigestItem {
+                    text: sentence.text.clone(),
+                    start_ms: sentence.start_ms,
+                    speaker: sentence.speaker,
+                    source: DigestSource::Cue,
+                    marker_id: None,
+                });
+            }
+        }
+        items.sort_by_key(|item| item.start_ms);
+        items
+    }
+
+    fn participants(&self) -> Vec<Participant> {
+        let mut participants: Vec<Participant> = self
+            .speakers()
+            .into_iter()
+            .map(|speaker| Participant {
+                speaker,
+                name: self.speaker_name(speaker),
+                talk_time_ms: self
+                    .segments
+                    .iter()
+                    .filter(|s| s.speaker == Some(speaker))
+                    .map(|s| s.end_ms.saturating_sub(s.start_ms))
+                    .sum(),
+            })
+            .collect();
+        participants.sort_by_key(|p| std::cmp::Reverse(p.talk_time_ms));
+        participants
+    }
+}
+
+/// Whether `text` contains one of `cues` as whole words.
+fn matches_cue(text: &str, cues: &[&str]) -> bool {
+    let normalized: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '\'' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let padded = format!(
+        " {} ",
+        normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+    );
+    cues.iter().any(|cue| padded.contains(&format!(" {cue} ")))
+}
+
+/// TF-IDF vector of each sentence, L2-normalized.
+fn tfidf(sentences: &[&Sentence]) -> Vec<HashMap<String, f32>> {
+    let docs: Vec<Vec<String>> = sentences.iter().map(|s| terms(&s.text)).collect();
+    let mut df: HashMap<&str, usize> = HashMap::new();
+    for doc in &docs {
+        let mut seen: Vec<&str> = doc.iter().map(String::as_str).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for term in seen {
+            *df.entry(term).or_default() += 1;
+        }
+    }
+
+    let n = docs.len() as f32;
+    docs.iter()
+        .map(|doc| {
+            let mut vector: HashMap<String, f32> = HashMap::new();
+            for term in doc {
+                let idf = (n / df[term.as_str()] as f32).ln() + 1.0;
+                *vector.entry(term.clone()).or_default() += idf;
+            }
+            let norm = vector.values().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                vector.values_mut().for_each(|v| *v /= norm);
+            }
+            vector
+        })
+        .collect()
+}
+
+/// LexRank: PageRank over the sentence similarity graph.
+fn summary(sentences: &[Sentence], limit: usize) -> Vec<DigestSentence> {
+    let candidates: Vec<&Sentence> = sentences
+        .iter()
+        .filter(|s| s.text.split_whitespace().count() >= MIN_SUMMARY_WORDS)
+        .collect();
+    if candidates.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+
+    let vectors = tfidf(&candidates);
+    let n = candidates.len();
+    let similarity: Vec<Vec<f32>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    if i == j {
+                        0.0
+                    } else {
+                        cosine(&vectors[i], &vectors[j])
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let out_weight: Vec<f32> = similarity.iter().map(|row| row.iter().sum()).collect();
+
+    let mut scores = vec![1.0 / n as f32; n];
+    for _ in 0..RANK_ITERATIONS {
+        scores = (0..n)
+            .map(|i| {
+                let incoming: f32 = (0..n)
+                    .filter(|&j| out_weight[j] > 0.0)
+                    .map(|j| similarity[j][i] / out_weight[j] * scores[j])
+                    .sum();
+                (1.0 - DAMPING) / n as f32 + DAMPING * incoming
+            })
+            .collect();
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+
+    let mut picked: Vec<usize> = Vec::new();
+    for i in order {
+        if picked.len() == limit {
+            break;
+        }
+        if picked
+            .iter()
+            .all(|&p| similarity[p][i] < DUPLICATE_SIMILARITY)
+        {
+            picked.push(i);
+        }
+    }
+    picked.sort_unstable();
+    picked
+        .into_iter()
+        .map(|i| DigestSentence {
+            text: candidates[i].text.clone(),
+            start_ms: candidates[i].start_ms,
+            speaker: candidates[i].speaker,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Marker, Segment};
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64, speaker: Option<i32>) -> Segment {
+        Segment {
+            id: Uuid::new_v4(),
+            text: text.to_string(),
+            raw_text: None,
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker,
+            is_final: true,
+        }
+    }
+
+    fn meeting() -> Transcript {
+        let mut t = Transcript::new();
+        t.segments = vec![
+            segment(
+                "The launch date for the mobile app is the main topic today.",
+                0,
+                4000,
+                Some(0),
+            ),
+            segment("Sounds good.", 4500, 5000, Some(1)),
+            segment(
+                "The mobile app launch depends on the payment review. \
+                 We decided to launch the mobile app on March 3.",
+                5000,
+                12_000,
+                Some(1),
+            ),
+            segment(
+                "I'll send the payment review notes to Anna by Friday.",
+                12_500,
+                15_000,
+                Some(0),
+            ),
+            segment(
+                "My cat knocked over a plant this morning.",
+                16_000,
+                18_000,
+                Some(2),
+            ),
+            segment(
+                "Can you update the app store listing?",
+                18_500,
+                20_000,
+                Some(1),
+            ),
+        ];
+        t.rename_speaker(0, "Anna");
+        t
+    }
+
+    #[test]
+    fn test_sentences_split_with_times() {
+        let sentences = meeting().sentences();
+        assert_eq!(sentences.len(), 7);
+        assert_eq!(
+            sentences[2].text,
+            "The mobile app launch depends on the payment review."
+        );
+        assert_eq!(sentences[2].start_ms, 5000);
+        assert_eq!(sentences[3].start_ms, sentences[2].end_ms);
+        assert_eq!(sentences[3].end_ms, 12_000);
+    }
+
+    #[test]
+    fn test_summary_prefers_central_sentences() {
+        let digest = meeting().summarize(&DigestOptions {
+            summary_sentences: 2,
+        });
+        let summary: Vec<&str> = digest.summary.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(summary.len(), 2);
+        assert!(summary.iter().all(|s| s.contains("mobile app")));
+        assert!(digest.summary[0].start_ms < digest.summary[1].start_ms);
+    }
+
+    #[test]
+    fn test_action_items_and_decisions() {
+        let mut t = meeting();
+        t.add_marker(Marker {
+            note: Some("Book the launch party".to_string()),
+            ..Marker::new(MarkerKind::ActionItem, 16_500)
+        });
+        let digest = t.summarize(&DigestOptions::default());
+
+        let actions: Vec<(&str, DigestSource)> = digest
+            .action_items
+            .iter()
+            .map(|i| (i.text.as_str(), i.source))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (
+                    "I'll send the payment review notes to Anna by Friday.",
+                    DigestSource::Cue
+                ),
+                ("Book the launch party", DigestSource::Marker),
+                ("Can you update the app store listing?", DigestSource::Cue),
+            ]
+        );
+        assert_eq!(digest.action_items[1].speaker, Some(2));
+
+        assert_eq!(digest.decisions.len(), 1);
+        assert_eq!(
+            digest.decisions[0].text,
+            "We decided to launch the mobile app on March 3."
+        );
+        assert_eq!(digest.decisions[0].speaker, Some(1));
+    }
+
+    #[test]
+    fn test_participants_by_talk_time() {
+        let digest = meeting().summarize(&DigestOptions::default());
+        let participants: Vec<(&str, u64)> = digest
+            .participants
+            .iter()
+            .map(|p| (p.name.as_str(), p.talk_time_ms))
+            .collect();
+        assert_eq!(
+            participants,
+            vec![("Speaker 2", 9000), ("Anna", 6500), ("Speaker 3", 2000)]
+        );
+
+        let mut unlabeled = meeting();
+        unlabeled.segments.iter_mut().for_each(|s| s.speaker = None);
+        assert!(unlabeled
+            .summarize(&DigestOptions::default())
+            .participants
+            .is_empty());
+    }
+}
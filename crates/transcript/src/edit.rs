@@ -0,0 +1,646 @@
+//! Transcript editing with an append-only revision log.
+//!
+//! Edits change `Transcript::segments` in place and are recorded in
+//! `Transcript::revisions` together with a snapshot of the segments before
+//! the first edit. Undo and redo are log entries too, so the state as of
+//! any revision is replayed from the snapshot and nothing is ever dropped.
+//!
+//! Words stay consistent with text: after an edit, a segment's `words`
+//! (when it has any) are its whitespace-separated tokens. Unchanged tokens
+//! keep their timings; new ones are spread over the gap they fill.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Segment, Transcript, Word};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditError {
+    #[error("segment not found: {0}")]
+    SegmentNotFound(Uuid),
+    #[error("invalid position: {0}")]
+    InvalidPosition(String),
+    #[error("segment text cannot be empty")]
+    EmptyText,
+    #[error("nothing to undo")]
+    NothingToUndo,
+    #[error("nothing to redo")]
+    NothingToRedo,
+    #[error("unknown revision: {0}")]
+    UnknownRevision(usize),
+}
+
+/// A word boundary inside a segment: before word `word` (0-based), or at
+/// the end when `word` equals the segment's word count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordPosition {
+    pub segment_id: Uuid,
+    pub word: usize,
+}
+
+/// An edit to the segments of a transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TranscriptEdit {
+    /// Replace the text of a segment.
+    EditText { segment_id: Uuid, text: String },
+    /// Split a segment before word `at_word`. The second half gets
+    /// `new_segment_id`, generated when the edit is created so that
+    /// replaying the log is deterministic.
+    Split {
+        segment_id: Uuid,
+        at_word: usize,
+        #[serde(default = "Uuid::new_v4")]
+        new_segment_id: Uuid,
+    },
+    /// Merge a segment with the one after it.
+    Merge { segment_id: Uuid },
+    /// Assign (or clear) the speaker of segments.
+    SetSpeaker {
+        segment_ids: Vec<Uuid>,
+        speaker: Option<i32>,
+    },
+    /// Delete the words from `start` up to `end`, possibly across
+    /// segments. Segments left empty are removed.
+    DeleteRange {
+        start: WordPosition,
+        end: WordPosition,
+    },
+}
+
+/// What a revision did.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RevisionAction {
+    Edit { edit: TranscriptEdit },
+    Undo,
+    Redo,
+}
+
+/// One entry of the revision log. Revision numbers start at 1; revision 0
+/// is the transcript as first saved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    pub number: usize,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub action: RevisionAction,
+}
+
+/// Append-only log of edits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RevisionLog {
+    /// Segments before the first edit.
+    base: Vec<Segment>,
+    entries: Vec<Revision>,
+}
+
+impl RevisionLog {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of the latest revision (0 when never edited).
+    pub fn current(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> &[Revision] {
+        &self.entries
+    }
+
+    /// Edits in effect and undone edits available to redo, after the
+    /// first `revision` entries.
+    fn stacks(&self, revision: usize) -> (Vec<&TranscriptEdit>, Vec<&TranscriptEdit>) {
+        let mut applied = Vec::new();
+        let mut undone = Vec::new();
+        for entry in &self.entries[..revision] {
+            match &entry.action {
+                RevisionAction::Edit { edit } => {
+                    applied.push(edit);
+                    undone.clear();
+                }
+                RevisionAction::Undo => undone.extend(applied.pop()),
+                RevisionAction::Redo => applied.extend(undone.pop()),
+            }
+        }
+        (applied, undone)
+    }
+
+    /// Segments as of `revision`.
+    fn segments_at(&self, revision: usize) -> Result<Vec<Segment>, EditError> {
+        let (applied, _) = self.stacks(revision);
+        let mut segments = self.base.clone();
+        for edit in applied {
+            apply(&mut segments, edit)?;
+        }
+        Ok(segments)
+    }
+
+    fn push(&mut self, action: RevisionAction) -> usize {
+        let number = self.entries.len() + 1;
+        self.entries.push(Revision {
+            number,
+            created_at: Utc::now(),
+            action,
+        });
+        number
+    }
+}
+
+impl Transcript {
+    /// Apply an edit and record it. Returns the new revision number.
+    pub fn apply_edit(&mut self, edit: TranscriptEdit) -> Result<usize, EditError> {
+        let mut segments = self.segments.clone();
+        apply(&mut segments, &edit)?;
+
+        if self.revisions.is_empty() {
+            self.revisions.base = std::mem::take(&mut self.segments);
+        }
+        self.segments = segments;
+        self.updated_at = Utc::now();
+        Ok(self.revisions.push(RevisionAction::Edit { edit }))
+    }
+
+    /// Undo the last edit in effect.
+    pub fn undo(&mut self) -> Result<usize, EditError> {
+        if !self.can_undo() {
+            return Err(EditError::NothingToUndo);
+        }
+        self.record(RevisionAction::Undo)
+    }
+
+    /// Redo the last undone edit.
+    pub fn redo(&mut self) -> Result<usize, EditError> {
+        if !self.can_redo() {
+            return Err(EditError::NothingToRedo);
+        }
+        self.record(RevisionAction::Redo)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.revisions.stacks(self.revisions.current()).0.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.revisions.stacks(self.revisions.current()).1.is_empty()
+    }
+
+    /// The transcript as it was at `revision` (0 is the original).
+    pub fn at_revision(&self, revision: usize) -> Result<Transcript, EditError> {
+        if revision > self.revisions.current() {
+            return Err(EditError::UnknownRevision(revision));
+        }
+        let mut transcript = self.clone();
+        if revision < self.revisions.current() {
+            transcript.segments = self.revisions.segments_at(revision)?;
+        }
+        Ok(transcript)
+    }
+
+    fn record(&mut self, action: RevisionAction) -> Result<usize, EditError> {
+        let number = self.revisions.push(action);
+        self.segments = self.revisions.segments_at(number)?;
+        self.updated_at = Utc::now();
+        Ok(number)
+    }
+}
+
+fn apply(segments: &mut Vec<Segment>, edit: &TranscriptEdit) -> Result<(), EditError> {
+    match edit {
+        TranscriptEdit::EditText { segment_id, text } => {
+            let tokens = tokens(text);
+            if tokens.is_empty() {
+                return Err(EditError::EmptyText);
+            }
+            let index = find(segments, segment_id)?;
+            let segment = &mut segments[index];
+            segment.words = realign(segment, &tokens);
+            segment.text = tokens.join(" ");
+            segment.raw_text = None;
+        }
+        TranscriptEdit::Split {
+            segment_id,
+            at_word,
+            new_segment_id,
+        } => {
+            let index = find(segments, segment_id)?;
+            let segment = &mut segments[index];
+            let words = tokens(&segment.text);
+            if *at_word == 0 || *at_word >= words.len() {
+                return Err(EditError::InvalidPosition(format!(
+                    "cannot split {} words at {at_word}",
+                    words.len()
+                )));
+            }
+            let times = token_times(segment);
+            let aligned = realign(segment, &words);
+            let boundary_ms = times[*at_word].0;
+
+            let second = Segment {
+                id: *new_segment_id,
+                text: words[*at_word..].join(" "),
+                raw_text: None,
+                start_ms: boundary_ms,
+                end_ms: segment.end_ms,
+                words: aligned.get(*at_word..).unwrap_or_default().to_vec(),
+                speaker: segment.speaker,
+                is_final: segment.is_final,
+            };
+            segment.text = words[..*at_word].join(" ");
+            segment.raw_text = None;
+            segment.end_ms = boundary_ms;
+            segment.words.clone_from(&aligned);
+            segment.words.truncate(*at_word);
+            segments.insert(index + 1, second);
+        }
+        TranscriptEdit::Merge { segment_id } => {
+            let index = find(segments, segment_id)?;
+            if index + 1 >= segments.len() {
+                return Err(EditError::InvalidPosition(
+                    "no segment after the last one".to_string(),
+                ));
+            }
+            let next = segments.remove(index + 1);
+            let segment = &mut segments[index];
+            let mut words = realign(segment, &tokens(&segment.text));
+            words.extend(realign(&next, &tokens(&next.text)));
+            // Word timings only survive if both halves had them.
+            if segment.words.is_empty() || next.words.is_empty() {
+                words.clear();
+            }
+            segment.text = format!("{} {}", segment.text.trim(), next.text.trim());
+            segment.raw_text = None;
+            segment.end_ms = segment.end_ms.max(next.end_ms);
+            segment.words = words;
+        }
+        TranscriptEdit::SetSpeaker {
+            segment_ids,
+            speaker,
+        } => {
+            let indices = segment_ids
+                .iter()
+                .map(|id| find(segments, id))
+                .collect::<Result<Vec<_>, _>>()?;
+            for index in indices {
+                segments[index].speaker = *speaker;
+            }
+        }
+        TranscriptEdit::DeleteRange { start, end } => delete_range(segments, start, end)?,
+    }
+    Ok(())
+}
+
+fn delete_range(
+    segments: &mut Vec<Segment>,
+    start: &WordPosition,
+    end: &WordPosition,
+) -> Result<(), EditError> {
+    let first = find(segments, &start.segment_id)?;
+    let last = find(segments, &end.segment_id)?;
+    let invalid = || EditError::InvalidPosition("range end is before its start".to_string());
+    if last < first || (first == last && end.word <= start.word) {
+        return Err(invalid());
+    }
+    let start_len = tokens(&segments[first].text).len();
+    let end_len = tokens(&segments[last].text).len();
+    if start.word >= start_len || end.word > end_len {
+        return Err(EditError::InvalidPosition(
+            "word index past the end of the segment".to_string(),
+        ));
+    }
+
+    let mut index = first;
+    for k in first..=last {
+        let segment = &mut segments[index];
+        let words = tokens(&segment.text);
+        let from = if k == first { start.word } else { 0 };
+        let to = if k == last { end.word } else { words.len() };
+
+        if from == 0 && to == words.len() {
+            segments.remove(index);
+            continue;
+        }
+
+        let times = token_times(segment);
+        let mut aligned = realign(segment, &words);
+        let had_words = !segment.words.is_empty();
+        if from == 0 {
+            segment.start_ms = times[to].0;
+        }
+        if to == words.len() {
+            segment.end_ms = times[from - 1].1;
+        }
+        let kept: Vec<&str> = words[..from].iter().chain(&words[to..]).copied().collect();
+        segment.text = kept.join(" ");
+        segment.raw_text = None;
+        if had_words {
+            aligned.drain(from..to);
+            segment.words = aligned;
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+fn find(segments: &[Segment], id: &Uuid) -> Result<usize, EditError> {
+    segments
+        .iter()
+        .position(|s| s.id == *id)
+        .ok_or(EditError::SegmentNotFound(*id))
+}
+
+fn tokens(text: &str) -> Vec<&str> {
+    text.split_whitespace().collect()
+}
+
+/// Start and end of each token: from word timings when the segment has
+/// them, otherwise interpolated by character count.
+fn token_times(segment: &Segment) -> Vec<(u64, u64)> {
+    let words = tokens(&segment.text);
+    if !segment.words.is_empty() {
+        return realign(segment, &words)
+            .iter()
+            .map(|w| (w.start_ms, w.end_ms))
+            .collect();
+    }
+
+    let total: usize = words.iter().map(|w| w.chars().count()).sum();
+    let span = segment.end_ms.saturating_sub(segment.start_ms);
+    let mut before = 0;
+    words
+        .iter()
+        .map(|word| {
+            let start = segment.start_ms + span * before as u64 / total.max(1) as u64;
+            before += word.chars().count();
+            let end = segment.start_ms + span * before as u64 / total.max(1) as u64;
+            (start, end)
+        })
+        .collect()
+}
+
+/// Words for `tokens`, reusing the segment's word timings.
+///
+/// Tokens matching an existing word (longest common subsequence, ignoring
+/// case and punctuation) keep its timing and confidence. Runs of new
+/// tokens share the gap between their matched neighbours. Segments without
+/// word timings stay without.
+fn realign(segment: &Segment, tokens: &[&str]) -> Vec<Word> {
+    let old = &segment.words;
+    if old.is_empty() {
+        return Vec::new();
+    }
+
+    let key = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let old_keys: Vec<String> = old.iter().map(|w| key(&w.text)).collect();
+    let new_keys: Vec<String> = tokens.iter().map(|t| key(t)).collect();
+
+    let mut lcs = vec![vec![0usize; new_keys.len() + 1]; old_keys.len() + 1];
+    for i in (0..old_keys.len()).rev() {
+        for j in (0..new_keys.len()).rev() {
+            lcs[i][j] = if old_keys[i] == new_keys[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Matched old word for each new token.
+    let mut matches: Vec<Option<usize>> = vec![None; tokens.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old_keys.len() && j < new_keys.len() {
+        if old_keys[i] == new_keys[j] {
+            matches[j] = Some(i);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    let mut words: Vec<Word> = Vec::with_capacity(tokens.len());
+    let mut j = 0;
+    while j < tokens.len() {
+        if let Some(i) = matches[j] {
+            words.push(Word {
+                text: tokens[j].to_string(),
+                ..old[i].clone()
+            });
+            j += 1;
+            continue;
+        }
+
+        // A run of new tokens between two anchors.
+        let run_end = (j..tokens.len())
+            .find(|&k| matches[k].is_some())
+            .unwrap_or(tokens.len());
+        let gap_start = words.last().map_or(segment.start_ms, |w| w.end_ms);
+        let gap_end = matches
+            .get(run_end)
+            .copied()
+            .flatten()
+            .map_or(segment.end_ms, |i| old[i].start_ms)
+            .max(gap_start);
+        let step = (gap_end - gap_start) / (run_end - j) as u64;
+        for (n, token) in tokens[j..run_end].iter().enumerate() {
+            let start = gap_start + step * n as u64;
+            words.push(Word {
+                text: token.to_string(),
+                start_ms: start,
+                end_ms: if j + n + 1 == run_end {
+                    gap_end
+                } else {
+                    start + step
+                },
+                confidence: 1.0,
+            });
+        }
+        j = run_end;
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64, timed: bool) -> Segment {
+        let words = if timed {
+            let count = text.split_whitespace().count() as u64;
+            let step = (end_ms - start_ms) / count;
+            text.split_whitespace()
+                .enumerate()
+                .map(|(i, w)| Word {
+                    text: w.to_string(),
+                    start_ms: start_ms + step * i as u64,
+                    end_ms: start_ms + step * (i as u64 + 1),
+                    confidence: 0.8,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Segment {
+            id: Uuid::new_v4(),
+            text: text.to_string(),
+            raw_text: None,
+            start_ms,
+            end_ms,
+            words,
+            speaker: Some(0),
+            is_final: true,
+        }
+    }
+
+    fn transcript(segments: Vec<Segment>) -> Transcript {
+        let mut transcript = Transcript::new();
+        transcript.segments = segments;
+        transcript
+    }
+
+    fn texts(transcript: &Transcript) -> Vec<&str> {
+        transcript
+            .segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_edit_text_keeps_word_timings() {
+        let mut t = transcript(vec![segment("eye scream for you", 0, 4000, true)]);
+        let id = t.segments[0].id;
+        t.apply_edit(TranscriptEdit::EditText {
+            segment_id: id,
+            text: "I scream for you.".to_string(),
+        })
+        .unwrap();
+
+        let words: Vec<(&str, u64, u64)> = t.segments[0]
+            .words
+            .iter()
+            .map(|w| (w.text.as_str(), w.start_ms, w.end_ms))
+            .collect();
+        assert_eq!(
+            words,
+            vec![
+                ("I", 0, 1000),
+                ("scream", 1000, 2000),
+                ("for", 2000, 3000),
+                ("you.", 3000, 4000)
+            ]
+        );
+        assert_eq!(t.segments[0].words[1].confidence, 0.8);
+        assert_eq!(t.segments[0].words[0].confidence, 1.0);
+    }
+
+    #[test]
+    fn test_split_and_merge() {
+        let mut t = transcript(vec![
+            segment("one two three four", 0, 4000, true),
+            segment("five", 5000, 6000, false),
+        ]);
+        let first = t.segments[0].id;
+        let second = Uuid::new_v4();
+        t.apply_edit(TranscriptEdit::Split {
+            segment_id: first,
+            at_word: 3,
+            new_segment_id: second,
+        })
+        .unwrap();
+        assert_eq!(texts(&t), vec!["one two three", "four", "five"]);
+        assert_eq!((t.segments[0].end_ms, t.segments[1].start_ms), (3000, 3000));
+        assert_eq!(t.segments[1].id, second);
+        assert_eq!(t.segments[1].words.len(), 1);
+
+        t.apply_edit(TranscriptEdit::Merge { segment_id: second })
+            .unwrap();
+        assert_eq!(texts(&t), vec!["one two three", "four five"]);
+        assert_eq!(t.segments[1].end_ms, 6000);
+        assert!(t.segments[1].words.is_empty());
+    }
+
+    #[test]
+    fn test_delete_range_across_segments() {
+        let mut t = transcript(vec![
+            segment("keep this drop", 0, 3000, true),
+            segment("all gone", 3000, 5000, false),
+            segment("drop keep", 5000, 7000, false),
+        ]);
+        let (a, c) = (t.segments[0].id, t.segments[2].id);
+        t.apply_edit(TranscriptEdit::DeleteRange {
+            start: WordPosition {
+                segment_id: a,
+                word: 2,
+            },
+            end: WordPosition {
+                segment_id: c,
+                word: 1,
+            },
+        })
+        .unwrap();
+
+        assert_eq!(texts(&t), vec!["keep this", "keep"]);
+        assert_eq!(t.segments[0].end_ms, 2000);
+        assert_eq!(t.segments[0].words.len(), 2);
+        assert_eq!(t.segments[1].start_ms, 6000);
+    }
+
+    #[test]
+    fn test_undo_redo_and_history() {
+        let mut t = transcript(vec![segment("hello world", 0, 1000, false)]);
+        let id = t.segments[0].id;
+        let edit = |text: &str| TranscriptEdit::EditText {
+            segment_id: id,
+            text: text.to_string(),
+        };
+
+        assert_eq!(t.apply_edit(edit("hello there")).unwrap(), 1);
+        assert_eq!(t.apply_edit(edit("hello again")).unwrap(), 2);
+        assert_eq!(t.undo().unwrap(), 3);
+        assert_eq!(texts(&t), vec!["hello there"]);
+        assert_eq!(t.redo().unwrap(), 4);
+        assert_eq!(texts(&t), vec!["hello again"]);
+
+        t.undo().unwrap();
+        t.apply_edit(edit("bye")).unwrap();
+        assert!(!t.can_redo());
+        assert!(matches!(t.redo(), Err(EditError::NothingToRedo)));
+
+        assert_eq!(texts(&t.at_revision(0).unwrap()), vec!["hello world"]);
+        assert_eq!(texts(&t.at_revision(2).unwrap()), vec!["hello again"]);
+        assert_eq!(texts(&t.at_revision(5).unwrap()), vec!["hello there"]);
+        assert_eq!(t.revisions.entries().len(), 6);
+        assert!(matches!(
+            t.at_revision(7),
+            Err(EditError::UnknownRevision(7))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_edits_are_not_recorded() {
+        let mut t = transcript(vec![segment("one two", 0, 1000, false)]);
+        let id = t.segments[0].id;
+        assert!(t
+            .apply_edit(TranscriptEdit::Split {
+                segment_id: id,
+                at_word: 2,
+                new_segment_id: Uuid::new_v4(),
+            })
+            .is_err());
+        assert!(t
+            .apply_edit(TranscriptEdit::Merge { segment_id: id })
+            .is_err());
+        assert!(t.revisions.is_empty());
+        assert!(matches!(t.undo(), Err(EditError::NothingToUndo)));
+    }
+}
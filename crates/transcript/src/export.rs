@@ -0,0 +1,680 @@
+//! Transcript exporters: subtitles (SRT, WebVTT) and documents (JSON,
+//! Markdown, plain text).
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Chapter, Marker, Segment, Transcript};
+
+/// Lines per subtitle cue.
+const MAX_CUE_LINES: usize = 2;
+
+/// A pause (ms) that starts a new paragraph in documents, even without a
+/// speaker change.
+const PARAGRAPH_GAP_MS: u64 = 3000;
+
+/// Output format of [`Transcript::export`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    #[serde(alias = "webvtt")]
+    Vtt,
+    Json,
+    #[serde(alias = "md")]
+    Markdown,
+    #[serde(alias = "txt")]
+    Text,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Json => "json",
+            Self::Markdown => "md",
+            Self::Text => "txt",
+        }
+    }
+}
+
+/// Layout options for subtitle exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// Maximum characters per subtitle line (cues have up to two lines).
+    pub max_line_chars: usize,
+    /// Maximum duration of a subtitle cue (ms).
+    pub max_cue_ms: u64,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            max_line_chars: 42,
+            max_cue_ms: 7000,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Clamp values into workable ranges.
+    pub fn sanitized(self) -> Self {
+        Self {
+            max_line_chars: self.max_line_chars.clamp(16, 200),
+            max_cue_ms: self.max_cue_ms.clamp(1000, 60_000),
+        }
+    }
+}
+
+impl Transcript {
+    /// Render the transcript in `format`.
+    pub fn export(&self, format: ExportFormat, options: &ExportOptions) -> String {
+        let options = options.sanitized();
+        match format {
+            ExportFormat::Srt => self.to_srt(&options),
+            ExportFormat::Vtt => self.to_vtt(&options),
+            ExportFormat::Json => self.to_json_export(),
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Text => self.to_text(),
+        }
+    }
+
+    fn to_srt(&self, options: &ExportOptions) -> String {
+        let mut out = String::new();
+        for (i, cue) in self.cues(options).iter().enumerate() {
+            out.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                timestamp(cue.start_ms, ','),
+                timestamp(cue.end_ms, ','),
+                cue.lines.join("\n")
+            ));
+        }
+        out
+    }
+
+    fn to_vtt(&self, options: &ExportOptions) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        let mut markers = self.markers.iter().peekable();
+        for cue in self.cues(options) {
+            // Markers become NOTE blocks ahead of the cue they fall before.
+            while let Some(marker) = markers.next_if(|m| m.timestamp_ms < cue.end_ms) {
+                out.push_str(&format!("NOTE {}\n\n", vtt_note(&marker_line(marker))));
+            }
+            let text = cue
+                .lines
+                .iter()
+                .map(|line| escape_vtt(line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let text = match cue.speaker {
+                Some(speaker) => {
+                    format!("<v {}>{}", escape_vtt(&self.speaker_name(speaker)), text)
+                }
+                None => text,
+            };
+            out.push_str(&format!(
+                "{} --> {}\n{}\n\n",
+                timestamp(cue.start_ms, '.'),
+                timestamp(cue.end_ms, '.'),
+                text
+            ));
+        }
+        for marker in markers {
+            out.push_str(&format!("NOTE {}\n\n", vtt_note(&marker_line(marker))));
+        }
+        out
+    }
+
+    fn to_json_export(&self) -> String {
+        let document = JsonTranscript {
+            id: self.id.to_string(),
+            title: self.title.clone(),
+            created_at: self.created_at.to_rfc3339(),
+            duration_ms: self.duration_ms,
+            speakers: self
+                .speakers()
+                .into_iter()
+                .map(|id| JsonSpeaker {
+                    id,
+                    name: self.speaker_name(id),
+                })
+                .collect(),
+            segments: self
+                .segments
+                .iter()
+                .map(|segment| JsonSegment {
+                    start_ms: segment.start_ms,
+                    end_ms: segment.end_ms,
+                    speaker: segment.speaker,
+                    text: segment.text.clone(),
+                    raw_text: segment.raw_text.clone(),
+                    words: segment
+                        .words
+                        .iter()
+                        .map(|word| JsonWord {
+                            text: word.text.clone(),
+                            start_ms: word.start_ms,
+                            end_ms: word.end_ms,
+                            confidence: word.confidence,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            markers: self.markers.clone(),
+            chapters: self.chapters.clone(),
+        };
+        let mut json =
+            serde_json::to_string_pretty(&document).expect("transcript JSON is serializable");
+        json.push('\n');
+        json
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# {}\n\n*{} · {}*\n",
+            self.title.as_deref().unwrap_or("Transcript"),
+            self.created_at.format("%Y-%m-%d %H:%M"),
+            duration(self.duration_ms)
+        );
+        for paragraph in self.paragraphs() {
+            if let Some(chapter) = paragraph.chapter {
+                out.push_str(&format!("\n## {}\n", self.chapters[chapter].title));
+            }
+            let heading = match paragraph.speaker {
+                Some(speaker) => format!("**{}** ", self.speaker_name(speaker)),
+                None => String::new(),
+            };
+            out.push_str(&format!(
+                "\n{}[{}]\n{}\n",
+                heading,
+                duration(paragraph.start_ms),
+                paragraph.text
+            ));
+        }
+        if !self.markers.is_empty() {
+            out.push_str("\n## Markers\n\n");
+            for marker in &self.markers {
+                out.push_str(&format!("- {}\n", marker_line(marker)));
+            }
+        }
+        out
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = self
+            .paragraphs()
+            .into_iter()
+            .map(|paragraph| {
+                let heading = match paragraph.chapter {
+                    Some(chapter) => {
+                        let title = &self.chapters[chapter].title;
+                        format!("{title}\n{}\n\n", "-".repeat(title.chars().count()))
+                    }
+                    None => String::new(),
+                };
+                match paragraph.speaker {
+                    Some(speaker) => format!(
+                        "{heading}{}: {}\n",
+                        self.speaker_name(speaker),
+                        paragraph.text
+                    ),
+                    None => format!("{heading}{}\n", paragraph.text),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !self.markers.is_empty() {
+            out.push_str("\nMarkers:\n");
+            for marker in &self.markers {
+                out.push_str(&format!("{}\n", marker_line(marker)));
+            }
+        }
+        out
+    }
+
+    /// Consecutive segments by the same speaker, split at long pauses and,
+    /// when there is more than one chapter, at chapter starts.
+    fn paragraphs(&self) -> Vec<Paragraph> {
+        let mut paragraphs: Vec<Paragraph> = Vec::new();
+        let mut last_end_ms = 0;
+        let chapters = if self.chapters.len() > 1 {
+            self.chapters.as_slice()
+        } else {
+            &[]
+        };
+        let mut next_chapter = 0;
+        for segment in &self.segments {
+            let text = segment.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let mut chapter = None;
+            while next_chapter < chapters.len()
+                && segment.start_ms >= chapters[next_chapter].start_ms
+            {
+                chapter = Some(next_chapter);
+                next_chapter += 1;
+            }
+            match paragraphs.last_mut() {
+                Some(paragraph)
+                    if chapter.is_none()
+                        && paragraph.speaker == segment.speaker
+                        && segment.start_ms.saturating_sub(last_end_ms) < PARAGRAPH_GAP_MS =>
+                {
+                    paragraph.text.push(' ');
+                    paragraph.text.push_str(text);
+                }
+                _ => paragraphs.push(Paragraph {
+                    speaker: segment.speaker,
+                    start_ms: segment.start_ms,
+                    text: text.to_string(),
+                    chapter,
+                }),
+            }
+            last_end_ms = segment.end_ms;
+        }
+        paragraphs
+    }
+
+    /// Split segments into subtitle cues. Cues never span segments, so a
+    /// speaker change always starts a new cue.
+    fn cues(&self, options: &ExportOptions) -> Vec<Cue> {
+        let mut cues = Vec::new();
+
+        for segment in &self.segments {
+            let words = timed_words(segment);
+            let mut start = 0;
+            while start < words.len() {
+                // Grow the cue while it fits the line and duration limits;
+                // a cue always takes at least one word.
+                let mut end = start + 1;
+                while end < words.len() {
+                    let candidate = &words[start..=end];
+                    let span_ms = candidate[candidate.len() - 1].2 - candidate[0].1;
+                    if span_ms > options.max_cue_ms
+                        || wrap(candidate, options.max_line_chars).len() > MAX_CUE_LINES
+                    {
+                        break;
+                    }
+                    end += 1;
+                }
+                let cue_words = &words[start..end];
+                cues.push(Cue {
+                    start_ms: cue_words[0].1,
+                    end_ms: cue_words[cue_words.len() - 1].2,
+                    speaker: segment.speaker,
+                    lines: wrap(cue_words, options.max_line_chars),
+                });
+                start = end;
+            }
+        }
+        cues
+    }
+}
+
+struct Cue {
+    start_ms: u64,
+    end_ms: u64,
+    speaker: Option<i32>,
+    lines: Vec<String>,
+}
+
+struct Paragraph {
+    speaker: Option<i32>,
+    start_ms: u64,
+    text: String,
+    /// Chapter this paragraph opens.
+    chapter: Option<usize>,
+}
+
+/// Layout of the JSON export, shared with the importer.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonTranscript {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) title: Option<String>,
+    pub(crate) created_at: String,
+    #[serde(default)]
+    pub(crate) duration_ms: u64,
+    #[serde(default)]
+    pub(crate) speakers: Vec<JsonSpeaker>,
+    pub(crate) segments: Vec<JsonSegment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) markers: Vec<Marker>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) chapters: Vec<Chapter>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonSpeaker {
+    pub(crate) id: i32,
+    pub(crate) name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonSegment {
+    pub(crate) start_ms: u64,
+    pub(crate) end_ms: u64,
+    #[serde(default)]
+    pub(crate) speaker: Option<i32>,
+    pub(crate) text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) raw_text: Option<String>,
+    #[serde(default)]
+    pub(crate) words: Vec<JsonWord>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonWord {
+    pub(crate) text: String,
+    pub(crate) start_ms: u64,
+    pub(crate) end_ms: u64,
+    #[serde(default = "full_confidence")]
+    pub(crate) confidence: f32,
+}
+
+fn full_confidence() -> f32 {
+    1.0
+}
+
+/// Words of a segment with timings. Segments saved without word timings
+/// get them interpolated by character count over the segment span.
+fn timed_words(segment: &Segment) -> Vec<(String, u64, u64)> {
+    // Word timings only line up with the text if nothing rewrote it.
+    if !segment.words.is_empty() && segment.raw_text.is_none() {
+        return segment
+            .words
+            .iter()
+            .map(|w| (w.text.trim().to_string(), w.start_ms, w.end_ms))
+            .filter(|(text, _, _)| !text.is_empty())
+            .collect();
+    }
+
+    let words: Vec<&str> = segment.text.split_whitespace().collect();
+    let total_chars: usize = words.iter().map(|w| w.chars().count()).sum();
+    let span_ms = segment.end_ms.saturating_sub(segment.start_ms);
+    let mut chars_before = 0;
+    words
+        .into_iter()
+        .map(|word| {
+            let start = segment.start_ms + span_ms * chars_before as u64 / total_chars as u64;
+            chars_before += word.chars().count();
+            let end = segment.start_ms + span_ms * chars_before as u64 / total_chars as u64;
+            (word.to_string(), start, end)
+        })
+        .collect()
+}
+
+/// Greedy word wrap. A single word longer than a line gets a line of its own.
+fn wrap(words: &[(String, u64, u64)], max_line_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for (word, _, _) in words {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= max_line_chars => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.clone()),
+        }
+    }
+    lines
+}
+
+/// `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT).
+fn timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// `M:SS`, or `H:MM:SS` from an hour on.
+fn duration(ms: u64) -> String {
+    let seconds = ms / 1000;
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// `Action item [1:05] (done): note — "marked text"`
+fn marker_line(marker: &Marker) -> String {
+    let mut line = format!(
+        "{} [{}]",
+        marker.kind.label(),
+        duration(marker.timestamp_ms)
+    );
+    if marker.done {
+        line.push_str(" (done)");
+    }
+    if let Some(author) = &marker.author {
+        line.push_str(&format!(" by {author}"));
+    }
+    let mut details = Vec::new();
+    if let Some(note) = &marker.note {
+        details.push(note.clone());
+    }
+    if let Some(span) = &marker.span {
+        details.push(format!("\"{}\"", span.text.trim()));
+    }
+    if !details.is_empty() {
+        line.push_str(": ");
+        line.push_str(&details.join(" — "));
+    }
+    line
+}
+
+/// NOTE text may not contain "-->" or blank lines.
+fn vtt_note(text: &str) -> String {
+    text.replace("-->", "->").replace('\n', " ")
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Word;
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64, speaker: Option<i32>) -> Segment {
+        Segment {
+            id: Uuid::nil(),
+            text: text.to_string(),
+            raw_text: None,
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker,
+            is_final: true,
+        }
+    }
+
+    fn transcript(segments: Vec<Segment>) -> Transcript {
+        let mut transcript = Transcript::new();
+        transcript.id = Uuid::nil();
+        transcript.title = Some("Weekly sync".to_string());
+        transcript.created_at = Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap();
+        transcript.duration_ms = 65_000;
+        transcript.segments = segments;
+        transcript
+    }
+
+    #[test]
+    fn test_srt_splits_long_segments() {
+        let t = transcript(vec![segment(
+            "the quick brown fox jumps over the lazy dog",
+            1000,
+            5000,
+            None,
+        )]);
+        let options = ExportOptions {
+            max_line_chars: 16,
+            max_cue_ms: 7000,
+        };
+        assert_eq!(
+            t.export(ExportFormat::Srt, &options),
+            "1\n00:00:01,000 --> 00:00:03,857\nthe quick brown\nfox jumps over\n\n\
+             2\n00:00:03,857 --> 00:00:05,000\nthe lazy dog\n\n"
+        );
+    }
+
+    #[test]
+    fn test_cues_respect_max_duration() {
+        let mut seg = segment("one two three four", 0, 8000, None);
+        seg.words = ["one", "two", "three", "four"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Word {
+                text: text.to_string(),
+                start_ms: i as u64 * 2000,
+                end_ms: i as u64 * 2000 + 1500,
+                confidence: 1.0,
+            })
+            .collect();
+        let t = transcript(vec![seg]);
+        let options = ExportOptions {
+            max_line_chars: 42,
+            max_cue_ms: 4000,
+        };
+        let srt = t.export(ExportFormat::Srt, &options);
+        assert!(srt.contains("00:00:00,000 --> 00:00:03,500\none two\n"));
+        assert!(srt.contains("00:00:04,000 --> 00:00:07,500\nthree four\n"));
+    }
+
+    #[test]
+    fn test_vtt_voice_tags() {
+        let mut t = transcript(vec![
+            segment("Hi <all>", 0, 1000, Some(0)),
+            segment("Hello", 1200, 2000, Some(1)),
+        ]);
+        t.rename_speaker(0, "Anna");
+        assert_eq!(
+            t.export(ExportFormat::Vtt, &ExportOptions::default()),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.000\n<v Anna>Hi &lt;all&gt;\n\n\
+             00:00:01.200 --> 00:00:02.000\n<v Speaker 2>Hello\n\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_groups_speakers() {
+        let mut t = transcript(vec![
+            segment("Let's start.", 0, 1000, Some(0)),
+            segment("First item.", 1200, 2000, Some(0)),
+            segment("Sounds good.", 2500, 3000, Some(1)),
+        ]);
+        t.rename_speaker(0, "Anna");
+        assert_eq!(
+            t.export(ExportFormat::Markdown, &ExportOptions::default()),
+            "# Weekly sync\n\n*2026-03-02 09:30 · 1:05*\n\n\
+             **Anna** [0:00]\nLet's start. First item.\n\n\
+             **Speaker 2** [0:02]\nSounds good.\n"
+        );
+        assert_eq!(
+            t.export(ExportFormat::Text, &ExportOptions::default()),
+            "Anna: Let's start. First item.\n\nSpeaker 2: Sounds good.\n"
+        );
+    }
+
+    #[test]
+    fn test_json_includes_words_and_speakers() {
+        let mut seg = segment("hello", 0, 500, Some(0));
+        seg.words = vec![Word {
+            text: "hello".to_string(),
+            start_ms: 10,
+            end_ms: 480,
+            confidence: 0.5,
+        }];
+        let json = transcript(vec![seg]).export(ExportFormat::Json, &ExportOptions::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["speakers"][0]["name"], "Speaker 1");
+        assert_eq!(value["segments"][0]["words"][0]["start_ms"], 10);
+    }
+
+    #[test]
+    fn test_chapter_headings() {
+        let mut t = transcript(vec![
+            segment("Budget first.", 0, 1000, Some(0)),
+            segment("Then hiring.", 1200, 2000, Some(0)),
+        ]);
+        t.chapters = vec![
+            Chapter {
+                start_ms: 0,
+                end_ms: 1200,
+                title: "Budget".to_string(),
+                keywords: Vec::new(),
+            },
+            Chapter {
+                start_ms: 1200,
+                end_ms: 2000,
+                title: "Hiring".to_string(),
+                keywords: Vec::new(),
+            },
+        ];
+        assert_eq!(
+            t.export(ExportFormat::Markdown, &ExportOptions::default()),
+            "# Weekly sync\n\n*2026-03-02 09:30 · 1:05*\n\n\
+             ## Budget\n\n**Speaker 1** [0:00]\nBudget first.\n\n\
+             ## Hiring\n\n**Speaker 1** [0:01]\nThen hiring.\n"
+        );
+        assert_eq!(
+            t.export(ExportFormat::Text, &ExportOptions::default()),
+            "Budget\n------\n\nSpeaker 1: Budget first.\n\n\
+             Hiring\n------\n\nSpeaker 1: Then hiring.\n"
+        );
+
+        let json = t.export(ExportFormat::Json, &ExportOptions::default());
+        let imported = Transcript::import(&json, ExportFormat::Json).unwrap();
+        assert_eq!(imported.chapters, t.chapters);
+    }
+
+    #[test]
+    fn test_markers_in_exports() {
+        let mut t = transcript(vec![
+            segment("We ship Friday.", 0, 1000, None),
+            segment("Anna writes notes.", 1200, 2000, None),
+        ]);
+        t.add_marker(Marker::new(crate::MarkerKind::Decision, 900));
+        t.add_marker(Marker {
+            note: Some("send by Monday".to_string()),
+            done: true,
+            ..Marker::new(crate::MarkerKind::ActionItem, 65_000)
+        });
+
+        let vtt = t.export(ExportFormat::Vtt, &ExportOptions::default());
+        assert!(vtt.starts_with(
+            "WEBVTT\n\nNOTE Decision [0:00]: \"We ship Friday.\"\n\n00:00:00.000 --> "
+        ));
+        assert!(vtt.ends_with(
+            "NOTE Action item [1:05] (done): send by Monday — \"Anna writes notes.\"\n\n"
+        ));
+
+        let markdown = t.export(ExportFormat::Markdown, &ExportOptions::default());
+        assert!(markdown.ends_with(
+            "\n## Markers\n\n- Decision [0:00]: \"We ship Friday.\"\n\
+             - Action item [1:05] (done): send by Monday — \"Anna writes notes.\"\n"
+        ));
+
+        let json = t.export(ExportFormat::Json, &ExportOptions::default());
+        let imported = Transcript::import(&json, ExportFormat::Json).unwrap();
+        assert_eq!(imported.markers, t.markers);
+    }
+}
@@ -0,0 +1,435 @@
+//! Transcript importers: SRT, WebVTT and the JSON export.
+//!
+//! Subtitle cues become segments. Speakers come from WebVTT voice tags
+//! (`<v Anna>`) or a leading "Name:" label; word timings from WebVTT
+//! inline timestamps (`word<00:00:01.500> next`) or the JSON export.
+
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::export::JsonTranscript;
+use crate::{ExportFormat, Segment, Transcript, Word};
+
+/// Longest "Name:" label taken as a speaker, in words.
+const MAX_SPEAKER_LABEL_WORDS: usize = 4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("line {line}: {message}")]
+    Invalid { line: usize, message: String },
+    #[error("invalid JSON transcript: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("no cues found")]
+    Empty,
+    #[error("{0:?} transcripts cannot be imported")]
+    Unsupported(ExportFormat),
+}
+
+impl ExportFormat {
+    /// Format for a file extension (case-insensitive).
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::Vtt),
+            "json" => Some(Self::Json),
+            "md" | "markdown" => Some(Self::Markdown),
+            "txt" | "text" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// Guess an importable format from file content.
+    pub fn sniff(content: &str) -> Option<Self> {
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+        if content.starts_with("WEBVTT") {
+            Some(Self::Vtt)
+        } else if content.starts_with('{') {
+            Some(Self::Json)
+        } else if content.lines().take(3).any(|line| line.contains("-->")) {
+            Some(Self::Srt)
+        } else {
+            None
+        }
+    }
+}
+
+impl Transcript {
+    /// Parse SRT, WebVTT or a JSON export into a transcript.
+    ///
+    /// JSON exports keep their id; callers saving the result must not
+    /// overwrite a session that already has it.
+    pub fn import(content: &str, format: ExportFormat) -> Result<Self, ImportError> {
+        let content = content.trim_start_matches('\u{feff}');
+        match format {
+            ExportFormat::Srt => from_cues(content, false),
+            ExportFormat::Vtt => from_cues(content, true),
+            ExportFormat::Json => from_json(content),
+            ExportFormat::Markdown | ExportFormat::Text => Err(ImportError::Unsupported(format)),
+        }
+    }
+}
+
+fn from_json(content: &str) -> Result<Transcript, ImportError> {
+    let document: JsonTranscript = serde_json::from_str(content)?;
+    let mut transcript = Transcript::new();
+    if let Ok(id) = Uuid::parse_str(&document.id) {
+        transcript.id = id;
+    }
+    transcript.title = document.title;
+    if let Ok(created_at) = DateTime::parse_from_rfc3339(&document.created_at) {
+        transcript.created_at = created_at.with_timezone(&Utc);
+    }
+    transcript.segments = document
+        .segments
+        .into_iter()
+        .map(|segment| Segment {
+            id: Uuid::new_v4(),
+            text: segment.text,
+            raw_text: segment.raw_text,
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            words: segment
+                .words
+                .into_iter()
+                .map(|word| Word {
+                    text: word.text,
+                    start_ms: word.start_ms,
+                    end_ms: word.end_ms,
+                    confidence: word.confidence,
+                })
+                .collect(),
+            speaker: segment.speaker,
+            is_final: true,
+        })
+        .collect();
+    for speaker in document.speakers {
+        if speaker.name != transcript.speaker_name(speaker.id) {
+            transcript.rename_speaker(speaker.id, &speaker.name);
+        }
+    }
+    transcript.chapters = document.chapters;
+    for marker in document.markers {
+        transcript.add_marker(marker);
+    }
+    transcript.duration_ms = document.duration_ms.max(end_of_segments(&transcript));
+    Ok(transcript)
+}
+
+/// Parse SRT or WebVTT: blocks separated by blank lines, each with a
+/// `start --> end` timing line followed by the cue text. Blocks without a
+/// timing line (the WebVTT header, NOTE, STYLE) are skipped.
+fn from_cues(content: &str, vtt: bool) -> Result<Transcript, ImportError> {
+    let lines: Vec<&str> = content.lines().map(|line| line.trim_end()).collect();
+    let mut speakers = SpeakerIds::default();
+    let mut segments = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let block_start = i;
+        while i < lines.len() && !lines[i].trim().is_empty() {
+            i += 1;
+        }
+        let block = &lines[block_start..i];
+        i += 1;
+
+        let Some(timing) = block.iter().position(|line| line.contains("-->")) else {
+            continue;
+        };
+        let line_number = block_start + timing + 1;
+        let (start_ms, end_ms) = parse_timing(block[timing]).ok_or(ImportError::Invalid {
+            line: line_number,
+            message: format!("invalid cue timing {:?}", block[timing]),
+        })?;
+
+        let text = block[timing + 1..].join(" ");
+        let (voice, text) = if vtt {
+            split_voice_tag(&text)
+        } else {
+            (None, text.as_str())
+        };
+        let (text, words) = cue_text(text, start_ms, end_ms, vtt);
+        let (label, text) = match voice {
+            Some(name) => (Some(name), text),
+            None => split_speaker_label(&text),
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        segments.push(Segment {
+            id: Uuid::new_v4(),
+            text,
+            raw_text: None,
+            start_ms,
+            end_ms,
+            words,
+            speaker: label.map(|name| speakers.id(&name)),
+            is_final: true,
+        });
+    }
+
+    if segments.is_empty() {
+        return Err(ImportError::Empty);
+    }
+
+    let mut transcript = Transcript::new();
+    transcript.segments = segments;
+    transcript.duration_ms = end_of_segments(&transcript);
+    for (name, id) in speakers.ids {
+        if name != transcript.speaker_name(id) {
+            transcript.rename_speaker(id, &name);
+        }
+    }
+    Ok(transcript)
+}
+
+/// Speaker ids in order of first appearance.
+#[derive(Default)]
+struct SpeakerIds {
+    ids: BTreeMap<String, i32>,
+}
+
+impl SpeakerIds {
+    fn id(&mut self, name: &str) -> i32 {
+        let next = self.ids.len() as i32;
+        *self.ids.entry(name.to_string()).or_insert(next)
+    }
+}
+
+fn end_of_segments(transcript: &Transcript) -> u64 {
+    transcript
+        .segments
+        .iter()
+        .map(|s| s.end_ms)
+        .max()
+        .unwrap_or(0)
+}
+
+/// `00:00:01,000 --> 00:00:04,000 [cue settings]`
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    let start_ms = parse_timestamp(start.trim())?;
+    let end_ms = parse_timestamp(end)?;
+    Some((start_ms, end_ms.max(start_ms)))
+}
+
+/// `HH:MM:SS,mmm`, `HH:MM:SS.mmm` or `MM:SS.mmm`.
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let (clock, millis) = text.split_once([',', '.'])?;
+    if millis.is_empty() || millis.len() > 3 || !millis.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // "5" after the separator is 500 ms.
+    let millis: u64 = format!("{millis:0<3}").parse().ok()?;
+
+    let parts: Vec<u64> = clock
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let seconds = match parts[..] {
+        [hours, minutes, seconds] => hours * 3600 + minutes * 60 + seconds,
+        [minutes, seconds] => minutes * 60 + seconds,
+        _ => return None,
+    };
+    Some(seconds * 1000 + millis)
+}
+
+/// Split a leading `<v Name>` (or `<v.class Name>`) voice tag.
+fn split_voice_tag(text: &str) -> (Option<String>, &str) {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with("<v ") && !trimmed.starts_with("<v.") {
+        return (None, text);
+    }
+    let Some(close) = trimmed.find('>') else {
+        return (None, text);
+    };
+    let name = trimmed[2..close]
+        .split_once(' ')
+        .map(|(_, name)| unescape(name.trim()))
+        .filter(|name| !name.is_empty());
+    (name, &trimmed[close + 1..])
+}
+
+/// Split a leading "Name: " label, as written by meeting tools.
+///
+/// Only short labels starting with an uppercase letter count, so a colon
+/// inside a sentence ("the plan: ship it") is left alone.
+fn split_speaker_label(text: &str) -> (Option<String>, String) {
+    let label = text.split_once(": ").filter(|(name, _)| {
+        let words = name.split_whitespace().count();
+        (1..=MAX_SPEAKER_LABEL_WORDS).contains(&words)
+            && name.chars().next().is_some_and(char::is_uppercase)
+            && !name.contains(['.', ',', '!', '?', '"'])
+    });
+    match label {
+        Some((name, rest)) => (Some(name.trim().to_string()), rest.trim().to_string()),
+        None => (None, text.to_string()),
+    }
+}
+
+/// Plain cue text, with word timings from WebVTT inline timestamps.
+///
+/// Markup (`<i>`, `<c.color>`, `{\an8}`) is dropped. Words between two
+/// inline timestamps share that span evenly.
+fn cue_text(text: &str, start_ms: u64, end_ms: u64, vtt: bool) -> (String, Vec<Word>) {
+    // Text runs, each with the time it starts at.
+    let mut runs: Vec<(u64, String)> = vec![(start_ms, String::new())];
+    let mut has_inline_timestamps = false;
+    let mut rest = text;
+    while let Some(open) = rest.find(['<', '{']) {
+        let close_char = if rest[open..].starts_with('<') {
+            '>'
+        } else {
+            '}'
+        };
+        let Some(close) = rest[open..].find(close_char).map(|i| open + i) else {
+            break;
+        };
+        runs.last_mut().unwrap().1.push_str(&rest[..open]);
+        let tag = &rest[open + 1..close];
+        if vtt && close_char == '>' {
+            if let Some(ms) = parse_timestamp(tag) {
+                runs.push((ms, String::new()));
+                has_inline_timestamps = true;
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    runs.last_mut().unwrap().1.push_str(rest);
+
+    let unescape_run = |run: &str| {
+        if vtt {
+            unescape(run)
+        } else {
+            run.to_string()
+        }
+    };
+    let plain = runs
+        .iter()
+        .map(|(_, run)| unescape_run(run))
+        .collect::<String>();
+    let plain = plain.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !has_inline_timestamps {
+        return (plain, Vec::new());
+    }
+
+    let mut words = Vec::new();
+    for (i, (run_start, run)) in runs.iter().enumerate() {
+        let run_end = runs.get(i + 1).map_or(end_ms, |(next, _)| *next);
+        let run = unescape_run(run);
+        let run_words: Vec<&str> = run.split_whitespace().collect();
+        let step = run_end.saturating_sub(*run_start) / run_words.len().max(1) as u64;
+        for (j, text) in run_words.iter().enumerate() {
+            let start = run_start + step * j as u64;
+            words.push(Word {
+                text: text.to_string(),
+                start_ms: start,
+                end_ms: if j + 1 == run_words.len() {
+                    run_end
+                } else {
+                    start + step
+                },
+                confidence: 1.0,
+            });
+        }
+    }
+    (plain, words)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExportOptions;
+
+    #[test]
+    fn test_import_srt() {
+        let srt =
+            "1\r\n00:00:01,000 --> 00:00:03,500\r\n<i>Hello</i> there,\r\ngeneral Kenobi.\r\n\r\n\
+                   2\r\n00:00:04,000 --> 00:00:05,000\r\nAnna: Next item.\r\n";
+        let transcript = Transcript::import(srt, ExportFormat::Srt).unwrap();
+
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[0].text, "Hello there, general Kenobi.");
+        assert_eq!(transcript.segments[0].start_ms, 1000);
+        assert_eq!(transcript.segments[0].speaker, None);
+        assert_eq!(transcript.segments[1].text, "Next item.");
+        assert_eq!(transcript.speaker_name(0), "Anna");
+        assert_eq!(transcript.duration_ms, 5000);
+    }
+
+    #[test]
+    fn test_import_vtt_voices_and_word_timings() {
+        let vtt = "WEBVTT\n\nNOTE exported\n\n\
+                   intro\n00:01.000 --> 00:02.000 align:start\n<v.loud Bob>Fish &amp; chips\n\n\
+                   00:00:02.000 --> 00:00:03.000\nsee<00:00:02.400> you<00:00:02.700> soon\n";
+        let transcript = Transcript::import(vtt, ExportFormat::Vtt).unwrap();
+
+        assert_eq!(transcript.segments[0].text, "Fish & chips");
+        assert_eq!(transcript.speaker_name(0), "Bob");
+        let words: Vec<(&str, u64, u64)> = transcript.segments[1]
+            .words
+            .iter()
+            .map(|w| (w.text.as_str(), w.start_ms, w.end_ms))
+            .collect();
+        assert_eq!(
+            words,
+            vec![
+                ("see", 2000, 2400),
+                ("you", 2400, 2700),
+                ("soon", 2700, 3000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_export_round_trips() {
+        let mut original = Transcript::import(
+            "WEBVTT\n\n00:00.000 --> 00:01.000\n<v Speaker 1>hi<00:00.400> all\n\n\
+             00:01.000 --> 00:02.000\n<v Anna>hello\n",
+            ExportFormat::Vtt,
+        )
+        .unwrap();
+        original.title = Some("Sync".to_string());
+
+        let json = original.export(ExportFormat::Json, &ExportOptions::default());
+        let imported = Transcript::import(&json, ExportFormat::Json).unwrap();
+
+        assert_eq!(imported.id, original.id);
+        assert_eq!(imported.title.as_deref(), Some("Sync"));
+        assert_eq!(imported.speaker_names, original.speaker_names);
+        assert_eq!(imported.segments[0].words.len(), 2);
+        assert_eq!(
+            imported.export(ExportFormat::Vtt, &ExportOptions::default()),
+            original.export(ExportFormat::Vtt, &ExportOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(matches!(
+            Transcript::import("1\n00:00:01 --> 00:00:02\nhi\n", ExportFormat::Srt),
+            Err(ImportError::Invalid { line: 2, .. })
+        ));
+        assert!(matches!(
+            Transcript::import("WEBVTT\n", ExportFormat::Vtt),
+            Err(ImportError::Empty)
+        ));
+        assert_eq!(
+            ExportFormat::sniff("\u{feff}WEBVTT\n"),
+            Some(ExportFormat::Vtt)
+        );
+        assert_eq!(
+            ExportFormat::sniff("1\n00:00:01,000 --> 00:00:02,000\n"),
+            Some(ExportFormat::Srt)
+        );
+    }
+}
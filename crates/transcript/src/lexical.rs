@@ -0,0 +1,42 @@
+//! Word-level text helpers shared by the digest and chapter segmentation.
+
+use std::collections::HashMap;
+
+/// Function words and fillers that carry no topic.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "all", "also", "an", "and", "are", "as", "at", "be", "been", "but", "by", "can",
+    "do", "does", "for", "from", "get", "got", "had", "has", "have", "he", "her", "his", "how",
+    "i", "if", "in", "is", "it", "its", "just", "know", "like", "me", "my", "no", "not", "of",
+    "oh", "ok", "okay", "on", "one", "or", "our", "really", "right", "so", "that", "the", "their",
+    "them", "then", "there", "these", "they", "think", "this", "to", "uh", "um", "up", "us", "was",
+    "we", "well", "were", "what", "when", "which", "who", "will", "with", "would", "yeah", "yes",
+    "you", "your",
+];
+
+/// Lowercased words, keeping inner apostrophes ("don't").
+pub(crate) fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\''))
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a word carries content: not a stopword, not a single character.
+pub(crate) fn is_term(word: &str) -> bool {
+    word.chars().count() > 1 && !STOPWORDS.contains(&word)
+}
+
+/// Content words of `text`.
+pub(crate) fn terms(text: &str) -> Vec<String> {
+    words(text).into_iter().filter(|w| is_term(w)).collect()
+}
+
+pub(crate) fn cosine(a: &HashMap<String, f32>, b: &HashMap<String, f32>) -> f32 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(term, x)| large.get(term).map(|y| x * y))
+        .sum()
+}
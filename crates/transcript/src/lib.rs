@@ -1,5 +1,21 @@
+mod chapters;
+mod digest;
+mod edit;
+mod export;
+mod import;
+mod lexical;
+mod marker;
+
+pub use chapters::{Chapter, ChapterOptions};
+pub use digest::{Digest, DigestItem, DigestOptions, DigestSentence, DigestSource, Participant};
+pub use edit::{EditError, Revision, RevisionAction, RevisionLog, TranscriptEdit, WordPosition};
+pub use export::{ExportFormat, ExportOptions};
+pub use import::ImportError;
+pub use marker::{Marker, MarkerKind, MarkerSpan};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 /// Repository trait for transcript persistence.
@@ -24,7 +40,11 @@ pub struct Word {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Segment {
     pub id: Uuid,
+    /// Written-form text (numbers, dates, ... as digits and symbols).
     pub text: String,
+    /// Text as the engine produced it, when normalization changed it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
     pub start_ms: u64,
     pub end_ms: u64,
     pub words: Vec<Word>,
@@ -40,6 +60,24 @@ pub struct Transcript {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub duration_ms: u64,
+    /// Context mode the session was recorded in ("Meeting", "Dev", ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Display names for diarized speaker ids (unnamed speakers are absent).
+    #[serde(default)]
+    pub speaker_names: BTreeMap<i32, String>,
+    /// Markers in timeline order.
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+    /// Topic chapters in order, covering the transcript.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    /// Meeting digest, when one was generated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<Digest>,
+    /// Edits made after the transcript was saved.
+    #[serde(default, skip_serializing_if = "RevisionLog::is_empty")]
+    pub revisions: RevisionLog,
 }
 
 impl Transcript {
@@ -52,6 +90,12 @@ impl Transcript {
             created_at: now,
             updated_at: now,
             duration_ms: 0,
+            mode: None,
+            speaker_names: BTreeMap::new(),
+            markers: Vec::new(),
+            chapters: Vec::new(),
+            digest: None,
+            revisions: RevisionLog::default(),
         }
     }
 
@@ -62,6 +106,37 @@ impl Transcript {
             .collect::<Vec<_>>()
             .join(" ")
     }
+
+    /// Distinct speaker ids in order of first appearance.
+    pub fn speakers(&self) -> Vec<i32> {
+        let mut speakers = Vec::new();
+        for speaker in self.segments.iter().filter_map(|s| s.speaker) {
+            if !speakers.contains(&speaker) {
+                speakers.push(speaker);
+            }
+        }
+        speakers
+    }
+
+    /// Display name for a speaker: the assigned name, or "Speaker N"
+    /// (1-based) if it has not been renamed.
+    pub fn speaker_name(&self, speaker: i32) -> String {
+        self.speaker_names
+            .get(&speaker)
+            .cloned()
+            .unwrap_or_else(|| format!("Speaker {}", speaker + 1))
+    }
+
+    /// Assign a display name to a speaker. An empty name reverts to the
+    /// default label.
+    pub fn rename_speaker(&mut self, speaker: i32, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.speaker_names.remove(&speaker);
+        } else {
+            self.speaker_names.insert(speaker, name.to_string());
+        }
+    }
 }
 
 impl Default for Transcript {
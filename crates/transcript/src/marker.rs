@@ -0,0 +1,212 @@
+//! Markers: action items, decisions, questions and bookmarks placed on the
+//! session timeline.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::Transcript;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerKind {
+    ActionItem,
+    Decision,
+    Question,
+    Important,
+    FollowUp,
+    Bookmark,
+}
+
+impl MarkerKind {
+    /// Parse a spoken or typed marker type ("todo", "decided", "flag", ...).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "action" | "action_item" | "todo" | "task" => Some(Self::ActionItem),
+            "decision" | "decided" => Some(Self::Decision),
+            "question" | "ask" | "?" => Some(Self::Question),
+            "important" | "key" | "!" => Some(Self::Important),
+            "followup" | "follow_up" | "follow-up" => Some(Self::FollowUp),
+            "bookmark" | "mark" | "flag" => Some(Self::Bookmark),
+            _ => None,
+        }
+    }
+
+    /// Stable identifier, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ActionItem => "action_item",
+            Self::Decision => "decision",
+            Self::Question => "question",
+            Self::Important => "important",
+            Self::FollowUp => "follow_up",
+            Self::Bookmark => "bookmark",
+        }
+    }
+
+    /// Human-readable label for exports.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ActionItem => "Action item",
+            Self::Decision => "Decision",
+            Self::Question => "Question",
+            Self::Important => "Important",
+            Self::FollowUp => "Follow-up",
+            Self::Bookmark => "Bookmark",
+        }
+    }
+}
+
+/// The stretch of transcript a marker refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkerSpan {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Marker {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+    pub kind: MarkerKind,
+    /// Position on the session timeline (ms from the start of the recording).
+    pub timestamp_ms: u64,
+    /// Marked transcript text. Filled in from the segment at the timestamp
+    /// when the marker is added without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<MarkerSpan>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Who placed the marker; `None` for the local user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Whether an action item or question has been dealt with.
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl Marker {
+    pub fn new(kind: MarkerKind, timestamp_ms: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            timestamp_ms,
+            span: None,
+            note: None,
+            author: None,
+            done: false,
+        }
+    }
+}
+
+impl Transcript {
+    /// Attach a marker, keeping markers in timeline order. Returns its id.
+    pub fn add_marker(&mut self, mut marker: Marker) -> Uuid {
+        if marker.span.is_none() {
+            marker.span = self.span_at(marker.timestamp_ms);
+        }
+        let id = marker.id;
+        let index = self
+            .markers
+            .partition_point(|m| m.timestamp_ms <= marker.timestamp_ms);
+        self.markers.insert(index, marker);
+        id
+    }
+
+    pub fn marker_mut(&mut self, id: &Uuid) -> Option<&mut Marker> {
+        self.markers.iter_mut().find(|m| m.id == *id)
+    }
+
+    pub fn remove_marker(&mut self, id: &Uuid) -> Option<Marker> {
+        let index = self.markers.iter().position(|m| m.id == *id)?;
+        Some(self.markers.remove(index))
+    }
+
+    /// The segment being spoken at `timestamp_ms`, or else the last one
+    /// before it: a spoken "mark that" refers to what was just said.
+    fn span_at(&self, timestamp_ms: u64) -> Option<MarkerSpan> {
+        let segment = self
+            .segments
+            .iter()
+            .find(|s| s.start_ms <= timestamp_ms && timestamp_ms <= s.end_ms)
+            .or_else(|| {
+                self.segments
+                    .iter()
+                    .rev()
+                    .find(|s| s.end_ms <= timestamp_ms)
+            })?;
+        Some(MarkerSpan {
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            text: segment.text.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Segment;
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> Segment {
+        Segment {
+            id: Uuid::new_v4(),
+            text: text.to_string(),
+            raw_text: None,
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+            speaker: None,
+            is_final: true,
+        }
+    }
+
+    #[test]
+    fn test_add_marker_fills_span_and_orders() {
+        let mut t = Transcript::new();
+        t.segments = vec![
+            segment("let's ship on friday", 0, 2000),
+            segment("anna will write the notes", 3000, 5000),
+        ];
+
+        t.add_marker(Marker::new(MarkerKind::ActionItem, 5500));
+        t.add_marker(Marker::new(MarkerKind::Decision, 1000));
+        let id = t.add_marker(Marker {
+            span: Some(MarkerSpan {
+                start_ms: 0,
+                end_ms: 0,
+                text: "custom".to_string(),
+            }),
+            ..Marker::new(MarkerKind::Bookmark, 6000)
+        });
+
+        let spans: Vec<(MarkerKind, &str)> = t
+            .markers
+            .iter()
+            .map(|m| (m.kind, m.span.as_ref().unwrap().text.as_str()))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (MarkerKind::Decision, "let's ship on friday"),
+                (MarkerKind::ActionItem, "anna will write the notes"),
+                (MarkerKind::Bookmark, "custom"),
+            ]
+        );
+
+        t.marker_mut(&id).unwrap().done = true;
+        assert!(t.remove_marker(&id).unwrap().done);
+        assert_eq!(t.markers.len(), 2);
+    }
+
+    #[test]
+    fn test_marker_kind_parse() {
+        assert_eq!(MarkerKind::parse("TODO"), Some(MarkerKind::ActionItem));
+        assert_eq!(MarkerKind::parse("follow-up"), Some(MarkerKind::FollowUp));
+        assert_eq!(MarkerKind::parse("note"), None);
+        assert_eq!(
+            serde_json::to_string(&MarkerKind::FollowUp).unwrap(),
+            format!("\"{}\"", MarkerKind::FollowUp.as_str())
+        );
+    }
+}
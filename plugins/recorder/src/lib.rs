@@ -303,12 +303,15 @@ async fn stop_recording<R: Runtime>(
         }
     }
 
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let recordings_dir = format!("{home}/Library/Application Support/gibberish/recordings");
+    let recordings_dir =
+        gibberish_audio::recordings_dir().ok_or_else(|| "Home directory not found".to_string())?;
     std::fs::create_dir_all(&recordings_dir).map_err(|e| e.to_string())?;
 
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let path = format!("{recordings_dir}/recording_{timestamp}.wav");
+    let path = recordings_dir
+        .join(format!("recording_{timestamp}.wav"))
+        .to_string_lossy()
+        .into_owned();
 
     state.recorder.save_wav(&path).map_err(|e| e.to_string())?;
 
@@ -9,6 +9,13 @@ fn main() {
         "get_current_model",
         "get_language",
         "set_language",
+        "load_refiner_model",
+        "unload_refiner_model",
+        "get_refiner_model",
+        "list_installed_models",
+        "get_model_storage_usage",
+        "delete_model",
+        "collect_model_garbage",
         "list_turn_models",
         "download_turn_model",
         "cancel_turn_download",
@@ -18,17 +25,62 @@ fn main() {
         "get_current_turn_model",
         "get_turn_settings",
         "set_turn_settings",
+        "list_speaker_models",
+        "download_speaker_model",
+        "cancel_speaker_download",
+        "is_speaker_downloading",
+        "load_speaker_model",
+        "unload_speaker_model",
+        "get_current_speaker_model",
+        "list_punctuation_models",
+        "download_punctuation_model",
+        "cancel_punctuation_download",
+        "is_punctuation_downloading",
+        "load_punctuation_model",
+        "unload_punctuation_model",
+        "get_current_punctuation_model",
         "transcribe_audio",
         "transcribe_file",
         "transcribe_streaming_chunk",
         "reset_streaming_buffer",
         "get_streaming_buffer_duration",
+        "get_streaming_policy",
+        "set_streaming_policy",
+        "get_vad_settings",
+        "set_vad_settings",
         "save_session",
         "list_sessions",
         "get_session",
+        "get_session_audio",
+        "export_backup",
+        "import_backup",
         "delete_session",
         "update_session_title",
+        "rename_speaker",
+        "edit_session",
+        "undo_session_edit",
+        "redo_session_edit",
+        "get_session_revisions",
+        "get_session_at_revision",
+        "add_session_marker",
+        "set_session_marker_done",
+        "delete_session_marker",
+        "query_markers",
+        "generate_session_digest",
+        "generate_session_chapters",
+        "export_session",
+        "import_session",
         "search_sessions",
+        "search_transcripts",
+        "get_encryption_status",
+        "unlock_database",
+        "enable_encryption",
+        "disable_encryption",
+        "rotate_encryption_key",
+        "get_retention_settings",
+        "set_retention_settings",
+        "preview_retention",
+        "apply_retention",
         // Audio bus listener commands
         "stt_start_listening",
         "stt_stop_listening",
@@ -5,6 +5,9 @@
 //! - Streaming models (Sherpa): Use dedicated streaming worker
 //! - Batch models (Parakeet, future models): Use batch_transcriber module
 //!
+//! With a refiner loaded, streaming commits are re-decoded in the background
+//! (see `two_pass`).
+//!
 //! This module is model-agnostic - routing is based on capabilities, not model identity.
 
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -17,8 +20,10 @@ use tauri::{Emitter, Runtime};
 use tokio_util::sync::CancellationToken;
 
 use crate::batch_transcriber;
-use crate::dto::{StreamingCommitPayload, StreamingResultDto, VadSilencePayload};
+use crate::dto::{StreamingResultDto, VadSilencePayload};
 use crate::state::SttState;
+use crate::two_pass;
+use crate::word_events;
 
 /// Shared storage for the AudioBusReceiver so it can be returned after listener stops.
 pub type ReceiverStorage = Arc<Mutex<Option<AudioBusReceiver>>>;
@@ -119,7 +124,7 @@ pub fn start_audio_listener<R: Runtime>(
             pipeline_status.add_audio_processed_ms(chunk.duration_ms());
 
             // Process the chunk through STT
-            if let Err(e) = process_audio_chunk(&app, &state, &chunk).await {
+            if let Err(e) = process_audio_chunk(&app, &state, &chunk, &pipeline_status).await {
                 tracing::warn!(error = %e, "Failed to process audio chunk");
             }
 
@@ -148,7 +153,7 @@ pub fn start_audio_listener<R: Runtime>(
 /// - Batch engine available → use batch transcriber (Parakeet, future models)
 #[tracing::instrument(
     level = "trace",
-    skip(app, state, chunk),
+    skip(app, state, chunk, pipeline_status),
     fields(
         chunk_seq = chunk.seq,
         chunk_duration_ms = chunk.duration_ms(),
@@ -158,6 +163,7 @@ async fn process_audio_chunk<R: Runtime>(
     app: &tauri::AppHandle<R>,
     state: &SttState,
     chunk: &AudioChunk,
+    pipeline_status: &PipelineStatus,
 ) -> Result<(), String> {
     // Capability-based routing (not model-specific)
 
@@ -168,7 +174,7 @@ async fn process_audio_chunk<R: Runtime>(
 
     // 2. Use batch transcriber for any loaded engine (Parakeet, future models)
     if let Some(engine) = state.get_engine().await {
-        return process_with_batch_engine(app, state, engine, chunk).await;
+        return process_with_batch_engine(app, state, engine, chunk, pipeline_status).await;
     }
 
     // No engine loaded
@@ -193,6 +199,9 @@ async fn process_with_streaming_worker<R: Runtime>(
         })
         .await;
 
+    // Keep the utterance audio for the two-pass refiner and diarizer
+    state.with_two_pass(|two_pass| two_pass.record(&chunk.samples));
+
     // Emit VAD silence event for decoupled turn detection
     if needs_silence_injection {
         let _ = app.emit(
@@ -231,19 +240,18 @@ async fn process_with_streaming_worker<R: Runtime>(
 
     if let Some(result) = result {
         // Emit commit event if there's a committed delta
-        if let Some(ref delta) = result.committed_delta {
-            let _ = app.emit(
-                "stt:stream_commit",
-                StreamingCommitPayload {
-                    text: delta.clone(),
-                    ts_ms: chrono::Utc::now().timestamp_millis(),
-                },
-            );
+        let delta = result
+            .committed_delta
+            .as_deref()
+            .map(|delta| state.punctuate_worker_commit(delta));
+        if let Some(ref delta) = delta {
+            two_pass::emit_stream_commit(app, state, delta, two_pass::ENDPOINT_COMMIT, None);
         }
+        word_events::emit_text_word_events(app, state, delta.as_deref(), &result.partial_text);
 
         // Emit stream result
         let dto = StreamingResultDto::from(gibberish_application::StreamingResult {
-            text: result.committed_text,
+            text: two_pass::display_committed_text(state, result.committed_text),
             volatile_text: result.partial_text,
             is_partial: result.is_partial,
             buffer_duration_ms: result.buffer_duration_ms,
@@ -268,8 +276,20 @@ async fn process_with_batch_engine<R: Runtime>(
     state: &SttState,
     engine: std::sync::Arc<dyn gibberish_stt::SttEngine>,
     chunk: &AudioChunk,
+    pipeline_status: &PipelineStatus,
 ) -> Result<(), String> {
+    state.with_two_pass(|two_pass| two_pass.record(&chunk.samples));
     let result = batch_transcriber::process_batch_audio(state, engine, &chunk.samples).await?;
-    batch_transcriber::emit_batch_events(app, &result);
+
+    // Track the real-time factor and let the decode interval follow it
+    if let Some(decode) = result.decode {
+        pipeline_status.update_rtf(decode.inference_ms, decode.audio_ms);
+        let real_time_factor = pipeline_status.real_time_factor();
+        state
+            .with_streaming_mut(|streamer| streamer.auto_tune(real_time_factor))
+            .await;
+    }
+
+    batch_transcriber::emit_batch_events(app, state, &result);
     Ok(())
 }
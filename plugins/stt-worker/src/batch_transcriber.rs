@@ -9,12 +9,17 @@
 
 use std::sync::Arc;
 
-use gibberish_application::{StreamingResult, TranscriptionService};
+use gibberish_application::{
+    CommitContext, DecodeStats, StreamingResult, StreamingTranscriber, TranscriptionService,
+    WordEvent,
+};
 use gibberish_stt::SttEngine;
 use tauri::{Emitter, Runtime};
 
-use crate::dto::{StreamingCommitPayload, StreamingResultDto, VadSilencePayload};
+use crate::dto::{StreamingResultDto, VadSilencePayload};
 use crate::state::SttState;
+use crate::two_pass;
+use crate::word_events;
 
 /// Result of a batch transcription operation.
 pub struct BatchTranscriptionResult {
@@ -22,10 +27,18 @@ pub struct BatchTranscriptionResult {
     pub result: StreamingResult,
     /// Delta text that was committed, if any.
     pub committed_delta: Option<String>,
+    /// Pause/turn context of the commit, if any.
+    pub commit_context: Option<CommitContext>,
+    /// Stream position (ms) where the committed words end, if any.
+    pub commit_end_ms: Option<u64>,
+    /// Word-level changes since the previous result.
+    pub word_events: Vec<WordEvent>,
     /// Whether VAD detected a silence (speech-to-silence transition).
     pub vad_silence_detected: bool,
     /// Current buffer duration in milliseconds.
     pub buffer_duration_ms: u64,
+    /// Timing of the decode run for this chunk, if one ran.
+    pub decode: Option<DecodeStats>,
 }
 
 /// Process audio through any batch-capable STT engine.
@@ -42,7 +55,7 @@ pub async fn process_batch_audio(
     engine: Arc<dyn SttEngine>,
     samples: &[f32],
 ) -> Result<BatchTranscriptionResult, String> {
-    let (result, committed_delta, vad_silence_detected, buffer_duration_ms) = state
+    state
         .with_streaming_mut(|streamer| {
             // Run transcription (handles buffering and periodic inference internally)
             let result = TranscriptionService::process_streaming_chunk(
@@ -54,26 +67,27 @@ pub async fn process_batch_audio(
                 0.5,   // Unused when disabled
             )?;
 
-            let committed_delta = streamer.take_last_committed_delta();
-            let vad_silence = streamer.take_silence_injection_pending();
-            let duration = streamer.buffer_duration_ms();
-
-            Ok::<_, gibberish_application::TranscriptionError>((
+            Ok::<_, gibberish_application::TranscriptionError>(BatchTranscriptionResult {
                 result,
-                committed_delta,
-                vad_silence,
-                duration,
-            ))
+                committed_delta: streamer.take_last_committed_delta(),
+                commit_context: streamer.take_last_commit_context(),
+                commit_end_ms: committed_end_ms(streamer),
+                word_events: streamer.take_word_events(),
+                vad_silence_detected: streamer.take_silence_injection_pending(),
+                buffer_duration_ms: streamer.buffer_duration_ms(),
+                decode: streamer.take_last_decode(),
+            })
         })
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(BatchTranscriptionResult {
-        result,
-        committed_delta,
-        vad_silence_detected,
-        buffer_duration_ms,
-    })
+        .map_err(|e| e.to_string())
+}
+
+/// End of the last committed word, in stream time.
+fn committed_end_ms(streamer: &mut StreamingTranscriber) -> Option<u64> {
+    streamer
+        .take_last_committed_words()
+        .last()
+        .map(|w| w.end_ms)
 }
 
 /// Run confirmed transcription on VAD silence.
@@ -86,21 +100,26 @@ pub async fn process_vad_confirmed(
     state: &SttState,
     engine: Arc<dyn SttEngine>,
 ) -> Result<BatchTranscriptionResult, String> {
-    let (result, committed_delta, buffer_duration_ms) = state
+    state
         .with_streaming_mut(|streamer| {
             // Force transcription regardless of periodic timing
             let buffer = streamer.get_buffer().to_vec();
             if buffer.is_empty() {
-                return Ok((
-                    StreamingResult {
+                return Ok(BatchTranscriptionResult {
+                    result: StreamingResult {
                         text: streamer.committed_text().to_string(),
                         volatile_text: String::new(),
                         is_partial: false,
                         buffer_duration_ms: 0,
                     },
-                    None,
-                    0u64,
-                ));
+                    committed_delta: None,
+                    commit_context: None,
+                    commit_end_ms: None,
+                    word_events: Vec::new(),
+                    vad_silence_detected: true,
+                    buffer_duration_ms: 0,
+                    decode: None,
+                });
             }
 
             // Run inference on complete buffer
@@ -129,30 +148,27 @@ pub async fn process_vad_confirmed(
                 streamer.clear_word_cache();
             }
 
-            let committed_delta = streamer.take_last_committed_delta();
             let duration = streamer.buffer_duration_ms();
             let (text, volatile_text) = streamer.build_full_display_text();
 
-            Ok::<_, gibberish_application::TranscriptionError>((
-                StreamingResult {
+            Ok::<_, gibberish_application::TranscriptionError>(BatchTranscriptionResult {
+                result: StreamingResult {
                     text,
                     volatile_text,
                     is_partial: false, // VAD confirmed = final
                     buffer_duration_ms: duration,
                 },
-                committed_delta,
-                duration,
-            ))
+                committed_delta: streamer.take_last_committed_delta(),
+                commit_context: streamer.take_last_commit_context(),
+                commit_end_ms: committed_end_ms(streamer),
+                word_events: streamer.take_word_events(),
+                vad_silence_detected: true,
+                buffer_duration_ms: duration,
+                decode: None,
+            })
         })
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(BatchTranscriptionResult {
-        result,
-        committed_delta,
-        vad_silence_detected: true,
-        buffer_duration_ms,
-    })
+        .map_err(|e| e.to_string())
 }
 
 /// Emit all events based on batch result (including VAD silence).
@@ -162,8 +178,13 @@ pub async fn process_vad_confirmed(
 /// Emits:
 /// - `stt:vad_silence` if VAD detected speech-to-silence
 /// - `stt:stream_commit` if there's a committed delta
+/// - `stt:word_events` if words changed
 /// - `stt:stream_result` with the transcription result
-pub fn emit_batch_events<R: Runtime>(app: &tauri::AppHandle<R>, result: &BatchTranscriptionResult) {
+pub fn emit_batch_events<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &SttState,
+    result: &BatchTranscriptionResult,
+) {
     // Emit VAD silence event for decoupled turn detection
     if result.vad_silence_detected {
         let _ = app.emit(
@@ -175,7 +196,7 @@ pub fn emit_batch_events<R: Runtime>(app: &tauri::AppHandle<R>, result: &BatchTr
         );
     }
 
-    emit_transcription_events(app, result);
+    emit_transcription_events(app, state, result);
 }
 
 /// Emit transcription events only (no VAD silence).
@@ -184,21 +205,19 @@ pub fn emit_batch_events<R: Runtime>(app: &tauri::AppHandle<R>, result: &BatchTr
 ///
 /// Emits:
 /// - `stt:stream_commit` if there's a committed delta
+/// - `stt:word_events` if words changed
 /// - `stt:stream_result` with the transcription result
 pub fn emit_transcription_events<R: Runtime>(
     app: &tauri::AppHandle<R>,
+    state: &SttState,
     result: &BatchTranscriptionResult,
 ) {
     // Emit commit event if there's a committed delta
     if let Some(ref delta) = result.committed_delta {
-        let _ = app.emit(
-            "stt:stream_commit",
-            StreamingCommitPayload {
-                text: delta.clone(),
-                ts_ms: chrono::Utc::now().timestamp_millis(),
-            },
-        );
+        let context = result.commit_context.unwrap_or_default();
+        two_pass::emit_stream_commit(app, state, delta, context, result.commit_end_ms);
     }
+    word_events::emit_word_events(app, result.word_events.clone());
 
     // Emit stream result
     let dto = StreamingResultDto::from(result.result.clone());
@@ -0,0 +1,78 @@
+//! Backup and restore of the whole local archive: sessions with their
+//! recordings, activities, settings, and the user's skills and tool packs.
+
+use crate::error::{Result, SttError};
+use crate::state::SttState;
+use gibberish_audio::recordings_dir;
+use gibberish_storage::{BackupManifest, BackupScope, ImportReport, Settings};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+
+/// User directories kept in backups, by archive name.
+fn user_dirs() -> Vec<(String, PathBuf)> {
+    let Some(config) = dirs::config_dir().map(|dir| dir.join("gibb.eri.sh")) else {
+        return Vec::new();
+    };
+    ["skills", "tools"]
+        .into_iter()
+        .map(|name| (name.to_string(), config.join(name)))
+        .collect()
+}
+
+fn scope<'a>(settings: Option<&'a Settings>, passphrase: Option<&'a str>) -> BackupScope<'a> {
+    BackupScope {
+        settings,
+        dirs: user_dirs(),
+        recordings_dir: recordings_dir(),
+        passphrase,
+        allow_plaintext: false,
+    }
+}
+
+/// Write a backup of everything to `path`, encrypted with `passphrase`.
+/// An encrypted database is only exported without one when
+/// `allow_plaintext` is set.
+#[tauri::command]
+pub async fn export_backup(
+    state: State<'_, Arc<SttState>>,
+    path: String,
+    passphrase: Option<String>,
+    allow_plaintext: bool,
+) -> Result<BackupManifest> {
+    let db = state.database().await?;
+    let settings = state.get_settings().await;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let manifest = tokio::task::spawn_blocking(move || {
+        let scope = BackupScope {
+            allow_plaintext,
+            ..scope(settings.as_deref(), passphrase.as_deref())
+        };
+        db.export_backup(&PathBuf::from(path), &scope, now_ms)
+    })
+    .await
+    .map_err(|e| SttError::Backup(e.to_string()))??;
+    Ok(manifest)
+}
+
+/// Merge the backup at `path` into the local archive; an encrypted one
+/// needs its `passphrase`. Items that differ from their local copy are
+/// left alone and reported as conflicts.
+#[tauri::command]
+pub async fn import_backup(
+    state: State<'_, Arc<SttState>>,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<ImportReport> {
+    let db = state.database().await?;
+    let settings = state.get_settings().await;
+    let report = tokio::task::spawn_blocking(move || {
+        db.import_backup(
+            &PathBuf::from(path),
+            &scope(settings.as_deref(), passphrase.as_deref()),
+        )
+    })
+    .await
+    .map_err(|e| SttError::Backup(e.to_string()))??;
+    Ok(report)
+}
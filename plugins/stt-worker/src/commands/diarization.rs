@@ -0,0 +1,150 @@
+use crate::dto::SpeakerModelInfoDto;
+use crate::error::{Result, SttError};
+use crate::state::SttState;
+use gibberish_diarization::{DiarizationConfig, Diarizer, OnnxSpeakerEmbedder};
+use gibberish_models::{is_speaker_model_downloaded, speaker_model_path, SpeakerModel};
+use std::sync::Arc;
+use tauri::{Emitter, Runtime, State};
+
+fn parse_speaker_model_name(name: &str) -> Result<SpeakerModel> {
+    SpeakerModel::from_name(name)
+        .ok_or_else(|| SttError::InvalidModelName(format!("unknown speaker model: {name}")))
+}
+
+#[tauri::command]
+pub async fn list_speaker_models() -> Vec<SpeakerModelInfoDto> {
+    SpeakerModel::ALL
+        .into_iter()
+        .map(|m| SpeakerModelInfoDto {
+            name: m.name().to_string(),
+            dir_name: m.dir_name().to_string(),
+            is_downloaded: is_speaker_model_downloaded(m),
+            size_bytes: m.size_bytes(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_current_speaker_model(state: State<'_, Arc<SttState>>) -> Result<Option<String>> {
+    Ok(state
+        .get_current_speaker_model()
+        .map(|m| m.name().to_string()))
+}
+
+#[tauri::command]
+pub async fn is_speaker_downloading(
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<bool> {
+    Ok(state.has_speaker_download(&model_name).await)
+}
+
+#[tauri::command]
+pub async fn cancel_speaker_download(
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<()> {
+    if state.cancel_speaker_download(&model_name).await {
+        tracing::info!("Speaker model download cancelled for: {}", model_name);
+        Ok(())
+    } else {
+        Err(SttError::NotDownloading)
+    }
+}
+
+#[tauri::command]
+pub async fn download_speaker_model<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<String> {
+    let model = parse_speaker_model_name(&model_name)?;
+
+    if is_speaker_model_downloaded(model) {
+        let path = speaker_model_path(model);
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    if state.has_speaker_download(&model_name).await {
+        return Err(SttError::DownloadInProgress);
+    }
+
+    let cancel_token = state.start_speaker_download(model_name.clone()).await;
+    tracing::info!("Downloading speaker model: {}", model_name);
+
+    let app_handle = app.clone();
+    let model_name_for_progress = model_name.clone();
+    let cancel_token_for_progress = cancel_token.clone();
+
+    let download_future =
+        gibberish_models::download_speaker_model(model, move |downloaded, total| {
+            if cancel_token_for_progress.is_cancelled() {
+                return;
+            }
+            let progress = if total > 0 {
+                ((downloaded.min(total) as f64 / total as f64) * 100.0).clamp(0.0, 100.0) as u32
+            } else {
+                0
+            };
+            let _ = app_handle.emit(
+                "stt:speaker-download-progress",
+                (model_name_for_progress.clone(), progress),
+            );
+        });
+
+    let result = tokio::select! {
+        res = download_future => res,
+        _ = cancel_token.cancelled() => {
+            Err(gibberish_models::ModelError::DownloadFailed("Cancelled".to_string()))
+        }
+    };
+
+    state.finish_speaker_download(&model_name).await;
+
+    match result {
+        Ok(path) => Ok(path.to_string_lossy().to_string()),
+        Err(e) => {
+            if e.to_string().contains("Cancelled") {
+                let dir = speaker_model_path(model);
+                let _ = std::fs::remove_dir_all(&dir);
+            }
+            Err(SttError::from(e))
+        }
+    }
+}
+
+/// Load a speaker-embedding model and enable diarization.
+///
+/// Live commits are tagged with speakers from then on, and saved sessions
+/// are re-clustered from their recording.
+#[tauri::command]
+pub async fn load_speaker_model(state: State<'_, Arc<SttState>>, model_name: String) -> Result<()> {
+    let model = parse_speaker_model_name(&model_name)?;
+
+    if !is_speaker_model_downloaded(model) {
+        return Err(SttError::Model(format!(
+            "speaker model not downloaded: {}",
+            model.name()
+        )));
+    }
+
+    let model_path = speaker_model_path(model).join(model.local_filename());
+    let embedder = tokio::task::spawn_blocking(move || OnnxSpeakerEmbedder::load(&model_path))
+        .await
+        .map_err(|e| SttError::Diarization(e.to_string()))?
+        .map_err(|e| SttError::Diarization(format!("failed to load {}: {}", model.name(), e)))?;
+
+    let diarizer = Diarizer::new(Arc::new(embedder), DiarizationConfig::default());
+    state.set_diarizer(model, Arc::new(diarizer));
+    gibberish_models::mark_used(&speaker_model_path(model));
+    tracing::info!("Speaker model loaded: {}", model.name());
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unload_speaker_model(state: State<'_, Arc<SttState>>) -> Result<()> {
+    state.clear_diarizer();
+    tracing::info!("Speaker model unloaded");
+    Ok(())
+}
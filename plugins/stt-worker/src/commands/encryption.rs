@@ -0,0 +1,236 @@
+//! Encryption at rest: the database through SQLCipher, recordings sealed
+//! with a file key kept inside the encrypted database.
+//!
+//! Without a passphrase a random key is generated and kept in the OS
+//! keyring, so the database opens on its own at startup. A passphrase is
+//! never stored; the database then stays locked until `unlock_database`.
+
+use crate::dto::EncryptionStatusDto;
+use crate::error::{Result, SttError};
+use crate::key_store;
+use crate::state::SttState;
+use gibberish_audio::recordings_dir;
+use gibberish_storage::{DatabaseKey, FileCipher};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Runtime, State};
+
+/// Recordings in [`recordings_dir`]: WAV files, compressed to FLAC once
+/// attached to a session.
+fn recordings() -> Vec<PathBuf> {
+    let Some(dir) = recordings_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "wav" || ext == "flac")
+        })
+        .collect()
+}
+
+/// Seal (or unseal) every recording. Returns the number of files that could
+/// not be rewritten; they are logged, and sealing failures are reported by
+/// `get_encryption_status`.
+fn seal_recordings(cipher: &FileCipher, seal: bool) -> usize {
+    let mut failed = 0;
+    for path in recordings() {
+        let result = if seal {
+            cipher.seal_file(&path)
+        } else {
+            cipher.unseal_file(&path)
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to rewrite recording {:?}: {}", path, e);
+            failed += 1;
+        }
+    }
+    failed
+}
+
+/// Recordings that are not sealed, counting unreadable ones.
+fn unsealed_recordings() -> usize {
+    recordings()
+        .iter()
+        .filter(|path| !FileCipher::is_sealed_file(path).unwrap_or(false))
+        .count()
+}
+
+fn key_from(passphrase: Option<String>) -> DatabaseKey {
+    match passphrase {
+        Some(passphrase) => DatabaseKey::Passphrase(passphrase),
+        None => DatabaseKey::generate(),
+    }
+}
+
+/// The current key: the passphrase when given, otherwise the keyring key.
+fn current_key(passphrase: Option<String>) -> Result<DatabaseKey> {
+    match passphrase {
+        Some(passphrase) => Ok(DatabaseKey::Passphrase(passphrase)),
+        None => key_store::load()?.ok_or_else(|| {
+            SttError::Encryption("the key is not in the keyring; enter the passphrase".to_string())
+        }),
+    }
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| SttError::Encryption(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn get_encryption_status(state: State<'_, Arc<SttState>>) -> Result<EncryptionStatusDto> {
+    let locked = state.is_database_locked().await;
+    let encrypted = locked
+        || state
+            .get_database()
+            .await
+            .is_some_and(|db| db.is_encrypted());
+    let uses_keyring = encrypted && key_store::load().ok().flatten().is_some();
+    let unsealed_recordings = if encrypted && !locked {
+        blocking(|| Ok(unsealed_recordings())).await?
+    } else {
+        0
+    };
+    Ok(EncryptionStatusDto {
+        encrypted,
+        locked,
+        uses_keyring,
+        unsealed_recordings,
+    })
+}
+
+/// Open a database encrypted under a passphrase, then load the settings
+/// stored in it. Recordings saved before it was unlocked are sealed.
+#[tauri::command]
+pub async fn unlock_database<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, Arc<SttState>>,
+    passphrase: String,
+) -> Result<()> {
+    if !state.is_database_locked().await {
+        return Ok(());
+    }
+    let db = state
+        .unlock_database(&DatabaseKey::Passphrase(passphrase))
+        .await?;
+    blocking(move || {
+        if let Some(cipher) = db.file_cipher()? {
+            seal_recordings(&cipher, true);
+        }
+        Ok(())
+    })
+    .await?;
+    crate::init_settings(&app, &state).await;
+    Ok(())
+}
+
+/// Encrypt the database in place and seal the recordings. Without a
+/// passphrase the key goes to the OS keyring.
+#[tauri::command]
+pub async fn enable_encryption(
+    state: State<'_, Arc<SttState>>,
+    passphrase: Option<String>,
+) -> Result<()> {
+    let db = state.database().await?;
+    if db.is_encrypted() {
+        return Err(SttError::Encryption(
+            "the database is already encrypted".to_string(),
+        ));
+    }
+    let key = key_from(passphrase);
+    let in_keyring = key.to_hex().is_some();
+    if in_keyring {
+        // Stored first: a key that only lived in memory would be lost on exit.
+        key_store::store(&key)?;
+    }
+
+    let result = blocking(move || {
+        db.encrypt(&key)?;
+        if let Some(cipher) = db.file_cipher()? {
+            seal_recordings(&cipher, true);
+        }
+        Ok(())
+    })
+    .await;
+    if result.is_err() && in_keyring {
+        let _ = key_store::delete();
+    }
+    result?;
+    tracing::info!("Database encrypted");
+    Ok(())
+}
+
+/// Decrypt the database in place and unseal the recordings.
+#[tauri::command]
+pub async fn disable_encryption(
+    state: State<'_, Arc<SttState>>,
+    passphrase: Option<String>,
+) -> Result<()> {
+    let db = state.database().await?;
+    let key = current_key(passphrase)?;
+    blocking(move || {
+        db.check_key(&key)?;
+        // The file key is dropped with the encryption, so every recording
+        // has to be readable without it first.
+        if let Some(cipher) = db.file_cipher()? {
+            let failed = seal_recordings(&cipher, false);
+            if failed > 0 {
+                return Err(SttError::Encryption(format!(
+                    "{failed} recordings could not be decrypted"
+                )));
+            }
+        }
+        db.decrypt(&key)?;
+        Ok(())
+    })
+    .await?;
+    key_store::delete()?;
+    tracing::info!("Database decrypted");
+    Ok(())
+}
+
+/// Replace the database key. Without a new passphrase a fresh key is
+/// generated and kept in the OS keyring. Recordings keep their file key.
+#[tauri::command]
+pub async fn rotate_encryption_key(
+    state: State<'_, Arc<SttState>>,
+    passphrase: Option<String>,
+    new_passphrase: Option<String>,
+) -> Result<()> {
+    let db = state.database().await?;
+    let key = current_key(passphrase)?;
+    let new_key = key_from(new_passphrase);
+    let previous = key_store::load()?;
+    let in_keyring = new_key.to_hex().is_some();
+    if in_keyring {
+        key_store::store(&new_key)?;
+    }
+
+    let result = blocking(move || {
+        db.check_key(&key)?;
+        db.rekey(&new_key)?;
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        // Put the keyring back the way the database still expects it.
+        if in_keyring {
+            let _ = match &previous {
+                Some(previous) => key_store::store(previous),
+                None => key_store::delete(),
+            };
+        }
+        return Err(e);
+    }
+    if !in_keyring {
+        key_store::delete()?;
+    }
+    tracing::info!("Database key rotated");
+    Ok(())
+}
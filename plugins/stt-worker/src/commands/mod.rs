@@ -1,11 +1,23 @@
+mod backup;
+mod diarization;
+mod encryption;
 mod listener;
 mod model;
+mod punctuation;
+mod retention;
 mod session;
+mod store;
 mod transcription;
 mod turn;
 
+pub use backup::*;
+pub use diarization::*;
+pub use encryption::*;
 pub use listener::*;
 pub use model::*;
+pub use punctuation::*;
+pub use retention::*;
 pub use session::*;
+pub use store::*;
 pub use transcription::*;
 pub use turn::*;
@@ -4,6 +4,7 @@ use crate::services::ModelService;
 use crate::state::SttState;
 use gibberish_models::model_path;
 use gibberish_sherpa::SherpaStreamingEngine;
+use gibberish_storage::Language;
 use std::sync::Arc;
 use tauri::{Emitter, Runtime, State};
 
@@ -128,6 +129,9 @@ pub async fn load_model(state: State<'_, Arc<SttState>>, model_name: String) ->
 
     state.set_engine(engine).await;
     state.set_current_model(model).await;
+    state.configure_punctuation().await;
+    state.configure_streaming_policy().await;
+    gibberish_models::mark_used(&ModelService::get_model_path(model));
 
     Ok(())
 }
@@ -137,6 +141,8 @@ pub async fn unload_model(state: State<'_, Arc<SttState>>) -> Result<()> {
     state.clear_sherpa_worker();
     state.clear_engine().await;
     state.clear_current_model().await;
+    state.configure_punctuation().await;
+    state.configure_streaming_policy().await;
     tracing::info!("Model unloaded");
     Ok(())
 }
@@ -167,6 +173,12 @@ pub async fn set_language(state: State<'_, Arc<SttState>>, language: String) ->
         )));
     }
 
+    apply_language(&state, language.clone()).await?;
+    state.persist_setting(Language(language)).await
+}
+
+/// Switch the transcription language, reloading any loaded models for it.
+pub(crate) async fn apply_language(state: &SttState, language: String) -> Result<()> {
     state.set_language(language.clone()).await;
     tracing::info!(language = %language, "Language preference updated");
 
@@ -193,7 +205,55 @@ pub async fn set_language(state: State<'_, Arc<SttState>>, language: String) ->
         }
 
         state.set_engine(engine).await;
+        state.configure_punctuation().await;
+    }
+
+    // Reload the two-pass refiner too
+    if let Some(model) = state.with_two_pass(|two_pass| two_pass.refiner_model()) {
+        let engine =
+            ModelService::load_engine_with_registry(state.engine_registry(), model, &language)
+                .map_err(|e| SttError::Model(e.to_string()))?;
+        state.with_two_pass(|two_pass| two_pass.set_refiner(model, engine));
     }
 
     Ok(())
 }
+
+/// Load a batch model that re-decodes streaming commits (two-pass mode).
+///
+/// The streaming model keeps driving live text; each committed utterance is
+/// re-transcribed by this model and announced via `stt:commit_revised`.
+#[tauri::command]
+pub async fn load_refiner_model(state: State<'_, Arc<SttState>>, model_name: String) -> Result<()> {
+    let model = ModelService::parse_model_name(&model_name)
+        .map_err(|e| SttError::InvalidModelName(e.to_string()))?;
+
+    let registry = state.engine_registry();
+    if registry.is_streaming(model.name()) {
+        return Err(SttError::InvalidModelName(format!(
+            "{model_name} is a streaming model; the refiner must be a batch model"
+        )));
+    }
+
+    let language = state.get_language().await;
+    let engine = ModelService::load_engine_with_registry(registry, model, &language)
+        .map_err(|e| SttError::Model(e.to_string()))?;
+
+    state.with_two_pass(|two_pass| two_pass.set_refiner(model, engine));
+    gibberish_models::mark_used(&ModelService::get_model_path(model));
+    tracing::info!(model = %model_name, "Two-pass refiner loaded");
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unload_refiner_model(state: State<'_, Arc<SttState>>) -> Result<()> {
+    state.with_two_pass(|two_pass| two_pass.clear_refiner());
+    tracing::info!("Two-pass refiner unloaded");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_refiner_model(state: State<'_, Arc<SttState>>) -> Result<Option<String>> {
+    Ok(state.with_two_pass(|two_pass| two_pass.refiner_model().map(|m| m.name().to_string())))
+}
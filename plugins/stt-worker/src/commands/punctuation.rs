@@ -0,0 +1,163 @@
+use crate::dto::PunctuationModelInfoDto;
+use crate::error::{Result, SttError};
+use crate::state::SttState;
+use gibberish_models::{is_punctuation_model_downloaded, punctuation_model_path, PunctuationModel};
+use gibberish_punctuation_onnx::OnnxPunctuator;
+use std::sync::Arc;
+use tauri::{Emitter, Runtime, State};
+
+fn parse_punctuation_model_name(name: &str) -> Result<PunctuationModel> {
+    PunctuationModel::from_name(name)
+        .ok_or_else(|| SttError::InvalidModelName(format!("unknown punctuation model: {name}")))
+}
+
+#[tauri::command]
+pub async fn list_punctuation_models() -> Vec<PunctuationModelInfoDto> {
+    PunctuationModel::ALL
+        .into_iter()
+        .map(|m| PunctuationModelInfoDto {
+            name: m.name().to_string(),
+            dir_name: m.dir_name().to_string(),
+            languages: m.languages().iter().map(|l| l.to_string()).collect(),
+            is_downloaded: is_punctuation_model_downloaded(m),
+            size_bytes: m.size_bytes(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_current_punctuation_model(
+    state: State<'_, Arc<SttState>>,
+) -> Result<Option<String>> {
+    Ok(state
+        .get_current_punctuation_model()
+        .map(|m| m.name().to_string()))
+}
+
+#[tauri::command]
+pub async fn is_punctuation_downloading(
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<bool> {
+    Ok(state.has_punctuation_download(&model_name).await)
+}
+
+#[tauri::command]
+pub async fn cancel_punctuation_download(
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<()> {
+    if state.cancel_punctuation_download(&model_name).await {
+        tracing::info!("Punctuation model download cancelled for: {}", model_name);
+        Ok(())
+    } else {
+        Err(SttError::NotDownloading)
+    }
+}
+
+#[tauri::command]
+pub async fn download_punctuation_model<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<String> {
+    let model = parse_punctuation_model_name(&model_name)?;
+
+    if is_punctuation_model_downloaded(model) {
+        let path = punctuation_model_path(model);
+        return Ok(path.to_string_lossy().to_string());
+    }
+
+    if state.has_punctuation_download(&model_name).await {
+        return Err(SttError::DownloadInProgress);
+    }
+
+    let cancel_token = state.start_punctuation_download(model_name.clone()).await;
+    tracing::info!("Downloading punctuation model: {}", model_name);
+
+    let app_handle = app.clone();
+    let model_name_for_progress = model_name.clone();
+    let cancel_token_for_progress = cancel_token.clone();
+
+    let download_future =
+        gibberish_models::download_punctuation_model(model, move |downloaded, total| {
+            if cancel_token_for_progress.is_cancelled() {
+                return;
+            }
+            let progress = if total > 0 {
+                ((downloaded.min(total) as f64 / total as f64) * 100.0).clamp(0.0, 100.0) as u32
+            } else {
+                0
+            };
+            let _ = app_handle.emit(
+                "stt:punctuation-download-progress",
+                (model_name_for_progress.clone(), progress),
+            );
+        });
+
+    let result = tokio::select! {
+        res = download_future => res,
+        _ = cancel_token.cancelled() => {
+            Err(gibberish_models::ModelError::DownloadFailed("Cancelled".to_string()))
+        }
+    };
+
+    state.finish_punctuation_download(&model_name).await;
+
+    match result {
+        Ok(path) => Ok(path.to_string_lossy().to_string()),
+        Err(e) => {
+            if e.to_string().contains("Cancelled") {
+                let dir = punctuation_model_path(model);
+                let _ = std::fs::remove_dir_all(&dir);
+            }
+            Err(SttError::from(e))
+        }
+    }
+}
+
+/// Load a punctuation model in place of the rule-based fallback.
+///
+/// It is applied to commits of engines that need punctuation, when the
+/// engine's language is one the model was trained on.
+#[tauri::command]
+pub async fn load_punctuation_model(
+    state: State<'_, Arc<SttState>>,
+    model_name: String,
+) -> Result<()> {
+    let model = parse_punctuation_model_name(&model_name)?;
+
+    if !is_punctuation_model_downloaded(model) {
+        return Err(SttError::Model(format!(
+            "punctuation model not downloaded: {}",
+            model.name()
+        )));
+    }
+
+    let dir = punctuation_model_path(model);
+    let model_path = dir.join(model.model_filename());
+    let vocab_path = dir.join(model.vocab_filename());
+    let punctuator =
+        tokio::task::spawn_blocking(move || OnnxPunctuator::load(&model_path, &vocab_path))
+            .await
+            .map_err(|e| SttError::Punctuation(e.to_string()))?
+            .map_err(|e| {
+                SttError::Punctuation(format!("failed to load {}: {}", model.name(), e))
+            })?;
+
+    state.set_punctuator(model, Arc::new(punctuator));
+    state.configure_punctuation().await;
+    gibberish_models::mark_used(&dir);
+    tracing::info!("Punctuation model loaded: {}", model.name());
+
+    Ok(())
+}
+
+/// Unload the punctuation model; the rule-based fallback stays in effect.
+#[tauri::command]
+pub async fn unload_punctuation_model(state: State<'_, Arc<SttState>>) -> Result<()> {
+    state.clear_punctuator();
+    state.configure_punctuation().await;
+    tracing::info!("Punctuation model unloaded");
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Retention rules: stored as the `retention` setting and applied hourly by
+//! the retention job, or on demand here.
+
+use crate::error::{Result, SttError};
+use crate::retention_job;
+use crate::state::SttState;
+use gibberish_storage::{RetentionReport, RetentionSettings, Settings};
+use std::sync::Arc;
+use tauri::State;
+
+async fn settings_store(state: &SttState) -> Result<Arc<Settings>> {
+    state.database().await?;
+    state
+        .get_settings()
+        .await
+        .ok_or(SttError::DatabaseNotInitialized)
+}
+
+#[tauri::command]
+pub async fn get_retention_settings(state: State<'_, Arc<SttState>>) -> Result<RetentionSettings> {
+    Ok(settings_store(&state).await?.get())
+}
+
+#[tauri::command]
+pub async fn set_retention_settings(
+    state: State<'_, Arc<SttState>>,
+    settings: RetentionSettings,
+) -> Result<()> {
+    settings_store(&state).await?.set(settings)?;
+    Ok(())
+}
+
+/// What the stored rules would delete now, without deleting it.
+#[tauri::command]
+pub async fn preview_retention(state: State<'_, Arc<SttState>>) -> Result<RetentionReport> {
+    retention_job::apply_retention(&state, true)
+        .await?
+        .ok_or(SttError::DatabaseNotInitialized)
+}
+
+/// Apply the stored rules now.
+#[tauri::command]
+pub async fn apply_retention(state: State<'_, Arc<SttState>>) -> Result<RetentionReport> {
+    retention_job::apply_retention(&state, false)
+        .await?
+        .ok_or(SttError::DatabaseNotInitialized)
+}
@@ -1,27 +1,59 @@
-use crate::dto::{SessionDto, SessionSegmentDto, SessionSummaryDto};
+use crate::dto::{
+    SessionDigestPayload, SessionDto, SessionExportDto, SessionRevisionDto, SessionSegmentDto,
+    SessionSummaryDto,
+};
 use crate::error::{Result, SttError};
 use crate::state::SttState;
+use gibberish_diarization::Diarizer;
+use gibberish_itn::InverseNormalizer;
+use gibberish_storage::{
+    AudioSpan, Database, MarkerQuery, RetentionSettings, SearchHit, SearchQuery, SessionMarker,
+    StorageError, HIGHLIGHT_END, HIGHLIGHT_START,
+};
+use gibberish_transcript::{
+    Chapter, ChapterOptions, Digest, DigestOptions, ExportFormat, ExportOptions, Marker, Segment,
+    Transcript, TranscriptEdit,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, Runtime, State};
 
+/// Save a finished session.
+///
+/// Segment text is rewritten into written form ("twenty five percent"
+/// becomes "25%") when the transcript language is known; the spoken form is
+/// kept in `raw_text`. When `audio_path` points at the session recording and
+/// a speaker model is loaded, speakers are re-clustered over the whole
+/// recording, replacing the live (online) assignments; the recording is
+/// then compressed and attached to the session, or deleted when the
+/// retention rules for its mode keep no recordings. Markers placed
+/// during the recording are attached to the transcript they point into.
+/// Sessions recorded in Meeting mode get an offline digest, stored with the session and
+/// emitted as `stt:session_digest`. The transcript is split into chapters,
+/// and an untitled session is named after its dominant chapter plus
+/// `title_context` (the meeting app or active project), when given.
 #[tauri::command]
-pub async fn save_session(
+#[allow(clippy::too_many_arguments)]
+pub async fn save_session<R: Runtime>(
+    app: tauri::AppHandle<R>,
     state: State<'_, Arc<SttState>>,
     segments: Vec<SessionSegmentDto>,
     duration_ms: u64,
     title: Option<String>,
+    audio_path: Option<String>,
+    markers: Option<Vec<Marker>>,
+    mode: Option<String>,
+    title_context: Option<String>,
 ) -> Result<String> {
     use chrono::Utc;
-    use gibberish_transcript::{Segment, Transcript, TranscriptRepository};
+    use gibberish_transcript::TranscriptRepository;
     use uuid::Uuid;
 
-    let db = state
-        .get_database()
-        .await
-        .ok_or(SttError::DatabaseNotInitialized)?;
+    let db = state.database().await?;
 
     let now = Utc::now();
-    let transcript = Transcript {
+    let mut transcript = Transcript {
         id: Uuid::new_v4(),
         title,
         segments: segments
@@ -29,6 +61,7 @@ pub async fn save_session(
             .map(|s| Segment {
                 id: Uuid::parse_str(&s.id).unwrap_or_else(|_| Uuid::new_v4()),
                 text: s.text,
+                raw_text: s.raw_text,
                 start_ms: s.start_ms,
                 end_ms: s.end_ms,
                 words: Vec::new(),
@@ -39,23 +72,111 @@ pub async fn save_session(
         created_at: now,
         updated_at: now,
         duration_ms,
+        mode,
+        speaker_names: Default::default(),
+        markers: Vec::new(),
+        chapters: Vec::new(),
+        digest: None,
+        revisions: Default::default(),
     };
 
+    if let Some(language) = state.transcript_language().await {
+        normalize_segments(&InverseNormalizer::new(&language), &mut transcript.segments);
+    }
+
+    let audio_path = audio_path.map(PathBuf::from);
+    if let (Some(path), Some(diarizer)) = (&audio_path, state.get_diarizer()) {
+        if let Err(e) = recluster_speakers(diarizer, path.clone(), &mut transcript.segments).await {
+            tracing::warn!("Offline diarization failed, keeping live speakers: {}", e);
+        }
+    }
+
+    for marker in markers.unwrap_or_default() {
+        transcript.add_marker(marker);
+    }
+    transcript.chapters = transcript.segment_chapters(&ChapterOptions::default());
+    if transcript.title.is_none() {
+        transcript.title = transcript.suggest_title(title_context.as_deref());
+    }
+    if transcript.mode.as_deref() == Some("Meeting") {
+        transcript.digest = Some(transcript.summarize(&DigestOptions::default()));
+    }
+
     let id = transcript.id.to_string();
     db.save(&transcript)?;
     tracing::info!("Session saved: {}", id);
 
+    if let Some(path) = audio_path {
+        if discards_audio(&state, transcript.mode.as_deref()).await {
+            discard_recording(&path);
+        } else {
+            attach_recording(&db, transcript.id, path).await?;
+        }
+    }
+
+    if let Some(digest) = transcript.digest {
+        let _ = app.emit(
+            "stt:session_digest",
+            SessionDigestPayload {
+                session_id: id.clone(),
+                digest,
+            },
+        );
+    }
+
     Ok(id)
 }
 
+/// Generate (or regenerate) the digest of a saved session and store it.
+#[tauri::command]
+pub async fn generate_session_digest(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    options: Option<DigestOptions>,
+) -> Result<Digest> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+    let digest = transcript.summarize(&options.unwrap_or_default());
+    transcript.digest = Some(digest.clone());
+
+    db.save(&transcript)?;
+    Ok(digest)
+}
+
+/// Re-run chapter segmentation over a saved session and store the result.
+/// An untitled session also gets a title from its dominant chapter.
+#[tauri::command]
+pub async fn generate_session_chapters(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    options: Option<ChapterOptions>,
+) -> Result<Vec<Chapter>> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+    transcript.chapters = transcript.segment_chapters(&options.unwrap_or_default());
+    if transcript.title.is_none() {
+        transcript.title = transcript.suggest_title(None);
+    }
+
+    db.save(&transcript)?;
+    Ok(transcript.chapters)
+}
+
 #[tauri::command]
 pub async fn list_sessions(state: State<'_, Arc<SttState>>) -> Result<Vec<SessionSummaryDto>> {
     use gibberish_transcript::TranscriptRepository;
 
-    let db = state
-        .get_database()
-        .await
-        .ok_or(SttError::DatabaseNotInitialized)?;
+    let db = state.database().await?;
 
     let transcripts = db.list()?;
 
@@ -91,43 +212,461 @@ pub async fn get_session(state: State<'_, Arc<SttState>>, id: String) -> Result<
     use gibberish_transcript::TranscriptRepository;
     use uuid::Uuid;
 
-    let db = state
-        .get_database()
-        .await
-        .ok_or(SttError::DatabaseNotInitialized)?;
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    session_dto(&db, db.get(&uuid)?)
+}
+
+/// Padding around a played span, so its first and last sounds are not cut.
+const AUDIO_PADDING_MS: u64 = 150;
+
+/// The audio of a time range, segment or word of a saved session, as WAV
+/// bytes (an `ArrayBuffer` in the frontend).
+#[tauri::command]
+pub async fn get_session_audio(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    span: AudioSpan,
+    padding_ms: Option<u64>,
+) -> Result<tauri::ipc::Response> {
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let padding_ms = padding_ms.unwrap_or(AUDIO_PADDING_MS);
+    let wav = tokio::task::spawn_blocking(move || {
+        db.session_audio_clip(&uuid, &span, padding_ms)?.to_wav()
+    })
+    .await
+    .map_err(|e| SttError::Audio(e.to_string()))??;
+
+    Ok(tauri::ipc::Response::new(wav))
+}
+
+/// Rename a diarized speaker in a saved session. An empty name restores the
+/// default "Speaker N" label.
+#[tauri::command]
+pub async fn rename_speaker(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    speaker: i32,
+    name: String,
+) -> Result<()> {
+    use chrono::Utc;
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+
+    transcript.rename_speaker(speaker, &name);
+    transcript.updated_at = Utc::now();
+
+    db.save(&transcript)?;
+    tracing::info!("Speaker {} renamed in session {}", speaker, id);
+
+    Ok(())
+}
+
+/// Apply an edit to a saved session and record it in its revision log.
+/// Returns the edited session.
+#[tauri::command]
+pub async fn edit_session(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    edit: TranscriptEdit,
+) -> Result<SessionDto> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+    let revision = transcript.apply_edit(edit)?;
+
+    db.save(&transcript)?;
+    tracing::debug!("Session {} edited (revision {})", id, revision);
+
+    session_dto(&db, transcript)
+}
+
+/// Undo the last edit of a saved session. The undo is itself a revision.
+#[tauri::command]
+pub async fn undo_session_edit(state: State<'_, Arc<SttState>>, id: String) -> Result<SessionDto> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+    transcript.undo()?;
+
+    db.save(&transcript)?;
+    session_dto(&db, transcript)
+}
+
+/// Redo the last undone edit of a saved session.
+#[tauri::command]
+pub async fn redo_session_edit(state: State<'_, Arc<SttState>>, id: String) -> Result<SessionDto> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+    transcript.redo()?;
+
+    db.save(&transcript)?;
+    session_dto(&db, transcript)
+}
+
+/// Revision log of a saved session, oldest first.
+#[tauri::command]
+pub async fn get_session_revisions(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+) -> Result<Vec<SessionRevisionDto>> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let transcript = db.get(&uuid)?;
+
+    Ok(transcript
+        .revisions
+        .entries()
+        .iter()
+        .map(SessionRevisionDto::from)
+        .collect())
+}
+
+/// A saved session as it was at `revision` (0 is the original). Read-only;
+/// the session itself is not changed.
+#[tauri::command]
+pub async fn get_session_at_revision(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    revision: usize,
+) -> Result<SessionDto> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let transcript = db.get(&uuid)?;
+
+    session_dto(&db, transcript.at_revision(revision)?)
+}
+
+/// Attach a marker to a saved session. Without a span, the marker points
+/// at the segment spoken at its timestamp. Returns the stored marker.
+#[tauri::command]
+pub async fn add_session_marker(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    marker: Marker,
+) -> Result<Marker> {
+    use chrono::Utc;
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let mut transcript = db.get(&uuid)?;
+    let marker_id = transcript.add_marker(marker);
+    transcript.updated_at = Utc::now();
+
+    db.save(&transcript)?;
+    Ok(transcript
+        .markers
+        .into_iter()
+        .find(|m| m.id == marker_id)
+        .expect("marker was just added"))
+}
+
+/// Mark an action item or question as done (or open again).
+#[tauri::command]
+pub async fn set_session_marker_done(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    marker_id: String,
+    done: bool,
+) -> Result<()> {
+    use chrono::Utc;
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let marker_uuid = Uuid::parse_str(&marker_id)?;
+    let mut transcript = db.get(&uuid)?;
+    transcript
+        .marker_mut(&marker_uuid)
+        .ok_or_else(|| StorageError::NotFound(format!("marker {marker_id}")))?
+        .done = done;
+    transcript.updated_at = Utc::now();
+
+    db.save(&transcript)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_session_marker(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    marker_id: String,
+) -> Result<()> {
+    use chrono::Utc;
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
+
+    let uuid = Uuid::parse_str(&id)?;
+    let marker_uuid = Uuid::parse_str(&marker_id)?;
+    let mut transcript = db.get(&uuid)?;
+    transcript
+        .remove_marker(&marker_uuid)
+        .ok_or_else(|| StorageError::NotFound(format!("marker {marker_id}")))?;
+    transcript.updated_at = Utc::now();
+
+    db.save(&transcript)?;
+    Ok(())
+}
+
+/// Markers across all sessions, newest first, e.g. open action items
+/// placed this week.
+#[tauri::command]
+pub async fn query_markers(
+    state: State<'_, Arc<SttState>>,
+    query: MarkerQuery,
+) -> Result<Vec<SessionMarker>> {
+    let db = state.database().await?;
+
+    Ok(db.query_markers(&query)?)
+}
+
+/// Render a saved session as subtitles (SRT, WebVTT) or a document (JSON,
+/// Markdown, plain text).
+#[tauri::command]
+pub async fn export_session(
+    state: State<'_, Arc<SttState>>,
+    id: String,
+    format: ExportFormat,
+    options: Option<ExportOptions>,
+) -> Result<SessionExportDto> {
+    use gibberish_transcript::TranscriptRepository;
+    use uuid::Uuid;
+
+    let db = state.database().await?;
 
     let uuid = Uuid::parse_str(&id)?;
     let transcript = db.get(&uuid)?;
+    let content = transcript.export(format, &options.unwrap_or_default());
 
+    Ok(SessionExportDto {
+        content,
+        file_name: format!(
+            "{}.{}",
+            export_file_stem(transcript.title.as_deref()),
+            format.extension()
+        ),
+    })
+}
+
+/// Import an SRT, WebVTT or JSON export file as a saved session.
+///
+/// The format comes from the file extension, or the content when the
+/// extension is unknown. Subtitle imports are titled after the file and
+/// dated by its modification time. A JSON export keeps its id, and is
+/// refused while a session with that id is saved, so the local copy and
+/// its edits are never overwritten. Returns the session id.
+#[tauri::command]
+pub async fn import_session(
+    state: State<'_, Arc<SttState>>,
+    path: String,
+    title: Option<String>,
+) -> Result<String> {
+    use chrono::{DateTime, Utc};
+    use gibberish_transcript::TranscriptRepository;
+
+    let db = state.database().await?;
+
+    let path = PathBuf::from(path);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| SttError::Import(format!("{}: {e}", path.display())))?;
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ExportFormat::from_extension)
+        .or_else(|| ExportFormat::sniff(&content))
+        .ok_or_else(|| SttError::Import(format!("unknown format: {}", path.display())))?;
+
+    let mut transcript = Transcript::import(&content, format)?;
+    if format != ExportFormat::Json {
+        if let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) {
+            transcript.created_at = DateTime::<Utc>::from(modified);
+        }
+        transcript.title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned());
+    }
+    if let Some(title) = title {
+        transcript.title = Some(title);
+    }
+    transcript.updated_at = Utc::now();
+
+    let id = transcript.id.to_string();
+    match db.get(&transcript.id) {
+        Ok(_) => {
+            return Err(SttError::Import(format!(
+                "session {id} is already saved; delete it to import this copy"
+            )))
+        }
+        Err(StorageError::NotFound(_)) => {}
+        Err(e) => return Err(e.into()),
+    }
+    db.save(&transcript)?;
+    tracing::info!(
+        "Session imported from {}: {} ({} segments)",
+        path.display(),
+        id,
+        transcript.segments.len()
+    );
+
+    Ok(id)
+}
+
+/// File name stem from a session title, without path separators or
+/// characters file systems reject.
+fn export_file_stem(title: Option<&str>) -> String {
+    let stem: String = title
+        .unwrap_or_default()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let stem = stem.trim().trim_matches('.');
+    if stem.is_empty() {
+        "transcript".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Rewrite segment text into written form, keeping the spoken form in
+/// `raw_text`.
+fn normalize_segments(normalizer: &InverseNormalizer, segments: &mut [Segment]) {
+    for segment in segments {
+        let normalized = normalizer.normalize(&segment.text);
+        if !normalized.is_unchanged() {
+            let raw = std::mem::replace(&mut segment.text, normalized.text);
+            segment.raw_text.get_or_insert(raw);
+        }
+    }
+}
+
+/// Encrypt the recording when the database is encrypted. Failures are
+/// logged; the session is already saved.
+fn seal_recording(db: &Database, path: &Path) {
+    match db.file_cipher() {
+        Ok(Some(cipher)) => {
+            if let Err(e) = cipher.seal_file(path) {
+                tracing::warn!("Failed to encrypt recording {:?}: {}", path, e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Recording {:?} left unencrypted: {}", path, e),
+    }
+}
+
+/// Whether the retention rules keep no recordings of sessions in `mode`.
+async fn discards_audio(state: &SttState, mode: Option<&str>) -> bool {
+    state
+        .get_settings()
+        .await
+        .is_some_and(|settings| settings.get::<RetentionSettings>().discards_audio(mode))
+}
+
+/// Delete a recording the retention rules do not keep.
+fn discard_recording(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => tracing::info!("Recording {:?} discarded by the retention rules", path),
+        Err(e) => tracing::warn!("Failed to discard recording {:?}: {}", path, e),
+    }
+}
+
+/// Compress the recording and attach it to the session. A recording that
+/// cannot be compressed is attached as it is.
+async fn attach_recording(db: &Arc<Database>, id: uuid::Uuid, path: PathBuf) -> Result<()> {
+    let compressed = {
+        let db = Arc::clone(db);
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || db.attach_session_audio(&id, &path))
+            .await
+            .map_err(|e| SttError::Audio(e.to_string()))?
+    };
+    if let Err(e) = compressed {
+        tracing::warn!("Failed to compress recording {:?}: {}", path, e);
+        db.set_session_audio(&id, Some(&path))?;
+        seal_recording(db, &path);
+    }
+    Ok(())
+}
+
+/// A session for the frontend, with whether a recording is attached.
+fn session_dto(db: &Database, transcript: Transcript) -> Result<SessionDto> {
+    let has_audio = db.session_audio(&transcript.id)?.is_some();
     Ok(SessionDto {
-        id: transcript.id.to_string(),
-        title: transcript.title,
-        created_at: transcript.created_at.timestamp(),
-        updated_at: transcript.updated_at.timestamp(),
-        duration_ms: transcript.duration_ms,
-        segments: transcript
-            .segments
-            .into_iter()
-            .map(|s| SessionSegmentDto {
-                id: s.id.to_string(),
-                text: s.text,
-                start_ms: s.start_ms,
-                end_ms: s.end_ms,
-                speaker: s.speaker,
-            })
-            .collect(),
+        has_audio,
+        ..transcript.into()
     })
 }
 
+/// Re-cluster speakers over the whole recording.
+async fn recluster_speakers(
+    diarizer: Arc<Diarizer>,
+    audio_path: PathBuf,
+    segments: &mut [Segment],
+) -> Result<()> {
+    let spans: Vec<(u64, u64)> = segments.iter().map(|s| (s.start_ms, s.end_ms)).collect();
+    let speakers = tokio::task::spawn_blocking(move || {
+        let audio = gibberish_stt::read_wav_mono_f32_16k(&audio_path)
+            .map_err(|e| SttError::Diarization(e.to_string()))?;
+        diarizer.diarize(&audio, &spans).map_err(SttError::from)
+    })
+    .await
+    .map_err(|e| SttError::Diarization(e.to_string()))??;
+
+    for (segment, speaker) in segments.iter_mut().zip(speakers) {
+        if speaker.is_some() {
+            segment.speaker = speaker;
+        }
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn delete_session(state: State<'_, Arc<SttState>>, id: String) -> Result<()> {
     use gibberish_transcript::TranscriptRepository;
     use uuid::Uuid;
 
-    let db = state
-        .get_database()
-        .await
-        .ok_or(SttError::DatabaseNotInitialized)?;
+    let db = state.database().await?;
 
     let uuid = Uuid::parse_str(&id)?;
     db.delete(&uuid)?;
@@ -146,10 +685,7 @@ pub async fn update_session_title(
     use gibberish_transcript::TranscriptRepository;
     use uuid::Uuid;
 
-    let db = state
-        .get_database()
-        .await
-        .ok_or(SttError::DatabaseNotInitialized)?;
+    let db = state.database().await?;
 
     let uuid = Uuid::parse_str(&id)?;
     let mut transcript = db.get(&uuid)?;
@@ -163,58 +699,50 @@ pub async fn update_session_title(
     Ok(())
 }
 
+/// Hits considered when grouping search results by session.
+const SEARCH_HIT_LIMIT: usize = 500;
+
+/// Sessions matching a full-text query, best match first, each previewed
+/// with the text around its best hit.
 #[tauri::command]
 pub async fn search_sessions(
     state: State<'_, Arc<SttState>>,
     query: String,
 ) -> Result<Vec<SessionSummaryDto>> {
-    use gibberish_transcript::TranscriptRepository;
+    let db = state.database().await?;
 
-    let db = state
-        .get_database()
-        .await
-        .ok_or(SttError::DatabaseNotInitialized)?;
+    let hits = db.search(&SearchQuery {
+        text: query,
+        limit: Some(SEARCH_HIT_LIMIT),
+        ..Default::default()
+    })?;
 
-    let transcripts = db.list()?;
-    let query_lower = query.to_lowercase();
-
-    Ok(transcripts
+    let mut seen = HashSet::new();
+    Ok(hits
         .into_iter()
-        .filter(|t| {
-            let title_match = t
-                .title
-                .as_ref()
-                .map(|title| title.to_lowercase().contains(&query_lower))
-                .unwrap_or(false);
-
-            let text_match = t
-                .segments
-                .iter()
-                .any(|s| s.text.to_lowercase().contains(&query_lower));
-
-            title_match || text_match
-        })
-        .map(|t| {
-            let preview = t
-                .segments
-                .first()
-                .map(|s| {
-                    let text = &s.text;
-                    if text.len() > 100 {
-                        format!("{}...", &text[..100])
-                    } else {
-                        text.clone()
-                    }
-                })
-                .unwrap_or_default();
-
-            SessionSummaryDto {
-                id: t.id.to_string(),
-                title: t.title,
-                created_at: t.created_at.timestamp(),
-                duration_ms: t.duration_ms,
-                preview,
-            }
+        .filter(|hit| seen.insert(hit.session_id))
+        .map(|hit| SessionSummaryDto {
+            id: hit.session_id.to_string(),
+            title: hit.session_title,
+            created_at: hit.created_at,
+            duration_ms: hit.duration_ms,
+            preview: hit
+                .snippet
+                .replace(HIGHLIGHT_START, "")
+                .replace(HIGHLIGHT_END, ""),
         })
         .collect())
 }
+
+/// Ranked segment and title hits across sessions, with highlighted
+/// snippets. Supports phrases, prefixes, boolean operators and filters by
+/// date, mode and speaker.
+#[tauri::command]
+pub async fn search_transcripts(
+    state: State<'_, Arc<SttState>>,
+    query: SearchQuery,
+) -> Result<Vec<SearchHit>> {
+    let db = state.database().await?;
+
+    Ok(db.search(&query)?)
+}
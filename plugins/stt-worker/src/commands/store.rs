@@ -0,0 +1,126 @@
+//! Model store commands: inventory, disk usage and cleanup.
+//!
+//! STT, turn, speaker and punctuation models are deleted here. FunctionGemma
+//! variants are listed for a complete footprint, but the tools plugin owns
+//! their lifecycle.
+
+use crate::dto::{InstalledModelDto, ModelGcReportDto, ModelStorageUsageDto};
+use crate::error::{Result, SttError};
+use crate::state::SttState;
+use gibberish_models::{functiongemma_dir, ModelKind, ModelStore};
+use std::sync::Arc;
+use tauri::{Manager, Runtime, State};
+
+fn model_store<R: Runtime>(app: &tauri::AppHandle<R>) -> ModelStore {
+    let store = ModelStore::default();
+    match app.path().app_data_dir() {
+        Ok(dir) => store.with_functiongemma_dir(functiongemma_dir(&dir)),
+        Err(e) => {
+            tracing::warn!(
+                "App data dir unavailable, skipping FunctionGemma models: {}",
+                e
+            );
+            store
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_installed_models<R: Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<InstalledModelDto>> {
+    let store = model_store(&app);
+    let models = tokio::task::spawn_blocking(move || store.list())
+        .await
+        .map_err(|e| SttError::Model(e.to_string()))??;
+    Ok(models.into_iter().map(InstalledModelDto::from).collect())
+}
+
+#[tauri::command]
+pub async fn get_model_storage_usage<R: Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<ModelStorageUsageDto> {
+    let store = model_store(&app);
+    let footprint = tokio::task::spawn_blocking(move || store.footprint())
+        .await
+        .map_err(|e| SttError::Model(e.to_string()))??;
+    Ok(footprint.into())
+}
+
+#[tauri::command]
+pub async fn delete_model(
+    state: State<'_, Arc<SttState>>,
+    kind: String,
+    model_name: String,
+) -> Result<u64> {
+    let kind = match ModelKind::parse(&kind) {
+        Some(
+            k @ (ModelKind::Stt | ModelKind::Turn | ModelKind::Speaker | ModelKind::Punctuation),
+        ) => k,
+        _ => {
+            return Err(SttError::InvalidModelName(format!(
+                "cannot delete models of kind: {kind}"
+            )))
+        }
+    };
+
+    let loaded = match kind {
+        ModelKind::Stt => state.get_current_model().await.map(|m| m.name()),
+        ModelKind::Speaker => state.get_current_speaker_model().map(|m| m.name()),
+        ModelKind::Punctuation => state.get_current_punctuation_model().map(|m| m.name()),
+        _ => state.get_current_turn_model().await.map(|m| m.name()),
+    };
+    let downloading = match kind {
+        ModelKind::Stt => state.has_download(&model_name).await,
+        ModelKind::Speaker => state.has_speaker_download(&model_name).await,
+        ModelKind::Punctuation => state.has_punctuation_download(&model_name).await,
+        _ => state.has_turn_download(&model_name).await,
+    };
+    let refiner = match kind {
+        ModelKind::Stt => {
+            state.with_two_pass(|two_pass| two_pass.refiner_model().map(|m| m.name()))
+        }
+        _ => None,
+    };
+    if loaded == Some(model_name.as_str()) || refiner == Some(model_name.as_str()) || downloading {
+        return Err(SttError::ModelInUse(model_name));
+    }
+
+    let store = ModelStore::default();
+    let freed = tokio::task::spawn_blocking(move || store.remove(kind, &model_name))
+        .await
+        .map_err(|e| SttError::Model(e.to_string()))??;
+    Ok(freed)
+}
+
+/// Remove partial downloads and orphaned directories from the STT, turn,
+/// speaker and punctuation stores.
+///
+/// Downloads currently in progress are skipped.
+#[tauri::command]
+pub async fn collect_model_garbage(state: State<'_, Arc<SttState>>) -> Result<ModelGcReportDto> {
+    let stt = state.active_downloads().await;
+    let turn = state.active_turn_downloads().await;
+    let speaker = state.active_speaker_downloads().await;
+    let punctuation = state.active_punctuation_downloads().await;
+
+    let store = ModelStore::default();
+    let report = tokio::task::spawn_blocking(move || {
+        let in_use: Vec<(ModelKind, &str)> = stt
+            .iter()
+            .map(|n| (ModelKind::Stt, n.as_str()))
+            .chain(turn.iter().map(|n| (ModelKind::Turn, n.as_str())))
+            .chain(speaker.iter().map(|n| (ModelKind::Speaker, n.as_str())))
+            .chain(
+                punctuation
+                    .iter()
+                    .map(|n| (ModelKind::Punctuation, n.as_str())),
+            )
+            .collect();
+        store.collect_garbage(&in_use)
+    })
+    .await
+    .map_err(|e| SttError::Model(e.to_string()))??;
+
+    Ok(report.into())
+}
@@ -1,11 +1,15 @@
 use crate::dto::{
-    StreamingCommitPayload, StreamingResultDto, TranscriptSegmentDto, TurnPredictionPayload,
+    StreamingPolicyDto, StreamingResultDto, TranscriptSegmentDto, TurnPredictionPayload,
+    VadSettingsDto,
 };
 use crate::error::{Result, SttError};
-use crate::state::SttState;
+use crate::state::{SttState, VadSettings};
+use crate::two_pass;
+use crate::word_events;
 use gibberish_application::TranscriptionService;
 use gibberish_bus::SAMPLE_RATE;
 use gibberish_sherpa::{InferenceResult, SherpaStreamingEngine};
+use gibberish_storage::Setting;
 use std::sync::Arc;
 use tauri::{Emitter, Runtime, State};
 
@@ -32,7 +36,12 @@ pub async fn transcribe_file(
     let engine = state.get_engine().await.ok_or(SttError::NoModelLoaded)?;
 
     let turn_boundaries_ms = state.get_turn_boundaries().await;
-    let segments = TranscriptionService::transcribe_file(engine, &file_path, &turn_boundaries_ms)?;
+    let mut segments =
+        TranscriptionService::transcribe_file(engine, &file_path, &turn_boundaries_ms)?;
+
+    if let Some(mut stage) = state.file_punctuation().await {
+        TranscriptionService::punctuate_segments(&mut stage, &mut segments);
+    }
 
     Ok(segments
         .into_iter()
@@ -50,6 +59,9 @@ pub async fn transcribe_streaming_chunk<R: Runtime>(
     let turn_detector = state.get_turn_detector().await;
     let turn_settings = state.get_turn_settings().await;
 
+    // Keep the utterance audio for the two-pass refiner and diarizer
+    state.with_two_pass(|two_pass| two_pass.record(&audio_chunk));
+
     // Try non-blocking streaming worker first (channel-based inference)
     // Worker path needs separate VAD processing for silence injection
     if state.has_streaming_worker() {
@@ -80,19 +92,17 @@ pub async fn transcribe_streaming_chunk<R: Runtime>(
                 .accept_streaming_chunk(SAMPLE_RATE, &audio_chunk)
                 .map_err(|e| SttError::Transcription(e.to_string()))?;
 
-            if let Some(delta) = sherpa.take_last_committed_delta() {
-                let _ = app.emit(
-                    "stt:stream_commit",
-                    StreamingCommitPayload {
-                        text: delta,
-                        ts_ms: chrono::Utc::now().timestamp_millis(),
-                    },
-                );
+            let delta = sherpa
+                .take_last_committed_delta()
+                .map(|delta| state.punctuate_worker_commit(&delta));
+            if let Some(ref delta) = delta {
+                two_pass::emit_stream_commit(&app, &state, delta, two_pass::ENDPOINT_COMMIT, None);
             }
+            word_events::emit_text_word_events(&app, &state, delta.as_deref(), &volatile_text);
 
             return Ok(Some(StreamingResultDto::from(
                 gibberish_application::StreamingResult {
-                    text,
+                    text: two_pass::display_committed_text(&state, text),
                     volatile_text,
                     is_partial,
                     buffer_duration_ms,
@@ -101,7 +111,15 @@ pub async fn transcribe_streaming_chunk<R: Runtime>(
         }
     }
 
-    let (result, committed_delta, turn_prediction, turn_end_ms) = state
+    let (
+        result,
+        committed_delta,
+        commit_context,
+        commit_end_ms,
+        events,
+        turn_prediction,
+        turn_end_ms,
+    ) = state
         .with_streaming_mut(|streamer| {
             let result = TranscriptionService::process_streaming_chunk(
                 streamer,
@@ -112,11 +130,20 @@ pub async fn transcribe_streaming_chunk<R: Runtime>(
                 turn_settings.threshold,
             )?;
             let committed_delta = streamer.take_last_committed_delta();
+            let commit_context = streamer.take_last_commit_context();
+            let commit_end_ms = streamer
+                .take_last_committed_words()
+                .last()
+                .map(|w| w.end_ms);
+            let events = streamer.take_word_events();
             let turn_prediction = streamer.take_last_turn_prediction();
             let turn_end_ms = streamer.take_last_turn_end_ms();
             Ok::<_, gibberish_application::TranscriptionError>((
                 result,
                 committed_delta,
+                commit_context,
+                commit_end_ms,
+                events,
                 turn_prediction,
                 turn_end_ms,
             ))
@@ -140,14 +167,10 @@ pub async fn transcribe_streaming_chunk<R: Runtime>(
     }
 
     if let Some(delta) = committed_delta {
-        let _ = app.emit(
-            "stt:stream_commit",
-            StreamingCommitPayload {
-                text: delta,
-                ts_ms: chrono::Utc::now().timestamp_millis(),
-            },
-        );
+        let context = commit_context.unwrap_or_default();
+        two_pass::emit_stream_commit(&app, &state, &delta, context, commit_end_ms);
     }
+    word_events::emit_word_events(&app, events);
 
     Ok(Some(StreamingResultDto::from(result)))
 }
@@ -164,6 +187,12 @@ pub async fn reset_streaming_buffer(state: State<'_, Arc<SttState>>) -> Result<(
         }
     }
     state.with_streaming_mut(|s| s.reset()).await;
+    state.with_two_pass(|two_pass| two_pass.reset());
+    state.reset_worker_punctuation();
+    state.with_text_words(|differ| differ.reset());
+    if let Some(diarizer) = state.get_diarizer() {
+        diarizer.reset_live();
+    }
     state.clear_turn_boundaries().await;
     tracing::debug!("Streaming state reset");
     Ok(())
@@ -212,22 +241,79 @@ fn try_worker_streaming<R: Runtime>(
     let result = result?;
 
     // Emit commit event if there's a committed delta
-    if let Some(ref delta) = result.committed_delta {
-        let _ = app.emit(
-            "stt:stream_commit",
-            StreamingCommitPayload {
-                text: delta.clone(),
-                ts_ms: chrono::Utc::now().timestamp_millis(),
-            },
-        );
+    let delta = result
+        .committed_delta
+        .as_deref()
+        .map(|delta| state.punctuate_worker_commit(delta));
+    if let Some(ref delta) = delta {
+        two_pass::emit_stream_commit(app, state, delta, two_pass::ENDPOINT_COMMIT, None);
     }
+    word_events::emit_text_word_events(app, state, delta.as_deref(), &result.partial_text);
 
     Some(StreamingResultDto::from(
         gibberish_application::StreamingResult {
-            text: result.committed_text,
+            text: two_pass::display_committed_text(state, result.committed_text),
             volatile_text: result.partial_text,
             is_partial: result.is_partial,
             buffer_duration_ms: result.buffer_duration_ms,
         },
     ))
 }
+
+#[tauri::command]
+pub async fn get_streaming_policy(state: State<'_, Arc<SttState>>) -> Result<StreamingPolicyDto> {
+    Ok(state
+        .with_streaming(|s| {
+            StreamingPolicyDto::new(s.policy(), s.is_auto_tune_enabled(), s.decode_interval_ms())
+        })
+        .await)
+}
+
+/// Override the streaming policy of the loaded engine.
+///
+/// Loading another model restores that model's default policy.
+#[tauri::command]
+pub async fn set_streaming_policy(
+    state: State<'_, Arc<SttState>>,
+    policy: StreamingPolicyDto,
+) -> Result<StreamingPolicyDto> {
+    Ok(state
+        .with_streaming_mut(|s| {
+            s.set_policy(policy.policy());
+            if s.is_auto_tune_enabled() != policy.auto_tune {
+                s.set_auto_tune(policy.auto_tune);
+            }
+            StreamingPolicyDto::new(s.policy(), s.is_auto_tune_enabled(), s.decode_interval_ms())
+        })
+        .await)
+}
+
+#[tauri::command]
+pub async fn get_vad_settings(state: State<'_, Arc<SttState>>) -> Result<VadSettingsDto> {
+    let s = state.get_vad_settings().await;
+    Ok(VadSettingsDto {
+        redemption_time_ms: s.redemption_time_ms,
+        min_speech_time_ms: s.min_speech_time_ms,
+    })
+}
+
+/// Change how long silence must last to end an utterance, and how short
+/// speech may be to count.
+#[tauri::command]
+pub async fn set_vad_settings(
+    state: State<'_, Arc<SttState>>,
+    redemption_time_ms: u32,
+    min_speech_time_ms: u32,
+) -> Result<VadSettingsDto> {
+    let settings = VadSettings {
+        redemption_time_ms,
+        min_speech_time_ms,
+    };
+    settings.validate().map_err(SttError::Settings)?;
+    state.set_vad_settings(settings).await;
+    state.persist_setting(settings).await?;
+    Ok(VadSettingsDto {
+        redemption_time_ms,
+        min_speech_time_ms,
+    })
+}
@@ -56,6 +56,7 @@ pub async fn set_turn_settings(
     let threshold = threshold.clamp(0.0, 1.0);
     let settings = TurnSettings { enabled, threshold };
     state.set_turn_settings(settings).await;
+    state.persist_setting(settings).await?;
     Ok(TurnSettingsDto { enabled, threshold })
 }
 
@@ -159,6 +160,7 @@ pub async fn load_turn_model(state: State<'_, Arc<SttState>>, model_name: String
 
     state.set_turn_detector(std::sync::Arc::new(detector)).await;
     state.set_current_turn_model(model).await;
+    gibberish_models::mark_used(&turn_model_path(model));
 
     Ok(())
 }
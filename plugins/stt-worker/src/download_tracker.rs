@@ -43,6 +43,11 @@ impl DownloadTracker {
         }
     }
 
+    /// Names of all downloads currently in progress.
+    pub async fn active(&self) -> Vec<String> {
+        self.tokens.read().await.keys().cloned().collect()
+    }
+
     /// Mark a download as finished (removes the token).
     pub async fn finish(&self, name: &str) {
         let mut tokens = self.tokens.write().await;
@@ -86,6 +91,15 @@ mod tests {
         assert!(token.is_cancelled());
     }
 
+    #[tokio::test]
+    async fn test_active_lists_in_progress_downloads() {
+        let tracker = DownloadTracker::new();
+        let _a = tracker.start("a".to_string()).await;
+        let _b = tracker.start("b".to_string()).await;
+        tracker.finish("a").await;
+        assert_eq!(tracker.active().await, vec!["b".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_cancel_returns_false_for_unknown_download() {
         let tracker = DownloadTracker::new();
@@ -7,6 +7,8 @@ pub struct TranscriptSegmentDto {
     pub start_ms: u64,
     pub end_ms: u64,
     pub speaker: Option<i32>,
+    /// Timings are estimates; no alignment model was available.
+    pub words_unaligned: bool,
 }
 
 impl From<gibberish_application::TranscriptSegment> for TranscriptSegmentDto {
@@ -16,6 +18,7 @@ impl From<gibberish_application::TranscriptSegment> for TranscriptSegmentDto {
             start_ms: seg.start_ms,
             end_ms: seg.end_ms,
             speaker: seg.speaker,
+            words_unaligned: seg.words_unaligned,
         }
     }
 }
@@ -43,6 +46,89 @@ impl From<crate::services::ModelInfo> for ModelInfoDto {
     }
 }
 
+/// A model directory found in the local model store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledModelDto {
+    pub id: String,
+    /// "stt", "turn" or "functiongemma"
+    pub kind: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Unix timestamp (ms) of the last load, if known.
+    pub last_used_ms: Option<i64>,
+    /// "complete", "partial" or "orphaned"
+    pub state: String,
+}
+
+impl From<gibberish_models::InstalledModel> for InstalledModelDto {
+    fn from(m: gibberish_models::InstalledModel) -> Self {
+        use gibberish_models::InstallState;
+        Self {
+            id: m.id,
+            kind: m.kind.as_str().to_string(),
+            path: m.path.to_string_lossy().to_string(),
+            size_bytes: m.size_bytes,
+            last_used_ms: m
+                .last_used
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64),
+            state: match m.state {
+                InstallState::Complete => "complete",
+                InstallState::Partial => "partial",
+                InstallState::Orphaned => "orphaned",
+            }
+            .to_string(),
+        }
+    }
+}
+
+/// Disk usage of the local model store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelStorageUsageDto {
+    pub total_bytes: u64,
+    pub stt_bytes: u64,
+    pub turn_bytes: u64,
+    pub speaker_bytes: u64,
+    pub punctuation_bytes: u64,
+    pub functiongemma_bytes: u64,
+    /// Bytes held by partial downloads and orphaned directories.
+    pub reclaimable_bytes: u64,
+}
+
+impl From<gibberish_models::StoreFootprint> for ModelStorageUsageDto {
+    fn from(fp: gibberish_models::StoreFootprint) -> Self {
+        Self {
+            total_bytes: fp.total_bytes(),
+            stt_bytes: fp.stt_bytes,
+            turn_bytes: fp.turn_bytes,
+            speaker_bytes: fp.speaker_bytes,
+            punctuation_bytes: fp.punctuation_bytes,
+            functiongemma_bytes: fp.functiongemma_bytes,
+            reclaimable_bytes: fp.reclaimable_bytes,
+        }
+    }
+}
+
+/// Result of a model store garbage collection pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelGcReportDto {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+impl From<gibberish_models::GcReport> for ModelGcReportDto {
+    fn from(r: gibberish_models::GcReport) -> Self {
+        Self {
+            removed: r
+                .removed
+                .into_iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            freed_bytes: r.freed_bytes,
+        }
+    }
+}
+
 /// Turn model information for listing available endpoint detectors
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurnModelInfoDto {
@@ -59,6 +145,98 @@ pub struct TurnSettingsDto {
     pub threshold: f32,
 }
 
+/// Voice activity detection timing
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct VadSettingsDto {
+    pub redemption_time_ms: u32,
+    pub min_speech_time_ms: u32,
+}
+
+/// Encryption at rest of the database and recordings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EncryptionStatusDto {
+    pub encrypted: bool,
+    /// Encrypted under a passphrase that has not been entered yet.
+    pub locked: bool,
+    /// The key is kept in the OS keyring rather than entered by the user.
+    pub uses_keyring: bool,
+    /// Recordings in plain text although the database is encrypted: sealing
+    /// them failed, or they are still being recorded.
+    pub unsealed_recordings: usize,
+}
+
+/// Streaming timing policy for the loaded engine
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StreamingPolicyDto {
+    pub decode_interval_ms: u64,
+    pub max_buffer_ms: u64,
+    pub commit_threshold_ms: u64,
+    pub trim_padding_ms: u64,
+    pub min_stability: u8,
+    pub min_timestamp_iou: f32,
+    pub commit_window_ms: u64,
+    pub display_tail_ms: u64,
+    /// Adapt the decode interval to the real-time factor.
+    pub auto_tune: bool,
+    /// Decode interval currently in effect (ignored on update).
+    #[serde(default)]
+    pub effective_decode_interval_ms: u64,
+}
+
+impl StreamingPolicyDto {
+    pub fn new(
+        policy: gibberish_application::StreamingPolicy,
+        auto_tune: bool,
+        effective_decode_interval_ms: u64,
+    ) -> Self {
+        Self {
+            decode_interval_ms: policy.decode_interval_ms,
+            max_buffer_ms: policy.max_buffer_ms,
+            commit_threshold_ms: policy.commit_threshold_ms,
+            trim_padding_ms: policy.trim_padding_ms,
+            min_stability: policy.min_stability,
+            min_timestamp_iou: policy.min_timestamp_iou,
+            commit_window_ms: policy.commit_window_ms,
+            display_tail_ms: policy.display_tail_ms,
+            auto_tune,
+            effective_decode_interval_ms,
+        }
+    }
+
+    pub fn policy(&self) -> gibberish_application::StreamingPolicy {
+        gibberish_application::StreamingPolicy {
+            decode_interval_ms: self.decode_interval_ms,
+            max_buffer_ms: self.max_buffer_ms,
+            commit_threshold_ms: self.commit_threshold_ms,
+            trim_padding_ms: self.trim_padding_ms,
+            min_stability: self.min_stability,
+            min_timestamp_iou: self.min_timestamp_iou,
+            commit_window_ms: self.commit_window_ms,
+            display_tail_ms: self.display_tail_ms,
+        }
+        .sanitized()
+    }
+}
+
+/// Speaker-embedding model information for diarization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerModelInfoDto {
+    pub name: String,
+    pub dir_name: String,
+    pub is_downloaded: bool,
+    pub size_bytes: u64,
+}
+
+/// Punctuation/truecasing model information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PunctuationModelInfoDto {
+    pub name: String,
+    pub dir_name: String,
+    pub languages: Vec<String>,
+    pub is_downloaded: bool,
+    pub size_bytes: u64,
+}
+
 /// Streaming transcription result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamingResultDto {
@@ -98,6 +276,89 @@ pub struct SessionDto {
     pub updated_at: i64,
     pub duration_ms: u64,
     pub segments: Vec<SessionSegmentDto>,
+    /// Speakers in order of first appearance, with display names.
+    pub speakers: Vec<SessionSpeakerDto>,
+    /// Latest revision of the edit log (0 when never edited).
+    pub revision: usize,
+    pub can_undo: bool,
+    pub can_redo: bool,
+    /// Markers in timeline order.
+    pub markers: Vec<gibberish_transcript::Marker>,
+    /// Topic chapters in timeline order.
+    pub chapters: Vec<gibberish_transcript::Chapter>,
+    pub digest: Option<gibberish_transcript::Digest>,
+    /// A recording is attached, so spans can be played back.
+    pub has_audio: bool,
+}
+
+impl From<gibberish_transcript::Transcript> for SessionDto {
+    fn from(transcript: gibberish_transcript::Transcript) -> Self {
+        let speakers = transcript
+            .speakers()
+            .into_iter()
+            .map(|speaker| SessionSpeakerDto {
+                id: speaker,
+                name: transcript.speaker_name(speaker),
+            })
+            .collect();
+        let revision = transcript.revisions.current();
+        let can_undo = transcript.can_undo();
+        let can_redo = transcript.can_redo();
+
+        Self {
+            id: transcript.id.to_string(),
+            title: transcript.title,
+            created_at: transcript.created_at.timestamp(),
+            updated_at: transcript.updated_at.timestamp(),
+            duration_ms: transcript.duration_ms,
+            segments: transcript
+                .segments
+                .into_iter()
+                .map(|s| SessionSegmentDto {
+                    id: s.id.to_string(),
+                    text: s.text,
+                    raw_text: s.raw_text,
+                    start_ms: s.start_ms,
+                    end_ms: s.end_ms,
+                    speaker: s.speaker,
+                })
+                .collect(),
+            speakers,
+            revision,
+            can_undo,
+            can_redo,
+            markers: transcript.markers,
+            chapters: transcript.chapters,
+            digest: transcript.digest,
+            has_audio: false,
+        }
+    }
+}
+
+/// Entry of a session's edit log
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRevisionDto {
+    pub number: usize,
+    pub created_at: i64,
+    #[serde(flatten)]
+    pub action: gibberish_transcript::RevisionAction,
+}
+
+impl From<&gibberish_transcript::Revision> for SessionRevisionDto {
+    fn from(revision: &gibberish_transcript::Revision) -> Self {
+        Self {
+            number: revision.number,
+            created_at: revision.created_at.timestamp(),
+            action: revision.action.clone(),
+        }
+    }
+}
+
+/// Diarized speaker of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSpeakerDto {
+    pub id: i32,
+    pub name: String,
 }
 
 /// Session segment for save/get operations
@@ -105,11 +366,22 @@ pub struct SessionDto {
 pub struct SessionSegmentDto {
     pub id: String,
     pub text: String,
+    /// Spoken-form text, when inverse text normalization changed it.
+    #[serde(default)]
+    pub raw_text: Option<String>,
     pub start_ms: u64,
     pub end_ms: u64,
     pub speaker: Option<i32>,
 }
 
+/// Session rendered by `export_session`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExportDto {
+    pub content: String,
+    /// Suggested file name, from the title and format.
+    pub file_name: String,
+}
+
 // --- Event Payloads ---
 // These are emitted via Tauri events to the frontend
 
@@ -118,6 +390,48 @@ pub struct SessionSegmentDto {
 pub struct StreamingCommitPayload {
     pub text: String,
     pub ts_ms: i64,
+    /// Id referenced by a later stt:commit_revised.
+    pub commit_id: u64,
+    /// Whether a refiner is re-decoding this commit (two-pass mode).
+    pub revision_pending: bool,
+    /// The commit starts after a pause (or the start of the recording).
+    pub after_pause: bool,
+    /// The commit ends at a speech pause.
+    pub at_pause: bool,
+    /// The commit closes a semantic turn.
+    pub turn_end: bool,
+}
+
+/// Payload for stt:commit_revised events (two-pass refiner output)
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitRevisedPayload {
+    /// First commit of the revised utterance.
+    pub commit_id: u64,
+    /// All commits of the utterance, oldest first; `text` replaces them.
+    pub commit_ids: Vec<u64>,
+    /// Streamed text of those commits.
+    pub original_text: String,
+    /// Refined text; equals `original_text` if the refiner produced nothing.
+    pub text: String,
+    /// Refiner model that produced the revision.
+    pub model: String,
+    pub ts_ms: i64,
+}
+
+/// Payload for stt:commit_speaker events (live diarizer output)
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitSpeakerPayload {
+    /// Commits of the utterance, oldest first.
+    pub commit_ids: Vec<u64>,
+    pub speaker: i32,
+    pub ts_ms: i64,
+}
+
+/// Payload for stt:session_digest events (a meeting session was saved)
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDigestPayload {
+    pub session_id: String,
+    pub digest: gibberish_transcript::Digest,
 }
 
 /// Payload for stt:turn_prediction events
@@ -136,3 +450,97 @@ pub struct VadSilencePayload {
     pub ts_ms: i64,
     pub buffer_duration_ms: u64,
 }
+
+/// A word-level change to the live transcript (see `WordEventsPayload`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WordEventDto {
+    Inserted {
+        id: u64,
+        /// Volatile word this one follows; `None` places it first.
+        after: Option<u64>,
+        text: String,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    Revised {
+        id: u64,
+        text: String,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    Removed {
+        id: u64,
+    },
+    Committed {
+        id: u64,
+        text: String,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    ParagraphBreak,
+}
+
+impl From<gibberish_application::WordEvent> for WordEventDto {
+    fn from(event: gibberish_application::WordEvent) -> Self {
+        use gibberish_application::WordEvent;
+        match event {
+            WordEvent::Inserted {
+                id,
+                after,
+                text,
+                start_ms,
+                end_ms,
+            } => Self::Inserted {
+                id,
+                after,
+                text,
+                start_ms,
+                end_ms,
+            },
+            WordEvent::Revised {
+                id,
+                text,
+                start_ms,
+                end_ms,
+            } => Self::Revised {
+                id,
+                text,
+                start_ms,
+                end_ms,
+            },
+            WordEvent::Removed { id } => Self::Removed { id },
+            WordEvent::Committed {
+                id,
+                text,
+                start_ms,
+                end_ms,
+            } => Self::Committed {
+                id,
+                text,
+                start_ms,
+                end_ms,
+            },
+            WordEvent::ParagraphBreak => Self::ParagraphBreak,
+        }
+    }
+}
+
+/// Payload for stt:word_events events.
+///
+/// Events are in order; applying them to the previous transcript yields
+/// the current one without re-diffing `stt:stream_result` text.
+#[derive(Debug, Clone, Serialize)]
+pub struct WordEventsPayload {
+    pub events: Vec<WordEventDto>,
+    pub ts_ms: i64,
+}
+
+impl WordEventsPayload {
+    pub fn new(events: Vec<gibberish_application::WordEvent>) -> Self {
+        Self {
+            events: events.into_iter().map(WordEventDto::from).collect(),
+            ts_ms: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
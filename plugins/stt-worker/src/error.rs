@@ -8,12 +8,18 @@ pub enum SttError {
     #[error("Database not initialized")]
     DatabaseNotInitialized,
 
+    #[error("Database is encrypted; unlock it with its passphrase")]
+    DatabaseLocked,
+
     #[error("Download already in progress")]
     DownloadInProgress,
 
     #[error("Model is not being downloaded")]
     NotDownloading,
 
+    #[error("Model is in use: {0}")]
+    ModelInUse(String),
+
     #[error("Invalid model name: {0}")]
     InvalidModelName(String),
 
@@ -26,11 +32,35 @@ pub enum SttError {
     #[error("Turn detection error: {0}")]
     Turn(String),
 
+    #[error("Diarization error: {0}")]
+    Diarization(String),
+
+    #[error("Punctuation error: {0}")]
+    Punctuation(String),
+
     #[error("Database error: {0}")]
     Database(String),
 
     #[error("Invalid UUID: {0}")]
     InvalidUuid(String),
+
+    #[error("Import error: {0}")]
+    Import(String),
+
+    #[error("Edit error: {0}")]
+    Edit(String),
+
+    #[error("Invalid settings: {0}")]
+    Settings(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("Audio error: {0}")]
+    Audio(String),
+
+    #[error("Backup error: {0}")]
+    Backup(String),
 }
 
 impl Serialize for SttError {
@@ -60,9 +90,45 @@ impl From<gibberish_turn::TurnError> for SttError {
     }
 }
 
+impl From<gibberish_diarization::DiarizationError> for SttError {
+    fn from(e: gibberish_diarization::DiarizationError) -> Self {
+        SttError::Diarization(e.to_string())
+    }
+}
+
+impl From<gibberish_punctuation::PunctuationError> for SttError {
+    fn from(e: gibberish_punctuation::PunctuationError) -> Self {
+        SttError::Punctuation(e.to_string())
+    }
+}
+
 impl From<gibberish_storage::StorageError> for SttError {
     fn from(e: gibberish_storage::StorageError) -> Self {
-        SttError::Database(e.to_string())
+        use gibberish_storage::StorageError;
+        match e {
+            StorageError::Locked => SttError::DatabaseLocked,
+            StorageError::KeyRejected | StorageError::Encryption(_) => {
+                SttError::Encryption(e.to_string())
+            }
+            StorageError::InvalidSetting { .. } | StorageError::SettingsFile(_) => {
+                SttError::Settings(e.to_string())
+            }
+            StorageError::Audio(_) => SttError::Audio(e.to_string()),
+            StorageError::Backup(_) => SttError::Backup(e.to_string()),
+            e => SttError::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<gibberish_transcript::ImportError> for SttError {
+    fn from(e: gibberish_transcript::ImportError) -> Self {
+        SttError::Import(e.to_string())
+    }
+}
+
+impl From<gibberish_transcript::EditError> for SttError {
+    fn from(e: gibberish_transcript::EditError) -> Self {
+        SttError::Edit(e.to_string())
     }
 }
 
@@ -0,0 +1,42 @@
+//! Database key in the OS keyring (Keychain on macOS).
+//!
+//! Only raw keys are kept here. A database encrypted under a passphrase has
+//! no keyring entry and is unlocked by the user.
+
+use gibberish_storage::DatabaseKey;
+use keyring::Entry;
+
+use crate::error::{Result, SttError};
+
+const SERVICE: &str = "gibb.eri.sh";
+const ACCOUNT: &str = "database-key";
+
+fn entry() -> Result<Entry> {
+    Entry::new(SERVICE, ACCOUNT).map_err(|e| SttError::Encryption(e.to_string()))
+}
+
+/// The stored key, if there is one.
+pub fn load() -> Result<Option<DatabaseKey>> {
+    match entry()?.get_password() {
+        Ok(hex) => Ok(Some(DatabaseKey::from_hex(&hex)?)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(SttError::Encryption(e.to_string())),
+    }
+}
+
+/// Store a raw key, replacing any earlier one.
+pub fn store(key: &DatabaseKey) -> Result<()> {
+    let hex = key
+        .to_hex()
+        .ok_or_else(|| SttError::Encryption("only raw keys are kept in the keyring".to_string()))?;
+    entry()?
+        .set_password(&hex)
+        .map_err(|e| SttError::Encryption(e.to_string()))
+}
+
+pub fn delete() -> Result<()> {
+    match entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(SttError::Encryption(e.to_string())),
+    }
+}
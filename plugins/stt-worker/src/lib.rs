@@ -4,17 +4,25 @@ mod commands;
 mod download_tracker;
 mod dto;
 mod error;
+mod event_bus;
+mod key_store;
+mod retention_job;
 mod services;
+mod settings_listener;
 mod state;
 mod turn_listener;
+mod two_pass;
+mod word_events;
 
 pub use error::{Result, SttError};
 
+use event_bus::TauriEventBus;
+use gibberish_storage::SettingsWatcher;
 use state::SttState;
 use std::sync::Arc;
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Runtime,
+    Emitter, Manager, Runtime,
 };
 
 pub use gibberish_application::{StreamingTranscriber, TimedWord};
@@ -31,6 +39,12 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             let cancel_token = tokio_util::sync::CancellationToken::new();
             turn_listener::start_turn_listener(app.clone(), Arc::clone(&state), cancel_token);
 
+            // Apply stored settings and config file edits
+            settings_listener::start_settings_listener(app, Arc::clone(&state));
+
+            // Apply retention rules in the background
+            retention_job::start_retention_job(app);
+
             // Initialize database in background
             let app_handle = app.clone();
             tauri::async_runtime::spawn(async move {
@@ -38,7 +52,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                     let state = app_handle.state::<Arc<SttState>>();
                     if let Err(e) = state.init_database(app_data_dir).await {
                         tracing::error!("Failed to initialize database: {}", e);
+                        return;
                     }
+                    if state.is_database_locked().await {
+                        // Settings live in the database; they are loaded by `unlock_database`.
+                        let _ = app_handle.emit("storage:locked", ());
+                        return;
+                    }
+                    init_settings(&app_handle, &state).await;
                 } else {
                     tracing::error!("Failed to get app data directory");
                 }
@@ -56,6 +77,13 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::get_current_model,
             commands::get_language,
             commands::set_language,
+            commands::load_refiner_model,
+            commands::unload_refiner_model,
+            commands::get_refiner_model,
+            commands::list_installed_models,
+            commands::get_model_storage_usage,
+            commands::delete_model,
+            commands::collect_model_garbage,
             commands::list_turn_models,
             commands::download_turn_model,
             commands::cancel_turn_download,
@@ -65,17 +93,62 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::get_current_turn_model,
             commands::get_turn_settings,
             commands::set_turn_settings,
+            commands::list_speaker_models,
+            commands::download_speaker_model,
+            commands::cancel_speaker_download,
+            commands::is_speaker_downloading,
+            commands::load_speaker_model,
+            commands::unload_speaker_model,
+            commands::get_current_speaker_model,
+            commands::list_punctuation_models,
+            commands::download_punctuation_model,
+            commands::cancel_punctuation_download,
+            commands::is_punctuation_downloading,
+            commands::load_punctuation_model,
+            commands::unload_punctuation_model,
+            commands::get_current_punctuation_model,
             commands::transcribe_audio,
             commands::transcribe_file,
             commands::transcribe_streaming_chunk,
             commands::reset_streaming_buffer,
             commands::get_streaming_buffer_duration,
+            commands::get_streaming_policy,
+            commands::set_streaming_policy,
+            commands::get_vad_settings,
+            commands::set_vad_settings,
             commands::save_session,
             commands::list_sessions,
             commands::get_session,
+            commands::get_session_audio,
+            commands::export_backup,
+            commands::import_backup,
             commands::delete_session,
             commands::update_session_title,
+            commands::rename_speaker,
+            commands::edit_session,
+            commands::undo_session_edit,
+            commands::redo_session_edit,
+            commands::get_session_revisions,
+            commands::get_session_at_revision,
+            commands::add_session_marker,
+            commands::set_session_marker_done,
+            commands::delete_session_marker,
+            commands::query_markers,
+            commands::generate_session_digest,
+            commands::generate_session_chapters,
+            commands::export_session,
+            commands::import_session,
             commands::search_sessions,
+            commands::search_transcripts,
+            commands::get_encryption_status,
+            commands::unlock_database,
+            commands::enable_encryption,
+            commands::disable_encryption,
+            commands::rotate_encryption_key,
+            commands::get_retention_settings,
+            commands::set_retention_settings,
+            commands::preview_retention,
+            commands::apply_retention,
             commands::stt_start_listening,
             commands::stt_stop_listening,
             commands::stt_is_listening,
@@ -83,3 +156,32 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         ])
         .build()
 }
+
+/// Wrapper to keep the settings file watcher alive for the app's lifetime.
+struct SettingsWatcherHandle {
+    _watcher: SettingsWatcher,
+}
+
+/// Open the settings store, sync it with the user's config file and publish
+/// the stored values. The store is managed as `Arc<Settings>` for the other
+/// plugins.
+pub(crate) async fn init_settings<R: Runtime>(app: &tauri::AppHandle<R>, state: &SttState) {
+    let bus = Arc::new(TauriEventBus::new(app.clone()));
+    let config_path = dirs::config_dir().map(|dir| dir.join("gibb.eri.sh").join("settings.toml"));
+    let settings = match state.init_settings(bus, config_path).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("Failed to initialize settings: {}", e);
+            return;
+        }
+    };
+
+    match SettingsWatcher::new(Arc::clone(&settings)) {
+        Ok(watcher) => {
+            app.manage(SettingsWatcherHandle { _watcher: watcher });
+        }
+        Err(e) => tracing::warn!("Settings file will not be reloaded: {}", e),
+    }
+    app.manage(Arc::clone(&settings));
+    settings.publish_all();
+}
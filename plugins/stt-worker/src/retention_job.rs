@@ -0,0 +1,58 @@
+//! Background retention job.
+//!
+//! Applies the `retention` setting shortly after startup and then every
+//! hour. Runs are skipped while the database is locked or the settings are
+//! not loaded yet. A run that deletes anything emits
+//! `storage:retention_applied` with its report.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use gibberish_storage::{RetentionReport, RetentionSettings};
+use tauri::{Emitter, Manager, Runtime};
+
+use crate::error::{Result, SttError};
+use crate::state::SttState;
+
+const FIRST_RUN: Duration = Duration::from_secs(60);
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Start the retention job.
+pub fn start_retention_job<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_RUN).await;
+        loop {
+            let state = Arc::clone(app.state::<Arc<SttState>>().inner());
+            match apply_retention(&state, false).await {
+                Ok(Some(report)) if !report.is_empty() => {
+                    let _ = app.emit("storage:retention_applied", report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Retention run failed: {}", e),
+            }
+            tokio::time::sleep(INTERVAL).await;
+        }
+    });
+}
+
+/// Apply (or with `dry_run`, plan) the stored retention rules. `None` while
+/// the database or settings are unavailable.
+pub async fn apply_retention(state: &SttState, dry_run: bool) -> Result<Option<RetentionReport>> {
+    let (Some(db), Some(settings)) = (state.get_database().await, state.get_settings().await)
+    else {
+        return Ok(None);
+    };
+    let rules = settings.get::<RetentionSettings>();
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let report = tokio::task::spawn_blocking(move || {
+        if dry_run {
+            db.plan_retention(&rules, now_ms)
+        } else {
+            db.apply_retention(&rules, now_ms)
+        }
+    })
+    .await
+    .map_err(|e| SttError::Database(e.to_string()))??;
+    Ok(Some(report))
+}
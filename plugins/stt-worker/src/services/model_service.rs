@@ -1,5 +1,5 @@
 use gibberish_models::{is_downloaded, model_path, SttModel};
-use gibberish_stt::{EngineLoader, SttEngine};
+use gibberish_stt::{AlignedEngine, EngineLoader, ForcedAligner, SttEngine};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -157,13 +157,53 @@ impl ModelService {
             .find_loader(model_id)
             .ok_or_else(|| ModelError::NoLoaderFound(model_id.to_string()))?;
 
-        let engine = loader
+        let mut engine = loader
             .load(model_id, &path, language)
             .map_err(|e| ModelError::LoadFailed(e.to_string()))?;
 
+        // Batch engines that only return text get word timings via forced alignment.
+        if !loader.is_streaming(model_id) && !loader.has_word_timestamps(model_id) {
+            let aligner = Self::load_aligner(registry, language);
+            tracing::info!(
+                model = model_id,
+                reference = aligner.reference_name().unwrap_or("none (estimated)"),
+                "Enabling forced alignment"
+            );
+            engine = Box::new(AlignedEngine::new(engine, aligner));
+        }
+
         tracing::info!("Model loaded: {} (via {})", model_id, loader.name());
         Ok(Arc::from(engine))
     }
+
+    /// CTC model used as the timing reference for forced alignment.
+    pub fn alignment_reference(language: &str) -> Option<SttModel> {
+        match language {
+            "en" => Some(SttModel::ParakeetCtc),
+            "ca" => Some(SttModel::NemoConformerCatalan),
+            _ => None,
+        }
+    }
+
+    /// Build a forced aligner for `language`.
+    ///
+    /// Uses the language's CTC reference model when it is downloaded.
+    /// Otherwise words are only estimated, and segments are flagged
+    /// `words_unaligned`.
+    fn load_aligner(registry: &EngineRegistry, language: &str) -> ForcedAligner {
+        let Some(reference) = Self::alignment_reference(language).filter(|m| is_downloaded(*m))
+        else {
+            return ForcedAligner::new();
+        };
+
+        match Self::load_engine_with_registry(registry, reference, language) {
+            Ok(engine) => ForcedAligner::with_reference(engine),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to load alignment reference");
+                ForcedAligner::new()
+            }
+        }
+    }
 }
 
 /// Create a registry with all available engine loaders.
@@ -171,7 +211,7 @@ impl ModelService {
 /// This is called at plugin initialization to wire up all concrete
 /// engine implementations.
 pub fn create_default_registry() -> EngineRegistry {
-    use gibberish_parakeet::ParakeetTdtLoader;
+    use gibberish_parakeet::{ParakeetCtcLoader, ParakeetTdtLoader};
     use gibberish_sherpa::{SherpaNemoCtcLoader, SherpaWhisperLoader, SherpaZipformerLoader};
 
     let mut registry = EngineRegistry::new();
@@ -179,5 +219,6 @@ pub fn create_default_registry() -> EngineRegistry {
     registry.register(Box::new(SherpaWhisperLoader));
     registry.register(Box::new(SherpaNemoCtcLoader));
     registry.register(Box::new(ParakeetTdtLoader));
+    registry.register(Box::new(ParakeetCtcLoader));
     registry
 }
@@ -0,0 +1,55 @@
+//! Applies settings published by the settings store.
+//!
+//! Listens for `settings:changed` events. Changes made through commands are
+//! already applied when their event arrives; this picks up the values stored
+//! by earlier runs and edits to the config file.
+
+use std::sync::Arc;
+
+use gibberish_events::{event_names, SettingsChangedEvent};
+use gibberish_storage::{Language, Setting, TurnSettings, VadSettings};
+use tauri::{Listener, Runtime};
+
+use crate::commands::apply_language;
+use crate::error::{Result, SttError};
+use crate::state::SttState;
+
+/// Start the settings listener.
+pub fn start_settings_listener<R: Runtime>(app: &tauri::AppHandle<R>, state: Arc<SttState>) {
+    app.listen_any(event_names::SETTINGS_CHANGED, move |event| {
+        let change: SettingsChangedEvent = match serde_json::from_str(event.payload()) {
+            Ok(change) => change,
+            Err(e) => {
+                tracing::warn!("Failed to parse settings:changed payload: {}", e);
+                return;
+            }
+        };
+
+        let state = Arc::clone(&state);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = apply_setting(&state, change).await {
+                tracing::warn!("Failed to apply setting: {}", e);
+            }
+        });
+    });
+}
+
+async fn apply_setting(state: &SttState, change: SettingsChangedEvent) -> Result<()> {
+    match change.key.as_str() {
+        Language::KEY => {
+            let Language(language) = parse(change.value)?;
+            // Switching language reloads the model, so skip it when unchanged.
+            if language != state.get_language().await {
+                apply_language(state, language).await?;
+            }
+        }
+        TurnSettings::KEY => state.set_turn_settings(parse(change.value)?).await,
+        VadSettings::KEY => state.set_vad_settings(parse(change.value)?).await,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn parse<S: Setting>(value: serde_json::Value) -> Result<S> {
+    serde_json::from_value(value).map_err(|e| SttError::Settings(format!("{}: {}", S::KEY, e)))
+}
@@ -2,34 +2,29 @@
 
 use crate::audio_listener::AudioListenerHandle;
 use crate::download_tracker::DownloadTracker;
+use crate::error::SttError;
+use crate::key_store;
 use crate::services::{create_default_registry, EngineRegistry};
-use gibberish_application::StreamingTranscriber;
+use crate::two_pass::TwoPassState;
+use gibberish_application::{StreamingPolicy, StreamingTranscriber, TextWordDiffer};
+use gibberish_diarization::Diarizer;
+use gibberish_events::EventBusRef;
+use gibberish_models::PunctuationModel;
+use gibberish_models::SpeakerModel;
 use gibberish_models::SttModel;
 use gibberish_models::TurnModel;
+use gibberish_punctuation::{PunctuationStage, Punctuator};
 use gibberish_sherpa::SherpaWorker;
-use gibberish_storage::Database;
+use gibberish_storage::{Database, DatabaseKey, Setting, Settings, StorageError};
+pub use gibberish_storage::{TurnSettings, VadSettings};
 use gibberish_stt::SttEngine;
 use gibberish_turn::TurnDetector;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone, Copy)]
-pub struct TurnSettings {
-    pub enabled: bool,
-    pub threshold: f32,
-}
-
-impl Default for TurnSettings {
-    fn default() -> Self {
-        Self {
-            enabled: true,
-            threshold: 0.5,
-        }
-    }
-}
-
 /// Application state for the STT plugin
 pub struct SttState {
     /// Registry of engine loaders for creating STT engines.
@@ -44,10 +39,18 @@ pub struct SttState {
     stt_downloads: DownloadTracker,
     /// Turn model download tracker
     turn_downloads: DownloadTracker,
+    /// Speaker model download tracker
+    speaker_downloads: DownloadTracker,
+    /// Punctuation model download tracker
+    punctuation_downloads: DownloadTracker,
     /// Streaming transcription state
     streaming: RwLock<StreamingTranscriber>,
     /// Database for transcript storage
     database: RwLock<Option<Arc<Database>>>,
+    /// Database file while it is locked, for `unlock_database`.
+    database_path: RwLock<Option<PathBuf>>,
+    /// Persisted settings, available once the database is open.
+    settings: RwLock<Option<Arc<Settings>>>,
     /// Loaded turn detector (semantic endpoint detection)
     turn_detector: RwLock<Option<Arc<dyn TurnDetector>>>,
     /// Currently loaded turn model
@@ -61,6 +64,22 @@ pub struct SttState {
     sherpa_worker: std::sync::Mutex<Option<SherpaWorker>>,
     /// Handle to control the audio bus listener task.
     audio_listener_handle: Arc<AudioListenerHandle>,
+    /// Batch refiner and commit log for two-pass transcription.
+    /// Uses std::sync::Mutex so event emitters can reach it synchronously.
+    two_pass: std::sync::Mutex<TwoPassState>,
+    /// Id for the next `stt:stream_commit`, referenced by `stt:commit_revised`.
+    next_commit_id: AtomicU64,
+    /// Loaded speaker diarizer and the embedding model behind it.
+    /// Uses std::sync::Mutex so commit emitters can reach it synchronously.
+    diarizer: std::sync::Mutex<Option<(SpeakerModel, Arc<Diarizer>)>>,
+    /// Loaded punctuation model, preferred over the rule-based fallback.
+    punctuator: std::sync::Mutex<Option<(PunctuationModel, Arc<dyn Punctuator>)>>,
+    /// Punctuation for Sherpa worker commits, which bypass the streaming
+    /// transcriber. Uses std::sync::Mutex so commit emitters can reach it.
+    worker_punctuation: std::sync::Mutex<Option<PunctuationStage>>,
+    /// Word ids for Sherpa worker results, which carry only strings.
+    /// Uses std::sync::Mutex so event emitters can reach it synchronously.
+    text_words: std::sync::Mutex<TextWordDiffer>,
 }
 
 impl Default for SttState {
@@ -72,14 +91,29 @@ impl Default for SttState {
             language: RwLock::new("auto".to_string()),
             stt_downloads: DownloadTracker::new(),
             turn_downloads: DownloadTracker::new(),
-            streaming: RwLock::new(StreamingTranscriber::new()),
+            speaker_downloads: DownloadTracker::new(),
+            punctuation_downloads: DownloadTracker::new(),
+            streaming: RwLock::new({
+                let mut streamer = StreamingTranscriber::new();
+                streamer.set_auto_tune(true);
+                streamer.set_word_events_enabled(true);
+                streamer
+            }),
             database: RwLock::new(None),
+            database_path: RwLock::new(None),
+            settings: RwLock::new(None),
             turn_detector: RwLock::new(None),
             current_turn_model: RwLock::new(None),
             turn_settings: RwLock::new(TurnSettings::default()),
             turn_boundaries_ms: RwLock::new(Vec::new()),
             sherpa_worker: std::sync::Mutex::new(None),
             audio_listener_handle: Arc::new(AudioListenerHandle::new()),
+            two_pass: std::sync::Mutex::new(TwoPassState::default()),
+            next_commit_id: AtomicU64::new(0),
+            diarizer: std::sync::Mutex::new(None),
+            punctuator: std::sync::Mutex::new(None),
+            worker_punctuation: std::sync::Mutex::new(None),
+            text_words: std::sync::Mutex::new(TextWordDiffer::new()),
         }
     }
 }
@@ -94,6 +128,9 @@ impl SttState {
         &self.engine_registry
     }
 
+    /// Open the database, with the key from the OS keyring if there is one.
+    /// A database encrypted under a passphrase stays locked until
+    /// [`SttState::unlock_database`].
     pub async fn init_database(&self, app_data_dir: PathBuf) -> Result<(), String> {
         let db_path = app_data_dir.join("gibberish.db");
 
@@ -101,16 +138,113 @@ impl SttState {
             std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
 
-        let db = Database::open(&db_path).map_err(|e| e.to_string())?;
+        let key = key_store::load().unwrap_or_else(|e| {
+            tracing::warn!("Failed to read the database key from the keyring: {}", e);
+            None
+        });
+        let db = match &key {
+            Some(key) => Database::open_encrypted(&db_path, key).or_else(|e| match e {
+                // A key left behind after the database was decrypted.
+                StorageError::KeyRejected => {
+                    let db = Database::open(&db_path)?;
+                    tracing::warn!("Removing a stale database key from the keyring");
+                    let _ = key_store::delete();
+                    Ok(db)
+                }
+                e => Err(e),
+            }),
+            None => Database::open(&db_path),
+        };
+        let db = match db {
+            Ok(db) => db,
+            Err(e @ (StorageError::Locked | StorageError::KeyRejected)) => {
+                tracing::warn!("Database at {:?} is locked: {}", db_path, e);
+                *self.database_path.write().await = Some(db_path);
+                return Ok(());
+            }
+            Err(e) => return Err(e.to_string()),
+        };
         let mut lock = self.database.write().await;
         *lock = Some(Arc::new(db));
         tracing::info!("Database initialized at {:?}", db_path);
         Ok(())
     }
 
+    /// Open a database that is encrypted under a passphrase.
+    pub async fn unlock_database(&self, key: &DatabaseKey) -> crate::error::Result<Arc<Database>> {
+        let mut lock = self.database.write().await;
+        if let Some(db) = lock.as_ref() {
+            return Ok(Arc::clone(db));
+        }
+        let db_path = self
+            .database_path
+            .read()
+            .await
+            .clone()
+            .ok_or(SttError::DatabaseNotInitialized)?;
+        let db = Arc::new(Database::open_encrypted(&db_path, key)?);
+        *lock = Some(Arc::clone(&db));
+        *self.database_path.write().await = None;
+        tracing::info!("Database unlocked at {:?}", db_path);
+        Ok(db)
+    }
+
     pub async fn get_database(&self) -> Option<Arc<Database>> {
         self.database.read().await.clone()
     }
+
+    /// The open database, or why there is none.
+    pub async fn database(&self) -> crate::error::Result<Arc<Database>> {
+        if let Some(db) = self.get_database().await {
+            return Ok(db);
+        }
+        if self.is_database_locked().await {
+            Err(SttError::DatabaseLocked)
+        } else {
+            Err(SttError::DatabaseNotInitialized)
+        }
+    }
+
+    /// Whether the database is encrypted and waits for its passphrase.
+    pub async fn is_database_locked(&self) -> bool {
+        self.database.read().await.is_none() && self.database_path.read().await.is_some()
+    }
+
+    /// Open the settings store and sync it with `config_path`. The stored
+    /// values are applied by the settings listener once published.
+    pub async fn init_settings(
+        &self,
+        bus: EventBusRef,
+        config_path: Option<PathBuf>,
+    ) -> Result<Arc<Settings>, String> {
+        let db = self
+            .get_database()
+            .await
+            .ok_or_else(|| "Database not initialized".to_string())?;
+        let settings = Arc::new(Settings::new(db, bus));
+        if let Some(path) = config_path {
+            // A broken config file must not keep the stored settings from loading.
+            if let Err(e) = settings.sync_file(&path) {
+                tracing::warn!("Failed to sync settings file {:?}: {}", path, e);
+            }
+        }
+        let mut lock = self.settings.write().await;
+        *lock = Some(Arc::clone(&settings));
+        Ok(settings)
+    }
+
+    pub async fn get_settings(&self) -> Option<Arc<Settings>> {
+        self.settings.read().await.clone()
+    }
+
+    /// Store an applied setting. Before the store is open the value only
+    /// lasts for this run.
+    pub async fn persist_setting<S: Setting>(&self, value: S) -> crate::error::Result<()> {
+        if let Some(settings) = self.get_settings().await {
+            settings.set(value)?;
+        }
+        Ok(())
+    }
 }
 
 impl SttState {
@@ -192,6 +326,18 @@ impl SttState {
         *lock = lang;
     }
 
+    /// Language of what is being transcribed: the current model's only
+    /// language, or the selected one. `None` while it is auto-detected.
+    pub async fn transcript_language(&self) -> Option<String> {
+        if let Some(model) = self.get_current_model().await {
+            if let [language] = model.supported_languages() {
+                return Some(language.to_string());
+            }
+        }
+        let language = self.get_language().await;
+        (language != "auto").then_some(language)
+    }
+
     // STT download management (delegates to DownloadTracker)
 
     pub async fn has_download(&self, model_name: &str) -> bool {
@@ -210,6 +356,10 @@ impl SttState {
         self.stt_downloads.finish(model_name).await
     }
 
+    pub async fn active_downloads(&self) -> Vec<String> {
+        self.stt_downloads.active().await
+    }
+
     // Turn model download management (delegates to DownloadTracker)
 
     pub async fn has_turn_download(&self, model_name: &str) -> bool {
@@ -228,6 +378,54 @@ impl SttState {
         self.turn_downloads.finish(model_name).await
     }
 
+    pub async fn active_turn_downloads(&self) -> Vec<String> {
+        self.turn_downloads.active().await
+    }
+
+    // Speaker model download management (delegates to DownloadTracker)
+
+    pub async fn has_speaker_download(&self, model_name: &str) -> bool {
+        self.speaker_downloads.has(model_name).await
+    }
+
+    pub async fn start_speaker_download(&self, model_name: String) -> CancellationToken {
+        self.speaker_downloads.start(model_name).await
+    }
+
+    pub async fn cancel_speaker_download(&self, model_name: &str) -> bool {
+        self.speaker_downloads.cancel(model_name).await
+    }
+
+    pub async fn finish_speaker_download(&self, model_name: &str) {
+        self.speaker_downloads.finish(model_name).await
+    }
+
+    pub async fn active_speaker_downloads(&self) -> Vec<String> {
+        self.speaker_downloads.active().await
+    }
+
+    // Punctuation model download management (delegates to DownloadTracker)
+
+    pub async fn has_punctuation_download(&self, model_name: &str) -> bool {
+        self.punctuation_downloads.has(model_name).await
+    }
+
+    pub async fn start_punctuation_download(&self, model_name: String) -> CancellationToken {
+        self.punctuation_downloads.start(model_name).await
+    }
+
+    pub async fn cancel_punctuation_download(&self, model_name: &str) -> bool {
+        self.punctuation_downloads.cancel(model_name).await
+    }
+
+    pub async fn finish_punctuation_download(&self, model_name: &str) {
+        self.punctuation_downloads.finish(model_name).await
+    }
+
+    pub async fn active_punctuation_downloads(&self) -> Vec<String> {
+        self.punctuation_downloads.active().await
+    }
+
     // Streaming transcription
 
     pub async fn with_streaming<F, R>(&self, f: F) -> R
@@ -285,6 +483,28 @@ impl SttState {
         *lock = settings;
     }
 
+    pub async fn get_vad_settings(&self) -> VadSettings {
+        let settings = self.with_streaming(|s| s.vad_settings()).await;
+        VadSettings {
+            redemption_time_ms: settings.redemption_time_ms,
+            min_speech_time_ms: settings.min_speech_time_ms,
+        }
+    }
+
+    /// Apply VAD timing. Reinitializes the VAD only when the timing changes.
+    pub async fn set_vad_settings(&self, settings: VadSettings) {
+        if self.get_vad_settings().await == settings {
+            return;
+        }
+        self.with_streaming_mut(|s| {
+            s.set_vad_settings(gibberish_application::VadSettings {
+                redemption_time_ms: settings.redemption_time_ms,
+                min_speech_time_ms: settings.min_speech_time_ms,
+            })
+        })
+        .await;
+    }
+
     pub async fn clear_turn_boundaries(&self) {
         let mut lock = self.turn_boundaries_ms.write().await;
         lock.clear();
@@ -314,4 +534,173 @@ impl SttState {
     pub fn stop_audio_listener(&self) {
         self.audio_listener_handle.stop();
     }
+
+    // Two-pass transcription
+
+    /// Execute a function with the two-pass state.
+    pub fn with_two_pass<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut TwoPassState) -> R,
+    {
+        let mut guard = self
+            .two_pass
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut guard)
+    }
+
+    /// Execute a function with the word differ for Sherpa worker results.
+    pub fn with_text_words<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut TextWordDiffer) -> R,
+    {
+        let mut guard = self
+            .text_words
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut guard)
+    }
+
+    /// Allocate the id for the next stream commit.
+    pub fn next_commit_id(&self) -> u64 {
+        self.next_commit_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // Speaker diarization
+
+    pub fn get_diarizer(&self) -> Option<Arc<Diarizer>> {
+        self.lock_diarizer()
+            .as_ref()
+            .map(|(_, diarizer)| Arc::clone(diarizer))
+    }
+
+    pub fn get_current_speaker_model(&self) -> Option<SpeakerModel> {
+        self.lock_diarizer().as_ref().map(|(model, _)| *model)
+    }
+
+    pub fn set_diarizer(&self, model: SpeakerModel, diarizer: Arc<Diarizer>) {
+        *self.lock_diarizer() = Some((model, diarizer));
+        self.with_two_pass(|two_pass| two_pass.set_diarizing(true));
+    }
+
+    pub fn clear_diarizer(&self) {
+        *self.lock_diarizer() = None;
+        self.with_two_pass(|two_pass| two_pass.set_diarizing(false));
+    }
+
+    fn lock_diarizer(&self) -> std::sync::MutexGuard<'_, Option<(SpeakerModel, Arc<Diarizer>)>> {
+        self.diarizer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Punctuation
+
+    pub fn get_current_punctuation_model(&self) -> Option<PunctuationModel> {
+        self.lock_punctuator().as_ref().map(|(model, _)| *model)
+    }
+
+    pub fn set_punctuator(&self, model: PunctuationModel, punctuator: Arc<dyn Punctuator>) {
+        *self.lock_punctuator() = Some((model, punctuator));
+    }
+
+    pub fn clear_punctuator(&self) {
+        *self.lock_punctuator() = None;
+    }
+
+    /// Install punctuation for the current STT model, or remove it.
+    ///
+    /// Only engines whose catalog entry needs punctuation get a stage. The
+    /// loaded punctuation model is used when it covers the engine's
+    /// language; otherwise the rule-based fallback is.
+    pub async fn configure_punctuation(&self) {
+        let stage = match self.get_current_model().await {
+            Some(model) if model.needs_punctuation() => {
+                let language = match model.supported_languages().first() {
+                    Some(language) => language.to_string(),
+                    None => self.get_language().await,
+                };
+                Some(self.punctuation_stage(&language))
+            }
+            _ => None,
+        };
+
+        if let Some(stage) = &stage {
+            tracing::info!(punctuator = stage.punctuator_name(), "Punctuation enabled");
+        }
+
+        *self.lock_worker_punctuation() = stage.clone();
+        self.with_streaming_mut(|streamer| streamer.set_punctuation(stage))
+            .await;
+    }
+
+    /// Apply the streaming policy for the current STT model.
+    ///
+    /// Whisper decodes cost far more than CTC/TDT ones, so Whisper models
+    /// decode less often over a longer buffer.
+    pub async fn configure_streaming_policy(&self) {
+        let policy = match self.get_current_model().await {
+            Some(model) if model.is_whisper() || model.is_whisper_onnx() => {
+                StreamingPolicy::relaxed()
+            }
+            _ => StreamingPolicy::default(),
+        };
+        self.with_streaming_mut(|streamer| streamer.set_policy(policy))
+            .await;
+    }
+
+    fn punctuation_stage(&self, language: &str) -> PunctuationStage {
+        let punctuator = self
+            .lock_punctuator()
+            .as_ref()
+            .filter(|(model, _)| model.languages().contains(&language))
+            .map(|(_, punctuator)| Arc::clone(punctuator));
+
+        match punctuator {
+            Some(punctuator) => PunctuationStage::new(punctuator, language),
+            None => PunctuationStage::rule_based(language),
+        }
+    }
+
+    /// A fresh punctuation stage for file transcription, if the current
+    /// model needs one.
+    pub async fn file_punctuation(&self) -> Option<PunctuationStage> {
+        self.with_streaming(|streamer| streamer.punctuation().cloned())
+            .await
+    }
+
+    /// Whether Sherpa worker commits are being punctuated.
+    pub fn has_worker_punctuation(&self) -> bool {
+        self.lock_worker_punctuation().is_some()
+    }
+
+    /// Punctuate a commit from the Sherpa worker (a no-op when disabled).
+    ///
+    /// Worker commits happen at endpoints, so each one closes its sentence.
+    pub fn punctuate_worker_commit(&self, text: &str) -> String {
+        match self.lock_worker_punctuation().as_mut() {
+            Some(stage) => stage.process_text(text, true),
+            None => text.to_string(),
+        }
+    }
+
+    pub fn reset_worker_punctuation(&self) {
+        if let Some(stage) = self.lock_worker_punctuation().as_mut() {
+            stage.reset();
+        }
+    }
+
+    fn lock_punctuator(
+        &self,
+    ) -> std::sync::MutexGuard<'_, Option<(PunctuationModel, Arc<dyn Punctuator>)>> {
+        self.punctuator
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_worker_punctuation(&self) -> std::sync::MutexGuard<'_, Option<PunctuationStage>> {
+        self.worker_punctuation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 }
@@ -149,7 +149,7 @@ async fn run_vad_confirmed_transcription<R: Runtime>(
     let result = batch_transcriber::process_vad_confirmed(state, engine).await?;
 
     // Emit only transcription events (NOT vad_silence - we're already responding to it)
-    batch_transcriber::emit_transcription_events(app, &result);
+    batch_transcriber::emit_transcription_events(app, state, &result);
 
     tracing::debug!(
         text_len = result.result.text.len(),
@@ -0,0 +1,629 @@
+//! Two-pass transcription: streaming partials, batch-model finals.
+//!
+//! The streaming engine drives live volatile text and commits text as it
+//! stabilizes. When a batch "refiner" engine is loaded, each utterance is
+//! re-decoded by it in the background once it ends (at a VAD pause or a turn
+//! end). The audio is cut at the end of the last committed word, so words
+//! that are not committed yet belong to the next utterance. The more accurate
+//! text replaces the utterance's streamed commits in the displayed transcript
+//! and is announced via `stt:commit_revised`, referencing the commit ids.
+//!
+//! The same utterance audio is embedded by the speaker diarizer, when loaded,
+//! off the commit path; the live speaker id follows via `stt:commit_speaker`.
+//! Without a refiner or a diarizer no audio is buffered.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use gibberish_application::CommitContext;
+use gibberish_diarization::Diarizer;
+use gibberish_models::SttModel;
+use gibberish_stt::{SttEngine, STT_SAMPLE_RATE};
+use tauri::{Emitter, Manager, Runtime};
+
+use crate::dto::{
+    CommitRevisedPayload, CommitSpeakerPayload, StreamingCommitPayload, StreamingResultDto,
+};
+use crate::state::SttState;
+
+/// Context of a streaming-engine commit: endpoints are pauses, and so
+/// was the previous one.
+pub const ENDPOINT_COMMIT: CommitContext = CommitContext {
+    after_pause: true,
+    at_pause: true,
+    turn_end: false,
+};
+
+/// Longest utterance kept for re-decoding (30s, the Whisper window).
+const MAX_UTTERANCE_SAMPLES: usize = STT_SAMPLE_RATE as usize * 30;
+
+/// A closed utterance: the commits it covers and their audio.
+#[derive(Debug)]
+pub struct Utterance {
+    /// Commits of the utterance, oldest first.
+    pub commit_ids: Vec<u64>,
+    /// Streamed text of those commits.
+    pub text: String,
+    /// Audio up to the end of the last committed word.
+    pub audio: Vec<f32>,
+    /// The start of the audio was dropped to stay under the cap, so it no
+    /// longer covers all of the commits.
+    pub truncated: bool,
+}
+
+impl Utterance {
+    /// Whether re-decoding the audio can stand in for the streamed text.
+    pub fn is_refinable(&self) -> bool {
+        !self.truncated && !self.audio.is_empty()
+    }
+}
+
+#[derive(Debug)]
+struct Commit {
+    id: u64,
+    text: String,
+    /// No revision can replace this commit any more.
+    settled: bool,
+}
+
+/// Refiner engine plus the utterance audio and commits of the current stream.
+#[derive(Default)]
+pub struct TwoPassState {
+    refiner: Option<(SttModel, Arc<dyn SttEngine>)>,
+    /// A speaker diarizer is loaded and embeds utterance audio.
+    diarizing: bool,
+    /// Audio since the end of the previous utterance.
+    audio: Vec<f32>,
+    /// Stream position, in samples, of the first sample in `audio`.
+    audio_start: u64,
+    /// The open utterance lost audio at its start.
+    truncated: bool,
+    /// Commits of the open utterance.
+    open: Vec<u64>,
+    /// Text of commits that can no longer be revised.
+    settled_text: String,
+    /// Commits that may still be revised (the open utterance and utterances
+    /// being refined), oldest first.
+    commits: VecDeque<Commit>,
+}
+
+impl TwoPassState {
+    pub fn refiner(&self) -> Option<Arc<dyn SttEngine>> {
+        self.refiner.as_ref().map(|(_, e)| Arc::clone(e))
+    }
+
+    pub fn refiner_model(&self) -> Option<SttModel> {
+        self.refiner.as_ref().map(|(m, _)| *m)
+    }
+
+    pub fn set_refiner(&mut self, model: SttModel, engine: Arc<dyn SttEngine>) {
+        self.refiner = Some((model, engine));
+    }
+
+    pub fn clear_refiner(&mut self) {
+        self.refiner = None;
+        // The open utterance will not be refined any more.
+        for commit in &mut self.commits {
+            if self.open.contains(&commit.id) {
+                commit.settled = true;
+            }
+        }
+        self.settle();
+        self.drop_unused_audio();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.refiner.is_some()
+    }
+
+    /// Track whether a speaker diarizer needs utterance audio.
+    pub fn set_diarizing(&mut self, diarizing: bool) {
+        self.diarizing = diarizing;
+        self.drop_unused_audio();
+    }
+
+    /// Whether anything consumes utterance audio.
+    fn needs_audio(&self) -> bool {
+        self.refiner.is_some() || self.diarizing
+    }
+
+    /// Append streamed audio to the open utterance.
+    ///
+    /// At most [`MAX_UTTERANCE_SAMPLES`] are kept; commits close the
+    /// utterance before that, so audio is only dropped from an utterance
+    /// with no commit for that long. Without a refiner or a diarizer only
+    /// the stream position advances.
+    pub fn record(&mut self, samples: &[f32]) {
+        if !self.needs_audio() {
+            self.audio_start += samples.len() as u64;
+            return;
+        }
+        self.audio.extend_from_slice(samples);
+        if self.audio.len() > MAX_UTTERANCE_SAMPLES {
+            let excess = self.audio.len() - MAX_UTTERANCE_SAMPLES;
+            self.audio.drain(..excess);
+            self.audio_start += excess as u64;
+            self.truncated |= !self.open.is_empty();
+        }
+    }
+
+    /// Record a commit. Returns the utterance if the commit closes it and a
+    /// refiner or diarizer needs its audio.
+    ///
+    /// An utterance closes at a pause, at a turn end, or once its audio
+    /// reaches [`MAX_UTTERANCE_SAMPLES`]. `end_ms` is the stream position
+    /// where the last committed word ends; the audio after it stays for the
+    /// next utterance. Without it, all buffered audio is taken.
+    pub fn commit(
+        &mut self,
+        commit_id: u64,
+        text: &str,
+        context: CommitContext,
+        end_ms: Option<u64>,
+    ) -> Option<Utterance> {
+        self.commits.push_back(Commit {
+            id: commit_id,
+            text: text.to_string(),
+            settled: !self.is_active(),
+        });
+        if !self.needs_audio() {
+            self.settle();
+            return None;
+        }
+        self.open.push(commit_id);
+
+        let closes =
+            context.at_pause || context.turn_end || self.audio.len() >= MAX_UTTERANCE_SAMPLES;
+        if !closes {
+            self.settle();
+            return None;
+        }
+
+        let cut = end_ms
+            .map(|ms| {
+                let end = ms * u64::from(STT_SAMPLE_RATE) / 1000;
+                end.saturating_sub(self.audio_start) as usize
+            })
+            .unwrap_or(usize::MAX)
+            .min(self.audio.len());
+        let audio: Vec<f32> = self.audio.drain(..cut).collect();
+        self.audio_start += cut as u64;
+
+        let commit_ids = std::mem::take(&mut self.open);
+        let text = self
+            .commits
+            .iter()
+            .filter(|c| commit_ids.contains(&c.id))
+            .map(|c| c.text.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.settle();
+
+        Some(Utterance {
+            commit_ids,
+            text,
+            audio,
+            truncated: std::mem::take(&mut self.truncated),
+        })
+    }
+
+    /// Replace the text of an utterance's commits: the first takes `text`,
+    /// the rest are emptied. Returns false if the commits are unknown (e.g.
+    /// the stream was reset while the refiner was running).
+    pub fn revise(&mut self, commit_ids: &[u64], text: &str) -> bool {
+        let Some(&first) = commit_ids.first() else {
+            return false;
+        };
+        if !self.commits.iter().any(|c| c.id == first) {
+            return false;
+        }
+        for commit in self
+            .commits
+            .iter_mut()
+            .filter(|c| commit_ids.contains(&c.id))
+        {
+            commit.text = if commit.id == first {
+                text.to_string()
+            } else {
+                String::new()
+            };
+            commit.settled = true;
+        }
+        self.settle();
+        true
+    }
+
+    /// Committed transcript with revisions applied.
+    pub fn committed_text(&self) -> String {
+        std::iter::once(self.settled_text.as_str())
+            .chain(self.commits.iter().map(|c| c.text.trim()))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Forget commits and buffered audio (keeps the refiner).
+    pub fn reset(&mut self) {
+        self.audio.clear();
+        self.audio_start = 0;
+        self.truncated = false;
+        self.open.clear();
+        self.settled_text.clear();
+        self.commits.clear();
+    }
+
+    /// Free buffered audio once nothing consumes it.
+    fn drop_unused_audio(&mut self) {
+        if self.needs_audio() {
+            return;
+        }
+        self.audio_start += self.audio.len() as u64;
+        self.audio.clear();
+        self.open.clear();
+        self.truncated = false;
+    }
+
+    /// Fold leading settled commits into `settled_text`.
+    fn settle(&mut self) {
+        while self.commits.front().is_some_and(|c| c.settled) {
+            let Some(commit) = self.commits.pop_front() else {
+                break;
+            };
+            let text = commit.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if !self.settled_text.is_empty() {
+                self.settled_text.push(' ');
+            }
+            self.settled_text.push_str(text);
+        }
+    }
+}
+
+/// Emit `stt:stream_commit` and, in two-pass mode, start re-decoding the
+/// utterance with the refiner engine once the commit closes it.
+///
+/// `end_ms` is the stream position where the committed words end, when the
+/// engine reports word timings.
+pub fn emit_stream_commit<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &SttState,
+    text: &str,
+    context: CommitContext,
+    end_ms: Option<u64>,
+) {
+    let commit_id = state.next_commit_id();
+    let (refiner, utterance) = state.with_two_pass(|two_pass| {
+        let utterance = two_pass.commit(commit_id, text, context, end_ms);
+        (two_pass.refiner(), utterance)
+    });
+    let _ = app.emit(
+        "stt:stream_commit",
+        StreamingCommitPayload {
+            text: text.to_string(),
+            ts_ms: chrono::Utc::now().timestamp_millis(),
+            commit_id,
+            revision_pending: refiner.is_some(),
+            after_pause: context.after_pause,
+            at_pause: context.at_pause,
+            turn_end: context.turn_end,
+        },
+    );
+
+    let Some(utterance) = utterance else {
+        return;
+    };
+    if let Some(diarizer) = state.get_diarizer().filter(|_| !utterance.audio.is_empty()) {
+        spawn_speaker_assignment(
+            app.clone(),
+            diarizer,
+            utterance.commit_ids.clone(),
+            utterance.audio.clone(),
+        );
+    }
+    if let Some(engine) = refiner {
+        spawn_refinement(app.clone(), engine, utterance);
+    }
+}
+
+/// Committed text to display for a streaming result.
+///
+/// In two-pass mode, or when worker commits are punctuated, this is the
+/// commit log (with revisions applied); otherwise the streaming engine's own
+/// committed text is used.
+pub fn display_committed_text(state: &SttState, streamed: String) -> String {
+    let punctuated = state.has_worker_punctuation();
+    state.with_two_pass(|two_pass| {
+        if two_pass.is_active() || punctuated {
+            two_pass.committed_text()
+        } else {
+            streamed
+        }
+    })
+}
+
+/// Embed the utterance off the listener path and emit `stt:commit_speaker`.
+fn spawn_speaker_assignment<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    diarizer: Arc<Diarizer>,
+    commit_ids: Vec<u64>,
+    audio: Vec<f32>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let assigned =
+            tauri::async_runtime::spawn_blocking(move || diarizer.assign_live(&audio)).await;
+        let speaker = match assigned {
+            Ok(Ok(speaker)) => speaker,
+            Ok(Err(e)) => {
+                tracing::warn!(?commit_ids, error = %e, "Live speaker assignment failed");
+                None
+            }
+            Err(e) => {
+                tracing::warn!(?commit_ids, error = %e, "Speaker assignment task failed");
+                None
+            }
+        };
+        // Too short to embed.
+        let Some(speaker) = speaker else {
+            return;
+        };
+
+        let _ = app.emit(
+            "stt:commit_speaker",
+            CommitSpeakerPayload {
+                commit_ids,
+                speaker,
+                ts_ms: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+    });
+}
+
+fn spawn_refinement<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    engine: Arc<dyn SttEngine>,
+    utterance: Utterance,
+) {
+    tauri::async_runtime::spawn(async move {
+        let model = engine.model_name().to_string();
+        let refinable = utterance.is_refinable();
+        let Utterance {
+            commit_ids,
+            text: original_text,
+            audio,
+            ..
+        } = utterance;
+        let commit_id = commit_ids.first().copied().unwrap_or_default();
+
+        let text = if refinable {
+            let decoded =
+                tauri::async_runtime::spawn_blocking(move || engine.transcribe(&audio)).await;
+            match decoded {
+                Ok(Ok(segments)) => segments
+                    .iter()
+                    .map(|s| s.text.trim())
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                Ok(Err(e)) => {
+                    tracing::warn!(commit_id, error = %e, "Refiner transcription failed");
+                    String::new()
+                }
+                Err(e) => {
+                    tracing::warn!(commit_id, error = %e, "Refiner task failed");
+                    String::new()
+                }
+            }
+        } else {
+            tracing::debug!(
+                commit_id,
+                "Utterance audio incomplete, keeping streamed text"
+            );
+            String::new()
+        };
+        // Keep the streamed text if the refiner produced nothing usable, but
+        // still answer so consumers waiting on the revision can proceed.
+        let text = if text.is_empty() {
+            original_text.clone()
+        } else {
+            text
+        };
+
+        let state = app.state::<Arc<SttState>>();
+        if !state.with_two_pass(|two_pass| two_pass.revise(&commit_ids, &text)) {
+            tracing::debug!(commit_id, "Dropping revision for a reset stream");
+            return;
+        }
+
+        tracing::debug!(
+            commit_id,
+            commits = commit_ids.len(),
+            changed = text != original_text,
+            "Utterance revised by refiner"
+        );
+
+        let _ = app.emit(
+            "stt:commit_revised",
+            CommitRevisedPayload {
+                commit_id,
+                commit_ids,
+                original_text,
+                text,
+                model,
+                ts_ms: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+
+        // Refresh the display with the revised transcript.
+        if let Some(latest) = state.with_sherpa_worker(|worker| worker.get_latest()) {
+            let dto = StreamingResultDto::from(gibberish_application::StreamingResult {
+                text: display_committed_text(&state, latest.committed_text),
+                volatile_text: latest.partial_text,
+                is_partial: latest.is_partial,
+                buffer_duration_ms: latest.buffer_duration_ms,
+            });
+            let _ = app.emit("stt:stream_result", dto);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+
+    struct NullEngine;
+
+    impl SttEngine for NullEngine {
+        fn transcribe(&self, _audio: &[f32]) -> gibberish_stt::Result<Vec<gibberish_stt::Segment>> {
+            Ok(Vec::new())
+        }
+
+        fn model_name(&self) -> &str {
+            "null"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    const MID_UTTERANCE: CommitContext = CommitContext {
+        after_pause: false,
+        at_pause: false,
+        turn_end: false,
+    };
+
+    fn active() -> TwoPassState {
+        let mut state = TwoPassState::default();
+        state.set_refiner(SttModel::WhisperOnnxSmall, Arc::new(NullEngine));
+        state
+    }
+
+    #[test]
+    fn utterance_closes_at_pause() {
+        let mut state = active();
+        state.record(&[0.1; 160]);
+        assert!(state.commit(0, "hello", MID_UTTERANCE, None).is_none());
+
+        let utterance = state.commit(1, "world", ENDPOINT_COMMIT, None).unwrap();
+        assert_eq!(utterance.commit_ids, vec![0, 1]);
+        assert_eq!(utterance.text, "hello world");
+        assert_eq!(utterance.audio.len(), 160);
+        assert!(utterance.is_refinable());
+    }
+
+    #[test]
+    fn audio_is_cut_at_committed_end() {
+        let mut state = active();
+        let second = STT_SAMPLE_RATE as usize;
+        state.record(&vec![0.0; second]);
+        let first = state.commit(0, "hi", ENDPOINT_COMMIT, Some(500)).unwrap();
+        assert_eq!(first.audio.len(), second / 2);
+
+        // The uncommitted tail opens the next utterance.
+        state.record(&vec![0.0; second]);
+        let next = state
+            .commit(1, "there", ENDPOINT_COMMIT, Some(1500))
+            .unwrap();
+        assert_eq!(next.audio.len(), second);
+    }
+
+    #[test]
+    fn utterance_closes_at_cap() {
+        let mut state = active();
+        state.record(&vec![0.0; MAX_UTTERANCE_SAMPLES]);
+
+        let utterance = state.commit(0, "long", MID_UTTERANCE, None).unwrap();
+        assert_eq!(utterance.audio.len(), MAX_UTTERANCE_SAMPLES);
+        assert!(!utterance.truncated);
+    }
+
+    #[test]
+    fn dropped_audio_truncates_open_utterance() {
+        let mut state = active();
+        assert!(state.commit(0, "early", MID_UTTERANCE, None).is_none());
+        state.record(&vec![0.0; MAX_UTTERANCE_SAMPLES + 10]);
+
+        let utterance = state.commit(1, "late", MID_UTTERANCE, None).unwrap();
+        assert_eq!(utterance.audio.len(), MAX_UTTERANCE_SAMPLES);
+        assert!(!utterance.is_refinable());
+    }
+
+    #[test]
+    fn revisions_replace_utterance_text() {
+        let mut state = active();
+        state.commit(0, "i scream", MID_UTTERANCE, None);
+        state.commit(1, "for ice cream", ENDPOINT_COMMIT, None);
+        assert_eq!(state.committed_text(), "i scream for ice cream");
+
+        assert!(state.revise(&[0, 1], "I scream, for ice cream."));
+        assert_eq!(state.committed_text(), "I scream, for ice cream.");
+        assert!(state.commits.is_empty());
+    }
+
+    #[test]
+    fn commits_settle_in_order() {
+        let mut state = active();
+        state.commit(0, "one", ENDPOINT_COMMIT, None);
+        state.commit(1, "two", ENDPOINT_COMMIT, None);
+
+        assert!(state.revise(&[1], "Two."));
+        assert_eq!(state.commits.len(), 2);
+        assert!(state.revise(&[0], "One."));
+        assert!(state.commits.is_empty());
+        assert_eq!(state.committed_text(), "One. Two.");
+    }
+
+    #[test]
+    fn commits_settle_without_refiner() {
+        let mut state = TwoPassState::default();
+        state.commit(0, "hello", MID_UTTERANCE, None);
+        state.commit(1, "world", ENDPOINT_COMMIT, None);
+
+        assert!(state.commits.is_empty());
+        assert_eq!(state.committed_text(), "hello world");
+    }
+
+    #[test]
+    fn audio_is_only_buffered_for_consumers() {
+        let mut state = TwoPassState::default();
+        let second = STT_SAMPLE_RATE as usize;
+        state.record(&vec![0.0; second]);
+        assert!(state
+            .commit(0, "unused", ENDPOINT_COMMIT, Some(1000))
+            .is_none());
+        assert!(state.audio.is_empty());
+
+        // Stream positions stay aligned once a diarizer needs the audio.
+        state.set_diarizing(true);
+        state.record(&vec![0.0; second]);
+        let utterance = state
+            .commit(1, "used", ENDPOINT_COMMIT, Some(1500))
+            .unwrap();
+        assert_eq!(utterance.commit_ids, vec![1]);
+        assert_eq!(utterance.audio.len(), second / 2);
+    }
+
+    #[test]
+    fn clearing_refiner_settles_open_utterance() {
+        let mut state = active();
+        state.commit(0, "open", MID_UTTERANCE, None);
+        state.clear_refiner();
+
+        assert!(state.commits.is_empty());
+        assert_eq!(state.committed_text(), "open");
+    }
+
+    #[test]
+    fn revisions_after_reset_are_dropped() {
+        let mut state = active();
+        state.commit(0, "before reset", ENDPOINT_COMMIT, None);
+        state.reset();
+
+        assert!(!state.revise(&[0], "revised"));
+        assert_eq!(state.committed_text(), "");
+    }
+}
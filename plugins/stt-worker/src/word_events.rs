@@ -0,0 +1,39 @@
+//! Word-level transcript events (`stt:word_events`).
+//!
+//! Batch engines get their events from the streaming transcriber's word
+//! tracker. The Sherpa worker only reports strings, so its partials and
+//! commits are diffed into events here.
+
+use gibberish_application::WordEvent;
+use tauri::{Emitter, Runtime};
+
+use crate::dto::WordEventsPayload;
+use crate::state::SttState;
+
+/// Emit `stt:word_events`, unless there is nothing to report.
+pub fn emit_word_events<R: Runtime>(app: &tauri::AppHandle<R>, events: Vec<WordEvent>) {
+    if events.is_empty() {
+        return;
+    }
+    let _ = app.emit("stt:word_events", WordEventsPayload::new(events));
+}
+
+/// Emit word events for a text-only streaming result.
+///
+/// `committed_delta` is the (punctuated) text committed by this result;
+/// `partial_text` is the engine's new partial.
+pub fn emit_text_word_events<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &SttState,
+    committed_delta: Option<&str>,
+    partial_text: &str,
+) {
+    let events = state.with_text_words(|differ| {
+        let mut events = committed_delta
+            .map(|delta| differ.commit(delta))
+            .unwrap_or_default();
+        events.extend(differ.update(partial_text));
+        events
+    });
+    emit_word_events(app, events);
+}
@@ -12,9 +12,14 @@ fn main() {
         "load_functiongemma_model",
         "unload_functiongemma_model",
         "get_current_functiongemma_model",
+        "delete_functiongemma_model",
+        "collect_functiongemma_garbage",
         "get_context",
         "pin_context_mode",
         "unpin_context_mode",
+        "start_live_dictation",
+        "stop_live_dictation",
+        "get_live_dictation_status",
     ])
     .build();
 }
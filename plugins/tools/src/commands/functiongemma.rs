@@ -7,11 +7,18 @@ use tauri::{Emitter, Manager, Runtime, State};
 const FUNCTIONGEMMA_DEFAULT_VARIANT: &str = "model_fp16";
 
 fn functiongemma_base_dir<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| e.to_string())?
-        .join("functiongemma"))
+    Ok(gibberish_models::functiongemma_dir(
+        &app.path().app_data_dir().map_err(|e| e.to_string())?,
+    ))
+}
+
+fn functiongemma_store<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Result<gibberish_models::ModelStore, String> {
+    // STT and turn models are managed by the stt-worker plugin.
+    Ok(gibberish_models::ModelStore::functiongemma_only(
+        functiongemma_base_dir(app)?,
+    ))
 }
 
 fn functiongemma_variant_dir<R: Runtime>(
@@ -306,6 +313,7 @@ pub async fn load_functiongemma_model<R: Runtime>(
         return Err("Model not downloaded yet".to_string());
     }
 
+    let variant_dir = functiongemma_variant_dir(&app, &variant)?;
     let model_path_for_load = model_path.clone();
     let tokenizer_path_for_load = tokenizer_path.clone();
     let runner = tokio::task::spawn_blocking(move || {
@@ -328,6 +336,7 @@ pub async fn load_functiongemma_model<R: Runtime>(
             runner: Arc::new(runner),
         });
     }
+    gibberish_models::mark_used(&variant_dir);
 
     let _ = app.emit(
         "tools:functiongemma_loaded",
@@ -344,6 +353,76 @@ pub async fn unload_functiongemma_model(state: State<'_, SharedState>) -> Result
     Ok(())
 }
 
+/// Delete a downloaded FunctionGemma variant, unloading it first if loaded.
+///
+/// Returns the number of bytes freed.
+#[tauri::command]
+pub async fn delete_functiongemma_model<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, SharedState>,
+    variant: String,
+) -> Result<u64, String> {
+    {
+        let mut guard = state.lock().await;
+        if guard
+            .functiongemma
+            .download
+            .as_ref()
+            .is_some_and(|d| d.variant == variant)
+        {
+            return Err("Download in progress".to_string());
+        }
+        if guard
+            .functiongemma
+            .model
+            .as_ref()
+            .is_some_and(|m| m.variant == variant)
+        {
+            guard.functiongemma.model = None;
+        }
+    }
+
+    let store = functiongemma_store(&app)?;
+    tokio::task::spawn_blocking(move || {
+        store.remove(gibberish_models::ModelKind::FunctionGemma, &variant)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Remove partially downloaded FunctionGemma variants and stray `.part` files.
+///
+/// Returns the number of bytes freed.
+#[tauri::command]
+pub async fn collect_functiongemma_garbage<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, SharedState>,
+) -> Result<u64, String> {
+    let downloading = {
+        let guard = state.lock().await;
+        guard
+            .functiongemma
+            .download
+            .as_ref()
+            .map(|d| d.variant.clone())
+    };
+
+    let store = functiongemma_store(&app)?;
+    let report = tokio::task::spawn_blocking(move || {
+        let in_use: Vec<(gibberish_models::ModelKind, &str)> = downloading
+            .iter()
+            .map(|v| (gibberish_models::ModelKind::FunctionGemma, v.as_str()))
+            .collect();
+        store.collect_garbage(&in_use)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    Ok(report.freed_bytes)
+}
+
 #[tauri::command]
 pub async fn get_current_functiongemma_model(
     state: State<'_, SharedState>,
@@ -1,5 +1,10 @@
 //! Commands for input emulation (The Typer).
 
+use tauri::{AppHandle, Runtime, State};
+
+use crate::error::Result;
+use crate::live_dictation::{LiveDictationHandle, LiveDictationOptions, LiveDictationStatus};
+
 /// Check if the application has accessibility permissions for input emulation.
 #[tauri::command]
 pub async fn check_input_access() -> bool {
@@ -13,3 +18,28 @@ pub async fn check_input_access() -> bool {
 pub async fn request_input_access() {
     gibberish_input::prompt_accessibility_access();
 }
+
+/// Start typing dictation into the focused app as it is spoken.
+#[tauri::command]
+pub async fn start_live_dictation<R: Runtime>(
+    app: AppHandle<R>,
+    handle: State<'_, LiveDictationHandle>,
+    options: Option<LiveDictationOptions>,
+) -> Result<LiveDictationStatus> {
+    handle.start(&app, options.unwrap_or_default())
+}
+
+/// Stop live dictation.
+#[tauri::command]
+pub async fn stop_live_dictation(handle: State<'_, LiveDictationHandle>) -> Result<()> {
+    handle.stop();
+    Ok(())
+}
+
+/// Get the live dictation state.
+#[tauri::command]
+pub async fn get_live_dictation_status(
+    handle: State<'_, LiveDictationHandle>,
+) -> Result<LiveDictationStatus> {
+    Ok(handle.status())
+}
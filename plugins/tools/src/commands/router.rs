@@ -1,5 +1,7 @@
 use crate::SharedState;
-use tauri::State;
+use gibberish_storage::{Setting, Settings};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime, State};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ActionRouterSettingsDto {
@@ -29,8 +31,11 @@ pub async fn get_action_router_settings(
     })
 }
 
+/// Update the router. Policy fields are persisted in the settings store
+/// when it is open; the manifest and instructions last for this run.
 #[tauri::command]
-pub async fn set_action_router_settings(
+pub async fn set_action_router_settings<R: Runtime>(
+    app: AppHandle<R>,
     state: State<'_, SharedState>,
     enabled: Option<bool>,
     auto_run_read_only: Option<bool>,
@@ -41,21 +46,33 @@ pub async fn set_action_router_settings(
     min_confidence: Option<f32>,
 ) -> Result<ActionRouterSettingsDto, String> {
     let mut guard = state.lock().await;
+    let mut settings = guard.router.settings();
     if let Some(v) = enabled {
-        guard.router.enabled = v;
+        settings.enabled = v;
     }
     if let Some(v) = auto_run_read_only {
-        guard.router.auto_run_read_only = v;
+        settings.auto_run_read_only = v;
     }
     if let Some(v) = auto_run_all {
-        guard.router.auto_run_all = v;
+        settings.auto_run_all = v;
     }
     if let Some(lang) = default_lang {
         let trimmed = lang.trim();
         if !trimmed.is_empty() {
-            guard.router.default_lang = trimmed.to_string();
+            settings.default_lang = trimmed.to_string();
         }
     }
+    if let Some(v) = min_confidence {
+        settings.min_confidence = v;
+    }
+    settings.validate()?;
+    if settings != guard.router.settings() {
+        if let Some(store) = app.try_state::<Arc<Settings>>() {
+            store.set(settings.clone()).map_err(|e| e.to_string())?;
+        }
+        guard.router.apply_settings(settings);
+    }
+
     let mut tool_manifest_changed = false;
     if let Some(m) = tool_manifest {
         let trimmed = m.trim();
@@ -82,12 +99,6 @@ pub async fn set_action_router_settings(
             guard.router.functiongemma_instructions, guard.router.functiongemma_declarations
         ));
     }
-    if let Some(v) = min_confidence {
-        if !(0.0..=1.0).contains(&v) {
-            return Err("min_confidence must be between 0 and 1".to_string());
-        }
-        guard.router.min_confidence = v;
-    }
     Ok(ActionRouterSettingsDto {
         enabled: guard.router.enabled,
         auto_run_read_only: guard.router.auto_run_read_only,
@@ -34,6 +34,9 @@ pub enum ToolsError {
 
     #[error("Download error: {0}")]
     Download(String),
+
+    #[error("Input error: {0}")]
+    Input(String),
 }
 
 impl Serialize for ToolsError {
@@ -51,6 +54,12 @@ impl From<std::io::Error> for ToolsError {
     }
 }
 
+impl From<gibberish_input::InputError> for ToolsError {
+    fn from(e: gibberish_input::InputError) -> Self {
+        ToolsError::Input(e.to_string())
+    }
+}
+
 impl From<crate::functiongemma_download::DownloadError> for ToolsError {
     fn from(e: crate::functiongemma_download::DownloadError) -> Self {
         ToolsError::Download(e.to_string())
@@ -7,6 +7,7 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use gibberish_application::CommitContext;
 use gibberish_events::{event_names, EventBus};
 
 use crate::adapters::PlatformClipboard;
@@ -49,6 +50,9 @@ pub enum ExecutionOutcome {
 /// Caching and cooldown are controlled by the tool through its ToolResult:
 /// - If `cooldown_key` is set, repeated calls are throttled
 /// - If `cache_key` is set, results are cached and reused
+///
+/// `commit_context` describes the pauses around the spoken text, for tools
+/// that type through the router's dictation editor.
 pub async fn execute_tool(
     state: &crate::SharedState,
     registry: &ToolRegistry,
@@ -56,6 +60,7 @@ pub async fn execute_tool(
     args: &serde_json::Value,
     evidence: &str,
     mode: ExecutionMode,
+    commit_context: CommitContext,
 ) -> ExecutionOutcome {
     let Some(tool) = registry.get(tool_name) else {
         return ExecutionOutcome::NotFound;
@@ -107,17 +112,19 @@ pub async fn execute_tool(
     }
 
     // Execute the tool with system environment
-    let (client, default_lang, abort_flag) = {
+    let (client, default_lang, abort_flag, dictation) = {
         let guard = state.lock().await;
         (
             guard.client.clone(),
             guard.router.default_lang.clone(),
             Arc::clone(&guard.global_abort),
+            Arc::clone(&guard.router.dictation),
         )
     };
 
     let env = Arc::new(RealSystemEnvironment::new(client));
-    let ctx = ToolContext::with_abort(env, default_lang, abort_flag);
+    let ctx = ToolContext::with_abort(env, default_lang, abort_flag)
+        .with_dictation(dictation, commit_context);
 
     // Resolve deictic references (clipboard, selection, etc.) before execution
     let clipboard_provider = PlatformClipboard::new();
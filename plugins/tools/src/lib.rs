@@ -18,6 +18,7 @@ mod functiongemma;
 mod functiongemma_download;
 mod functiongemma_models;
 mod inference;
+mod live_dictation;
 mod parser;
 mod pipeline;
 mod policy;
@@ -96,6 +97,8 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::load_functiongemma_model,
             commands::unload_functiongemma_model,
             commands::get_current_functiongemma_model,
+            commands::delete_functiongemma_model,
+            commands::collect_functiongemma_garbage,
             commands::get_context,
             commands::pin_context_mode,
             commands::unpin_context_mode,
@@ -106,6 +109,9 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::reload_tool_packs,
             commands::list_tool_packs,
             commands::reload_all_tools,
+            commands::start_live_dictation,
+            commands::stop_live_dictation,
+            commands::get_live_dictation_status,
         ])
         .setup(|app, _api| {
             // Create shared abort flag for panic hotkey
@@ -117,6 +123,10 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             tracing::info!("Panic hotkey listener started (Esc x3 to abort)");
 
             let event_bus = Arc::new(TauriEventBus::new(app.clone()));
+            app.manage(live_dictation::LiveDictationHandle::new(Arc::clone(
+                &global_abort,
+            )));
+
             let state = Arc::new(Mutex::new(state::ToolsState::with_abort_flag(
                 event_bus,
                 global_abort,
@@ -135,8 +145,32 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                 if payload.trim().is_empty() {
                     return;
                 }
+                // Dictated text is typed, not interpreted as commands.
+                if live_dictation::on_stt_stream_commit(&app_handle, payload) {
+                    return;
+                }
                 router::on_stt_stream_commit(&app_handle, payload);
             });
+
+            let app_handle = app.app_handle().clone();
+            app.listen_any(event_names::STT_WORD_EVENTS, move |event| {
+                live_dictation::on_stt_word_events(&app_handle, event.payload());
+            });
+
+            let app_handle = app.app_handle().clone();
+            app.listen_any("stt:commit_revised", move |event| {
+                let payload = event.payload();
+                if payload.trim().is_empty() {
+                    return;
+                }
+                router::on_stt_commit_revised(&app_handle, payload);
+            });
+
+            // Router policy is persisted by the settings store (stt plugin).
+            let app_handle = app.app_handle().clone();
+            app.listen_any(event_names::SETTINGS_CHANGED, move |event| {
+                router::on_settings_changed(&app_handle, event.payload());
+            });
             Ok(())
         })
         .build()
@@ -215,6 +249,12 @@ fn start_context_poller<R: Runtime>(app: &tauri::AppHandle<R>) {
                         };
 
                         guard.context.detected_mode = event.mode;
+
+                        // Edit commands must not act on text typed into another app
+                        let prev_app = guard.context.system.active_app.as_ref();
+                        if prev_app.map(|app| &app.bundle_id) != event.active_app.as_ref() {
+                            guard.router.reset_dictation();
+                        }
                         if let Some(ref bundle_id) = event.active_app {
                             guard.context.system.active_app = Some(gibberish_context::AppInfo {
                                 bundle_id: bundle_id.clone(),
@@ -0,0 +1,351 @@
+//! Live dictation: type into the focused app as you speak.
+//!
+//! Commits (`stt:stream_commit`) and word events (`stt:word_events`) feed
+//! a [`LiveDictation`], and its key sequences are played back by a typing
+//! thread that owns the `InputController`.
+//!
+//! - The focused app is captured when dictation starts. With
+//!   `verify_focus`, focus is checked before every key sequence; if it
+//!   moved, dictation stops instead of typing into another window.
+//! - The volatile tail is typed provisionally and backspace-corrected.
+//!   Apps in `PREFER_PASTE_APPS` have slow text fields, so only commits
+//!   are typed there. Smart paste still pastes long commits.
+//! - While dictation runs, commits bypass the action router.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+
+use gibberish_application::{CommitContext, DictationEdit, LiveDictation, WordEvent};
+use gibberish_events::{event_names, StreamCommitEvent, WordEventPayload, WordEventsEvent};
+use gibberish_input::{
+    FocusChecker, InputController, InputError, KeyAction, TypeOptions, PREFER_PASTE_APPS,
+};
+use gibberish_itn::InverseNormalizer;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, Runtime};
+
+use crate::adapters::PlatformFocusChecker;
+use crate::error::{Result, ToolsError};
+use crate::state::GlobalAbortFlag;
+
+/// Options for a live dictation session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveDictationOptions {
+    /// Language of the voice commands ("en", "es", "ca" or "auto").
+    pub language: String,
+    /// Stop if the focused app changes.
+    pub verify_focus: bool,
+    /// Type the volatile tail before it commits.
+    pub type_tail: bool,
+}
+
+impl Default for LiveDictationOptions {
+    fn default() -> Self {
+        Self {
+            language: "auto".to_string(),
+            verify_focus: true,
+            type_tail: true,
+        }
+    }
+}
+
+/// State of live dictation.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LiveDictationStatus {
+    pub active: bool,
+    /// Bundle id of the app being typed into.
+    pub target_app: Option<String>,
+    /// Whether the volatile tail is typed (off for paste-preferring apps).
+    pub type_tail: bool,
+}
+
+/// Payload for `tools:live_dictation_stopped`.
+#[derive(Debug, Clone, Serialize)]
+struct StoppedPayload {
+    /// "focus_changed", "aborted" or "input_failed".
+    reason: &'static str,
+    error: Option<String>,
+}
+
+struct Session {
+    dictation: LiveDictation,
+    normalizer: InverseNormalizer,
+    actions: mpsc::Sender<Vec<KeyAction>>,
+    /// Cleared by the typing thread when it stops on its own.
+    running: Arc<AtomicBool>,
+    status: LiveDictationStatus,
+}
+
+/// Live dictation session, managed as plugin state.
+pub struct LiveDictationHandle {
+    session: Mutex<Option<Session>>,
+    global_abort: GlobalAbortFlag,
+}
+
+impl LiveDictationHandle {
+    pub fn new(global_abort: GlobalAbortFlag) -> Self {
+        Self {
+            session: Mutex::new(None),
+            global_abort,
+        }
+    }
+
+    /// Start typing into the focused app, replacing any running session.
+    pub fn start<R: Runtime>(
+        &self,
+        app: &tauri::AppHandle<R>,
+        options: LiveDictationOptions,
+    ) -> Result<LiveDictationStatus> {
+        self.stop();
+
+        let running = Arc::new(AtomicBool::new(true));
+        let (actions, receiver) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+        let worker = TypingWorker {
+            app: app.clone(),
+            running: Arc::clone(&running),
+            global_abort: Arc::clone(&self.global_abort),
+            verify_focus: options.verify_focus,
+        };
+        std::thread::spawn(move || worker.run(receiver, ready_tx));
+
+        let target_app = ready_rx
+            .recv()
+            .map_err(|_| ToolsError::Input("typing thread exited".to_string()))??;
+        let type_tail = options.type_tail
+            && !target_app
+                .as_deref()
+                .is_some_and(|app| PREFER_PASTE_APPS.contains(&app));
+
+        let status = LiveDictationStatus {
+            active: true,
+            target_app,
+            type_tail,
+        };
+        tracing::info!(target_app = ?status.target_app, type_tail, "Live dictation started");
+        *self.lock() = Some(Session {
+            dictation: LiveDictation::new(&options.language, type_tail),
+            normalizer: InverseNormalizer::new(&options.language),
+            actions,
+            running,
+            status: status.clone(),
+        });
+        Ok(status)
+    }
+
+    /// Stop the session. Dropping the sender ends the typing thread.
+    pub fn stop(&self) {
+        if let Some(session) = self.lock().take() {
+            session.running.store(false, Ordering::SeqCst);
+            tracing::info!("Live dictation stopped");
+        }
+    }
+
+    pub fn status(&self) -> LiveDictationStatus {
+        self.with_session(|session| session.status.clone())
+            .unwrap_or_default()
+    }
+
+    /// Type a commit. Returns false when no session is running.
+    pub fn on_stream_commit(&self, event: &StreamCommitEvent) -> bool {
+        self.with_session(|session| {
+            let context = CommitContext {
+                after_pause: event.after_pause,
+                at_pause: event.at_pause,
+                turn_end: event.turn_end,
+            };
+            // "at three thirty" is typed as "at 3:30"
+            let text = session.normalizer.normalize(&event.text).text;
+            let edits = session.dictation.commit(&text, context);
+            session.send(edits);
+        })
+        .is_some()
+    }
+
+    /// Correct the volatile tail.
+    pub fn on_word_events(&self, event: WordEventsEvent) {
+        self.with_session(|session| {
+            let events: Vec<WordEvent> = event.events.into_iter().map(word_event).collect();
+            let edits = session.dictation.apply_word_events(&events);
+            session.send(edits);
+        });
+    }
+
+    /// Run `f` on the running session, dropping one that stopped.
+    fn with_session<T>(&self, f: impl FnOnce(&mut Session) -> T) -> Option<T> {
+        let mut guard = self.lock();
+        if guard
+            .as_ref()
+            .is_some_and(|session| !session.running.load(Ordering::SeqCst))
+        {
+            *guard = None;
+        }
+        guard.as_mut().map(f)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Session>> {
+        self.session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Session {
+    fn send(&self, edits: Vec<DictationEdit>) {
+        if edits.is_empty() {
+            return;
+        }
+        let actions = edits.into_iter().map(key_action).collect();
+        if self.actions.send(actions).is_err() {
+            self.running.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Owns the input controller and plays back key sequences in order.
+struct TypingWorker<R: Runtime> {
+    app: tauri::AppHandle<R>,
+    running: Arc<AtomicBool>,
+    global_abort: GlobalAbortFlag,
+    verify_focus: bool,
+}
+
+impl<R: Runtime> TypingWorker<R> {
+    fn run(
+        self,
+        receiver: mpsc::Receiver<Vec<KeyAction>>,
+        ready: mpsc::SyncSender<Result<Option<String>>>,
+    ) {
+        // InputController holds platform types that may not be Send, so it
+        // is created on this thread.
+        let focus_checker: Arc<dyn FocusChecker> = Arc::new(PlatformFocusChecker::new());
+        let target_app = focus_checker.get_current_focus();
+        let mut controller = match InputController::new(Some(Arc::clone(&focus_checker))) {
+            Ok(controller) => controller,
+            Err(e) => {
+                let _ = ready.send(Err(e.into()));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(target_app.clone()));
+
+        let options = TypeOptions {
+            verify_focus: self.verify_focus,
+            active_app: target_app.clone(),
+            ..TypeOptions::default()
+        };
+
+        while let Ok(actions) = receiver.recv() {
+            if !self.running.load(Ordering::SeqCst) {
+                break;
+            }
+            if self.global_abort.load(Ordering::SeqCst) {
+                self.stop("aborted", None);
+                break;
+            }
+            if self.verify_focus {
+                let current = focus_checker.get_current_focus();
+                if current.is_some() && current != target_app {
+                    let error = current.map(|app| format!("focus moved to {app}"));
+                    self.stop("focus_changed", error);
+                    break;
+                }
+            }
+            match controller.send_keys(&actions, options.clone()) {
+                Ok(()) => {}
+                Err(e @ InputError::FocusChanged { .. }) => {
+                    self.stop("focus_changed", Some(e.to_string()));
+                    break;
+                }
+                Err(InputError::Aborted) => {
+                    self.stop("aborted", None);
+                    break;
+                }
+                Err(e) => {
+                    self.stop("input_failed", Some(e.to_string()));
+                    break;
+                }
+            }
+        }
+    }
+
+    fn stop(&self, reason: &'static str, error: Option<String>) {
+        self.running.store(false, Ordering::SeqCst);
+        tracing::warn!(reason, ?error, "Live dictation stopped");
+        let _ = self.app.emit(
+            event_names::LIVE_DICTATION_STOPPED,
+            StoppedPayload { reason, error },
+        );
+    }
+}
+
+/// Route a `stt:stream_commit` payload to live dictation.
+///
+/// Returns false when dictation is off, leaving the commit to the router.
+pub fn on_stt_stream_commit<R: Runtime>(app: &tauri::AppHandle<R>, payload_json: &str) -> bool {
+    let Ok(event) = serde_json::from_str::<StreamCommitEvent>(payload_json) else {
+        return false;
+    };
+    app.state::<LiveDictationHandle>().on_stream_commit(&event)
+}
+
+/// Route a `stt:word_events` payload to live dictation.
+pub fn on_stt_word_events<R: Runtime>(app: &tauri::AppHandle<R>, payload_json: &str) {
+    let Ok(event) = serde_json::from_str::<WordEventsEvent>(payload_json) else {
+        return;
+    };
+    app.state::<LiveDictationHandle>().on_word_events(event);
+}
+
+pub(crate) fn key_action(edit: DictationEdit) -> KeyAction {
+    match edit {
+        DictationEdit::Type(text) => KeyAction::Type(text),
+        DictationEdit::Backspace(count) => KeyAction::Backspace(count),
+        DictationEdit::Return(count) => KeyAction::Return(count),
+        DictationEdit::SelectLeft(count) => KeyAction::SelectLeft(count),
+        DictationEdit::Deselect => KeyAction::Deselect,
+    }
+}
+
+fn word_event(payload: WordEventPayload) -> WordEvent {
+    match payload {
+        WordEventPayload::Inserted {
+            id,
+            after,
+            text,
+            start_ms,
+            end_ms,
+        } => WordEvent::Inserted {
+            id,
+            after,
+            text,
+            start_ms,
+            end_ms,
+        },
+        WordEventPayload::Revised {
+            id,
+            text,
+            start_ms,
+            end_ms,
+        } => WordEvent::Revised {
+            id,
+            text,
+            start_ms,
+            end_ms,
+        },
+        WordEventPayload::Removed { id } => WordEvent::Removed { id },
+        WordEventPayload::Committed {
+            id,
+            text,
+            start_ms,
+            end_ms,
+        } => WordEvent::Committed {
+            id,
+            text,
+            start_ms,
+            end_ms,
+        },
+        WordEventPayload::ParagraphBreak => WordEvent::ParagraphBreak,
+    }
+}
@@ -10,6 +10,14 @@ pub const DEFAULT_TOOL_COOLDOWN: Duration = Duration::from_secs(45);
 /// Debounce delay for router queue processing.
 pub const DEBOUNCE: Duration = Duration::from_millis(650);
 
+/// How long the router holds a streaming commit waiting for its two-pass
+/// revision before acting on the streamed text.
+pub const REVISION_GRACE: Duration = Duration::from_millis(1500);
+
+/// How long the router holds a commit whose utterance is still open: its
+/// revision only starts once the utterance ends.
+pub const REVISION_MAX_HOLD: Duration = Duration::from_secs(10);
+
 /// Time-to-live for cached results (e.g., Wikipedia lookups).
 pub const CACHE_TTL: Duration = Duration::from_secs(60 * 15);
 
@@ -30,6 +38,8 @@ mod tests {
     fn test_constants() {
         assert!(DEFAULT_TOOL_COOLDOWN.as_secs() > 0);
         assert!(DEBOUNCE.as_millis() > 0);
+        assert!(REVISION_GRACE.as_millis() > 0);
+        assert!(REVISION_MAX_HOLD > REVISION_GRACE);
         assert!(CACHE_TTL.as_secs() > 0);
         assert!(DEFAULT_MIN_CONFIDENCE > 0.0 && DEFAULT_MIN_CONFIDENCE < 1.0);
     }
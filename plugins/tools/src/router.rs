@@ -16,10 +16,17 @@ use crate::pipeline::{self, ChainDecision, PipelineContext};
 use crate::policy::DEBOUNCE;
 use crate::registry::ToolRegistry;
 use crate::router_logic::{self, RouterConfig};
+use crate::state::RouterState;
 use crate::tool_manifest::ToolPolicy;
-use gibberish_events::{event_names, EventBus, StreamCommitEvent};
+use crate::tools::send_edits_blocking;
+use gibberish_application::{CommitContext, DictationEdit};
+use gibberish_events::{
+    event_names, CommitRevisedEvent, EventBus, SettingsChangedEvent, StreamCommitEvent,
+};
+use gibberish_storage::{RouterSettings, Setting};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tauri::{Manager, Runtime};
 use tokio_util::sync::CancellationToken;
 
@@ -141,11 +148,12 @@ async fn process_router_queue<R: Runtime>(app: tauri::AppHandle<R>) {
         }
 
         // Extract state snapshot under lock
-        let (pending_text, runner, enabled, router_settings, infer_cancel) = {
+        let (pending_text, commit_context, runner, enabled, router_settings, infer_cancel) = {
             let mut guard = state.lock().await;
 
             let pending_text = guard.router.pending_text.trim().to_string();
             guard.router.pending_text.clear();
+            let commit_context = std::mem::take(&mut guard.router.pending_context);
 
             let runner = guard
                 .functiongemma
@@ -155,6 +163,7 @@ async fn process_router_queue<R: Runtime>(app: tauri::AppHandle<R>) {
 
             (
                 pending_text,
+                commit_context,
                 runner,
                 guard.router.enabled,
                 RouterConfig {
@@ -419,6 +428,7 @@ async fn process_router_queue<R: Runtime>(app: tauri::AppHandle<R>) {
             &args,
             &proposal.evidence,
             execution_mode,
+            commit_context,
         )
         .await;
 
@@ -549,6 +559,7 @@ async fn process_router_queue<R: Runtime>(app: tauri::AppHandle<R>) {
                                             &next_args,
                                             &step.evidence,
                                             next_execution_mode,
+                                            commit_context,
                                         )
                                         .await;
                                     }
@@ -627,6 +638,11 @@ async fn process_router_queue<R: Runtime>(app: tauri::AppHandle<R>) {
 }
 
 /// Handle incoming STT stream commit events.
+///
+/// Commits with a pending two-pass revision are held, and everything after
+/// them waits in the same queue so text is acted on in spoken order. The
+/// head is released when it is revised (see `on_stt_commit_revised`) or when
+/// its grace period expires.
 pub fn on_stt_stream_commit<R: Runtime>(app: &tauri::AppHandle<R>, payload_json: &str) {
     let Ok(payload) = serde_json::from_str::<StreamCommitEvent>(payload_json) else {
         return;
@@ -636,10 +652,21 @@ pub fn on_stt_stream_commit<R: Runtime>(app: &tauri::AppHandle<R>, payload_json:
     tauri::async_runtime::spawn(async move {
         let state = app.state::<crate::SharedState>();
 
-        // Get event bus and emit status
-        let event_bus = {
-            let guard = state.lock().await;
-            Arc::clone(&guard.event_bus)
+        let (event_bus, deadline) = {
+            let mut guard = state.lock().await;
+            let context = CommitContext {
+                after_pause: payload.after_pause,
+                at_pause: payload.at_pause,
+                turn_end: payload.turn_end,
+            };
+            let deadline = guard.router.held_commits.hold(
+                payload.commit_id,
+                payload.text.clone(),
+                context,
+                payload.revision_pending && payload.commit_id.is_some(),
+                Instant::now(),
+            );
+            (Arc::clone(&guard.event_bus), deadline)
         };
 
         emit_router_status(
@@ -648,18 +675,168 @@ pub fn on_stt_stream_commit<R: Runtime>(app: &tauri::AppHandle<R>, payload_json:
             serde_json::json!({ "text": payload.text }),
         );
 
-        let (should_spawn, notify) = {
+        release_held_commits(app.clone()).await;
+
+        // Release the commit as streamed if its revision is late.
+        tokio::time::sleep_until(deadline.into()).await;
+        release_held_commits(app).await;
+    });
+}
+
+/// Handle two-pass revisions of earlier commits.
+///
+/// Revisions of commits already released after their grace period are
+/// ignored; the router has acted on the streamed text.
+pub fn on_stt_commit_revised<R: Runtime>(app: &tauri::AppHandle<R>, payload_json: &str) {
+    let Ok(payload) = serde_json::from_str::<CommitRevisedEvent>(payload_json) else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<crate::SharedState>();
+        let commit_ids = if payload.commit_ids.is_empty() {
+            vec![payload.commit_id]
+        } else {
+            payload.commit_ids.clone()
+        };
+
+        let (event_bus, revised) = {
             let mut guard = state.lock().await;
-            if !guard.router.enabled {
-                return;
-            }
+            (
+                Arc::clone(&guard.event_bus),
+                guard.router.held_commits.revise(&commit_ids, &payload.text),
+            )
+        };
 
-            // Append text to pending buffer
-            if !guard.router.pending_text.is_empty() {
-                guard.router.pending_text.push(' ');
+        if !revised {
+            tracing::debug!(
+                commit_id = payload.commit_id,
+                "Revision arrived after grace window, ignoring"
+            );
+            return;
+        }
+
+        emit_router_status(
+            &*event_bus,
+            "commit_revised",
+            serde_json::json!({
+                "commit_id": payload.commit_id,
+                "commit_ids": commit_ids,
+                "original": payload.original_text,
+                "text": payload.text,
+            }),
+        );
+
+        release_held_commits(app).await;
+    });
+}
+
+/// Handle a `settings:changed` event: apply stored router policy, from
+/// startup or from an edit to the config file.
+pub fn on_settings_changed<R: Runtime>(app: &tauri::AppHandle<R>, payload_json: &str) {
+    let Ok(change) = serde_json::from_str::<SettingsChangedEvent>(payload_json) else {
+        return;
+    };
+    if change.key != RouterSettings::KEY {
+        return;
+    }
+    let settings = match serde_json::from_value::<RouterSettings>(change.value) {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::warn!(error = %e, "Ignoring unreadable router settings");
+            return;
+        }
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<crate::SharedState>();
+        state.lock().await.router.apply_settings(settings);
+    });
+}
+
+/// Queue the held commits that are ready, in order, and run the router if
+/// idle.
+async fn release_held_commits<R: Runtime>(app: tauri::AppHandle<R>) {
+    queue_router_text(app, |router, event_bus| {
+        router
+            .held_commits
+            .release(Instant::now())
+            .into_iter()
+            .map(|released| {
+                if released.timed_out {
+                    emit_router_status(
+                        event_bus,
+                        "revision_timeout",
+                        serde_json::json!({ "commit_id": released.commit_id }),
+                    );
+                }
+                (released.text, released.context)
+            })
+            .collect()
+    })
+    .await;
+}
+
+/// Append text taken from the router state to the pending buffer and wake
+/// the router. Taking and appending under one lock keeps concurrent callers
+/// in order.
+///
+/// Edit commands spoken on their own ("scratch that") while nothing is
+/// pending act on the dictated text directly instead of reaching the model.
+async fn queue_router_text<R, F>(app: tauri::AppHandle<R>, take: F)
+where
+    R: Runtime,
+    F: FnOnce(&mut RouterState, &dyn EventBus) -> Vec<(String, CommitContext)>,
+{
+    let state = app.state::<crate::SharedState>();
+
+    let (edits, abort_flag, queued) = {
+        let mut guard = state.lock().await;
+        let event_bus = Arc::clone(&guard.event_bus);
+        let texts = take(&mut guard.router, &*event_bus);
+        let texts: Vec<(&str, CommitContext)> = texts
+            .iter()
+            .map(|(text, context)| (text.trim(), *context))
+            .filter(|(text, _)| !text.is_empty())
+            .collect();
+        if !guard.router.enabled || texts.is_empty() {
+            return;
+        }
+
+        // Append text to pending buffer
+        let mut edits: Vec<DictationEdit> = Vec::new();
+        let router = &mut guard.router;
+        for (text, context) in texts {
+            if router.pending_text.is_empty() {
+                let edit = router
+                    .dictation
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .edit_command(text, context);
+                if let Some(edit) = edit {
+                    emit_router_status(
+                        &*event_bus,
+                        "dictation_edit",
+                        serde_json::json!({ "text": text }),
+                    );
+                    edits.extend(edit);
+                    continue;
+                }
+                router.pending_context = context;
+            } else {
+                router.pending_text.push(' ');
+                router.pending_context.at_pause = context.at_pause;
+                router.pending_context.turn_end = context.turn_end;
             }
-            guard.router.pending_text.push_str(payload.text.trim());
+            router.pending_text.push_str(text);
+        }
+        let abort_flag = Arc::clone(&guard.global_abort);
 
+        if guard.router.pending_text.is_empty() {
+            (edits, abort_flag, None)
+        } else {
             // Get notify handle to signal after lock is released
             let notify = Arc::clone(&guard.router.text_notify);
 
@@ -675,15 +852,29 @@ pub fn on_stt_stream_commit<R: Runtime>(app: &tauri::AppHandle<R>, payload_json:
                 guard.router.inflight = true;
                 true
             };
-            (spawn, notify)
-        };
-
-        // Signal the notify to reset debounce timer (even if worker is already running)
-        notify.notify_one();
+            (edits, abort_flag, Some((spawn, notify, event_bus)))
+        }
+    };
 
-        if should_spawn {
-            emit_router_status(&*event_bus, "worker_start", serde_json::json!({}));
-            process_router_queue(app).await;
+    if !edits.is_empty() {
+        let sent =
+            tokio::task::spawn_blocking(move || send_edits_blocking(edits, abort_flag)).await;
+        match sent {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => tracing::warn!(error = %e, "Dictation edit failed"),
+            Err(e) => tracing::warn!(error = %e, "Dictation edit task failed"),
         }
-    });
+    }
+
+    let Some((should_spawn, notify, event_bus)) = queued else {
+        return;
+    };
+
+    // Signal the notify to reset debounce timer (even if worker is already running)
+    notify.notify_one();
+
+    if should_spawn {
+        emit_router_status(&*event_bus, "worker_start", serde_json::json!({}));
+        process_router_queue(app).await;
+    }
 }
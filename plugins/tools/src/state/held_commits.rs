@@ -0,0 +1,222 @@
+//! Streaming commits held for their two-pass revision.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use gibberish_application::CommitContext;
+
+use crate::policy::{REVISION_GRACE, REVISION_MAX_HOLD};
+
+#[derive(Debug)]
+struct HeldCommit {
+    commit_id: Option<u64>,
+    text: String,
+    context: CommitContext,
+    /// Ready to release: revised, or never waiting for a revision.
+    ready: bool,
+    deadline: Instant,
+}
+
+/// A commit leaving the queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleasedCommit {
+    pub commit_id: Option<u64>,
+    /// Revised text, or the streamed text if the revision timed out. Empty
+    /// for commits merged into an earlier one by their revision.
+    pub text: String,
+    pub context: CommitContext,
+    pub timed_out: bool,
+}
+
+/// FIFO of streaming commits waiting for a two-pass revision.
+///
+/// Commits are released only from the head, when it is revised or its grace
+/// period expires, so text reaches the router in the order it was spoken.
+#[derive(Debug, Default)]
+pub struct HeldCommits {
+    queue: VecDeque<HeldCommit>,
+}
+
+impl HeldCommits {
+    /// Queue a commit.
+    ///
+    /// Commits without a pending revision are ready at once but still wait
+    /// behind older ones. A commit that ends its utterance starts the grace
+    /// period for itself and the commits before it; commits of an open
+    /// utterance wait up to `REVISION_MAX_HOLD`.
+    pub fn hold(
+        &mut self,
+        commit_id: Option<u64>,
+        text: String,
+        context: CommitContext,
+        revision_pending: bool,
+        now: Instant,
+    ) -> Instant {
+        let deadline = if context.at_pause || context.turn_end {
+            let deadline = now + REVISION_GRACE;
+            for held in &mut self.queue {
+                held.deadline = held.deadline.min(deadline);
+            }
+            deadline
+        } else {
+            now + REVISION_MAX_HOLD
+        };
+        self.queue.push_back(HeldCommit {
+            commit_id,
+            text,
+            context,
+            ready: !revision_pending,
+            deadline,
+        });
+        deadline
+    }
+
+    /// Apply a revision of `commit_ids` (oldest first): the first takes
+    /// `text` and the pause after the last, the rest are emptied.
+    ///
+    /// Returns false unless all of them are still held; a revision of
+    /// partly released commits would repeat their text.
+    pub fn revise(&mut self, commit_ids: &[u64], text: &str) -> bool {
+        let Some(&first) = commit_ids.first() else {
+            return false;
+        };
+        let all_held = commit_ids
+            .iter()
+            .all(|id| self.queue.iter().any(|held| held.commit_id == Some(*id)));
+        if !all_held {
+            return false;
+        }
+
+        let mut revised: Option<&mut HeldCommit> = None;
+        for held in &mut self.queue {
+            let Some(id) = held.commit_id.filter(|id| commit_ids.contains(id)) else {
+                continue;
+            };
+            held.ready = true;
+            if id == first {
+                held.text = text.to_string();
+                revised = Some(held);
+            } else {
+                held.text.clear();
+                if let Some(revised) = revised.as_deref_mut() {
+                    revised.context.at_pause = held.context.at_pause;
+                    revised.context.turn_end = held.context.turn_end;
+                }
+            }
+        }
+        true
+    }
+
+    /// Take the commits at the head that are ready or past their deadline.
+    pub fn release(&mut self, now: Instant) -> Vec<ReleasedCommit> {
+        let mut released = Vec::new();
+        while let Some(head) = self.queue.front() {
+            if !head.ready && head.deadline > now {
+                break;
+            }
+            let Some(head) = self.queue.pop_front() else {
+                break;
+            };
+            released.push(ReleasedCommit {
+                commit_id: head.commit_id,
+                text: head.text,
+                context: head.context,
+                timed_out: !head.ready,
+            });
+        }
+        released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const OPEN: CommitContext = CommitContext {
+        after_pause: false,
+        at_pause: false,
+        turn_end: false,
+    };
+    const CLOSING: CommitContext = CommitContext {
+        after_pause: false,
+        at_pause: true,
+        turn_end: false,
+    };
+
+    fn texts(released: &[ReleasedCommit]) -> Vec<&str> {
+        released.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    #[test]
+    fn releases_in_order_after_head_revision() {
+        let now = Instant::now();
+        let mut held = HeldCommits::default();
+        held.hold(Some(1), "one".into(), CLOSING, true, now);
+        held.hold(Some(2), "two".into(), CLOSING, true, now);
+
+        // A revision of the second commit waits behind the first.
+        assert!(held.revise(&[2], "Two."));
+        assert!(held.release(now).is_empty());
+
+        assert!(held.revise(&[1], "One."));
+        assert_eq!(texts(&held.release(now)), vec!["One.", "Two."]);
+        assert!(held.release(now + REVISION_MAX_HOLD).is_empty());
+    }
+
+    #[test]
+    fn expired_head_releases_streamed_text() {
+        let now = Instant::now();
+        let mut held = HeldCommits::default();
+        held.hold(Some(1), "one".into(), CLOSING, true, now);
+        held.hold(None, "plain".into(), CLOSING, false, now);
+
+        let released = held.release(now + REVISION_GRACE);
+        assert_eq!(texts(&released), vec!["one", "plain"]);
+        assert!(released[0].timed_out);
+        assert!(!released[1].timed_out);
+    }
+
+    #[test]
+    fn utterance_revision_merges_commits() {
+        let now = Instant::now();
+        let mut held = HeldCommits::default();
+        held.hold(Some(1), "i scream".into(), OPEN, true, now);
+        held.hold(Some(2), "for ice cream".into(), CLOSING, true, now);
+
+        assert!(held.revise(&[1, 2], "I scream, for ice cream."));
+        let released = held.release(now);
+        assert_eq!(texts(&released), vec!["I scream, for ice cream.", ""]);
+        assert_eq!(released[0].context, CLOSING);
+    }
+
+    #[test]
+    fn open_utterance_waits_for_its_end() {
+        let now = Instant::now();
+        let mut held = HeldCommits::default();
+        held.hold(Some(1), "open".into(), OPEN, true, now);
+        assert!(held.release(now + REVISION_GRACE).is_empty());
+
+        let later = now + Duration::from_secs(1);
+        held.hold(Some(2), "closed".into(), CLOSING, true, later);
+        assert_eq!(held.release(later + REVISION_GRACE).len(), 2);
+    }
+
+    #[test]
+    fn revision_of_released_commits_is_refused() {
+        let now = Instant::now();
+        let mut held = HeldCommits::default();
+        held.hold(Some(1), "one".into(), OPEN, true, now);
+        held.hold(Some(2), "two".into(), OPEN, true, now);
+        held.release(now + REVISION_MAX_HOLD);
+        held.hold(
+            Some(3),
+            "three".into(),
+            CLOSING,
+            true,
+            now + REVISION_MAX_HOLD,
+        );
+
+        assert!(!held.revise(&[2, 3], "Two three."));
+    }
+}
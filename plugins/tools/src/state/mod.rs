@@ -2,10 +2,12 @@
 
 mod cache;
 mod functiongemma;
+mod held_commits;
 mod router;
 
 pub use cache::{CacheEntry, CacheState};
 pub use functiongemma::{FunctionGemmaDownload, FunctionGemmaModel, FunctionGemmaState};
+pub use held_commits::HeldCommits;
 pub use router::RouterState;
 
 use std::sync::atomic::{AtomicBool, Ordering};
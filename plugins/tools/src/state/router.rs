@@ -1,18 +1,21 @@
 //! Router state for action detection and dispatch.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
+use super::HeldCommits;
 use crate::policy::{CLARIFICATION_THRESHOLD, MIN_CONFIDENCE};
 use crate::registry::ToolRegistry;
 use crate::skill_loader::SkillManager;
 use crate::tool_manifest;
 use crate::tool_manifest::ToolPolicy;
 use crate::tool_pack_loader::ToolPackManager;
+use gibberish_application::{CommitContext, DictationEditor};
 use gibberish_context::Mode;
+use gibberish_storage::RouterSettings;
 
 /// State for the action router.
 pub struct RouterState {
@@ -32,10 +35,18 @@ pub struct RouterState {
     /// Generic cooldown tracking: maps cooldown_key -> last execution time.
     pub cooldowns: HashMap<String, Instant>,
     pub pending_text: String,
+    /// Pauses around the pending text: before its first commit and after
+    /// its last.
+    pub pending_context: CommitContext,
+    /// What dictation typed into the focused app, so spoken edit commands
+    /// ("scratch that") act on it.
+    pub dictation: Arc<Mutex<DictationEditor>>,
     pub inflight: bool,
     pub infer_cancel: CancellationToken,
     /// Notify to wake up the router when new text arrives.
     pub text_notify: Arc<Notify>,
+    /// Streaming commits held for a two-pass revision.
+    pub held_commits: HeldCommits,
 }
 
 impl std::fmt::Debug for RouterState {
@@ -89,9 +100,12 @@ impl RouterState {
             clarification_threshold: CLARIFICATION_THRESHOLD,
             cooldowns: HashMap::new(),
             pending_text: String::new(),
+            pending_context: CommitContext::default(),
+            dictation: Arc::new(Mutex::new(DictationEditor::new("en"))),
             inflight: false,
             infer_cancel: CancellationToken::new(),
             text_notify: Arc::new(Notify::new()),
+            held_commits: HeldCommits::default(),
         }
     }
 
@@ -135,12 +149,47 @@ impl RouterState {
             clarification_threshold: CLARIFICATION_THRESHOLD,
             cooldowns: HashMap::new(),
             pending_text: String::new(),
+            pending_context: CommitContext::default(),
+            dictation: Arc::new(Mutex::new(DictationEditor::new("en"))),
             inflight: false,
             infer_cancel: CancellationToken::new(),
             text_notify: Arc::new(Notify::new()),
+            held_commits: HeldCommits::default(),
         }
     }
 
+    /// The persisted part of the router state.
+    pub fn settings(&self) -> RouterSettings {
+        RouterSettings {
+            enabled: self.enabled,
+            auto_run_read_only: self.auto_run_read_only,
+            auto_run_all: self.auto_run_all,
+            default_lang: self.default_lang.clone(),
+            min_confidence: self.min_confidence,
+            clarification_threshold: self.clarification_threshold,
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: RouterSettings) {
+        self.enabled = settings.enabled;
+        self.auto_run_read_only = settings.auto_run_read_only;
+        self.auto_run_all = settings.auto_run_all;
+        if settings.default_lang != self.default_lang {
+            self.dictation = Arc::new(Mutex::new(DictationEditor::new(&settings.default_lang)));
+        }
+        self.default_lang = settings.default_lang;
+        self.min_confidence = settings.min_confidence;
+        self.clarification_threshold = settings.clarification_threshold;
+    }
+
+    /// Forget what dictation typed, e.g. when focus moves to another app.
+    pub fn reset_dictation(&self) {
+        self.dictation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .reset();
+    }
+
     /// Update the manifest and instructions for a new mode (built-in tools only).
     pub fn update_for_mode(&mut self, mode: Mode) {
         let registry = ToolRegistry::build_all();
@@ -234,9 +283,12 @@ impl Default for RouterState {
             clarification_threshold: CLARIFICATION_THRESHOLD,
             cooldowns: HashMap::new(),
             pending_text: String::new(),
+            pending_context: CommitContext::default(),
+            dictation: Arc::new(Mutex::new(DictationEditor::new("en"))),
             inflight: false,
             infer_cancel: CancellationToken::new(),
             text_notify: Arc::new(Notify::new()),
+            held_commits: HeldCommits::default(),
         }
     }
 }
\ No newline at end of file
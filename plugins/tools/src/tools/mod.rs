@@ -22,13 +22,15 @@ pub use git_voice::GitVoiceTool;
 pub use paste::PasteTool;
 pub use system_control::SystemControlTool;
 pub use transcript_marker::TranscriptMarkerTool;
+pub(crate) use typer::send_edits_blocking;
 pub use typer::TyperTool;
 pub use web_search::WebSearchTool;
 
 use async_trait::async_trait;
+use gibberish_application::{CommitContext, DictationEditor};
 use gibberish_context::Mode;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::environment::SystemEnvironment;
 
@@ -51,6 +53,10 @@ pub struct ToolContext {
     pub default_lang: String,
     /// Global abort flag (set by panic hotkey Esc x3).
     pub abort_flag: Arc<AtomicBool>,
+    /// Dictation state shared with the router, for tools that type.
+    pub dictation: Arc<Mutex<DictationEditor>>,
+    /// Pauses around the spoken text that triggered the tool.
+    pub commit_context: CommitContext,
 }
 
 impl ToolContext {
@@ -63,13 +69,28 @@ impl ToolContext {
         default_lang: String,
         abort_flag: Arc<AtomicBool>,
     ) -> Self {
+        let dictation = Arc::new(Mutex::new(DictationEditor::new(&default_lang)));
         Self {
             env,
             default_lang,
             abort_flag,
+            dictation,
+            commit_context: CommitContext::default(),
         }
     }
 
+    /// Type through a shared dictation editor, for text spoken with
+    /// `commit_context`.
+    pub fn with_dictation(
+        mut self,
+        dictation: Arc<Mutex<DictationEditor>>,
+        commit_context: CommitContext,
+    ) -> Self {
+        self.dictation = dictation;
+        self.commit_context = commit_context;
+        self
+    }
+
     pub fn client(&self) -> &reqwest::Client {
         self.env.http_client()
     }
@@ -4,53 +4,30 @@
 
 use super::{Mode, Tool, ToolContext, ToolError, ToolResult};
 use async_trait::async_trait;
+use gibberish_transcript::MarkerKind;
 use serde_json::json;
 use std::borrow::Cow;
 
 /// Tool for marking moments in meeting transcripts.
 pub struct TranscriptMarkerTool;
 
-/// Type of marker to add.
-#[derive(Debug, Clone, Copy, serde::Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum MarkerType {
-    ActionItem,
-    Decision,
-    Question,
-    Important,
-    FollowUp,
-    Bookmark,
-}
-
-impl MarkerType {
-    fn parse(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "action" | "action_item" | "todo" | "task" => Some(MarkerType::ActionItem),
-            "decision" | "decided" => Some(MarkerType::Decision),
-            "question" | "ask" | "?" => Some(MarkerType::Question),
-            "important" | "key" | "!" => Some(MarkerType::Important),
-            "followup" | "follow_up" | "follow-up" => Some(MarkerType::FollowUp),
-            "bookmark" | "mark" | "flag" => Some(MarkerType::Bookmark),
-            _ => None,
-        }
-    }
-
-    fn emoji(&self) -> &'static str {
-        match self {
-            MarkerType::ActionItem => "📋",
-            MarkerType::Decision => "✅",
-            MarkerType::Question => "❓",
-            MarkerType::Important => "⭐",
-            MarkerType::FollowUp => "🔄",
-            MarkerType::Bookmark => "🔖",
-        }
+fn emoji(kind: MarkerKind) -> &'static str {
+    match kind {
+        MarkerKind::ActionItem => "📋",
+        MarkerKind::Decision => "✅",
+        MarkerKind::Question => "❓",
+        MarkerKind::Important => "⭐",
+        MarkerKind::FollowUp => "🔄",
+        MarkerKind::Bookmark => "🔖",
     }
 }
 
-/// A marker in the transcript.
+/// A marker in the transcript. The frontend converts the wall-clock
+/// timestamp to a position on the session timeline and saves it with the
+/// session.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct Marker {
-    pub marker_type: MarkerType,
+    pub marker_type: MarkerKind,
     pub note: Option<String>,
     pub timestamp_ms: i64,
 }
@@ -134,7 +111,7 @@ impl Tool for TranscriptMarkerTool {
                     .unwrap_or("bookmark");
 
                 let marker_type =
-                    MarkerType::parse(marker_type_str).unwrap_or(MarkerType::Bookmark);
+                    MarkerKind::parse(marker_type_str).unwrap_or(MarkerKind::Bookmark);
 
                 let note = args.get("note").and_then(|v| v.as_str()).map(String::from);
 
@@ -146,16 +123,16 @@ impl Tool for TranscriptMarkerTool {
                     timestamp_ms,
                 };
 
-                // The frontend will handle storing and displaying markers
+                // The frontend attaches markers to the session being recorded
                 Ok(ToolResult {
                     event_name: Cow::Borrowed("tools:transcript_marker"),
                     payload: serde_json::json!({
                         "action": "mark",
                         "marker": marker,
-                        "emoji": marker_type.emoji(),
+                        "emoji": emoji(marker_type),
                         "message": format!(
                             "{} Marked as {}{}",
-                            marker_type.emoji(),
+                            emoji(marker_type),
                             marker_type_str,
                             note.map(|n| format!(": {}", n)).unwrap_or_default()
                         ),
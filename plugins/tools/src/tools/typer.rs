@@ -1,12 +1,18 @@
 //! Typer tool for voice-controlled text input.
 //!
 //! Allows users to type text by voice command.
+//! Spoken numbers, dates, amounts, emails etc. are typed in written form.
+//! Text goes through the router's dictation editor, so spoken formatting
+//! ("new line") becomes keys and later edit commands can undo it.
 //! Requires accessibility permissions on macOS.
 
 use super::{Tool, ToolContext, ToolError, ToolResult};
 use crate::adapters::PlatformFocusChecker;
+use crate::live_dictation::key_action;
 use async_trait::async_trait;
-use gibberish_input::{FocusChecker, InputController, TypeOptions};
+use gibberish_application::DictationEdit;
+use gibberish_input::{FocusChecker, InputController, KeyAction, TypeOptions};
+use gibberish_itn::InverseNormalizer;
 use serde_json::json;
 use std::borrow::Cow;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -79,7 +85,7 @@ impl Tool for TyperTool {
             return Err(ToolError::ExecutionFailed("Aborted by panic hotkey".to_string()));
         }
 
-        let text = args
+        let raw_text = args
             .get("text")
             .and_then(|v| v.as_str())
             .map(|s| s.trim())
@@ -87,12 +93,23 @@ impl Tool for TyperTool {
             .ok_or(ToolError::MissingArg("text"))?
             .to_string();
 
+        // "at three thirty" is typed as "at 3:30"
+        let text = InverseNormalizer::new(&ctx.default_lang)
+            .normalize(&raw_text)
+            .text;
+
+        let edits = ctx
+            .dictation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .process(&text, ctx.commit_context);
+
         // Clone the abort flag for the blocking task
         let abort_flag = Arc::clone(&ctx.abort_flag);
 
         // Run typing on a blocking thread since InputController contains
         // platform-specific types that may not be Send
-        let result = tokio::task::spawn_blocking(move || type_text_blocking(&text, abort_flag))
+        let result = tokio::task::spawn_blocking(move || send_edits_blocking(edits, abort_flag))
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("Task join error: {}", e)))??;
 
@@ -100,6 +117,7 @@ impl Tool for TyperTool {
             event_name: Cow::Borrowed("tools:typer_result"),
             payload: json!({
                 "text": result.text,
+                "raw_text": raw_text,
                 "chars_typed": result.chars_typed,
                 "completed": result.completed,
             }),
@@ -110,14 +128,17 @@ impl Tool for TyperTool {
 }
 
 /// Result from blocking typing operation.
-struct TyperResult {
+pub(crate) struct TyperResult {
     text: String,
     chars_typed: usize,
     completed: bool,
 }
 
-/// Execute typing on the current thread (called via spawn_blocking).
-fn type_text_blocking(text: &str, abort_flag: Arc<AtomicBool>) -> Result<TyperResult, ToolError> {
+/// Send dictation edits on the current thread (called via spawn_blocking).
+pub(crate) fn send_edits_blocking(
+    edits: Vec<DictationEdit>,
+    abort_flag: Arc<AtomicBool>,
+) -> Result<TyperResult, ToolError> {
     // Check abort before starting
     if abort_flag.load(Ordering::SeqCst) {
         return Err(ToolError::ExecutionFailed("Aborted by panic hotkey".to_string()));
@@ -144,12 +165,21 @@ fn type_text_blocking(text: &str, abort_flag: Arc<AtomicBool>) -> Result<TyperRe
         });
     }
 
-    // Type with default options
-    let result = controller.type_text(text, TypeOptions::default())?;
+    let text: String = edits
+        .iter()
+        .filter_map(|edit| match edit {
+            DictationEdit::Type(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    let actions: Vec<KeyAction> = edits.into_iter().map(key_action).collect();
+
+    // Send with default options
+    controller.send_keys(&actions, TypeOptions::default())?;
 
     Ok(TyperResult {
-        text: text.to_string(),
-        chars_typed: result.chars_typed,
-        completed: result.completed,
+        chars_typed: text.chars().count(),
+        text,
+        completed: true,
     })
 }